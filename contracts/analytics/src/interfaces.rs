@@ -0,0 +1,56 @@
+//! Typed interfaces of the contracts metrics are read from.
+
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env, Symbol, Vec};
+
+/// Mirror of the user-profile contract's `PlatformStats`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlatformStats {
+    pub total_users: u32,
+    pub total_deposits: i128,
+    pub total_withdrawals: i128,
+    pub active_subscriptions: u32,
+}
+
+/// Subset of the contract directory used to find every source contract.
+#[allow(dead_code)]
+#[contractclient(name = "DirectoryClient")]
+pub trait DirectoryInterface {
+    fn get_address(env: Env, name: Symbol) -> Option<Address>;
+}
+
+/// Subset of the user-profile contract holding user and deposit totals.
+#[allow(dead_code)]
+#[contractclient(name = "ProfileClient")]
+pub trait ProfileInterface {
+    fn get_platform_stats(env: Env) -> PlatformStats;
+}
+
+/// Subset of the order contract holding order and escrow totals.
+#[allow(dead_code)]
+#[contractclient(name = "OrderClient")]
+pub trait OrderInterface {
+    fn get_order_count(env: Env) -> u32;
+    fn get_total_escrowed(env: Env) -> i128;
+}
+
+/// Subset of the DePIN registry used to enumerate rated DePINs.
+#[allow(dead_code)]
+#[contractclient(name = "RegistryClient")]
+pub trait RegistryInterface {
+    fn list_depins(env: Env, offset: u32, limit: u32) -> Vec<BytesN<32>>;
+}
+
+/// Subset of the reputation contract: (average, count, min, max) per DePIN.
+#[allow(dead_code)]
+#[contractclient(name = "ReputationClient")]
+pub trait ReputationInterface {
+    fn get_rating_stats(env: Env, depin_id: BytesN<32>) -> (Option<i32>, u32, i32, i32);
+}
+
+/// Subset of the treasury holding platform balances.
+#[allow(dead_code)]
+#[contractclient(name = "TreasuryClient")]
+pub trait TreasuryInterface {
+    fn get_balance(env: Env, token: Address) -> i128;
+}
@@ -0,0 +1,229 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, Env, Symbol, Vec,
+    panic_with_error, symbol_short
+};
+
+mod interfaces;
+use interfaces::{
+    DirectoryClient, OrderClient, ProfileClient, RegistryClient, ReputationClient, TreasuryClient,
+};
+
+/// Most snapshots returned by one `get_series` call
+pub const MAX_SERIES_LEN: u64 = 100;
+
+/// DePIN IDs read per `list_depins` call, the most the registry returns
+const REGISTRY_PAGE_SIZE: u32 = 100;
+
+#[contract]
+pub struct AnalyticsContract;
+
+/// Platform metrics captured once per epoch
+///
+/// Counters are cumulative; dashboards derive per-epoch volume by diffing
+/// consecutive snapshots. Sources missing from the directory read as zero.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetricsSnapshot {
+    pub epoch: u64,
+    pub timestamp: u64,
+    pub total_users: u32,
+    pub active_subscriptions: u32,
+    pub total_deposits: i128,
+    pub total_withdrawals: i128,
+    pub order_count: u32,
+    pub escrow_outstanding: i128,
+    pub treasury_balance: i128,         // Of the configured treasury token
+    pub average_rating_x100: u32,       // Review-weighted mean of per-DePIN averages, x100
+    pub review_count: u32,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Directory,                  // Contract directory resolving every source
+    TreasuryToken,              // Token whose treasury balance is tracked
+    GenesisTime,                // u64 start of epoch 0
+    EpochLength,                // u64 seconds
+    Snapshot(u64),              // epoch -> MetricsSnapshot
+    LatestEpoch,                // u64 epoch of the most recent snapshot
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    InvalidEpochLength = 4,
+    AlreadySnapshotted = 5,
+}
+
+#[contractimpl]
+impl AnalyticsContract {
+    /// Initialize with the directory of source contracts and the snapshot cadence
+    ///
+    /// Epoch 0 starts at initialization.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        directory: Address,
+        treasury_token: Address,
+        epoch_length: u64,
+    ) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+        if epoch_length == 0 {
+            panic_with_error!(&env, Error::InvalidEpochLength);
+        }
+
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::Directory, &directory);
+        env.storage().persistent().set(&DataKey::TreasuryToken, &treasury_token);
+        env.storage().persistent().set(&DataKey::GenesisTime, &env.ledger().timestamp());
+        env.storage().persistent().set(&DataKey::EpochLength, &epoch_length);
+        true
+    }
+
+    /// Set the token whose treasury balance is tracked (admin only)
+    pub fn set_treasury_token(env: Env, admin: Address, token: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::TreasuryToken, &token);
+        true
+    }
+
+    /// Capture the current epoch's metrics (permissionless, once per epoch)
+    pub fn snapshot(env: Env) -> MetricsSnapshot {
+        let epoch = Self::current_epoch(env.clone());
+        if env.storage().persistent().has(&DataKey::Snapshot(epoch)) {
+            panic_with_error!(&env, Error::AlreadySnapshotted);
+        }
+
+        let mut snapshot = MetricsSnapshot {
+            epoch,
+            timestamp: env.ledger().timestamp(),
+            total_users: 0,
+            active_subscriptions: 0,
+            total_deposits: 0,
+            total_withdrawals: 0,
+            order_count: 0,
+            escrow_outstanding: 0,
+            treasury_balance: 0,
+            average_rating_x100: 0,
+            review_count: 0,
+        };
+
+        if let Some(profile) = Self::resolve(&env, symbol_short!("profile")) {
+            let stats = ProfileClient::new(&env, &profile).get_platform_stats();
+            snapshot.total_users = stats.total_users;
+            snapshot.active_subscriptions = stats.active_subscriptions;
+            snapshot.total_deposits = stats.total_deposits;
+            snapshot.total_withdrawals = stats.total_withdrawals;
+        }
+
+        if let Some(order) = Self::resolve(&env, symbol_short!("order")) {
+            let client = OrderClient::new(&env, &order);
+            snapshot.order_count = client.get_order_count();
+            snapshot.escrow_outstanding = client.get_total_escrowed();
+        }
+
+        if let Some(treasury) = Self::resolve(&env, symbol_short!("treasury")) {
+            let token: Address = env.storage().persistent()
+                .get(&DataKey::TreasuryToken)
+                .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+            snapshot.treasury_balance = TreasuryClient::new(&env, &treasury).get_balance(&token);
+        }
+
+        let registry = Self::resolve(&env, symbol_short!("registry"));
+        let reputation = Self::resolve(&env, Symbol::new(&env, "reputation"));
+        if let (Some(registry), Some(reputation)) = (registry, reputation) {
+            let reputation = ReputationClient::new(&env, &reputation);
+            let registry = RegistryClient::new(&env, &registry);
+            let mut weighted: i64 = 0;
+            let mut offset = 0;
+            loop {
+                let depin_ids = registry.list_depins(&offset, &REGISTRY_PAGE_SIZE);
+                for depin_id in depin_ids.iter() {
+                    let (average, count, _, _) = reputation.get_rating_stats(&depin_id);
+                    if let Some(average) = average {
+                        weighted += average as i64 * count as i64;
+                        snapshot.review_count += count;
+                    }
+                }
+                if depin_ids.len() < REGISTRY_PAGE_SIZE {
+                    break;
+                }
+                offset += REGISTRY_PAGE_SIZE;
+            }
+            if snapshot.review_count > 0 {
+                snapshot.average_rating_x100 = (weighted * 100 / snapshot.review_count as i64) as u32;
+            }
+        }
+
+        env.storage().persistent().set(&DataKey::Snapshot(epoch), &snapshot);
+        env.storage().persistent().set(&DataKey::LatestEpoch, &epoch);
+
+        env.events().publish((symbol_short!("snapshot"), epoch), snapshot.timestamp);
+        snapshot
+    }
+
+    /// Get the epoch the ledger is currently in
+    pub fn current_epoch(env: Env) -> u64 {
+        let genesis: u64 = env.storage().persistent()
+            .get(&DataKey::GenesisTime)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let length: u64 = env.storage().persistent()
+            .get(&DataKey::EpochLength)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        (env.ledger().timestamp() - genesis) / length
+    }
+
+    /// Get the snapshot of an epoch
+    pub fn get_snapshot(env: Env, epoch: u64) -> Option<MetricsSnapshot> {
+        env.storage().persistent().get(&DataKey::Snapshot(epoch))
+    }
+
+    /// Get the most recent snapshot
+    pub fn get_latest(env: Env) -> Option<MetricsSnapshot> {
+        let epoch: u64 = env.storage().persistent().get(&DataKey::LatestEpoch)?;
+        Self::get_snapshot(env, epoch)
+    }
+
+    /// Get the snapshots of epochs `from..=to`, skipping epochs nobody captured
+    ///
+    /// At most `MAX_SERIES_LEN` epochs are scanned; page through longer ranges.
+    pub fn get_series(env: Env, from: u64, to: u64) -> Vec<MetricsSnapshot> {
+        let to = to.min(from.saturating_add(MAX_SERIES_LEN - 1));
+        let mut series = Vec::new(&env);
+        for epoch in from..=to {
+            if let Some(snapshot) = Self::get_snapshot(env.clone(), epoch) {
+                series.push_back(snapshot);
+            }
+        }
+        series
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn resolve(env: &Env, name: Symbol) -> Option<Address> {
+        let directory: Address = env.storage().persistent()
+            .get(&DataKey::Directory)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        DirectoryClient::new(env, &directory).get_address(&name)
+    }
+}
+
+mod test;
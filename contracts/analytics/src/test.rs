@@ -0,0 +1,159 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as AddressTestUtils, Ledger},
+    token::StellarAssetClient,
+    Address, BytesN, Env, String
+};
+
+/// Stand-in for the DePIN registry as seen by the order contract, pricing
+/// every service at 10 per hour
+#[contract]
+struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn depin_exists(_env: Env, _depin_id: BytesN<32>) -> bool {
+        true
+    }
+
+    pub fn supports_chain(_env: Env, _depin_id: BytesN<32>, _chain: String) -> bool {
+        true
+    }
+
+    pub fn get_payout_address(_env: Env, _depin_id: BytesN<32>) -> Option<Address> {
+        None
+    }
+
+    pub fn get_service_price(_env: Env, _depin_id: BytesN<32>, _service_type: String) -> Option<i128> {
+        Some(10)
+    }
+
+    pub fn reserve_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+
+    pub fn release_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+}
+
+/// Stand-in for the reputation contract: every DePIN has three 4-star reviews
+#[contract]
+struct MockReputation;
+
+#[contractimpl]
+impl MockReputation {
+    pub fn get_rating_stats(_env: Env, _depin_id: BytesN<32>) -> (Option<i32>, u32, i32, i32) {
+        (Some(4), 3, 4, 4)
+    }
+}
+
+const EPOCH: u64 = 24 * 3600;
+
+struct Setup<'a> {
+    analytics: AnalyticsContractClient<'a>,
+    directory: directory::DirectoryContractClient<'a>,
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let admin = Address::generate(env);
+    let user = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    StellarAssetClient::new(env, &token).mint(&user, &1_000);
+
+    let profile = user_profile::UserProfileContractClient::new(env, &env.register(user_profile::UserProfileContract, ()));
+    profile.initialize(&admin, &token);
+    profile.create_user_profile(
+        &user,
+        &String::from_str(env, "testuser"),
+        &BytesN::from_array(env, &[1u8; 32]),
+        &None,
+    );
+    profile.deposit_funds(&user, &token, &500);
+
+    let registry = depin_registry::ContractClient::new(env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    registry.add_depin(&admin, &String::from_str(env, "GPU"), &String::from_str(env, "A100"), &99, &95, &10);
+    registry.add_depin(&admin, &String::from_str(env, "Storage"), &String::from_str(env, "S3"), &99, &95, &1);
+
+    let orders = order::OrderContractClient::new(env, &env.register(order::OrderContract, ()));
+    orders.initialize(&admin);
+    orders.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    orders.set_escrow_contract(&admin, &env.register(escrow::EscrowContract, ()));
+    orders.set_payment_token(&admin, &token);
+    orders.set_treasury_wallet(&admin, &Address::generate(env));
+    orders.create_order(
+        &user,
+        &BytesN::from_array(env, &[1u8; 32]),
+        &String::from_str(env, "gpu"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(env, "ethereum"),
+        &String::from_str(env, "{}"),
+        &None,
+        &order::OrderPriority::Normal,
+    );
+
+    let treasury = treasury::TreasuryContractClient::new(env, &env.register(treasury::TreasuryContract, ()));
+    treasury.initialize(&admin);
+    treasury.deposit(&token, &user, &100);
+
+    let directory = directory::DirectoryContractClient::new(env, &env.register(directory::DirectoryContract, ()));
+    directory.initialize(&admin);
+    directory.set_contract(&symbol_short!("profile"), &profile.address, &1);
+    directory.set_contract(&symbol_short!("registry"), &registry.address, &1);
+    directory.set_contract(&symbol_short!("order"), &orders.address, &1);
+    directory.set_contract(&symbol_short!("treasury"), &treasury.address, &1);
+
+    let analytics = AnalyticsContractClient::new(env, &env.register(AnalyticsContract, ()));
+    analytics.initialize(&admin, &directory.address, &token, &EPOCH);
+
+    Setup { analytics, directory }
+}
+
+#[test]
+fn test_snapshot_reads_every_source() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.directory.set_contract(&Symbol::new(&env, "reputation"), &env.register(MockReputation, ()), &1);
+    let snapshot = s.analytics.snapshot();
+
+    assert_eq!(snapshot.epoch, 0);
+    assert_eq!(snapshot.total_users, 1);
+    assert_eq!(snapshot.total_deposits, 500);
+    assert_eq!(snapshot.order_count, 1);
+    assert_eq!(snapshot.escrow_outstanding, 240);
+    assert_eq!(snapshot.treasury_balance, 100);
+    assert_eq!(snapshot.review_count, 6);
+    assert_eq!(snapshot.average_rating_x100, 400);
+    assert_eq!(s.analytics.get_latest(), Some(snapshot));
+}
+
+#[test]
+fn test_series_skips_missing_epochs() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.analytics.snapshot();
+    env.ledger().with_mut(|li| li.timestamp += 2 * EPOCH);
+    s.analytics.snapshot();
+
+    let series = s.analytics.get_series(&0, &5);
+    assert_eq!(series.len(), 2);
+    assert_eq!(series.get(1).unwrap().epoch, 2);
+    // Without a reputation entry ratings read as zero
+    assert_eq!(series.get(0).unwrap().review_count, 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_one_snapshot_per_epoch() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.analytics.snapshot();
+    s.analytics.snapshot();
+}
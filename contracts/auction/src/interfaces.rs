@@ -0,0 +1,22 @@
+//! Typed interfaces of the contracts auctions settle into.
+
+use soroban_sdk::{contractclient, Address, BytesN, Env, String};
+
+/// Subset of the order contract used to turn winning bids into orders.
+#[allow(dead_code)]
+#[contractclient(name = "OrderClient")]
+pub trait OrderInterface {
+    fn get_payment_token(env: Env) -> Option<Address>;
+    fn create_funded_order(
+        env: Env,
+        source: Address,
+        user: Address,
+        depin_id: BytesN<32>,
+        service_type: String,
+        duration_hours: u64,
+        total_amount: i128,
+        payment_token: Address,
+        deployment_chain: String,
+        service_params: String,
+    ) -> BytesN<32>;
+}
@@ -0,0 +1,307 @@
+#![no_std]
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    Address, BytesN, Env, IntoVal, String, Symbol,
+    panic_with_error, symbol_short, token, vec
+};
+
+mod interfaces;
+use interfaces::OrderClient;
+
+#[contract]
+pub struct AuctionContract;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auction {
+    pub auction_id: u64,
+    pub provider: Address,
+    pub depin_id: BytesN<32>,
+    pub service_type: String,
+    pub duration_hours: u64,            // Capacity slot length the winner's order covers
+    pub deployment_chain: String,
+    pub token: Address,                 // Order payment token at listing time
+    pub reserve_price: i128,            // Minimum first bid
+    pub min_increment: i128,            // Minimum raise over the current highest bid
+    pub end_time: u64,
+    pub highest_bidder: Option<Address>,
+    pub highest_bid: i128,
+    pub service_params: String,         // Winning bidder's deployment parameters
+    pub status: AuctionStatus,
+    pub order_id: Option<BytesN<32>>,   // Order created for the winner on settlement
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuctionStatus {
+    Open,           // Accepting bids until end_time
+    Settled,        // Winner's order created or bid refunded, or closed without bids
+    Cancelled,      // Withdrawn by the provider before any bid
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    OrderContract,              // Order contract that receives winning bids
+    Provider(BytesN<32>),       // depin_id -> Address allowed to list its capacity
+    AuctionCounter,             // u64 counter for auction IDs
+    Auction(u64),               // auction_id -> Auction
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    NotProvider = 4,
+    AuctionNotFound = 5,
+    InvalidAuction = 6,
+    AuctionClosed = 7,
+    AuctionNotEnded = 8,
+    BidTooLow = 9,
+    HasBids = 10,
+    PaymentTokenNotSet = 11,
+}
+
+#[contractimpl]
+impl AuctionContract {
+    /// Initialize with admin and the order contract winners are billed through
+    pub fn initialize(env: Env, admin: Address, order_contract: Address) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::OrderContract, &order_contract);
+        env.storage().persistent().set(&DataKey::AuctionCounter, &0u64);
+        true
+    }
+
+    /// Let an address list auctions for a DePIN's capacity (admin only)
+    pub fn set_provider(env: Env, admin: Address, depin_id: BytesN<32>, provider: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::Provider(depin_id), &provider);
+        true
+    }
+
+    /// List a capacity slot for ascending bids (DePIN provider only)
+    pub fn create_auction(
+        env: Env,
+        provider: Address,
+        depin_id: BytesN<32>,
+        service_type: String,
+        duration_hours: u64,
+        deployment_chain: String,
+        reserve_price: i128,
+        min_increment: i128,
+        end_time: u64,
+    ) -> u64 {
+        provider.require_auth();
+
+        let listed_by: Option<Address> = env.storage().persistent().get(&DataKey::Provider(depin_id.clone()));
+        if listed_by != Some(provider.clone()) {
+            panic_with_error!(&env, Error::NotProvider);
+        }
+        if duration_hours == 0 || reserve_price <= 0 || min_increment <= 0
+            || end_time <= env.ledger().timestamp()
+        {
+            panic_with_error!(&env, Error::InvalidAuction);
+        }
+
+        // Bids are taken in the token orders are paid in
+        let token = Self::order(&env)
+            .get_payment_token()
+            .unwrap_or_else(|| panic_with_error!(&env, Error::PaymentTokenNotSet));
+
+        let mut counter: u64 = env.storage().persistent()
+            .get(&DataKey::AuctionCounter)
+            .unwrap_or(0);
+        counter += 1;
+        env.storage().persistent().set(&DataKey::AuctionCounter, &counter);
+
+        let auction = Auction {
+            auction_id: counter,
+            provider,
+            depin_id,
+            service_type,
+            duration_hours,
+            deployment_chain,
+            token,
+            reserve_price,
+            min_increment,
+            end_time,
+            highest_bidder: None,
+            highest_bid: 0,
+            service_params: String::from_str(&env, ""),
+            status: AuctionStatus::Open,
+            order_id: None,
+        };
+        env.storage().persistent().set(&DataKey::Auction(counter), &auction);
+
+        env.events().publish((symbol_short!("listed"), counter), (auction.depin_id, reserve_price, end_time));
+        counter
+    }
+
+    /// Place a bid, escrowing it here and refunding the previous highest bidder
+    pub fn bid(env: Env, bidder: Address, auction_id: u64, amount: i128, service_params: String) -> bool {
+        bidder.require_auth();
+
+        let mut auction = Self::load(&env, auction_id);
+        if auction.status != AuctionStatus::Open || env.ledger().timestamp() >= auction.end_time {
+            panic_with_error!(&env, Error::AuctionClosed);
+        }
+
+        let minimum = match auction.highest_bidder {
+            Some(_) => auction.highest_bid + auction.min_increment,
+            None => auction.reserve_price,
+        };
+        if amount < minimum {
+            panic_with_error!(&env, Error::BidTooLow);
+        }
+
+        let client = token::Client::new(&env, &auction.token);
+        let this = env.current_contract_address();
+        client.transfer(&bidder, &this, &amount);
+        if let Some(previous) = &auction.highest_bidder {
+            client.transfer(&this, previous, &auction.highest_bid);
+        }
+
+        auction.highest_bidder = Some(bidder.clone());
+        auction.highest_bid = amount;
+        auction.service_params = service_params;
+        env.storage().persistent().set(&DataKey::Auction(auction_id), &auction);
+
+        env.events().publish((symbol_short!("bid"), auction_id), (bidder, amount));
+        true
+    }
+
+    /// Withdraw a listing that has not received any bids (provider only)
+    pub fn cancel_auction(env: Env, provider: Address, auction_id: u64) -> bool {
+        provider.require_auth();
+
+        let mut auction = Self::load(&env, auction_id);
+        if provider != auction.provider {
+            panic_with_error!(&env, Error::NotProvider);
+        }
+        if auction.status != AuctionStatus::Open {
+            panic_with_error!(&env, Error::AuctionClosed);
+        }
+        if auction.highest_bidder.is_some() {
+            panic_with_error!(&env, Error::HasBids);
+        }
+
+        auction.status = AuctionStatus::Cancelled;
+        env.storage().persistent().set(&DataKey::Auction(auction_id), &auction);
+
+        env.events().publish((symbol_short!("cancelled"), auction_id), provider);
+        true
+    }
+
+    /// Close an ended auction and create the winner's order (anyone)
+    ///
+    /// The winning bid is handed to the order contract, which locks it in
+    /// escrow for the winner. If the order cannot be created, e.g. because the
+    /// DePIN is full or retired, the bid is refunded instead. Returns None
+    /// when nobody bid or the bid was refunded.
+    pub fn settle(env: Env, auction_id: u64) -> Option<BytesN<32>> {
+        let mut auction = Self::load(&env, auction_id);
+        if auction.status != AuctionStatus::Open {
+            panic_with_error!(&env, Error::AuctionClosed);
+        }
+        if env.ledger().timestamp() < auction.end_time {
+            panic_with_error!(&env, Error::AuctionNotEnded);
+        }
+
+        auction.status = AuctionStatus::Settled;
+        if let Some(winner) = auction.highest_bidder.clone() {
+            let order = Self::order(&env);
+            let this = env.current_contract_address();
+
+            // Let the order contract pull the bid; a failed order keeps it here
+            env.authorize_as_current_contract(vec![
+                &env,
+                InvokerContractAuthEntry::Contract(SubContractInvocation {
+                    context: ContractContext {
+                        contract: auction.token.clone(),
+                        fn_name: Symbol::new(&env, "transfer"),
+                        args: (this.clone(), order.address.clone(), auction.highest_bid).into_val(&env),
+                    },
+                    sub_invocations: vec![&env],
+                }),
+            ]);
+            let created = order.try_create_funded_order(
+                &this,
+                &winner,
+                &auction.depin_id,
+                &auction.service_type,
+                &auction.duration_hours,
+                &auction.highest_bid,
+                &auction.token,
+                &auction.deployment_chain,
+                &auction.service_params,
+            );
+            match created {
+                Ok(Ok(order_id)) => auction.order_id = Some(order_id),
+                _ => {
+                    token::Client::new(&env, &auction.token).transfer(&this, &winner, &auction.highest_bid);
+                    env.events().publish((symbol_short!("refunded"), auction_id), (winner, auction.highest_bid));
+                }
+            }
+        }
+        env.storage().persistent().set(&DataKey::Auction(auction_id), &auction);
+
+        env.events().publish(
+            (symbol_short!("settled"), auction_id),
+            (auction.highest_bidder, auction.highest_bid),
+        );
+        auction.order_id
+    }
+
+    /// Get auction details
+    pub fn get_auction(env: Env, auction_id: u64) -> Auction {
+        Self::load(&env, auction_id)
+    }
+
+    /// Get the address allowed to list a DePIN's capacity
+    pub fn get_provider(env: Env, depin_id: BytesN<32>) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Provider(depin_id))
+    }
+
+    /// Get number of auctions listed
+    pub fn get_auction_count(env: Env) -> u64 {
+        env.storage().persistent()
+            .get(&DataKey::AuctionCounter)
+            .unwrap_or(0)
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn order(env: &Env) -> OrderClient<'_> {
+        let address: Address = env.storage().persistent()
+            .get(&DataKey::OrderContract)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        OrderClient::new(env, &address)
+    }
+
+    fn load(env: &Env, auction_id: u64) -> Auction {
+        env.storage().persistent()
+            .get(&DataKey::Auction(auction_id))
+            .unwrap_or_else(|| panic_with_error!(env, Error::AuctionNotFound))
+    }
+}
+
+mod test;
@@ -0,0 +1,275 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env, String
+};
+use order::{OrderContract, OrderContractClient, OrderStatus};
+
+/// Stand-in for the DePIN registry that accepts every DePIN ID, priced at 10 per hour,
+/// until they are all retired
+#[contract]
+struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn retire_all(env: Env) {
+        env.storage().instance().set(&symbol_short!("retired"), &true);
+    }
+
+    pub fn depin_exists(env: Env, _depin_id: BytesN<32>) -> bool {
+        !env.storage().instance().has(&symbol_short!("retired"))
+    }
+
+    pub fn supports_chain(_env: Env, _depin_id: BytesN<32>, _chain: String) -> bool {
+        true
+    }
+
+    pub fn get_payout_address(_env: Env, _depin_id: BytesN<32>) -> Option<Address> {
+        None
+    }
+
+    pub fn get_service_price(_env: Env, _depin_id: BytesN<32>, _service_type: String) -> Option<i128> {
+        Some(10)
+    }
+
+    pub fn reserve_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+
+    pub fn release_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+}
+
+struct Setup<'a> {
+    auctions: AuctionContractClient<'a>,
+    orders: OrderContractClient<'a>,
+    token: TokenClient<'a>,
+    escrow: Address,
+    registry: Address,
+    admin: Address,
+    provider: Address,
+    depin_id: BytesN<32>,
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let provider = Address::generate(env);
+    let depin_id = BytesN::from_array(env, &[1u8; 32]);
+    let token_id = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+
+    let order_id = env.register(OrderContract, ());
+    let orders = OrderContractClient::new(env, &order_id);
+    orders.initialize(&admin);
+    let escrow = env.register(escrow::EscrowContract, ());
+    let registry = env.register(MockRegistry, ());
+    orders.set_depin_registry_contract(&admin, &registry);
+    orders.set_escrow_contract(&admin, &escrow);
+    orders.set_payment_token(&admin, &token_id);
+    orders.set_treasury_wallet(&admin, &Address::generate(env));
+
+    let auction_id = env.register(AuctionContract, ());
+    let auctions = AuctionContractClient::new(env, &auction_id);
+    auctions.initialize(&admin, &order_id);
+    auctions.set_provider(&admin, &depin_id, &provider);
+    orders.set_order_source(&admin, &auction_id, &true);
+
+    Setup {
+        auctions,
+        orders,
+        token: TokenClient::new(env, &token_id),
+        escrow,
+        registry,
+        admin,
+        provider,
+        depin_id,
+    }
+}
+
+fn list(env: &Env, s: &Setup) -> u64 {
+    s.auctions.create_auction(
+        &s.provider,
+        &s.depin_id,
+        &String::from_str(env, "gpu"),
+        &24,
+        &String::from_str(env, "ethereum"),
+        &100,
+        &10,
+        &1_000,
+    )
+}
+
+fn bidder(env: &Env, s: &Setup) -> Address {
+    let bidder = Address::generate(env);
+    StellarAssetClient::new(env, &s.token.address).mint(&bidder, &1_000);
+    bidder
+}
+
+#[test]
+fn test_outbid_bidder_is_refunded() {
+    let env = Env::default();
+    let s = setup(&env);
+    let auction_id = list(&env, &s);
+    let alice = bidder(&env, &s);
+    let bob = bidder(&env, &s);
+
+    s.auctions.bid(&alice, &auction_id, &100, &String::from_str(&env, "{}"));
+    s.auctions.bid(&bob, &auction_id, &120, &String::from_str(&env, "{\"gpus\":2}"));
+
+    assert_eq!(s.token.balance(&alice), 1_000);
+    assert_eq!(s.token.balance(&bob), 880);
+    assert_eq!(s.token.balance(&s.auctions.address), 120);
+
+    let auction = s.auctions.get_auction(&auction_id);
+    assert_eq!(auction.highest_bidder, Some(bob));
+    assert_eq!(auction.highest_bid, 120);
+}
+
+#[test]
+fn test_settle_creates_order_for_winner() {
+    let env = Env::default();
+    let s = setup(&env);
+    let auction_id = list(&env, &s);
+    let alice = bidder(&env, &s);
+    let bob = bidder(&env, &s);
+
+    s.auctions.bid(&alice, &auction_id, &100, &String::from_str(&env, "{}"));
+    s.auctions.bid(&bob, &auction_id, &240, &String::from_str(&env, "{\"gpus\":2}"));
+
+    // Settling needs no signatures; the auction authorizes paying the bid over
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    env.set_auths(&[]);
+    let order_id = s.auctions.settle(&auction_id).unwrap();
+
+    let order = s.orders.get_order(&order_id);
+    assert_eq!(order.user, bob);
+    assert_eq!(order.depin_id, s.depin_id);
+    assert_eq!(order.total_amount, 240);
+    assert_eq!(order.price_per_hour, 10);
+    assert_eq!(order.status, OrderStatus::Pending);
+    assert_eq!(order.service_params, String::from_str(&env, "{\"gpus\":2}"));
+
+    assert_eq!(s.token.balance(&s.escrow), 240);
+    assert_eq!(s.token.balance(&s.auctions.address), 0);
+
+    let auction = s.auctions.get_auction(&auction_id);
+    assert_eq!(auction.status, AuctionStatus::Settled);
+    assert_eq!(auction.order_id, Some(order_id));
+}
+
+#[test]
+fn test_settle_escrows_the_token_bids_were_taken_in() {
+    let env = Env::default();
+    let s = setup(&env);
+    let auction_id = list(&env, &s);
+    let alice = bidder(&env, &s);
+    s.auctions.bid(&alice, &auction_id, &240, &String::from_str(&env, "{}"));
+
+    // The platform switches tokens while the auction runs
+    let new_token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    s.orders.set_payment_token(&s.admin, &new_token);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let order_id = s.auctions.settle(&auction_id).unwrap();
+    assert_eq!(s.orders.get_order(&order_id).payment_token, s.token.address);
+    assert_eq!(s.token.balance(&s.escrow), 240);
+}
+
+#[test]
+fn test_settle_refunds_winner_when_order_fails() {
+    let env = Env::default();
+    let s = setup(&env);
+    let auction_id = list(&env, &s);
+    let alice = bidder(&env, &s);
+    s.auctions.bid(&alice, &auction_id, &240, &String::from_str(&env, "{}"));
+
+    // The DePIN is retired before the auction ends
+    MockRegistryClient::new(&env, &s.registry).retire_all();
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert_eq!(s.auctions.settle(&auction_id), None);
+    assert_eq!(s.token.balance(&alice), 1_000);
+    assert_eq!(s.token.balance(&s.auctions.address), 0);
+    assert_eq!(s.token.balance(&s.orders.address), 0);
+
+    let auction = s.auctions.get_auction(&auction_id);
+    assert_eq!(auction.status, AuctionStatus::Settled);
+    assert_eq!(auction.order_id, None);
+}
+
+#[test]
+fn test_settle_without_bids() {
+    let env = Env::default();
+    let s = setup(&env);
+    let auction_id = list(&env, &s);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert_eq!(s.auctions.settle(&auction_id), None);
+    assert_eq!(s.auctions.get_auction(&auction_id).status, AuctionStatus::Settled);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_bid_below_increment() {
+    let env = Env::default();
+    let s = setup(&env);
+    let auction_id = list(&env, &s);
+    let alice = bidder(&env, &s);
+    let bob = bidder(&env, &s);
+
+    s.auctions.bid(&alice, &auction_id, &100, &String::from_str(&env, "{}"));
+    s.auctions.bid(&bob, &auction_id, &105, &String::from_str(&env, "{}"));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_bid_after_end() {
+    let env = Env::default();
+    let s = setup(&env);
+    let auction_id = list(&env, &s);
+    let alice = bidder(&env, &s);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    s.auctions.bid(&alice, &auction_id, &100, &String::from_str(&env, "{}"));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_settle_before_end() {
+    let env = Env::default();
+    let s = setup(&env);
+    let auction_id = list(&env, &s);
+
+    s.auctions.settle(&auction_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_only_provider_can_list() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.auctions.create_auction(
+        &Address::generate(&env),
+        &s.depin_id,
+        &String::from_str(&env, "gpu"),
+        &24,
+        &String::from_str(&env, "ethereum"),
+        &100,
+        &10,
+        &1_000,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_cancel_with_bids() {
+    let env = Env::default();
+    let s = setup(&env);
+    let auction_id = list(&env, &s);
+    let alice = bidder(&env, &s);
+
+    s.auctions.bid(&alice, &auction_id, &100, &String::from_str(&env, "{}"));
+    s.auctions.cancel_auction(&s.provider, &auction_id);
+}
@@ -0,0 +1,175 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, Env, String,
+    panic_with_error, symbol_short
+};
+
+#[contract]
+pub struct ComplianceContract;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub attestor: Address,
+    pub jurisdiction: String,       // e.g. ISO 3166 country code
+    pub kyc_level: u32,             // 1: basic, 2: enhanced, ... (0 is never stored)
+    pub issued_at: u64,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Attestor(Address),              // address -> bool, parties allowed to attest
+    Attestation(Address),           // subject -> Attestation
+    BlockedJurisdiction(String),    // jurisdiction -> bool
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    NotAttestor = 4,
+    InvalidLevel = 5,
+    InvalidExpiry = 6,
+    AttestationNotFound = 7,
+}
+
+#[contractimpl]
+impl ComplianceContract {
+    /// Initialize the contract with admin address
+    pub fn initialize(env: Env, admin: Address) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        true
+    }
+
+    /// Allow or disallow an attestor (admin only)
+    ///
+    /// Attestations stop counting as soon as their attestor is removed.
+    pub fn set_attestor(env: Env, admin: Address, attestor: Address, allowed: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::Attestor(attestor), &allowed);
+        true
+    }
+
+    /// Block or unblock every attestation issued for a jurisdiction (admin only)
+    pub fn set_jurisdiction_blocked(env: Env, admin: Address, jurisdiction: String, blocked: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::BlockedJurisdiction(jurisdiction), &blocked);
+        true
+    }
+
+    /// Record an attestation for a subject, replacing any earlier one (approved attestors only)
+    pub fn attest(
+        env: Env,
+        attestor: Address,
+        subject: Address,
+        jurisdiction: String,
+        kyc_level: u32,
+        expires_at: u64,
+    ) -> bool {
+        attestor.require_auth();
+        if !Self::is_attestor(env.clone(), attestor.clone()) {
+            panic_with_error!(&env, Error::NotAttestor);
+        }
+        if kyc_level == 0 {
+            panic_with_error!(&env, Error::InvalidLevel);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            panic_with_error!(&env, Error::InvalidExpiry);
+        }
+
+        let attestation = Attestation {
+            attestor,
+            jurisdiction,
+            kyc_level,
+            issued_at: env.ledger().timestamp(),
+            expires_at,
+        };
+        env.storage().persistent().set(&DataKey::Attestation(subject.clone()), &attestation);
+
+        env.events().publish((symbol_short!("attest"), subject), (kyc_level, expires_at));
+        true
+    }
+
+    /// Revoke a subject's attestation (issuing attestor or admin)
+    pub fn revoke(env: Env, caller: Address, subject: Address) -> bool {
+        let attestation = Self::get_attestation(env.clone(), subject.clone())
+            .unwrap_or_else(|| panic_with_error!(&env, Error::AttestationNotFound));
+
+        let admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        if caller != attestation.attestor && caller != admin {
+            panic_with_error!(&env, Error::NotAttestor);
+        }
+        caller.require_auth();
+
+        env.storage().persistent().remove(&DataKey::Attestation(subject.clone()));
+        env.events().publish((symbol_short!("revoke"), subject), caller);
+        true
+    }
+
+    /// Get a subject's stored attestation, valid or not
+    pub fn get_attestation(env: Env, subject: Address) -> Option<Attestation> {
+        env.storage().persistent().get(&DataKey::Attestation(subject))
+    }
+
+    /// Get a subject's effective KYC level
+    ///
+    /// 0 when there is no attestation, or it expired, its attestor was
+    /// removed or its jurisdiction is blocked.
+    pub fn get_kyc_level(env: Env, subject: Address) -> u32 {
+        let attestation = match Self::get_attestation(env.clone(), subject) {
+            Some(attestation) => attestation,
+            None => return 0,
+        };
+        if env.ledger().timestamp() >= attestation.expires_at
+            || !Self::is_attestor(env.clone(), attestation.attestor)
+            || Self::is_jurisdiction_blocked(env.clone(), attestation.jurisdiction)
+        {
+            return 0;
+        }
+        attestation.kyc_level
+    }
+
+    /// Check whether a subject holds a valid attestation of at least `min_level`
+    pub fn is_compliant(env: Env, subject: Address, min_level: u32) -> bool {
+        Self::get_kyc_level(env, subject) >= min_level
+    }
+
+    /// Check whether an address is an approved attestor
+    pub fn is_attestor(env: Env, attestor: Address) -> bool {
+        env.storage().persistent()
+            .get(&DataKey::Attestor(attestor))
+            .unwrap_or(false)
+    }
+
+    /// Check whether a jurisdiction is blocked
+    pub fn is_jurisdiction_blocked(env: Env, jurisdiction: String) -> bool {
+        env.storage().persistent()
+            .get(&DataKey::BlockedJurisdiction(jurisdiction))
+            .unwrap_or(false)
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+}
+
+mod test;
@@ -0,0 +1,95 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    Address, Env, String
+};
+
+const NOW: u64 = 100_000;
+const YEAR: u64 = 365 * 24 * 3600;
+
+struct Setup<'a> {
+    compliance: ComplianceContractClient<'a>,
+    admin: Address,
+    attestor: Address,
+    user: Address,
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = NOW);
+
+    let admin = Address::generate(env);
+    let attestor = Address::generate(env);
+    let compliance = ComplianceContractClient::new(env, &env.register(ComplianceContract, ()));
+    compliance.initialize(&admin);
+    compliance.set_attestor(&admin, &attestor, &true);
+
+    Setup {
+        compliance,
+        admin,
+        attestor,
+        user: Address::generate(env),
+    }
+}
+
+#[test]
+fn test_attestation_grants_level_until_expiry() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.compliance.attest(&s.attestor, &s.user, &String::from_str(&env, "DE"), &2, &(NOW + YEAR));
+    assert_eq!(s.compliance.get_kyc_level(&s.user), 2);
+    assert!(s.compliance.is_compliant(&s.user, &2));
+    assert!(!s.compliance.is_compliant(&s.user, &3));
+
+    env.ledger().with_mut(|li| li.timestamp = NOW + YEAR);
+    assert_eq!(s.compliance.get_kyc_level(&s.user), 0);
+    assert!(s.compliance.get_attestation(&s.user).is_some());
+}
+
+#[test]
+fn test_removed_attestor_and_blocked_jurisdiction_void_attestations() {
+    let env = Env::default();
+    let s = setup(&env);
+    let jurisdiction = String::from_str(&env, "DE");
+
+    s.compliance.attest(&s.attestor, &s.user, &jurisdiction, &1, &(NOW + YEAR));
+    s.compliance.set_jurisdiction_blocked(&s.admin, &jurisdiction, &true);
+    assert!(!s.compliance.is_compliant(&s.user, &1));
+
+    s.compliance.set_jurisdiction_blocked(&s.admin, &jurisdiction, &false);
+    assert!(s.compliance.is_compliant(&s.user, &1));
+
+    s.compliance.set_attestor(&s.admin, &s.attestor, &false);
+    assert!(!s.compliance.is_compliant(&s.user, &1));
+}
+
+#[test]
+fn test_admin_revokes_attestation() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.compliance.attest(&s.attestor, &s.user, &String::from_str(&env, "DE"), &1, &(NOW + YEAR));
+    s.compliance.revoke(&s.admin, &s.user);
+    assert_eq!(s.compliance.get_attestation(&s.user), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_only_attestors_attest() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.compliance.attest(&s.user, &s.user, &String::from_str(&env, "DE"), &1, &(NOW + YEAR));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_expired_attestation_rejected() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.compliance.attest(&s.attestor, &s.user, &String::from_str(&env, "DE"), &1, &NOW);
+}
@@ -0,0 +1,242 @@
+#![no_std]
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, BytesN, Env, String,
+    panic_with_error, symbol_short
+};
+
+/// Basis-point denominator for percentage discounts (100% = 10_000 bps)
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+#[contract]
+pub struct CouponContract;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Discount {
+    Percent(u32),       // Basis points off the order total
+    Fixed(i128),        // Flat amount off the order total
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Coupon {
+    pub code: String,
+    pub discount: Discount,
+    pub expires_at: u64,                    // 0 = never expires
+    pub max_uses: u32,                      // 0 = unlimited
+    pub per_user_limit: u32,                // 0 = unlimited
+    pub depin_id: Option<BytesN<32>>,       // Only valid for this DePIN
+    pub service_type: Option<String>,       // Only valid for this service type
+    pub uses: u32,
+    pub active: bool,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Coupon(String),                 // code -> Coupon
+    UserUses(String, Address),      // (code, user) -> u32
+    Redeemer(Address),              // address -> bool, contracts allowed to redeem
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    CouponExists = 4,
+    CouponNotFound = 5,
+    CouponInactive = 6,
+    CouponExpired = 7,
+    UsageCapReached = 8,
+    UserLimitReached = 9,
+    NotApplicable = 10,
+    InvalidDiscount = 11,
+    NotRedeemer = 12,
+}
+
+#[contractimpl]
+impl CouponContract {
+    /// Initialize the contract with admin address
+    pub fn initialize(env: Env, admin: Address) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        true
+    }
+
+    /// Allow or disallow a contract to redeem coupons, e.g. the order contract (admin only)
+    pub fn set_redeemer(env: Env, admin: Address, redeemer: Address, allowed: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::Redeemer(redeemer), &allowed);
+        true
+    }
+
+    /// Issue a new coupon (admin only)
+    pub fn create_coupon(
+        env: Env,
+        admin: Address,
+        code: String,
+        discount: Discount,
+        expires_at: u64,
+        max_uses: u32,
+        per_user_limit: u32,
+        depin_id: Option<BytesN<32>>,
+        service_type: Option<String>,
+    ) -> bool {
+        Self::assert_admin(&env, &admin);
+
+        if env.storage().persistent().has(&DataKey::Coupon(code.clone())) {
+            panic_with_error!(&env, Error::CouponExists);
+        }
+        let valid = match discount {
+            Discount::Percent(bps) => bps > 0 && bps <= BPS_DENOMINATOR,
+            Discount::Fixed(amount) => amount > 0,
+        };
+        if code.is_empty() || !valid {
+            panic_with_error!(&env, Error::InvalidDiscount);
+        }
+
+        let coupon = Coupon {
+            code: code.clone(),
+            discount,
+            expires_at,
+            max_uses,
+            per_user_limit,
+            depin_id,
+            service_type,
+            uses: 0,
+            active: true,
+        };
+        env.storage().persistent().set(&DataKey::Coupon(code.clone()), &coupon);
+
+        env.events().publish((symbol_short!("created"), code), coupon.discount);
+        true
+    }
+
+    /// Enable or disable a coupon (admin only)
+    pub fn set_coupon_active(env: Env, admin: Address, code: String, active: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+
+        let mut coupon = Self::load(&env, &code);
+        coupon.active = active;
+        env.storage().persistent().set(&DataKey::Coupon(code), &coupon);
+        true
+    }
+
+    /// Discount a coupon would grant on an order, without redeeming it
+    pub fn quote_discount(
+        env: Env,
+        code: String,
+        user: Address,
+        depin_id: BytesN<32>,
+        service_type: String,
+        amount: i128,
+    ) -> i128 {
+        let coupon = Self::load(&env, &code);
+        Self::validate(&env, &coupon, &user, &depin_id, &service_type);
+        Self::discount_for(&coupon, amount)
+    }
+
+    /// Validate and consume one use of a coupon, returning the discount
+    /// (approved redeemers only)
+    pub fn redeem(
+        env: Env,
+        redeemer: Address,
+        code: String,
+        user: Address,
+        depin_id: BytesN<32>,
+        service_type: String,
+        amount: i128,
+    ) -> i128 {
+        redeemer.require_auth();
+        let allowed: bool = env.storage().persistent()
+            .get(&DataKey::Redeemer(redeemer))
+            .unwrap_or(false);
+        if !allowed {
+            panic_with_error!(&env, Error::NotRedeemer);
+        }
+
+        let mut coupon = Self::load(&env, &code);
+        Self::validate(&env, &coupon, &user, &depin_id, &service_type);
+        let discount = Self::discount_for(&coupon, amount);
+
+        coupon.uses += 1;
+        env.storage().persistent().set(&DataKey::Coupon(code.clone()), &coupon);
+        let user_uses = Self::get_user_uses(env.clone(), code.clone(), user.clone());
+        env.storage().persistent().set(&DataKey::UserUses(code.clone(), user.clone()), &(user_uses + 1));
+
+        env.events().publish((symbol_short!("redeemed"), code), (user, discount));
+        discount
+    }
+
+    /// Get coupon details
+    pub fn get_coupon(env: Env, code: String) -> Coupon {
+        Self::load(&env, &code)
+    }
+
+    /// Get how many times a user has redeemed a coupon
+    pub fn get_user_uses(env: Env, code: String, user: Address) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::UserUses(code, user))
+            .unwrap_or(0)
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn load(env: &Env, code: &String) -> Coupon {
+        env.storage().persistent()
+            .get(&DataKey::Coupon(code.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, Error::CouponNotFound))
+    }
+
+    fn validate(env: &Env, coupon: &Coupon, user: &Address, depin_id: &BytesN<32>, service_type: &String) {
+        if !coupon.active {
+            panic_with_error!(env, Error::CouponInactive);
+        }
+        if coupon.expires_at != 0 && env.ledger().timestamp() > coupon.expires_at {
+            panic_with_error!(env, Error::CouponExpired);
+        }
+        if coupon.max_uses != 0 && coupon.uses >= coupon.max_uses {
+            panic_with_error!(env, Error::UsageCapReached);
+        }
+        if coupon.per_user_limit != 0 {
+            let user_uses: u32 = env.storage().persistent()
+                .get(&DataKey::UserUses(coupon.code.clone(), user.clone()))
+                .unwrap_or(0);
+            if user_uses >= coupon.per_user_limit {
+                panic_with_error!(env, Error::UserLimitReached);
+            }
+        }
+        if coupon.depin_id.as_ref().is_some_and(|id| id != depin_id)
+            || coupon.service_type.as_ref().is_some_and(|s| s != service_type)
+        {
+            panic_with_error!(env, Error::NotApplicable);
+        }
+    }
+
+    fn discount_for(coupon: &Coupon, amount: i128) -> i128 {
+        let discount = match coupon.discount {
+            Discount::Percent(bps) => amount * bps as i128 / BPS_DENOMINATOR as i128,
+            Discount::Fixed(value) => value,
+        };
+        discount.min(amount)
+    }
+}
+
+mod test;
@@ -0,0 +1,168 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    Address, Env, String
+};
+
+fn init_coupon<'a>(env: &'a Env, admin: &Address) -> CouponContractClient<'a> {
+    let contract_id = env.register(CouponContract, ());
+    let client = CouponContractClient::new(env, &contract_id);
+    client.initialize(admin);
+    client
+}
+
+fn depin(env: &Env, n: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[n; 32])
+}
+
+fn compute(env: &Env) -> String {
+    String::from_str(env, "compute")
+}
+
+#[test]
+fn test_percent_and_fixed_quotes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let coupons = init_coupon(&env, &admin);
+
+    let pct = String::from_str(&env, "PCT25");
+    let flat = String::from_str(&env, "FLAT50");
+    coupons.create_coupon(&admin, &pct, &Discount::Percent(2_500), &0, &0, &0, &None, &None);
+    coupons.create_coupon(&admin, &flat, &Discount::Fixed(50), &0, &0, &0, &None, &None);
+
+    assert_eq!(coupons.quote_discount(&pct, &user, &depin(&env, 1), &compute(&env), &400), 100);
+    assert_eq!(coupons.quote_discount(&flat, &user, &depin(&env, 1), &compute(&env), &400), 50);
+    // A fixed discount never exceeds the order total
+    assert_eq!(coupons.quote_discount(&flat, &user, &depin(&env, 1), &compute(&env), &30), 30);
+}
+
+#[test]
+fn test_redeem_counts_uses() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let order_contract = Address::generate(&env);
+    let user = Address::generate(&env);
+    let coupons = init_coupon(&env, &admin);
+    coupons.set_redeemer(&admin, &order_contract, &true);
+
+    let code = String::from_str(&env, "LAUNCH");
+    coupons.create_coupon(&admin, &code, &Discount::Fixed(10), &0, &5, &2, &None, &None);
+
+    let discount = coupons.redeem(&order_contract, &code, &user, &depin(&env, 1), &compute(&env), &100);
+    assert_eq!(discount, 10);
+    assert_eq!(coupons.get_coupon(&code).uses, 1);
+    assert_eq!(coupons.get_user_uses(&code, &user), 1);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #12)")]
+fn test_only_redeemers_can_redeem() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let coupons = init_coupon(&env, &admin);
+    let code = String::from_str(&env, "LAUNCH");
+    coupons.create_coupon(&admin, &code, &Discount::Fixed(10), &0, &0, &0, &None, &None);
+
+    coupons.redeem(&user, &code, &user, &depin(&env, 1), &compute(&env), &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #9)")]
+fn test_per_user_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let order_contract = Address::generate(&env);
+    let user = Address::generate(&env);
+    let coupons = init_coupon(&env, &admin);
+    coupons.set_redeemer(&admin, &order_contract, &true);
+    let code = String::from_str(&env, "ONCE");
+    coupons.create_coupon(&admin, &code, &Discount::Fixed(10), &0, &0, &1, &None, &None);
+
+    coupons.redeem(&order_contract, &code, &user, &depin(&env, 1), &compute(&env), &100);
+    coupons.redeem(&order_contract, &code, &user, &depin(&env, 1), &compute(&env), &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #8)")]
+fn test_usage_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let order_contract = Address::generate(&env);
+    let coupons = init_coupon(&env, &admin);
+    coupons.set_redeemer(&admin, &order_contract, &true);
+    let code = String::from_str(&env, "FIRST1");
+    coupons.create_coupon(&admin, &code, &Discount::Fixed(10), &0, &1, &0, &None, &None);
+
+    coupons.redeem(&order_contract, &code, &Address::generate(&env), &depin(&env, 1), &compute(&env), &100);
+    coupons.redeem(&order_contract, &code, &Address::generate(&env), &depin(&env, 1), &compute(&env), &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_expired_coupon() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let coupons = init_coupon(&env, &admin);
+    let code = String::from_str(&env, "SUMMER");
+    coupons.create_coupon(&admin, &code, &Discount::Fixed(10), &1_000, &0, &0, &None, &None);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_001);
+    coupons.quote_discount(&code, &admin, &depin(&env, 1), &compute(&env), &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #10)")]
+fn test_depin_restriction() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let coupons = init_coupon(&env, &admin);
+    let code = String::from_str(&env, "NODEX");
+    coupons.create_coupon(&admin, &code, &Discount::Fixed(10), &0, &0, &0, &Some(depin(&env, 1)), &None);
+
+    coupons.quote_discount(&code, &admin, &depin(&env, 2), &compute(&env), &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #6)")]
+fn test_inactive_coupon() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let coupons = init_coupon(&env, &admin);
+    let code = String::from_str(&env, "PAUSED");
+    coupons.create_coupon(&admin, &code, &Discount::Fixed(10), &0, &0, &0, &None, &Some(compute(&env)));
+    coupons.set_coupon_active(&admin, &code, &false);
+
+    coupons.quote_discount(&code, &admin, &depin(&env, 1), &compute(&env), &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #11)")]
+fn test_invalid_percent_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let coupons = init_coupon(&env, &admin);
+
+    coupons.create_coupon(&admin, &String::from_str(&env, "TOOMUCH"), &Discount::Percent(10_001), &0, &0, &0, &None, &None);
+}
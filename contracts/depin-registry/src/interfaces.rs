@@ -0,0 +1,24 @@
+//! Typed interfaces of the contracts the registry queries.
+
+use soroban_sdk::{contractclient, Address, BytesN, Env, Symbol};
+
+/// Subset of the fee-manager contract used for listing fees.
+#[allow(dead_code)]
+#[contractclient(name = "FeeManagerClient")]
+pub trait FeeManagerInterface {
+    fn get_listing_fee(env: Env) -> i128;
+}
+
+/// Subset of the order contract consulted before retiring a DePIN.
+#[allow(dead_code)]
+#[contractclient(name = "OrderClient")]
+pub trait OrderInterface {
+    fn get_unsettled_depin_order_count(env: Env, depin_id: BytesN<32>) -> u32;
+}
+
+/// Subset of the pause guardian consulted before listing changes.
+#[allow(dead_code)]
+#[contractclient(name = "GuardianClient")]
+pub trait GuardianInterface {
+    fn is_paused(env: Env, target: Address, function: Symbol) -> bool;
+}
@@ -1,18 +1,159 @@
 #![no_std]
-use soroban_sdk::{contracttype, contract, contractimpl, Env, String, Vec, Address, Map};
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{contracttype, contract, contractimpl, token, Env, String, Symbol, Vec, Address, Map};
+
+mod interfaces;
+use interfaces::{FeeManagerClient, GuardianClient, OrderClient};
+
+// Most reports kept per DePIN; rolling averages cover this window
+pub const MAX_METRICS_HISTORY: u32 = 30;
+
+// Most DePINs returned by one page
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+// Most DePIN IDs one filtered page scans
+pub const MAX_PAGE_SCAN: u32 = 500;
 
 #[contracttype]
 pub enum DataKey {
     Admin,
-    DepinMap,
+    DepinMap, // Map of LegacyDePIN tuples written before DePIN became a struct
+    DepinIdAt(u32), // listing position -> BytesN<32> of the DePIN listed there
+    DepinCount, // u32 number of DePINs listed, so DepinIdAt(0..DepinCount) is the listing order
     Counter,  // Add counter for DePIN IDs
+    FeeManager, // Address of the fee-manager contract
+    Timelock, // Timelock that authorizes admin rotations
+    PauseGuardian, // Guardian that can pause listing changes during incidents
+    PayoutAddress(soroban_sdk::BytesN<32>), // depin_id -> Address receiving the provider share of orders
+    ServicePrice(soroban_sdk::BytesN<32>, String), // (depin_id, service_type) -> i128 price per hour, before PriceTable
+    OrderContract, // Order contract allowed to reserve and release capacity
+    Capacity(soroban_sdk::BytesN<32>), // depin_id -> u32 order slots; unlimited when unset
+    Reserved(soroban_sdk::BytesN<32>), // depin_id -> u32 order slots held by open orders
+    RegistrationStake, // (token, amount) providers deposit to self-register a DePIN
+    Stake(soroban_sdk::BytesN<32>), // depin_id -> (token, amount) its owner deposited
+    CategoryDepins(String), // category -> Vec<BytesN<32>> of DePINs listed under it
+    TagDepins(String), // tag -> Vec<BytesN<32>> of DePINs carrying it
+    PriceTable(soroban_sdk::BytesN<32>), // depin_id -> Map<String, i128> price per hour by service type
+    RegionDepins(String), // region -> Vec<BytesN<32>> of DePINs deployed there
+    Reporter(Address), // Oracles allowed to report measured uptime and latency
+    MetricsHistory(soroban_sdk::BytesN<32>), // depin_id -> Vec<MetricsReport>, oldest first
+    Treasury, // Address slashed stake is paid to
+    Slasher, // SLA module allowed to slash stakes alongside the admin
+    SupportedChains(soroban_sdk::BytesN<32>), // depin_id -> Vec<String> chains it deploys to; any when unset
+    Specs(soroban_sdk::BytesN<32>), // depin_id -> Specs of the hardware it runs on
+    SlashHistory(soroban_sdk::BytesN<32>), // depin_id -> Vec<SlashRecord>, oldest first
+    Depin(soroban_sdk::BytesN<32>), // depin_id -> DePIN
 }
 
 #[contract]
 pub struct Contract;
 
-// DePIN as tuple for storage compatibility
-type DePIN = (soroban_sdk::BytesN<32>, String, String, bool, i32, i32, i32);
+// Where a DePIN is in its listing lifecycle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DepinLifecycle {
+    Active,     // Takes new orders
+    Deprecated, // Serves its open orders but takes no new ones
+    Retired,    // No open orders left; kept for reads only
+}
+
+// Badge showing how far the admin has vetted a DePIN's provider
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrustLevel {
+    Unverified,
+    Verified, // Provider identity reviewed by the admin
+    Audited,  // Provider passed an external audit
+}
+
+// A listed DePIN and the service levels it commits to
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DePIN {
+    pub id: soroban_sdk::BytesN<32>,
+    pub name: String,
+    pub description: String,
+    pub active: bool,
+    pub uptime: i32,      // Committed uptime, percent
+    pub reliability: i32, // Reliability score, percent
+    pub cost: i32,
+    pub owner: Option<Address>, // Provider that registered it; None for admin listings
+    pub approved: bool,          // Self-registrations take no orders until the admin approves them
+    pub trust_level: TrustLevel,
+    pub category: Option<String>, // Kind of service, e.g. "compute", "storage" or "bandwidth"
+    pub tags: Vec<String>,
+    pub region: Option<String>,  // Where it deploys, e.g. "EU" or "US"
+    pub country: Option<String>, // Jurisdiction it operates under, e.g. "DE"
+    pub lifecycle: DepinLifecycle,
+    pub metadata_uri: Option<String>,                    // IPFS or URL of the long-form listing
+    pub metadata_hash: Option<soroban_sdk::BytesN<32>>, // sha256 of the content at metadata_uri
+}
+
+// Uptime and latency a reporter measured for a DePIN
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetricsReport {
+    pub reporter: Address,
+    pub uptime: i32,  // Percent
+    pub latency: u32, // Milliseconds
+    pub timestamp: u64,
+}
+
+// Stake taken from a DePIN's owner for breaking its service levels
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SlashRecord {
+    pub amount: i128,
+    pub reason: String,
+    pub slasher: Address,
+    pub timestamp: u64,
+}
+
+// Hardware a DePIN serves orders from
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Specs {
+    pub cpu_cores: u32,
+    pub gpu_model: String, // Empty without GPUs
+    pub gpu_count: u32,
+    pub gpu_memory_gb: u32, // Per GPU
+    pub ram_gb: u32,
+    pub storage_gb: u32,
+    pub bandwidth_mbps: u32,
+}
+
+impl Specs {
+    // Whether every figure is at least the one in `min`; the GPU model must match unless `min` leaves it empty
+    fn meets(&self, min: &Specs) -> bool {
+        self.cpu_cores >= min.cpu_cores
+            && (min.gpu_model.is_empty() || self.gpu_model == min.gpu_model)
+            && self.gpu_count >= min.gpu_count
+            && self.gpu_memory_gb >= min.gpu_memory_gb
+            && self.ram_gb >= min.ram_gb
+            && self.storage_gb >= min.storage_gb
+            && self.bandwidth_mbps >= min.bandwidth_mbps
+    }
+}
+
+// One page of DePINs; `next_offset` resumes the scan, None once the list is exhausted
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepinPage {
+    pub depins: Vec<DePIN>,
+    pub next_offset: Option<u32>,
+}
+
+// Averages over a DePIN's recent reports
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RollingMetrics {
+    pub uptime: i32,
+    pub latency: u32,
+    pub samples: u32,
+}
+
+// (id, name, description, active, uptime, reliability, cost) as DePINs were stored before
+type LegacyDePIN = (soroban_sdk::BytesN<32>, String, String, bool, i32, i32, i32);
 
 impl Contract {
     fn assert_admin(env: &Env, invoker: &Address) {
@@ -20,22 +161,106 @@ impl Contract {
         if invoker != &admin {
             panic!("Only admin can perform this action");
         }
+        invoker.require_auth();
     }
-}
 
-#[contractimpl]
-impl Contract {
-    // Initialize contract and set admin
-    pub fn initialize(env: Env, admin: Address) {
-        env.storage().persistent().set(&DataKey::Admin, &admin);
-        env.storage().persistent().set(&DataKey::DepinMap, &Map::<soroban_sdk::BytesN<32>, DePIN>::new(&env));
-        env.storage().persistent().set(&DataKey::Counter, &0u32); // Initialize counter
+    fn assert_timelock(env: &Env) {
+        let timelock: Address = env.storage().persistent().get(&DataKey::Timelock).expect("Timelock not set");
+        timelock.require_auth();
     }
 
-    // Add a new DePIN (admin only)
-    pub fn add_depin(env: Env, invoker: Address, name: String, description: String, uptime: i32, reliability: i32, cost: i32) -> soroban_sdk::BytesN<32> {
-        Self::assert_admin(&env, &invoker);
-        
+    fn assert_order_contract(env: &Env, caller: &Address) {
+        let order_contract: Address = env.storage().persistent().get(&DataKey::OrderContract).expect("Order contract not set");
+        if caller != &order_contract {
+            panic!("Only the order contract can change reservations");
+        }
+        caller.require_auth();
+    }
+
+    // DePINs still held in the tuple map older versions wrote, as structs
+    fn legacy_depins(env: &Env) -> Map<soroban_sdk::BytesN<32>, DePIN> {
+        let legacy: Map<soroban_sdk::BytesN<32>, LegacyDePIN> = env.storage().persistent()
+            .get(&DataKey::DepinMap)
+            .unwrap_or_else(|| Map::new(env));
+        let mut depins = Map::new(env);
+        for (depin_id, (id, name, description, active, uptime, reliability, cost)) in legacy.iter() {
+            depins.set(depin_id, DePIN { id, name, description, active, uptime, reliability, cost, owner: None, approved: true, trust_level: TrustLevel::Unverified, category: None, tags: Vec::new(env), region: None, country: None, lifecycle: DepinLifecycle::Active, metadata_uri: None, metadata_hash: None });
+        }
+        depins
+    }
+
+    // A DePIN's own entry, falling back to the legacy map until migrate_depins moves it
+    fn load_depin(env: &Env, depin_id: &soroban_sdk::BytesN<32>) -> Option<DePIN> {
+        if let Some(depin) = env.storage().persistent().get(&DataKey::Depin(depin_id.clone())) {
+            return Some(depin);
+        }
+        if !env.storage().persistent().has(&DataKey::DepinMap) {
+            return None;
+        }
+        Self::legacy_depins(env).get(depin_id.clone())
+    }
+
+    fn save_depin(env: &Env, depin: &DePIN) {
+        env.storage().persistent().set(&DataKey::Depin(depin.id.clone()), depin);
+    }
+
+    // Admin listings are managed by the admin, self-registered ones by their owner only
+    fn assert_can_manage(env: &Env, invoker: &Address, depin: &DePIN) {
+        assert!(depin.lifecycle != DepinLifecycle::Retired, "DePIN is retired");
+        match &depin.owner {
+            Some(owner) => {
+                assert!(invoker == owner, "Only the owning provider can manage this DePIN");
+                invoker.require_auth();
+            }
+            None => Self::assert_admin(env, invoker),
+        }
+    }
+
+    // Number of DePINs in the listing index; migrate_depins adds those listed before it
+    fn depin_count(env: &Env) -> u32 {
+        env.storage().persistent().get(&DataKey::DepinCount).unwrap_or(0)
+    }
+
+    // The ID at a listing position below depin_count
+    fn depin_id_at(env: &Env, index: u32) -> soroban_sdk::BytesN<32> {
+        env.storage().persistent().get(&DataKey::DepinIdAt(index)).unwrap()
+    }
+
+    // Append a DePIN to the listing index
+    fn push_depin_id(env: &Env, depin_id: &soroban_sdk::BytesN<32>) {
+        let count = Self::depin_count(env);
+        env.storage().persistent().set(&DataKey::DepinIdAt(count), depin_id);
+        env.storage().persistent().set(&DataKey::DepinCount, &(count + 1));
+    }
+
+    fn depins_page(env: &Env, offset: u32, limit: u32, filter: impl Fn(&DePIN) -> bool) -> DepinPage {
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let count = Self::depin_count(env);
+        let scan_end = count.min(offset.saturating_add(MAX_PAGE_SCAN));
+        let mut depins = Vec::new(env);
+        let mut index = offset;
+        while index < scan_end && depins.len() < limit {
+            let depin = Self::load_depin(env, &Self::depin_id_at(env, index)).unwrap();
+            index += 1;
+
+            if !filter(&depin) {
+                continue;
+            }
+            depins.push_back(depin);
+        }
+
+        DepinPage {
+            depins,
+            next_offset: if index < count { Some(index) } else { None },
+        }
+    }
+
+    fn assert_listed(env: &Env, depin_id: &soroban_sdk::BytesN<32>) {
+        assert!(Self::load_depin(env, depin_id).is_some(), "DePIN not found");
+    }
+
+    // Validate and store a new DePIN under the next ID
+    fn insert_depin(env: &Env, name: String, description: String, uptime: i32, reliability: i32, cost: i32, owner: Option<Address>) -> soroban_sdk::BytesN<32> {
         // Get and increment counter
         let mut counter: u32 = env.storage().persistent().get(&DataKey::Counter).unwrap();
         counter += 1;
@@ -47,91 +272,632 @@ impl Contract {
         // Validate input parameters
         assert!(!name.is_empty(), "Name cannot be empty");
         assert!(!description.is_empty(), "Description cannot be empty");
-        assert!(uptime >= 0 && uptime <= 100, "Uptime must be between 0 and 100");
-        assert!(reliability >= 0 && reliability <= 100, "Reliability must be between 0 and 100");
+        assert!((0..=100).contains(&uptime), "Uptime must be between 0 and 100");
+        assert!((0..=100).contains(&reliability), "Reliability must be between 0 and 100");
         assert!(cost >= 0, "Cost must be non-negative");
 
-        let depin_id = soroban_sdk::BytesN::from_array(&env, &bytes);
-        let depin: DePIN = (depin_id.clone(), name, description, true, uptime, reliability, cost);
-        let mut depin_map: Map<soroban_sdk::BytesN<32>, DePIN> = env.storage().persistent().get(&DataKey::DepinMap).unwrap();
-        depin_map.set(depin_id.clone(), depin.clone());
-        env.storage().persistent().set(&DataKey::DepinMap, &depin_map);
+        let depin_id = soroban_sdk::BytesN::from_array(env, &bytes);
+        let approved = owner.is_none();
+        let depin = DePIN { id: depin_id.clone(), name, description, active: true, uptime, reliability, cost, owner, approved, trust_level: TrustLevel::Unverified, category: None, tags: Vec::new(env), region: None, country: None, lifecycle: DepinLifecycle::Active, metadata_uri: None, metadata_hash: None };
+        Self::push_depin_id(env, &depin_id);
+        Self::save_depin(env, &depin);
         depin_id
     }
 
-    // Update DePIN details (admin only)
-    pub fn update_depin(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, name: String, description: String, uptime: i32, reliability: i32, cost: i32) {
+    fn index_add(env: &Env, key: &DataKey, depin_id: &soroban_sdk::BytesN<32>) {
+        let mut ids: Vec<soroban_sdk::BytesN<32>> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+        if !ids.contains(depin_id) {
+            ids.push_back(depin_id.clone());
+            env.storage().persistent().set(key, &ids);
+        }
+    }
+
+    fn index_remove(env: &Env, key: &DataKey, depin_id: &soroban_sdk::BytesN<32>) {
+        let mut ids: Vec<soroban_sdk::BytesN<32>> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+        if let Some(i) = ids.first_index_of(depin_id) {
+            ids.remove(i);
+            if ids.is_empty() {
+                env.storage().persistent().remove(key);
+            } else {
+                env.storage().persistent().set(key, &ids);
+            }
+        }
+    }
+
+    // Up to `limit` IDs of an index from `offset`, at most MAX_PAGE_SIZE
+    fn index_page(env: &Env, key: &DataKey, offset: u32, limit: u32) -> Vec<soroban_sdk::BytesN<32>> {
+        let ids: Vec<soroban_sdk::BytesN<32>> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(ids.len());
+        if offset >= end {
+            return Vec::new(env);
+        }
+        ids.slice(offset..end)
+    }
+
+    // Drop a DePIN from the indexes of its category and tags
+    fn unindex_category(env: &Env, depin: &DePIN) {
+        if let Some(category) = &depin.category {
+            Self::index_remove(env, &DataKey::CategoryDepins(category.clone()), &depin.id);
+        }
+        for tag in depin.tags.iter() {
+            Self::index_remove(env, &DataKey::TagDepins(tag), &depin.id);
+        }
+    }
+
+    // Drop a DePIN from the index of its region
+    fn unindex_region(env: &Env, depin: &DePIN) {
+        if let Some(region) = &depin.region {
+            Self::index_remove(env, &DataKey::RegionDepins(region.clone()), &depin.id);
+        }
+    }
+
+    fn rolling_metrics(history: &Vec<MetricsReport>) -> RollingMetrics {
+        let samples = history.len();
+        let mut uptime: i64 = 0;
+        let mut latency: u64 = 0;
+        for report in history.iter() {
+            uptime += report.uptime as i64;
+            latency += report.latency as u64;
+        }
+        RollingMetrics {
+            uptime: (uptime / samples as i64) as i32,
+            latency: (latency / samples as u64) as u32,
+            samples,
+        }
+    }
+
+    fn assert_not_paused(env: &Env, function: &str) {
+        if let Some(guardian) = env.storage().persistent().get::<_, Address>(&DataKey::PauseGuardian) {
+            let paused = GuardianClient::new(env, &guardian)
+                .is_paused(&env.current_contract_address(), &Symbol::new(env, function));
+            assert!(!paused, "Contract is paused");
+        }
+    }
+}
+
+#[contractimpl]
+impl Contract {
+    // Initialize contract and set admin
+    pub fn initialize(env: Env, admin: Address) {
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::Counter, &0u32); // Initialize counter
+    }
+
+    // Set the timelock that authorizes admin rotations (admin once, then timelock only)
+    pub fn set_timelock(env: Env, invoker: Address, timelock: Address) {
+        if env.storage().persistent().has(&DataKey::Timelock) {
+            Self::assert_timelock(&env);
+        } else {
+            Self::assert_admin(&env, &invoker);
+        }
+        env.storage().persistent().set(&DataKey::Timelock, &timelock);
+    }
+
+    // Rotate the admin key (timelock only)
+    pub fn set_admin(env: Env, new_admin: Address) {
+        Self::assert_timelock(&env);
+        env.storage().persistent().set(&DataKey::Admin, &new_admin);
+    }
+
+    // Get the admin address
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().persistent().get(&DataKey::Admin).unwrap()
+    }
+
+    // Get the timelock address
+    pub fn get_timelock(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Timelock)
+    }
+
+    // Set the fee-manager contract used to price listings (admin only)
+    pub fn set_fee_manager(env: Env, invoker: Address, fee_manager: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::FeeManager, &fee_manager);
+    }
+
+    // Get the current listing fee (0 when no fee manager is configured)
+    pub fn get_listing_fee(env: Env) -> i128 {
+        match env.storage().persistent().get::<_, Address>(&DataKey::FeeManager) {
+            Some(fee_manager) => FeeManagerClient::new(&env, &fee_manager).get_listing_fee(),
+            None => 0,
+        }
+    }
+
+    // Set the pause guardian; deprecating, retiring and deactivating DePINs stays available while paused (admin only)
+    pub fn set_pause_guardian(env: Env, invoker: Address, guardian: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::PauseGuardian, &guardian);
+    }
+
+    // Add a new DePIN (admin only)
+    pub fn add_depin(env: Env, invoker: Address, name: String, description: String, uptime: i32, reliability: i32, cost: i32) -> soroban_sdk::BytesN<32> {
+        Self::assert_not_paused(&env, "add_depin");
+        Self::assert_admin(&env, &invoker);
+        Self::insert_depin(&env, name, description, uptime, reliability, cost, None)
+    }
+
+    // Register a DePIN owned by the calling provider, depositing the registration
+    // stake if one is set; it takes no orders until the admin verifies it
+    pub fn register_depin(env: Env, provider: Address, name: String, description: String, uptime: i32, reliability: i32, cost: i32) -> soroban_sdk::BytesN<32> {
+        Self::assert_not_paused(&env, "register_depin");
+        provider.require_auth();
+
+        let depin_id = Self::insert_depin(&env, name, description, uptime, reliability, cost, Some(provider.clone()));
+        if let Some((stake_token, amount)) = Self::get_registration_stake(env.clone()) {
+            token::Client::new(&env, &stake_token).transfer(&provider, &env.current_contract_address(), &amount);
+            env.storage().persistent().set(&DataKey::Stake(depin_id.clone()), &(stake_token, amount));
+        }
+        depin_id
+    }
+
+    // Approve a self-registered DePIN to take orders (admin only)
+    pub fn approve_depin(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>) {
         Self::assert_admin(&env, &invoker);
+        let mut depin = Self::load_depin(&env, &depin_id).expect("DePIN not found");
+        depin.approved = true;
+        Self::save_depin(&env, &depin);
+    }
+
+    // Set the badge showing how far a DePIN's provider is vetted (admin only)
+    pub fn set_trust_level(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, trust_level: TrustLevel) {
+        Self::assert_admin(&env, &invoker);
+        let mut depin = Self::load_depin(&env, &depin_id).expect("DePIN not found");
+        depin.trust_level = trust_level;
+        Self::save_depin(&env, &depin);
+        env.events().publish((Symbol::new(&env, "trust_level"), depin_id), trust_level);
+    }
+
+    // Get how far a DePIN's provider is vetted, if it is listed
+    pub fn get_trust_level(env: Env, depin_id: soroban_sdk::BytesN<32>) -> Option<TrustLevel> {
+        Self::get_depin(env, depin_id).map(|depin| depin.trust_level)
+    }
+
+    // Require providers to deposit `amount` of `stake_token` to self-register; zero removes the requirement (admin only)
+    pub fn set_registration_stake(env: Env, invoker: Address, stake_token: Address, amount: i128) {
+        Self::assert_admin(&env, &invoker);
+        assert!(amount >= 0, "Stake must be non-negative");
+        if amount == 0 {
+            env.storage().persistent().remove(&DataKey::RegistrationStake);
+        } else {
+            env.storage().persistent().set(&DataKey::RegistrationStake, &(stake_token, amount));
+        }
+    }
+
+    // Get the token and amount providers stake to self-register, if any
+    pub fn get_registration_stake(env: Env) -> Option<(Address, i128)> {
+        env.storage().persistent().get(&DataKey::RegistrationStake)
+    }
+
+    // Get the token and amount staked for a DePIN, if any
+    pub fn get_stake(env: Env, depin_id: soroban_sdk::BytesN<32>) -> Option<(Address, i128)> {
+        env.storage().persistent().get(&DataKey::Stake(depin_id))
+    }
+
+    // Update DePIN details (admin for admin listings, the owner for self-registered ones)
+    pub fn update_depin(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, name: String, description: String, uptime: i32, reliability: i32, cost: i32) {
+        Self::assert_not_paused(&env, "update_depin");
 
         // Validate input parameters
         assert!(!name.is_empty(), "Name cannot be empty");
         assert!(!description.is_empty(), "Description cannot be empty");
-        assert!(uptime >= 0 && uptime <= 100, "Uptime must be between 0 and 100");
-        assert!(reliability >= 0 && reliability <= 100, "Reliability must be between 0 and 100");
+        assert!((0..=100).contains(&uptime), "Uptime must be between 0 and 100");
+        assert!((0..=100).contains(&reliability), "Reliability must be between 0 and 100");
         assert!(cost >= 0, "Cost must be non-negative");
 
-        let mut depin_map: Map<soroban_sdk::BytesN<32>, DePIN> = env.storage().persistent().get(&DataKey::DepinMap).unwrap();
-        if let Some(mut depin) = depin_map.get(depin_id.clone()) {
-            depin.1 = name;
-            depin.2 = description;
-            depin.4 = uptime;
-            depin.5 = reliability;
-            depin.6 = cost;
-            depin_map.set(depin_id, depin);
-            env.storage().persistent().set(&DataKey::DepinMap, &depin_map);
+        let existing = Self::load_depin(&env, &depin_id);
+        match &existing {
+            Some(depin) => Self::assert_can_manage(&env, &invoker, depin),
+            None => Self::assert_admin(&env, &invoker),
+        }
+        if let Some(mut depin) = existing {
+            depin.name = name;
+            depin.description = description;
+            depin.uptime = uptime;
+            depin.reliability = reliability;
+            depin.cost = cost;
+            Self::save_depin(&env, &depin);
+        }
+    }
+
+    // Stop a DePIN taking new orders while its open orders run out (admin, or the owner of a self-registered DePIN)
+    pub fn deprecate_depin(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>) {
+        let mut depin = Self::load_depin(&env, &depin_id).expect("DePIN not found");
+        Self::assert_can_manage(&env, &invoker, &depin);
+        depin.lifecycle = DepinLifecycle::Deprecated;
+        Self::save_depin(&env, &depin);
+        env.events().publish((Symbol::new(&env, "deprecated"), depin_id), ());
+    }
+
+    // Retire a deprecated DePIN once the order contract reports none of its orders are
+    // still unsettled, returning its owner's stake; the record stays readable (admin only)
+    pub fn retire_depin(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>) {
+        Self::assert_admin(&env, &invoker);
+        let mut depin = Self::load_depin(&env, &depin_id).expect("DePIN not found");
+        assert!(depin.lifecycle == DepinLifecycle::Deprecated, "Only deprecated DePINs can be retired");
+        let order_contract: Address = env.storage().persistent().get(&DataKey::OrderContract).expect("Order contract not set");
+        let unsettled = OrderClient::new(&env, &order_contract).get_unsettled_depin_order_count(&depin_id);
+        assert!(unsettled == 0, "DePIN still has unsettled orders");
+
+        Self::unindex_category(&env, &depin);
+        Self::unindex_region(&env, &depin);
+        depin.lifecycle = DepinLifecycle::Retired;
+        depin.active = false;
+        Self::save_depin(&env, &depin);
+
+        // Return the owner's registration stake
+        let stake: Option<(Address, i128)> = env.storage().persistent().get(&DataKey::Stake(depin_id.clone()));
+        if let (Some((stake_token, amount)), Some(owner)) = (stake, depin.owner) {
+            token::Client::new(&env, &stake_token).transfer(&env.current_contract_address(), &owner, &amount);
+            env.storage().persistent().remove(&DataKey::Stake(depin_id.clone()));
+        }
+        env.storage().persistent().remove(&DataKey::Capacity(depin_id.clone()));
+        env.storage().persistent().remove(&DataKey::Reserved(depin_id.clone()));
+        env.events().publish((Symbol::new(&env, "retired"), depin_id), ());
+    }
+
+    // Set the category and tags a DePIN is listed under (admin, or the owner of a self-registered DePIN)
+    pub fn set_depin_category(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, category: String, tags: Vec<String>) {
+        Self::assert_not_paused(&env, "set_depin_category");
+        let mut depin = Self::load_depin(&env, &depin_id).expect("DePIN not found");
+        Self::assert_can_manage(&env, &invoker, &depin);
+        assert!(!category.is_empty(), "Category cannot be empty");
+        for tag in tags.iter() {
+            assert!(!tag.is_empty(), "Tags cannot be empty");
+        }
+
+        Self::unindex_category(&env, &depin);
+        Self::index_add(&env, &DataKey::CategoryDepins(category.clone()), &depin_id);
+        for tag in tags.iter() {
+            Self::index_add(&env, &DataKey::TagDepins(tag), &depin_id);
         }
+        depin.category = Some(category);
+        depin.tags = tags;
+        Self::save_depin(&env, &depin);
+    }
+
+    // Set where a DePIN deploys and the jurisdiction it operates under (admin, or the owner of a self-registered DePIN)
+    pub fn set_depin_location(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, region: String, country: String) {
+        Self::assert_not_paused(&env, "set_depin_location");
+        let mut depin = Self::load_depin(&env, &depin_id).expect("DePIN not found");
+        Self::assert_can_manage(&env, &invoker, &depin);
+        assert!(!region.is_empty(), "Region cannot be empty");
+        assert!(!country.is_empty(), "Country cannot be empty");
+
+        Self::unindex_region(&env, &depin);
+        Self::index_add(&env, &DataKey::RegionDepins(region.clone()), &depin_id);
+        depin.region = Some(region);
+        depin.country = Some(country);
+        Self::save_depin(&env, &depin);
+    }
+
+    // List up to `limit` DePINs deployed in a region, starting at `offset`
+    pub fn list_depins_by_region(env: Env, region: String, offset: u32, limit: u32) -> Vec<soroban_sdk::BytesN<32>> {
+        Self::index_page(&env, &DataKey::RegionDepins(region), offset, limit)
+    }
+
+    // Set the chains a DePIN deploys to; orders for other chains are rejected (admin, or the owner of a self-registered DePIN)
+    pub fn set_supported_chains(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, chains: Vec<String>) {
+        Self::assert_not_paused(&env, "set_supported_chains");
+        let depin = Self::get_depin(env.clone(), depin_id.clone()).expect("DePIN not found");
+        Self::assert_can_manage(&env, &invoker, &depin);
+        assert!(!chains.is_empty(), "At least one chain must be supported");
+        env.storage().persistent().set(&DataKey::SupportedChains(depin_id), &chains);
+    }
+
+    // Get the chains a DePIN deploys to; empty when it has not restricted them
+    pub fn get_supported_chains(env: Env, depin_id: soroban_sdk::BytesN<32>) -> Vec<String> {
+        env.storage().persistent().get(&DataKey::SupportedChains(depin_id)).unwrap_or(Vec::new(&env))
+    }
+
+    // Check whether a DePIN deploys to `chain`; DePINs that list no chains accept any
+    pub fn supports_chain(env: Env, depin_id: soroban_sdk::BytesN<32>, chain: String) -> bool {
+        let chains = Self::get_supported_chains(env, depin_id);
+        chains.is_empty() || chains.contains(&chain)
+    }
+
+    // Point a DePIN at its off-chain listing and the sha256 of that content, so catalogs
+    // can verify what they fetch (admin, or the owner of a self-registered DePIN)
+    pub fn set_metadata(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, metadata_uri: String, metadata_hash: soroban_sdk::BytesN<32>) {
+        Self::assert_not_paused(&env, "set_metadata");
+        let mut depin = Self::load_depin(&env, &depin_id).expect("DePIN not found");
+        Self::assert_can_manage(&env, &invoker, &depin);
+        assert!(!metadata_uri.is_empty(), "Metadata URI cannot be empty");
+
+        depin.metadata_uri = Some(metadata_uri.clone());
+        depin.metadata_hash = Some(metadata_hash.clone());
+        Self::save_depin(&env, &depin);
+        env.events().publish((Symbol::new(&env, "metadata"), depin_id), (metadata_uri, metadata_hash));
+    }
+
+    // Describe the hardware a DePIN runs on (admin, or the owner of a self-registered DePIN)
+    pub fn set_specs(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, specs: Specs) {
+        Self::assert_not_paused(&env, "set_specs");
+        let depin = Self::get_depin(env.clone(), depin_id.clone()).expect("DePIN not found");
+        Self::assert_can_manage(&env, &invoker, &depin);
+        assert!(specs.gpu_count == 0 || !specs.gpu_model.is_empty(), "GPU model cannot be empty");
+        env.storage().persistent().set(&DataKey::Specs(depin_id), &specs);
     }
 
-    // Remove DePIN (admin only)
-    pub fn remove_depin(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>) {
+    // Get the hardware a DePIN runs on, if described
+    pub fn get_specs(env: Env, depin_id: soroban_sdk::BytesN<32>) -> Option<Specs> {
+        env.storage().persistent().get(&DataKey::Specs(depin_id))
+    }
+
+    // Check whether a DePIN's hardware meets `min`; DePINs without specs never do
+    pub fn meets_specs(env: Env, depin_id: soroban_sdk::BytesN<32>, min: Specs) -> bool {
+        Self::get_specs(env, depin_id).is_some_and(|specs| specs.meets(&min))
+    }
+
+    // Page through the DePINs whose hardware meets `min`, e.g. a minimum GPU memory.
+    // Scans at most MAX_PAGE_SCAN IDs from `offset`, collecting up to `limit` matches.
+    pub fn list_depins_by_specs(env: Env, min: Specs, offset: u32, limit: u32) -> DepinPage {
+        Self::depins_page(&env, offset, limit, |depin| Self::meets_specs(env.clone(), depin.id.clone(), min.clone()))
+    }
+
+    // List up to `limit` DePINs in a category, starting at `offset`
+    pub fn list_depins_by_category(env: Env, category: String, offset: u32, limit: u32) -> Vec<soroban_sdk::BytesN<32>> {
+        Self::index_page(&env, &DataKey::CategoryDepins(category), offset, limit)
+    }
+
+    // List up to `limit` DePINs carrying a tag, starting at `offset`
+    pub fn list_depins_by_tag(env: Env, tag: String, offset: u32, limit: u32) -> Vec<soroban_sdk::BytesN<32>> {
+        Self::index_page(&env, &DataKey::TagDepins(tag), offset, limit)
+    }
+
+    // Move DePINs older versions stored as tuples in one map to their own entries and
+    // the listing index; entries already written since are kept (admin only)
+    pub fn migrate_depins(env: Env, invoker: Address) {
         Self::assert_admin(&env, &invoker);
-        let mut depin_map: Map<soroban_sdk::BytesN<32>, DePIN> = env.storage().persistent().get(&DataKey::DepinMap).unwrap();
-        // Ensure the DePIN exists before removing
-        assert!(depin_map.contains_key(depin_id.clone()), "DePIN not found");
-        depin_map.remove(depin_id);
-        env.storage().persistent().set(&DataKey::DepinMap, &depin_map);
+        for (depin_id, depin) in Self::legacy_depins(&env).iter() {
+            if !env.storage().persistent().has(&DataKey::Depin(depin_id.clone())) {
+                env.storage().persistent().set(&DataKey::Depin(depin_id.clone()), &depin);
+                Self::push_depin_id(&env, &depin_id);
+            }
+        }
+        env.storage().persistent().remove(&DataKey::DepinMap);
     }
 
     // Change DePIN status (admin only)
     pub fn set_depin_status(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, status: bool) {
         Self::assert_admin(&env, &invoker);
-        let mut depin_map: Map<soroban_sdk::BytesN<32>, DePIN> = env.storage().persistent().get(&DataKey::DepinMap).unwrap();
-        if let Some(mut depin) = depin_map.get(depin_id.clone()) {
-            depin.3 = status;
-            depin_map.set(depin_id, depin);
-            env.storage().persistent().set(&DataKey::DepinMap, &depin_map);
+        if let Some(mut depin) = Self::load_depin(&env, &depin_id) {
+            depin.active = status;
+            Self::save_depin(&env, &depin);
         }
     }
 
+    // Set the address the provider share of a DePIN's orders is paid to (admin, or the owner of a self-registered DePIN)
+    pub fn set_payout_address(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, payout: Address) {
+        let depin = Self::get_depin(env.clone(), depin_id.clone()).expect("DePIN not found");
+        Self::assert_can_manage(&env, &invoker, &depin);
+        env.storage().persistent().set(&DataKey::PayoutAddress(depin_id.clone()), &payout);
+    }
+
+    // Get the payout address of a DePIN, if one is set
+    pub fn get_payout_address(env: Env, depin_id: soroban_sdk::BytesN<32>) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::PayoutAddress(depin_id))
+    }
+
+    // Set the hourly price orders pay for a service type of a DePIN (admin, or the owner of a self-registered DePIN)
+    pub fn set_service_price(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, service_type: String, price_per_hour: i128) {
+        Self::assert_not_paused(&env, "set_service_price");
+        let depin = Self::get_depin(env.clone(), depin_id.clone()).expect("DePIN not found");
+        Self::assert_can_manage(&env, &invoker, &depin);
+        assert!(price_per_hour > 0, "Price must be positive");
+
+        let mut table = Self::get_price_table(env.clone(), depin_id.clone());
+        table.set(service_type.clone(), price_per_hour);
+        env.storage().persistent().set(&DataKey::PriceTable(depin_id.clone()), &table);
+        env.storage().persistent().remove(&DataKey::ServicePrice(depin_id, service_type));
+    }
+
+    // Stop offering a service type of a DePIN (admin, or the owner of a self-registered DePIN)
+    pub fn remove_service_price(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, service_type: String) {
+        Self::assert_not_paused(&env, "remove_service_price");
+        let depin = Self::get_depin(env.clone(), depin_id.clone()).expect("DePIN not found");
+        Self::assert_can_manage(&env, &invoker, &depin);
+
+        let mut table = Self::get_price_table(env.clone(), depin_id.clone());
+        table.remove(service_type.clone());
+        env.storage().persistent().set(&DataKey::PriceTable(depin_id.clone()), &table);
+        env.storage().persistent().remove(&DataKey::ServicePrice(depin_id, service_type));
+    }
+
+    // Get the hourly price of a service type of a DePIN, if one is listed
+    pub fn get_service_price(env: Env, depin_id: soroban_sdk::BytesN<32>, service_type: String) -> Option<i128> {
+        let table: Option<Map<String, i128>> = env.storage().persistent().get(&DataKey::PriceTable(depin_id.clone()));
+        table
+            .and_then(|table| table.get(service_type.clone()))
+            .or_else(|| env.storage().persistent().get(&DataKey::ServicePrice(depin_id, service_type)))
+    }
+
+    // Get every service type of a DePIN set since the price table was introduced, with its hourly price
+    pub fn get_price_table(env: Env, depin_id: soroban_sdk::BytesN<32>) -> Map<String, i128> {
+        env.storage().persistent().get(&DataKey::PriceTable(depin_id)).unwrap_or(Map::new(&env))
+    }
+
+    // Set the order contract that reserves capacity for its orders (admin only)
+    pub fn set_order_contract(env: Env, invoker: Address, order_contract: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::OrderContract, &order_contract);
+    }
+
+    // Set how many orders a DePIN can serve at once (admin only)
+    pub fn set_capacity(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, slots: u32) {
+        Self::assert_admin(&env, &invoker);
+        Self::assert_listed(&env, &depin_id);
+        env.storage().persistent().set(&DataKey::Capacity(depin_id), &slots);
+    }
+
+    // Hold slots of a DePIN for a new order (order contract only)
+    pub fn reserve_capacity(env: Env, caller: Address, depin_id: soroban_sdk::BytesN<32>, slots: u32) {
+        Self::assert_order_contract(&env, &caller);
+        if let Some(available) = Self::get_available_capacity(env.clone(), depin_id.clone()) {
+            assert!(slots <= available, "Not enough capacity available");
+        }
+        let reserved = Self::get_reserved_capacity(env.clone(), depin_id.clone());
+        env.storage().persistent().set(&DataKey::Reserved(depin_id), &(reserved + slots));
+    }
+
+    // Free the slots of an order that has ended (order contract only)
+    pub fn release_capacity(env: Env, caller: Address, depin_id: soroban_sdk::BytesN<32>, slots: u32) {
+        Self::assert_order_contract(&env, &caller);
+        let reserved = Self::get_reserved_capacity(env.clone(), depin_id.clone());
+        env.storage().persistent().set(&DataKey::Reserved(depin_id), &reserved.saturating_sub(slots));
+    }
+
+    // Get how many slots of a DePIN open orders hold
+    pub fn get_reserved_capacity(env: Env, depin_id: soroban_sdk::BytesN<32>) -> u32 {
+        env.storage().persistent().get(&DataKey::Reserved(depin_id)).unwrap_or(0)
+    }
+
+    // Get how many more orders a DePIN can take (None when its capacity is unlimited)
+    pub fn get_available_capacity(env: Env, depin_id: soroban_sdk::BytesN<32>) -> Option<u32> {
+        let capacity: Option<u32> = env.storage().persistent().get(&DataKey::Capacity(depin_id.clone()));
+        capacity.map(|capacity| capacity.saturating_sub(Self::get_reserved_capacity(env.clone(), depin_id)))
+    }
+
+    // Set the address slashed stake is paid to (admin only)
+    pub fn set_treasury(env: Env, invoker: Address, treasury: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::Treasury, &treasury);
+    }
+
+    // Set the SLA module that may slash stakes (admin only)
+    pub fn set_slasher(env: Env, invoker: Address, slasher: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::Slasher, &slasher);
+    }
+
+    // Move `amount` of a DePIN's stake to the treasury for an SLA violation (admin or the slasher)
+    pub fn slash(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, amount: i128, reason: String) {
+        let slasher: Option<Address> = env.storage().persistent().get(&DataKey::Slasher);
+        if slasher.as_ref() == Some(&invoker) {
+            invoker.require_auth();
+        } else {
+            Self::assert_admin(&env, &invoker);
+        }
+        assert!(amount > 0, "Slash amount must be positive");
+        let (stake_token, staked): (Address, i128) = env.storage().persistent()
+            .get(&DataKey::Stake(depin_id.clone()))
+            .expect("DePIN has no stake");
+        assert!(amount <= staked, "Slash exceeds the stake");
+        let treasury: Address = env.storage().persistent().get(&DataKey::Treasury).expect("Treasury not set");
+
+        token::Client::new(&env, &stake_token).transfer(&env.current_contract_address(), &treasury, &amount);
+        if amount == staked {
+            env.storage().persistent().remove(&DataKey::Stake(depin_id.clone()));
+        } else {
+            env.storage().persistent().set(&DataKey::Stake(depin_id.clone()), &(stake_token, staked - amount));
+        }
+
+        let mut history = Self::get_slash_history(env.clone(), depin_id.clone());
+        history.push_back(SlashRecord { amount, reason: reason.clone(), slasher: invoker, timestamp: env.ledger().timestamp() });
+        env.storage().persistent().set(&DataKey::SlashHistory(depin_id.clone()), &history);
+        env.events().publish((Symbol::new(&env, "slash"), depin_id), (amount, reason));
+    }
+
+    // Get every slash of a DePIN's stake, oldest first
+    pub fn get_slash_history(env: Env, depin_id: soroban_sdk::BytesN<32>) -> Vec<SlashRecord> {
+        env.storage().persistent().get(&DataKey::SlashHistory(depin_id)).unwrap_or(Vec::new(&env))
+    }
+
+    // Allow an oracle to report measured metrics (admin only)
+    pub fn add_reporter(env: Env, invoker: Address, reporter: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::Reporter(reporter), &true);
+    }
+
+    // Revoke an oracle's reporting rights (admin only)
+    pub fn remove_reporter(env: Env, invoker: Address, reporter: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().remove(&DataKey::Reporter(reporter));
+    }
+
+    // Check whether an address may report metrics
+    pub fn is_reporter(env: Env, reporter: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Reporter(reporter))
+    }
+
+    // Record uptime and latency measured at `timestamp` (reporters only). Only the last
+    // MAX_METRICS_HISTORY reports are kept; the committed uptime on the DePIN is untouched
+    pub fn report_metrics(env: Env, reporter: Address, depin_id: soroban_sdk::BytesN<32>, uptime: i32, latency: u32, timestamp: u64) {
+        Self::assert_not_paused(&env, "report_metrics");
+        reporter.require_auth();
+        assert!(Self::is_reporter(env.clone(), reporter.clone()), "Only reporters can report metrics");
+        assert!((0..=100).contains(&uptime), "Uptime must be between 0 and 100");
+        assert!(timestamp <= env.ledger().timestamp(), "Timestamp is in the future");
+
+        Self::assert_listed(&env, &depin_id);
+        let mut history = Self::get_metrics_history(env.clone(), depin_id.clone());
+        if let Some(last) = history.last() {
+            assert!(timestamp > last.timestamp, "Report is older than the latest one");
+        }
+        history.push_back(MetricsReport { reporter: reporter.clone(), uptime, latency, timestamp });
+        while history.len() > MAX_METRICS_HISTORY {
+            history.pop_front();
+        }
+        env.storage().persistent().set(&DataKey::MetricsHistory(depin_id.clone()), &history);
+        env.events().publish((Symbol::new(&env, "metrics"), depin_id), (reporter, uptime, latency, timestamp));
+    }
+
+    // Get the reports kept for a DePIN, oldest first
+    pub fn get_metrics_history(env: Env, depin_id: soroban_sdk::BytesN<32>) -> Vec<MetricsReport> {
+        env.storage().persistent().get(&DataKey::MetricsHistory(depin_id)).unwrap_or(Vec::new(&env))
+    }
+
+    // Get the average uptime and latency over a DePIN's kept reports, if it has any
+    pub fn get_rolling_metrics(env: Env, depin_id: soroban_sdk::BytesN<32>) -> Option<RollingMetrics> {
+        let history = Self::get_metrics_history(env, depin_id);
+        if history.is_empty() {
+            return None;
+        }
+        Some(Self::rolling_metrics(&history))
+    }
+
     // Get DePIN details
     pub fn get_depin(env: Env, depin_id: soroban_sdk::BytesN<32>) -> Option<DePIN> {
-        let depin_map: Map<soroban_sdk::BytesN<32>, DePIN> = env.storage().persistent().get(&DataKey::DepinMap).unwrap();
-        depin_map.get(depin_id)
+        Self::load_depin(&env, &depin_id)
     }
 
-    // List all DePINs (returns vector of DePIN IDs)
-    pub fn list_depins(env: Env) -> Vec<soroban_sdk::BytesN<32>> {
-        let depin_map: Map<soroban_sdk::BytesN<32>, DePIN> = env.storage().persistent().get(&DataKey::DepinMap).unwrap();
+    // List up to `limit` DePIN IDs in listing order from `offset`, at most MAX_PAGE_SIZE
+    pub fn list_depins(env: Env, offset: u32, limit: u32) -> Vec<soroban_sdk::BytesN<32>> {
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(Self::depin_count(&env));
         let mut depin_ids = Vec::new(&env);
-        
-        for i in 0..depin_map.len() {
-            if let Some(key) = depin_map.keys().get(i) {
-                depin_ids.push_back(key);
-            }
+        for index in offset..end {
+            depin_ids.push_back(Self::depin_id_at(&env, index));
         }
         depin_ids
     }
 
+    // Page through every DePIN in listing order, up to MAX_PAGE_SIZE at a time
+    pub fn list_depins_page(env: Env, offset: u32, limit: u32) -> DepinPage {
+        Self::depins_page(&env, offset, limit, |_| true)
+    }
+
+    // Page through the DePINs taking orders: active, approved and not deprecated or retired.
+    // Scans at most MAX_PAGE_SCAN IDs from `offset`, collecting up to `limit` matches.
+    pub fn list_active_depins_page(env: Env, offset: u32, limit: u32) -> DepinPage {
+        Self::depins_page(&env, offset, limit, |depin| {
+            depin.active && depin.approved && depin.lifecycle == DepinLifecycle::Active
+        })
+    }
+
+    // Page through the DePINs whose provider is Verified or Audited.
+    // Scans at most MAX_PAGE_SCAN IDs from `offset`, collecting up to `limit` matches.
+    pub fn list_verified_depins(env: Env, offset: u32, limit: u32) -> DepinPage {
+        Self::depins_page(&env, offset, limit, |depin| depin.trust_level != TrustLevel::Unverified)
+    }
+
     // Get total count of DePINs
     pub fn get_depin_count(env: Env) -> u32 {
-        let depin_map: Map<soroban_sdk::BytesN<32>, DePIN> = env.storage().persistent().get(&DataKey::DepinMap).unwrap();
-        depin_map.len()
+        Self::depin_count(&env)
     }
 
-    // Check if a DePIN exists
+    // Check if a DePIN exists and can take orders; unapproved self-registrations
+    // and deprecated or retired DePINs cannot
     pub fn depin_exists(env: Env, depin_id: soroban_sdk::BytesN<32>) -> bool {
-        let depin_map: Map<soroban_sdk::BytesN<32>, DePIN> = env.storage().persistent().get(&DataKey::DepinMap).unwrap();
-        depin_map.contains_key(depin_id)
+        Self::load_depin(&env, &depin_id).is_some_and(|depin| depin.approved && depin.lifecycle == DepinLifecycle::Active)
     }
 }
 
@@ -2,7 +2,7 @@
 extern crate std;
 
 use super::*;
-use soroban_sdk::{Env, Address, String, BytesN, testutils::Address as _};
+use soroban_sdk::{contract, contractimpl, Env, Address, String, BytesN, testutils::{Address as _, Ledger}};
 
 fn u32_from_id(id: &BytesN<32>) -> u32 {
     let mut four = [0u8;4];
@@ -22,6 +22,27 @@ fn create_depin_registry<'a>(env: &'a Env, admin: &'a Address) -> ContractClient
     init_registry(env, admin)
 }
 
+fn retire(env: &Env, registry: &ContractClient, admin: &Address, depin_id: &BytesN<32>) {
+    registry.set_order_contract(admin, &env.register(MockOrders, ()));
+    registry.deprecate_depin(admin, depin_id);
+    registry.retire_depin(admin, depin_id);
+}
+
+/// Stand-in for the order contract reporting however many unsettled orders a test sets
+#[contract]
+struct MockOrders;
+
+#[contractimpl]
+impl MockOrders {
+    pub fn set_open_orders(env: Env, depin_id: BytesN<32>, count: u32) {
+        env.storage().persistent().set(&depin_id, &count);
+    }
+
+    pub fn get_unsettled_depin_order_count(env: Env, depin_id: BytesN<32>) -> u32 {
+        env.storage().persistent().get(&depin_id).unwrap_or(0)
+    }
+}
+
 #[test]
 fn test_depin_registry_happy_path() {
     let env = Env::default();
@@ -54,15 +75,15 @@ fn test_depin_registry_happy_path() {
     );
     registry.set_depin_status(&admin, &depin_id1, &false);
     let depin1 = registry.get_depin(&depin_id1).unwrap();
-    assert_eq!(depin1.1, String::from_str(&env, "NodeX Updated"));
-    assert_eq!(depin1.3, false);
-    assert_eq!(depin1.4, 100);
-    assert_eq!(depin1.5, 98);
-    assert_eq!(depin1.6, 12);
+    assert_eq!(depin1.name, String::from_str(&env, "NodeX Updated"));
+    assert!(!depin1.active);
+    assert_eq!(depin1.uptime, 100);
+    assert_eq!(depin1.reliability, 98);
+    assert_eq!(depin1.cost, 12);
 
-    // Remove it
-    registry.remove_depin(&admin, &depin_id1);
-    assert!(registry.get_depin(&depin_id1).is_none());
+    // Retire it; the record stays readable
+    retire(&env, &registry, &admin, &depin_id1);
+    assert_eq!(registry.get_depin(&depin_id1).unwrap().lifecycle, DepinLifecycle::Retired);
 
     // Add second DePIN
     let depin_id2 = registry.add_depin(
@@ -76,16 +97,57 @@ fn test_depin_registry_happy_path() {
     assert_eq!(u32_from_id(&depin_id2), 2);
 
     // Test listing DePINs
-    let depin_list = registry.list_depins();
-    assert_eq!(depin_list.len(), 1); // Only depin_id2 should exist (depin_id1 was removed)
-    assert_eq!(depin_list.get(0).unwrap(), depin_id2);
+    let depin_list = registry.list_depins(&0, &10);
+    assert_eq!(depin_list.len(), 2); // Retired DePINs are still listed
+    assert_eq!(depin_list.get(1).unwrap(), depin_id2);
 
     // Test depin_exists
     assert!(registry.depin_exists(&depin_id2));
-    assert!(!registry.depin_exists(&depin_id1)); // This was removed
+    assert!(!registry.depin_exists(&depin_id1)); // This was retired
 
     // Test get_depin_count
-    assert_eq!(registry.get_depin_count(), 1);
+    assert_eq!(registry.get_depin_count(), 2);
+}
+
+#[test]
+fn test_depin_pages() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let mut ids = soroban_sdk::vec![&env];
+    for _ in 0..5 {
+        ids.push_back(registry.add_depin(
+            &admin,
+            &String::from_str(&env, "NodeX"),
+            &String::from_str(&env, "A test node"),
+            &99,
+            &95,
+            &10,
+        ));
+    }
+    registry.set_depin_status(&admin, &ids.get(1).unwrap(), &false);
+    registry.deprecate_depin(&admin, &ids.get(2).unwrap());
+
+    let page = registry.list_depins_page(&0, &3);
+    assert_eq!(page.depins.len(), 3);
+    assert_eq!(page.depins.get(0).unwrap().id, ids.get(0).unwrap());
+    assert_eq!(page.next_offset, Some(3));
+    let page = registry.list_depins_page(&3, &3);
+    assert_eq!(page.depins.len(), 2);
+    assert_eq!(page.next_offset, None);
+    assert_eq!(registry.list_depins(&3, &10), ids.slice(3..5));
+    assert!(registry.list_depins(&5, &10).is_empty());
+
+    // Inactive and deprecated DePINs are skipped
+    let page = registry.list_active_depins_page(&0, &2);
+    assert_eq!(page.depins.get(0).unwrap().id, ids.get(0).unwrap());
+    assert_eq!(page.depins.get(1).unwrap().id, ids.get(3).unwrap());
+    assert_eq!(page.next_offset, Some(4));
+    let page = registry.list_active_depins_page(&4, &2);
+    assert_eq!(page.depins.get(0).unwrap().id, ids.get(4).unwrap());
+    assert_eq!(page.next_offset, None);
 }
 
 #[test]
@@ -131,7 +193,7 @@ fn test_invalid_uptime() {
 
 #[test]
 #[should_panic(expected = "DePIN not found")]
-fn test_remove_non_existent_depin() {
+fn test_retire_non_existent_depin() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -143,8 +205,8 @@ fn test_remove_non_existent_depin() {
     bytes[0] = 1;
     let fake_id = BytesN::from_array(&env, &bytes);
 
-    // Try to remove a non-existent DePIN
-    registry.remove_depin(&admin, &fake_id);
+    // Try to retire a non-existent DePIN
+    registry.retire_depin(&admin, &fake_id);
 }
 
 #[test]
@@ -167,17 +229,618 @@ fn test_depin_status_management() {
 
     // Check initial status (should be true)
     let depin = registry.get_depin(&depin_id).unwrap();
-    assert_eq!(depin.3, true); // status field
+    assert!(depin.active);
 
     // Deactivate DePIN
     registry.set_depin_status(&admin, &depin_id, &false);
     let depin = registry.get_depin(&depin_id).unwrap();
-    assert_eq!(depin.3, false);
+    assert!(!depin.active);
 
     // Reactivate DePIN
     registry.set_depin_status(&admin, &depin_id, &true);
     let depin = registry.get_depin(&depin_id).unwrap();
-    assert_eq!(depin.3, true);
+    assert!(depin.active);
+}
+
+#[test]
+fn test_payout_address_kept_on_retirement() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let payout = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+    assert_eq!(registry.get_payout_address(&depin_id), None);
+
+    registry.set_payout_address(&admin, &depin_id, &payout);
+    assert_eq!(registry.get_payout_address(&depin_id), Some(payout.clone()));
+
+    // Orders that ran on a retired DePIN can still pay out to it
+    retire(&env, &registry, &admin, &depin_id);
+    assert_eq!(registry.get_payout_address(&depin_id), Some(payout));
+}
+
+#[test]
+fn test_retirement_waits_for_open_orders() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let orders = MockOrdersClient::new(&env, &env.register(MockOrders, ()));
+    registry.set_order_contract(&admin, &orders.address);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+    orders.set_open_orders(&depin_id, &1);
+
+    // Active DePINs must be deprecated first
+    assert!(registry.try_retire_depin(&admin, &depin_id).is_err());
+
+    // Deprecated DePINs take no new orders but stay readable
+    registry.deprecate_depin(&admin, &depin_id);
+    assert!(!registry.depin_exists(&depin_id));
+    assert_eq!(registry.get_depin(&depin_id).unwrap().lifecycle, DepinLifecycle::Deprecated);
+    assert!(registry.try_retire_depin(&admin, &depin_id).is_err());
+
+    orders.set_open_orders(&depin_id, &0);
+    registry.retire_depin(&admin, &depin_id);
+    let depin = registry.get_depin(&depin_id).unwrap();
+    assert_eq!(depin.lifecycle, DepinLifecycle::Retired);
+    assert!(!depin.active);
+    assert!(registry
+        .try_update_depin(&admin, &depin_id, &String::from_str(&env, "NodeX"), &String::from_str(&env, "x"), &1, &1, &1)
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "Order contract not set")]
+fn test_retirement_needs_order_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+    registry.deprecate_depin(&admin, &depin_id);
+    registry.retire_depin(&admin, &depin_id);
+}
+
+#[test]
+fn test_provider_registration_with_stake() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+
+    let stake_token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &stake_token).mint(&provider, &1_000);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &stake_token);
+    registry.set_registration_stake(&admin, &stake_token, &400);
+
+    let depin_id = registry.register_depin(
+        &provider,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A provider node"),
+        &99,
+        &95,
+        &10,
+    );
+    assert_eq!(token_client.balance(&provider), 600);
+    assert_eq!(registry.get_stake(&depin_id), Some((stake_token.clone(), 400)));
+
+    // Unapproved self-registrations cannot take orders yet
+    let depin = registry.get_depin(&depin_id).unwrap();
+    assert_eq!(depin.owner, Some(provider.clone()));
+    assert!(!depin.approved);
+    assert!(!registry.depin_exists(&depin_id));
+
+    registry.approve_depin(&admin, &depin_id);
+    assert!(registry.depin_exists(&depin_id));
+
+    // Only the owner manages its listing
+    assert!(registry
+        .try_update_depin(&admin, &depin_id, &String::from_str(&env, "Hijacked"), &String::from_str(&env, "x"), &1, &1, &1)
+        .is_err());
+    registry.update_depin(&provider, &depin_id, &String::from_str(&env, "NodeX v2"), &String::from_str(&env, "Upgraded"), &98, &96, &12);
+    assert_eq!(registry.get_depin(&depin_id).unwrap().name, String::from_str(&env, "NodeX v2"));
+    registry.set_service_price(&provider, &depin_id, &String::from_str(&env, "compute"), &30);
+    assert_eq!(registry.get_service_price(&depin_id, &String::from_str(&env, "compute")), Some(30));
+
+    // The owner points catalogs at its long-form listing
+    let uri = String::from_str(&env, "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi");
+    let hash = BytesN::from_array(&env, &[9u8; 32]);
+    assert!(registry.try_set_metadata(&admin, &depin_id, &uri, &hash).is_err());
+    registry.set_metadata(&provider, &depin_id, &uri, &hash);
+    let depin = registry.get_depin(&depin_id).unwrap();
+    assert_eq!((depin.metadata_uri, depin.metadata_hash), (Some(uri), Some(hash)));
+
+    // Retirement returns the stake
+    registry.set_order_contract(&admin, &env.register(MockOrders, ()));
+    registry.deprecate_depin(&provider, &depin_id);
+    registry.retire_depin(&admin, &depin_id);
+    assert_eq!(token_client.balance(&provider), 1_000);
+    assert_eq!(registry.get_stake(&depin_id), None);
+}
+
+#[test]
+fn test_slash_moves_stake_to_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let sla = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+
+    let stake_token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &stake_token).mint(&provider, &1_000);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &stake_token);
+    registry.set_registration_stake(&admin, &stake_token, &400);
+    registry.set_treasury(&admin, &treasury);
+    registry.set_slasher(&admin, &sla);
+
+    let depin_id = registry.register_depin(
+        &provider,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A provider node"),
+        &99,
+        &95,
+        &10,
+    );
+
+    let downtime = String::from_str(&env, "Missed uptime commitment");
+    registry.slash(&sla, &depin_id, &150, &downtime);
+    registry.slash(&admin, &depin_id, &50, &downtime);
+    assert!(registry.try_slash(&provider, &depin_id, &50, &downtime).is_err());
+    assert!(registry.try_slash(&admin, &depin_id, &250, &downtime).is_err());
+
+    assert_eq!(token_client.balance(&treasury), 200);
+    assert_eq!(registry.get_stake(&depin_id), Some((stake_token.clone(), 200)));
+    let history = registry.get_slash_history(&depin_id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().slasher, sla);
+    assert_eq!(history.get(1).unwrap().amount, 50);
+
+    // Only what is left of the stake is returned on retirement
+    registry.set_order_contract(&admin, &env.register(MockOrders, ()));
+    registry.deprecate_depin(&provider, &depin_id);
+    registry.retire_depin(&admin, &depin_id);
+    assert_eq!(token_client.balance(&provider), 800);
+}
+
+#[test]
+fn test_depins_listed_by_category_and_tag() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+
+    let compute = String::from_str(&env, "compute");
+    let gpu = String::from_str(&env, "gpu");
+    let mut ids = soroban_sdk::vec![&env];
+    for _ in 0..3 {
+        let depin_id = registry.add_depin(
+            &admin,
+            &String::from_str(&env, "NodeX"),
+            &String::from_str(&env, "A test node"),
+            &99,
+            &95,
+            &10,
+        );
+        registry.set_depin_category(&admin, &depin_id, &compute, &soroban_sdk::vec![&env, gpu.clone()]);
+        ids.push_back(depin_id);
+    }
+
+    assert_eq!(registry.list_depins_by_category(&compute, &0, &2), ids.slice(0..2));
+    assert_eq!(registry.list_depins_by_category(&compute, &2, &2), ids.slice(2..3));
+    assert_eq!(registry.list_depins_by_category(&compute, &5, &2).len(), 0);
+    assert_eq!(registry.list_depins_by_tag(&gpu, &0, &10), ids);
+    assert_eq!(registry.list_depins_by_tag(&gpu, &1, &1), ids.slice(1..2));
+
+    // Recategorizing moves the DePIN between indexes
+    let storage = String::from_str(&env, "storage");
+    let first = ids.get(0).unwrap();
+    registry.set_depin_category(&admin, &first, &storage, &soroban_sdk::vec![&env]);
+    assert_eq!(registry.get_depin(&first).unwrap().category, Some(storage.clone()));
+    assert_eq!(registry.list_depins_by_category(&storage, &0, &10), soroban_sdk::vec![&env, first.clone()]);
+    assert_eq!(registry.list_depins_by_category(&compute, &0, &10), ids.slice(1..3));
+    assert_eq!(registry.list_depins_by_tag(&gpu, &0, &10), ids.slice(1..3));
+
+    retire(&env, &registry, &admin, &ids.get(1).unwrap());
+    assert_eq!(registry.list_depins_by_category(&compute, &0, &10), ids.slice(2..3));
+    assert_eq!(registry.list_depins_by_tag(&gpu, &0, &10), ids.slice(2..3));
+}
+
+#[test]
+fn test_depins_listed_by_region() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let add = || registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+    let eu_node = add();
+    let us_node = add();
+
+    let eu = String::from_str(&env, "EU");
+    let us = String::from_str(&env, "US");
+    registry.set_depin_location(&admin, &eu_node, &eu, &String::from_str(&env, "DE"));
+    registry.set_depin_location(&admin, &us_node, &us, &String::from_str(&env, "US"));
+    assert_eq!(registry.list_depins_by_region(&eu, &0, &10), soroban_sdk::vec![&env, eu_node.clone()]);
+    assert_eq!(registry.get_depin(&eu_node).unwrap().country, Some(String::from_str(&env, "DE")));
+    assert_eq!(registry.list_depins_by_region(&eu, &1, &10).len(), 0);
+
+    // Recategorizing leaves the region index alone
+    registry.set_depin_category(&admin, &eu_node, &String::from_str(&env, "compute"), &soroban_sdk::vec![&env]);
+    registry.set_depin_category(&admin, &eu_node, &String::from_str(&env, "storage"), &soroban_sdk::vec![&env]);
+    assert_eq!(registry.list_depins_by_region(&eu, &0, &10), soroban_sdk::vec![&env, eu_node.clone()]);
+
+    // Relocating moves the DePIN to the new region's index
+    registry.set_depin_location(&admin, &us_node, &eu, &String::from_str(&env, "FR"));
+    assert_eq!(registry.list_depins_by_region(&eu, &0, &10), soroban_sdk::vec![&env, eu_node.clone(), us_node.clone()]);
+    assert_eq!(registry.list_depins_by_region(&us, &0, &10).len(), 0);
+
+    retire(&env, &registry, &admin, &eu_node);
+    assert_eq!(registry.list_depins_by_region(&eu, &0, &10), soroban_sdk::vec![&env, us_node]);
+}
+
+#[test]
+fn test_reporter_metrics_update_rolling_uptime() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(10_000);
+
+    let admin = Address::generate(&env);
+    let reporter = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+
+    assert!(registry.try_report_metrics(&reporter, &depin_id, &90, &40, &100).is_err());
+    registry.add_reporter(&admin, &reporter);
+    registry.report_metrics(&reporter, &depin_id, &90, &40, &100);
+    registry.report_metrics(&reporter, &depin_id, &80, &60, &200);
+    assert_eq!(
+        registry.get_rolling_metrics(&depin_id),
+        Some(RollingMetrics { uptime: 85, latency: 50, samples: 2 })
+    );
+    // Measurements never move the committed uptime
+    assert_eq!(registry.get_depin(&depin_id).unwrap().uptime, 99);
+
+    // Stale reports are rejected and the history stays bounded
+    assert!(registry.try_report_metrics(&reporter, &depin_id, &90, &40, &150).is_err());
+    for i in 0..MAX_METRICS_HISTORY as u64 {
+        registry.report_metrics(&reporter, &depin_id, &100, &10, &(300 + i));
+    }
+    let history = registry.get_metrics_history(&depin_id);
+    assert_eq!(history.len(), MAX_METRICS_HISTORY);
+    assert_eq!(history.get(0).unwrap().timestamp, 300);
+    assert_eq!(registry.get_rolling_metrics(&depin_id).unwrap().uptime, 100);
+    assert_eq!(registry.get_depin(&depin_id).unwrap().uptime, 99);
+
+    registry.remove_reporter(&admin, &reporter);
+    assert!(!registry.is_reporter(&reporter));
+    assert!(registry.try_report_metrics(&reporter, &depin_id, &90, &40, &5_000).is_err());
+}
+
+#[test]
+fn test_service_price_per_service_type() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+
+    let compute = String::from_str(&env, "compute");
+    registry.set_service_price(&admin, &depin_id, &compute, &25);
+    assert_eq!(registry.get_service_price(&depin_id, &compute), Some(25));
+    assert_eq!(registry.get_service_price(&depin_id, &String::from_str(&env, "storage")), None);
+
+    let storage = String::from_str(&env, "storage");
+    registry.set_service_price(&admin, &depin_id, &storage, &4);
+    let table = registry.get_price_table(&depin_id);
+    assert_eq!(table.len(), 2);
+    assert_eq!(table.get(storage.clone()), Some(4));
+
+    registry.remove_service_price(&admin, &depin_id, &compute);
+    assert_eq!(registry.get_service_price(&depin_id, &compute), None);
+    assert_eq!(registry.get_service_price(&depin_id, &storage), Some(4));
+}
+
+#[test]
+fn test_registration_admin_calls_need_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let stake_token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let depin_id = registry.register_depin(
+        &provider,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A provider node"),
+        &99,
+        &95,
+        &10,
+    );
+
+    // A provider cannot approve itself or lift the stake by naming the admin
+    env.set_auths(&[]);
+    assert!(registry.try_approve_depin(&admin, &depin_id).is_err());
+    assert!(registry.try_set_registration_stake(&admin, &stake_token, &0).is_err());
+    assert!(!registry.depin_exists(&depin_id));
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_slash_needs_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+
+    // Passing the admin's address without its signature is not enough
+    env.set_auths(&[]);
+    assert!(registry.try_set_treasury(&admin, &attacker).is_err());
+    assert!(registry.try_set_slasher(&admin, &attacker).is_err());
+    registry.slash(&admin, &depin_id, &1, &String::from_str(&env, "Downtime"));
+}
+
+#[test]
+fn test_trust_levels_and_verified_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let mut ids = soroban_sdk::vec![&env];
+    for _ in 0..3 {
+        ids.push_back(registry.add_depin(
+            &admin,
+            &String::from_str(&env, "NodeX"),
+            &String::from_str(&env, "A test node"),
+            &99,
+            &95,
+            &10,
+        ));
+    }
+    assert_eq!(registry.get_trust_level(&ids.get(0).unwrap()), Some(TrustLevel::Unverified));
+    assert_eq!(registry.list_verified_depins(&0, &10).depins.len(), 0);
+
+    registry.set_trust_level(&admin, &ids.get(0).unwrap(), &TrustLevel::Verified);
+    registry.set_trust_level(&admin, &ids.get(2).unwrap(), &TrustLevel::Audited);
+    let page = registry.list_verified_depins(&0, &10);
+    assert_eq!(page.depins.len(), 2);
+    assert_eq!(page.depins.get(1).unwrap().trust_level, TrustLevel::Audited);
+
+    assert!(registry.try_set_trust_level(&Address::generate(&env), &ids.get(1).unwrap(), &TrustLevel::Audited).is_err());
+
+    // Naming the admin without its signature is rejected too
+    env.set_auths(&[]);
+    assert!(registry.try_set_trust_level(&admin, &ids.get(1).unwrap(), &TrustLevel::Verified).is_err());
+    assert_eq!(registry.get_trust_level(&ids.get(1).unwrap()), Some(TrustLevel::Unverified));
+}
+
+fn specs(env: &Env, gpu_model: &str, gpu_count: u32, gpu_memory_gb: u32) -> Specs {
+    Specs {
+        cpu_cores: 32,
+        gpu_model: String::from_str(env, gpu_model),
+        gpu_count,
+        gpu_memory_gb,
+        ram_gb: 256,
+        storage_gb: 2_000,
+        bandwidth_mbps: 10_000,
+    }
+}
+
+#[test]
+fn test_depins_filtered_by_specs() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let mut ids = soroban_sdk::vec![&env];
+    for _ in 0..3 {
+        ids.push_back(registry.add_depin(
+            &admin,
+            &String::from_str(&env, "NodeX"),
+            &String::from_str(&env, "A test node"),
+            &99,
+            &95,
+            &10,
+        ));
+    }
+    registry.set_specs(&admin, &ids.get(0).unwrap(), &specs(&env, "A100", 8, 80));
+    registry.set_specs(&admin, &ids.get(1).unwrap(), &specs(&env, "RTX 4090", 4, 24));
+    assert_eq!(registry.get_specs(&ids.get(1).unwrap()).unwrap().gpu_memory_gb, 24);
+    assert_eq!(registry.get_specs(&ids.get(2).unwrap()), None);
+
+    // At least 40 GB of GPU memory, any model
+    let page = registry.list_depins_by_specs(&specs(&env, "", 1, 40), &0, &10);
+    assert_eq!(page.depins.len(), 1);
+    assert_eq!(page.depins.get(0).unwrap().id, ids.get(0).unwrap());
+
+    assert!(registry.meets_specs(&ids.get(1).unwrap(), &specs(&env, "RTX 4090", 2, 16)));
+    assert!(!registry.meets_specs(&ids.get(1).unwrap(), &specs(&env, "A100", 2, 16)));
+    assert!(!registry.meets_specs(&ids.get(2).unwrap(), &specs(&env, "", 0, 0)));
+}
+
+#[test]
+fn test_supported_chains() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+    let stellar = String::from_str(&env, "stellar");
+    let ethereum = String::from_str(&env, "ethereum");
+
+    // DePINs that list no chains accept any
+    assert!(registry.supports_chain(&depin_id, &ethereum));
+
+    registry.set_supported_chains(&admin, &depin_id, &soroban_sdk::vec![&env, stellar.clone()]);
+    assert!(registry.supports_chain(&depin_id, &stellar));
+    assert!(!registry.supports_chain(&depin_id, &ethereum));
+    assert_eq!(registry.get_supported_chains(&depin_id), soroban_sdk::vec![&env, stellar]);
+}
+
+#[test]
+#[should_panic(expected = "Price must be positive")]
+fn test_zero_service_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+
+    registry.set_service_price(&admin, &depin_id, &String::from_str(&env, "compute"), &0);
+}
+
+#[test]
+fn test_capacity_reservations() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let order_contract = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+    registry.set_order_contract(&admin, &order_contract);
+    assert_eq!(registry.get_available_capacity(&depin_id), None);
+
+    registry.set_capacity(&admin, &depin_id, &2);
+    registry.reserve_capacity(&order_contract, &depin_id, &2);
+    assert_eq!(registry.get_available_capacity(&depin_id), Some(0));
+    assert!(registry.try_reserve_capacity(&order_contract, &depin_id, &1).is_err());
+
+    registry.release_capacity(&order_contract, &depin_id, &1);
+    assert_eq!(registry.get_reserved_capacity(&depin_id), 1);
+    assert_eq!(registry.get_available_capacity(&depin_id), Some(1));
+}
+
+#[test]
+#[should_panic(expected = "Only the order contract can change reservations")]
+fn test_only_order_contract_reserves_capacity() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+    registry.set_order_contract(&admin, &Address::generate(&env));
+
+    registry.reserve_capacity(&admin, &depin_id, &1);
+}
+
+#[test]
+fn test_capacity_settings_need_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &10,
+    );
+
+    env.set_auths(&[]);
+    assert!(registry.try_set_order_contract(&admin, &Address::generate(&env)).is_err());
+    assert!(registry.try_set_capacity(&admin, &depin_id, &5).is_err());
+    assert_eq!(registry.get_available_capacity(&depin_id), None);
 }
 
 #[test]
@@ -199,11 +862,11 @@ fn test_depin_data_validation() {
     );
 
     let depin = registry.get_depin(&depin_id).unwrap();
-    assert_eq!(depin.1, String::from_str(&env, "ValidNode"));
-    assert_eq!(depin.2, String::from_str(&env, "A valid test node"));
-    assert_eq!(depin.4, 85); // uptime
-    assert_eq!(depin.5, 92); // reliability
-    assert_eq!(depin.6, 20); // cost
+    assert_eq!(depin.name, String::from_str(&env, "ValidNode"));
+    assert_eq!(depin.description, String::from_str(&env, "A valid test node"));
+    assert_eq!(depin.uptime, 85);
+    assert_eq!(depin.reliability, 92);
+    assert_eq!(depin.cost, 20);
 }
 
 #[test]
@@ -285,3 +948,140 @@ fn test_negative_cost() {
         &-5, // Invalid negative cost
     );
 }
+
+#[test]
+fn test_listing_fee_from_fee_manager() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+
+    // No fee manager configured yet
+    assert_eq!(registry.get_listing_fee(), 0);
+
+    let fee_manager_id = env.register(fee_manager::FeeManagerContract, ());
+    let fee_manager = fee_manager::FeeManagerContractClient::new(&env, &fee_manager_id);
+    fee_manager.initialize(&admin);
+    fee_manager.set_listing_fee(&admin, &50);
+
+    registry.set_fee_manager(&admin, &fee_manager_id);
+    assert_eq!(registry.get_listing_fee(), 50);
+}
+
+#[test]
+#[should_panic(expected = "Contract is paused")]
+fn test_paused_registry_rejects_new_listings() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &100,
+    );
+
+    let guardian_id = env.register(guardian::GuardianContract, ());
+    let guardian = guardian::GuardianContractClient::new(&env, &guardian_id);
+    guardian.initialize(&admin, &Address::generate(&env));
+    guardian.set_guardian(&admin, &admin, &true);
+    registry.set_pause_guardian(&admin, &guardian_id);
+    guardian.pause_contract(&admin, &registry.address);
+
+    // Taking a listing offline stays possible during an incident
+    registry.set_depin_status(&admin, &depin_id, &false);
+    registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeY"),
+        &String::from_str(&env, "Another node"),
+        &99,
+        &95,
+        &100,
+    );
+}
+
+#[test]
+fn test_paused_registry_freezes_listing_details() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = create_depin_registry(&env, &admin);
+    let depin_id = registry.add_depin(
+        &admin,
+        &String::from_str(&env, "NodeX"),
+        &String::from_str(&env, "A test node"),
+        &99,
+        &95,
+        &100,
+    );
+
+    let guardian_id = env.register(guardian::GuardianContract, ());
+    let guardian = guardian::GuardianContractClient::new(&env, &guardian_id);
+    guardian.initialize(&admin, &Address::generate(&env));
+    guardian.set_guardian(&admin, &admin, &true);
+    registry.set_pause_guardian(&admin, &guardian_id);
+    guardian.pause_contract(&admin, &registry.address);
+
+    let uri = String::from_str(&env, "ipfs://listing");
+    let hash = BytesN::from_array(&env, &[7u8; 32]);
+    assert!(registry.try_set_metadata(&admin, &depin_id, &uri, &hash).is_err());
+    let compute = String::from_str(&env, "compute");
+    assert!(registry.try_set_service_price(&admin, &depin_id, &compute, &10).is_err());
+    assert!(registry.try_remove_service_price(&admin, &depin_id, &compute).is_err());
+    let chains = soroban_sdk::vec![&env, String::from_str(&env, "ethereum")];
+    assert!(registry.try_set_supported_chains(&admin, &depin_id, &chains).is_err());
+    assert!(registry.try_set_specs(&admin, &depin_id, &specs(&env, "A100", 8, 80)).is_err());
+
+    // Unpausing lets them through again
+    guardian.unpause_contract(&registry.address);
+    registry.set_metadata(&admin, &depin_id, &uri, &hash);
+    registry.set_service_price(&admin, &depin_id, &compute, &10);
+    registry.remove_service_price(&admin, &depin_id, &compute);
+    registry.set_supported_chains(&admin, &depin_id, &chains);
+    registry.set_specs(&admin, &depin_id, &specs(&env, "A100", 8, 80));
+}
+
+#[test]
+fn test_legacy_tuple_depins_migrate_to_structs() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = init_registry(&env, &admin);
+
+    // A registry written before DePIN became a struct
+    let depin_id = BytesN::from_array(&env, &[1u8; 32]);
+    env.as_contract(&registry.address, || {
+        let mut legacy: Map<BytesN<32>, LegacyDePIN> = Map::new(&env);
+        legacy.set(depin_id.clone(), (
+            depin_id.clone(),
+            String::from_str(&env, "NodeX"),
+            String::from_str(&env, "A test node"),
+            true,
+            99,
+            95,
+            10,
+        ));
+        env.storage().persistent().set(&DataKey::DepinMap, &legacy);
+    });
+
+    let depin = registry.get_depin(&depin_id).unwrap();
+    assert_eq!(depin.name, String::from_str(&env, "NodeX"));
+    assert_eq!(depin.uptime, 99);
+    assert_eq!(registry.get_depin_count(), 0);
+
+    registry.migrate_depins(&admin);
+    env.as_contract(&registry.address, || {
+        assert!(!env.storage().persistent().has(&DataKey::DepinMap));
+        assert!(env.storage().persistent().has(&DataKey::Depin(depin_id.clone())));
+    });
+    assert!(registry.depin_exists(&depin_id));
+    assert_eq!(registry.get_depin_count(), 1);
+    assert_eq!(registry.list_depins_page(&0, &10).depins.get(0).unwrap().name, String::from_str(&env, "NodeX"));
+}
@@ -0,0 +1,131 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, Env, Symbol, Vec,
+    panic_with_error, symbol_short
+};
+
+#[contract]
+pub struct DirectoryContract;
+
+/// Canonical deployment of a platform contract
+///
+/// Well-known names: `profile`, `order`, `registry`, `reputation`, `treasury`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractEntry {
+    pub address: Address,
+    pub version: u32,
+    pub updated_at: u64,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,                      // Manages entries until a timelock is set
+    Timelock,                   // Governance that authorizes entry updates once set
+    Entry(Symbol),              // name -> ContractEntry
+    Names,                      // Vec<Symbol> of every registered name
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidVersion = 3,
+    ContractNotFound = 4,
+}
+
+#[contractimpl]
+impl DirectoryContract {
+    /// Initialize the contract with admin address
+    pub fn initialize(env: Env, admin: Address) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::Names, &Vec::<Symbol>::new(&env));
+        true
+    }
+
+    /// Hand entry updates over to a timelock (admin once, then timelock only)
+    pub fn set_timelock(env: Env, timelock: Address) -> bool {
+        Self::assert_governance(&env);
+        env.storage().persistent().set(&DataKey::Timelock, &timelock);
+        true
+    }
+
+    /// Point a name at a new deployment (admin, or timelock once set)
+    ///
+    /// Versions must strictly increase so a stale deployment cannot be
+    /// re-registered over a newer one.
+    pub fn set_contract(env: Env, name: Symbol, address: Address, version: u32) -> bool {
+        Self::assert_governance(&env);
+
+        match Self::get_contract(env.clone(), name.clone()) {
+            Some(current) if version <= current.version => {
+                panic_with_error!(&env, Error::InvalidVersion);
+            }
+            Some(_) => {}
+            None => {
+                let mut names = Self::get_names(env.clone());
+                names.push_back(name.clone());
+                env.storage().persistent().set(&DataKey::Names, &names);
+            }
+        }
+
+        let entry = ContractEntry {
+            address: address.clone(),
+            version,
+            updated_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&DataKey::Entry(name.clone()), &entry);
+
+        env.events().publish((symbol_short!("set"), name), (address, version));
+        true
+    }
+
+    /// Get the entry registered under a name
+    pub fn get_contract(env: Env, name: Symbol) -> Option<ContractEntry> {
+        env.storage().persistent().get(&DataKey::Entry(name))
+    }
+
+    /// Get the address registered under a name
+    pub fn get_address(env: Env, name: Symbol) -> Option<Address> {
+        Self::get_contract(env, name).map(|entry| entry.address)
+    }
+
+    /// Get the version registered under a name
+    pub fn get_version(env: Env, name: Symbol) -> u32 {
+        Self::get_contract(env.clone(), name)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::ContractNotFound))
+            .version
+    }
+
+    /// Get every registered name
+    pub fn get_names(env: Env) -> Vec<Symbol> {
+        env.storage().persistent()
+            .get(&DataKey::Names)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+    }
+
+    /// Get the governing timelock, if handed over
+    pub fn get_timelock(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Timelock)
+    }
+
+    // Helper functions
+    fn assert_governance(env: &Env) {
+        if let Some(timelock) = env.storage().persistent().get::<_, Address>(&DataKey::Timelock) {
+            timelock.require_auth();
+            return;
+        }
+        let admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        admin.require_auth();
+    }
+}
+
+mod test;
@@ -0,0 +1,58 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils},
+    Address, Env, Symbol
+};
+
+fn setup(env: &Env) -> (DirectoryContractClient<'_>, Address) {
+    env.mock_all_auths();
+    let admin = Address::generate(env);
+    let directory = DirectoryContractClient::new(env, &env.register(DirectoryContract, ()));
+    directory.initialize(&admin);
+    (directory, admin)
+}
+
+#[test]
+fn test_set_and_resolve_contracts() {
+    let env = Env::default();
+    let (directory, _) = setup(&env);
+    let order_v1 = Address::generate(&env);
+    let order_v2 = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    directory.set_contract(&symbol_short!("order"), &order_v1, &1);
+    directory.set_contract(&symbol_short!("treasury"), &treasury, &1);
+    directory.set_contract(&symbol_short!("order"), &order_v2, &2);
+
+    assert_eq!(directory.get_address(&symbol_short!("order")), Some(order_v2));
+    assert_eq!(directory.get_version(&symbol_short!("order")), 2);
+    assert_eq!(directory.get_address(&Symbol::new(&env, "reputation")), None);
+    assert_eq!(directory.get_names().len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_version_must_increase() {
+    let env = Env::default();
+    let (directory, _) = setup(&env);
+
+    directory.set_contract(&symbol_short!("order"), &Address::generate(&env), &2);
+    directory.set_contract(&symbol_short!("order"), &Address::generate(&env), &2);
+}
+
+#[test]
+fn test_timelock_takes_over_updates() {
+    let env = Env::default();
+    let (directory, admin) = setup(&env);
+    let timelock = Address::generate(&env);
+
+    directory.set_contract(&symbol_short!("order"), &Address::generate(&env), &1);
+    assert_eq!(env.auths()[0].0, admin);
+
+    directory.set_timelock(&timelock);
+    directory.set_contract(&symbol_short!("order"), &Address::generate(&env), &2);
+    assert_eq!(env.auths()[0].0, timelock);
+    assert_eq!(directory.get_timelock(), Some(timelock));
+}
@@ -0,0 +1,381 @@
+#![no_std]
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, BytesN, Env, Vec,
+    panic_with_error, symbol_short, token
+};
+
+#[contract]
+pub struct EscrowContract;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Escrow {
+    pub escrow_id: BytesN<32>,
+    pub controller: Address,        // Contract or account driving the escrow (e.g. order contract)
+    pub depositor: Address,         // Party whose funds are locked
+    pub beneficiary: Address,       // Party funds are released to
+    pub arbiter: Option<Address>,   // Optional neutral party with release/refund authority
+    pub token: Address,
+    pub amount: i128,
+    pub released: i128,
+    pub refunded: i128,
+    pub status: EscrowStatus,
+    pub created_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EscrowStatus {
+    Created,        // Terms recorded, no funds locked yet
+    Locked,         // Funds held by the escrow contract
+    Settled,        // Every locked unit has been released or refunded
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EscrowRole {
+    Controller,
+    Depositor,
+    Beneficiary,
+    Arbiter,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Escrow(BytesN<32>),         // escrow_id -> Escrow
+    TotalLocked(Address),       // token -> i128 currently held across all escrows
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    EscrowExists = 1,
+    EscrowNotFound = 2,
+    InvalidAmount = 3,
+    InvalidStatus = 4,
+    Unauthorized = 5,
+    InsufficientEscrow = 6,
+}
+
+#[contractimpl]
+impl EscrowContract {
+    /// Record the terms of a new escrow (controller only)
+    pub fn create_escrow(
+        env: Env,
+        controller: Address,
+        escrow_id: BytesN<32>,
+        depositor: Address,
+        beneficiary: Address,
+        token: Address,
+        amount: i128,
+        arbiter: Option<Address>,
+    ) -> bool {
+        controller.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        if env.storage().persistent().has(&DataKey::Escrow(escrow_id.clone())) {
+            panic_with_error!(&env, Error::EscrowExists);
+        }
+
+        let escrow = Escrow {
+            escrow_id: escrow_id.clone(),
+            controller,
+            depositor,
+            beneficiary,
+            arbiter,
+            token,
+            amount,
+            released: 0,
+            refunded: 0,
+            status: EscrowStatus::Created,
+            created_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&DataKey::Escrow(escrow_id.clone()), &escrow);
+
+        env.events().publish(
+            (symbol_short!("created"), escrow_id),
+            (escrow.depositor, escrow.beneficiary, amount),
+        );
+        true
+    }
+
+    /// Pull the escrowed amount from the depositor into the contract
+    pub fn lock(env: Env, caller: Address, escrow_id: BytesN<32>) -> bool {
+        caller.require_auth();
+
+        let mut escrow = Self::load(&env, &escrow_id);
+        Self::assert_role(&env, &escrow, &caller, &[EscrowRole::Controller, EscrowRole::Depositor]);
+
+        if escrow.status != EscrowStatus::Created {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        if caller != escrow.depositor {
+            escrow.depositor.require_auth();
+        }
+        token::Client::new(&env, &escrow.token)
+            .transfer(&escrow.depositor, &env.current_contract_address(), &escrow.amount);
+
+        escrow.status = EscrowStatus::Locked;
+        env.storage().persistent().set(&DataKey::Escrow(escrow_id.clone()), &escrow);
+        Self::adjust_total_locked(&env, &escrow.token, escrow.amount);
+
+        env.events().publish((symbol_short!("locked"), escrow_id), escrow.amount);
+        true
+    }
+
+    /// Lock an escrow whose tokens the controller already transferred in
+    ///
+    /// Used when the funds come from another contract's custody (e.g. a
+    /// winning auction bid) rather than the depositor's wallet. Only tokens
+    /// not already backing other escrows count towards the amount.
+    pub fn lock_funded(env: Env, caller: Address, escrow_id: BytesN<32>) -> bool {
+        caller.require_auth();
+
+        let mut escrow = Self::load(&env, &escrow_id);
+        Self::assert_role(&env, &escrow, &caller, &[EscrowRole::Controller]);
+
+        if escrow.status != EscrowStatus::Created {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        let locked = Self::get_total_locked(env.clone(), escrow.token.clone());
+        let balance = token::Client::new(&env, &escrow.token).balance(&env.current_contract_address());
+        if balance - locked < escrow.amount {
+            panic_with_error!(&env, Error::InsufficientEscrow);
+        }
+
+        escrow.status = EscrowStatus::Locked;
+        env.storage().persistent().set(&DataKey::Escrow(escrow_id.clone()), &escrow);
+        Self::adjust_total_locked(&env, &escrow.token, escrow.amount);
+
+        env.events().publish((symbol_short!("locked"), escrow_id), escrow.amount);
+        true
+    }
+
+    /// Pull `amount` more from the depositor into a locked escrow
+    /// (controller or depositor), e.g. when the escrowed order is extended
+    pub fn top_up(env: Env, caller: Address, escrow_id: BytesN<32>, amount: i128) -> bool {
+        caller.require_auth();
+
+        let mut escrow = Self::load(&env, &escrow_id);
+        Self::assert_role(&env, &escrow, &caller, &[EscrowRole::Controller, EscrowRole::Depositor]);
+
+        if escrow.status != EscrowStatus::Locked {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if amount <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        if caller != escrow.depositor {
+            escrow.depositor.require_auth();
+        }
+        token::Client::new(&env, &escrow.token)
+            .transfer(&escrow.depositor, &env.current_contract_address(), &amount);
+
+        escrow.amount += amount;
+        env.storage().persistent().set(&DataKey::Escrow(escrow_id.clone()), &escrow);
+        Self::adjust_total_locked(&env, &escrow.token, amount);
+
+        env.events().publish((symbol_short!("topped_up"), escrow_id), amount);
+        true
+    }
+
+    /// Hand the depositor role, and future refunds, to a new address
+    /// (controller only), e.g. when the escrowed order is resold
+    pub fn set_depositor(env: Env, caller: Address, escrow_id: BytesN<32>, depositor: Address) -> bool {
+        caller.require_auth();
+
+        let mut escrow = Self::load(&env, &escrow_id);
+        Self::assert_role(&env, &escrow, &caller, &[EscrowRole::Controller]);
+        if escrow.status == EscrowStatus::Settled {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        let previous = escrow.depositor.clone();
+        escrow.depositor = depositor.clone();
+        env.storage().persistent().set(&DataKey::Escrow(escrow_id.clone()), &escrow);
+
+        env.events().publish((symbol_short!("depositor"), escrow_id), (previous, depositor));
+        true
+    }
+
+    /// Release part of the locked funds to the beneficiary
+    /// (controller, depositor or arbiter)
+    pub fn release(env: Env, caller: Address, escrow_id: BytesN<32>, amount: i128) -> bool {
+        caller.require_auth();
+
+        let mut escrow = Self::load(&env, &escrow_id);
+        Self::assert_role(
+            &env,
+            &escrow,
+            &caller,
+            &[EscrowRole::Controller, EscrowRole::Depositor, EscrowRole::Arbiter],
+        );
+        Self::assert_disbursable(&env, &escrow, amount);
+
+        let beneficiary = escrow.beneficiary.clone();
+        Self::pay_out(&env, &escrow, &beneficiary, amount);
+        escrow.released += amount;
+        Self::settle_if_drained(&mut escrow);
+        env.storage().persistent().set(&DataKey::Escrow(escrow_id.clone()), &escrow);
+
+        env.events().publish((symbol_short!("released"), escrow_id), (beneficiary, amount));
+        true
+    }
+
+    /// Return part of the locked funds to the depositor
+    /// (controller, beneficiary or arbiter)
+    pub fn refund(env: Env, caller: Address, escrow_id: BytesN<32>, amount: i128) -> bool {
+        caller.require_auth();
+
+        let mut escrow = Self::load(&env, &escrow_id);
+        Self::assert_role(
+            &env,
+            &escrow,
+            &caller,
+            &[EscrowRole::Controller, EscrowRole::Beneficiary, EscrowRole::Arbiter],
+        );
+        Self::assert_disbursable(&env, &escrow, amount);
+
+        let depositor = escrow.depositor.clone();
+        Self::pay_out(&env, &escrow, &depositor, amount);
+        escrow.refunded += amount;
+        Self::settle_if_drained(&mut escrow);
+        env.storage().persistent().set(&DataKey::Escrow(escrow_id.clone()), &escrow);
+
+        env.events().publish((symbol_short!("refunded"), escrow_id), (depositor, amount));
+        true
+    }
+
+    /// Distribute locked funds across several recipients in one call
+    /// (controller or arbiter). Payments to the depositor count as refunds,
+    /// everything else counts as released.
+    pub fn split(env: Env, caller: Address, escrow_id: BytesN<32>, shares: Vec<(Address, i128)>) -> bool {
+        caller.require_auth();
+
+        let mut escrow = Self::load(&env, &escrow_id);
+        Self::assert_role(&env, &escrow, &caller, &[EscrowRole::Controller, EscrowRole::Arbiter]);
+
+        let mut total: i128 = 0;
+        for (_, amount) in shares.iter() {
+            if amount <= 0 {
+                panic_with_error!(&env, Error::InvalidAmount);
+            }
+            total += amount;
+        }
+        Self::assert_disbursable(&env, &escrow, total);
+
+        for (recipient, amount) in shares.iter() {
+            Self::pay_out(&env, &escrow, &recipient, amount);
+            if recipient == escrow.depositor {
+                escrow.refunded += amount;
+            } else {
+                escrow.released += amount;
+            }
+        }
+        Self::settle_if_drained(&mut escrow);
+        env.storage().persistent().set(&DataKey::Escrow(escrow_id.clone()), &escrow);
+
+        env.events().publish((symbol_short!("split"), escrow_id), (shares.len(), total));
+        true
+    }
+
+    /// Get escrow details
+    pub fn get_escrow(env: Env, escrow_id: BytesN<32>) -> Escrow {
+        Self::load(&env, &escrow_id)
+    }
+
+    /// Get the amount still held for an escrow
+    pub fn get_remaining(env: Env, escrow_id: BytesN<32>) -> i128 {
+        let escrow = Self::load(&env, &escrow_id);
+        Self::remaining(&escrow)
+    }
+
+    /// Get the roles an address holds on an escrow
+    pub fn get_roles(env: Env, escrow_id: BytesN<32>, address: Address) -> Vec<EscrowRole> {
+        let escrow = Self::load(&env, &escrow_id);
+        let mut roles = Vec::new(&env);
+        for role in [EscrowRole::Controller, EscrowRole::Depositor, EscrowRole::Beneficiary, EscrowRole::Arbiter] {
+            if Self::has_role(&escrow, &address, &role) {
+                roles.push_back(role);
+            }
+        }
+        roles
+    }
+
+    /// Get total amount of a token currently locked across all escrows
+    pub fn get_total_locked(env: Env, token: Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::TotalLocked(token))
+            .unwrap_or(0)
+    }
+
+    // Helper functions
+    fn load(env: &Env, escrow_id: &BytesN<32>) -> Escrow {
+        env.storage().persistent()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, Error::EscrowNotFound))
+    }
+
+    fn has_role(escrow: &Escrow, address: &Address, role: &EscrowRole) -> bool {
+        match role {
+            EscrowRole::Controller => *address == escrow.controller,
+            EscrowRole::Depositor => *address == escrow.depositor,
+            EscrowRole::Beneficiary => *address == escrow.beneficiary,
+            EscrowRole::Arbiter => escrow.arbiter.as_ref() == Some(address),
+        }
+    }
+
+    fn assert_role(env: &Env, escrow: &Escrow, caller: &Address, allowed: &[EscrowRole]) {
+        if !allowed.iter().any(|role| Self::has_role(escrow, caller, role)) {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+    }
+
+    fn assert_disbursable(env: &Env, escrow: &Escrow, amount: i128) {
+        if escrow.status != EscrowStatus::Locked {
+            panic_with_error!(env, Error::InvalidStatus);
+        }
+        if amount <= 0 {
+            panic_with_error!(env, Error::InvalidAmount);
+        }
+        if amount > Self::remaining(escrow) {
+            panic_with_error!(env, Error::InsufficientEscrow);
+        }
+    }
+
+    fn remaining(escrow: &Escrow) -> i128 {
+        match escrow.status {
+            EscrowStatus::Created => 0,
+            _ => escrow.amount - escrow.released - escrow.refunded,
+        }
+    }
+
+    fn pay_out(env: &Env, escrow: &Escrow, to: &Address, amount: i128) {
+        token::Client::new(env, &escrow.token)
+            .transfer(&env.current_contract_address(), to, &amount);
+        Self::adjust_total_locked(env, &escrow.token, -amount);
+    }
+
+    fn settle_if_drained(escrow: &mut Escrow) {
+        if Self::remaining(escrow) == 0 {
+            escrow.status = EscrowStatus::Settled;
+        }
+    }
+
+    fn adjust_total_locked(env: &Env, token: &Address, delta: i128) {
+        let current: i128 = env.storage().persistent()
+            .get(&DataKey::TotalLocked(token.clone()))
+            .unwrap_or(0);
+        env.storage().persistent().set(&DataKey::TotalLocked(token.clone()), &(current + delta));
+    }
+}
+
+mod test;
@@ -0,0 +1,278 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::Address as AddressTestUtils,
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, Env
+};
+
+struct Setup<'a> {
+    escrow: EscrowContractClient<'a>,
+    token: TokenClient<'a>,
+    controller: Address,
+    depositor: Address,
+    beneficiary: Address,
+    arbiter: Address,
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let escrow = EscrowContractClient::new(env, &contract_id);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    let depositor = Address::generate(env);
+    StellarAssetClient::new(env, &token_address).mint(&depositor, &1_000);
+
+    Setup {
+        escrow,
+        token: TokenClient::new(env, &token_address),
+        controller: Address::generate(env),
+        depositor,
+        beneficiary: Address::generate(env),
+        arbiter: Address::generate(env),
+    }
+}
+
+fn create_locked(env: &Env, s: &Setup, amount: i128) -> BytesN<32> {
+    let escrow_id = BytesN::from_array(env, &[7u8; 32]);
+    s.escrow.create_escrow(
+        &s.controller,
+        &escrow_id,
+        &s.depositor,
+        &s.beneficiary,
+        &s.token.address,
+        &amount,
+        &Some(s.arbiter.clone()),
+    );
+    s.escrow.lock(&s.controller, &escrow_id);
+    escrow_id
+}
+
+#[test]
+fn test_create_and_lock() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    let escrow_id = create_locked(&env, &s, 400);
+
+    let escrow = s.escrow.get_escrow(&escrow_id);
+    assert_eq!(escrow.status, EscrowStatus::Locked);
+    assert_eq!(escrow.amount, 400);
+    assert_eq!(s.escrow.get_remaining(&escrow_id), 400);
+    assert_eq!(s.escrow.get_total_locked(&s.token.address), 400);
+    assert_eq!(s.token.balance(&s.depositor), 600);
+    assert_eq!(s.token.balance(&s.escrow.address), 400);
+}
+
+#[test]
+fn test_partial_release_then_refund_settles() {
+    let env = Env::default();
+    let s = setup(&env);
+    let escrow_id = create_locked(&env, &s, 400);
+
+    s.escrow.release(&s.controller, &escrow_id, &150);
+    assert_eq!(s.token.balance(&s.beneficiary), 150);
+    assert_eq!(s.escrow.get_escrow(&escrow_id).status, EscrowStatus::Locked);
+
+    s.escrow.refund(&s.arbiter, &escrow_id, &250);
+    assert_eq!(s.token.balance(&s.depositor), 850);
+
+    let escrow = s.escrow.get_escrow(&escrow_id);
+    assert_eq!(escrow.released, 150);
+    assert_eq!(escrow.refunded, 250);
+    assert_eq!(escrow.status, EscrowStatus::Settled);
+    assert_eq!(s.escrow.get_total_locked(&s.token.address), 0);
+}
+
+#[test]
+fn test_top_up_adds_to_locked_amount() {
+    let env = Env::default();
+    let s = setup(&env);
+    let escrow_id = create_locked(&env, &s, 400);
+
+    s.escrow.release(&s.controller, &escrow_id, &400);
+    assert_eq!(s.escrow.get_escrow(&escrow_id).status, EscrowStatus::Settled);
+    assert_eq!(
+        s.escrow.try_top_up(&s.controller, &escrow_id, &100),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidStatus as u32)))
+    );
+
+    let escrow_id = BytesN::from_array(&env, &[8u8; 32]);
+    s.escrow.create_escrow(&s.controller, &escrow_id, &s.depositor, &s.beneficiary, &s.token.address, &200, &None);
+    s.escrow.lock(&s.controller, &escrow_id);
+    s.escrow.top_up(&s.controller, &escrow_id, &100);
+
+    assert_eq!(s.escrow.get_escrow(&escrow_id).amount, 300);
+    assert_eq!(s.escrow.get_remaining(&escrow_id), 300);
+    assert_eq!(s.escrow.get_total_locked(&s.token.address), 300);
+    assert_eq!(s.token.balance(&s.depositor), 300);
+}
+
+#[test]
+fn test_split_between_recipients() {
+    let env = Env::default();
+    let s = setup(&env);
+    let escrow_id = create_locked(&env, &s, 400);
+    let provider = Address::generate(&env);
+
+    s.escrow.split(
+        &s.arbiter,
+        &escrow_id,
+        &vec![
+            &env,
+            (s.depositor.clone(), 100i128),
+            (s.beneficiary.clone(), 50i128),
+            (provider.clone(), 250i128),
+        ],
+    );
+
+    let escrow = s.escrow.get_escrow(&escrow_id);
+    assert_eq!(escrow.refunded, 100);
+    assert_eq!(escrow.released, 300);
+    assert_eq!(escrow.status, EscrowStatus::Settled);
+    assert_eq!(s.token.balance(&provider), 250);
+    assert_eq!(s.token.balance(&s.beneficiary), 50);
+}
+
+#[test]
+fn test_get_roles() {
+    let env = Env::default();
+    let s = setup(&env);
+    let escrow_id = create_locked(&env, &s, 100);
+
+    assert_eq!(s.escrow.get_roles(&escrow_id, &s.arbiter), vec![&env, EscrowRole::Arbiter]);
+    assert_eq!(s.escrow.get_roles(&escrow_id, &Address::generate(&env)).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #5)")]
+fn test_beneficiary_cannot_release() {
+    let env = Env::default();
+    let s = setup(&env);
+    let escrow_id = create_locked(&env, &s, 100);
+
+    s.escrow.release(&s.beneficiary, &escrow_id, &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #5)")]
+fn test_depositor_cannot_refund() {
+    let env = Env::default();
+    let s = setup(&env);
+    let escrow_id = create_locked(&env, &s, 100);
+
+    s.escrow.refund(&s.depositor, &escrow_id, &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #6)")]
+fn test_release_more_than_remaining() {
+    let env = Env::default();
+    let s = setup(&env);
+    let escrow_id = create_locked(&env, &s, 100);
+
+    s.escrow.release(&s.controller, &escrow_id, &60);
+    s.escrow.release(&s.controller, &escrow_id, &60);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #4)")]
+fn test_release_before_lock() {
+    let env = Env::default();
+    let s = setup(&env);
+    let escrow_id = BytesN::from_array(&env, &[1u8; 32]);
+
+    s.escrow.create_escrow(
+        &s.controller,
+        &escrow_id,
+        &s.depositor,
+        &s.beneficiary,
+        &s.token.address,
+        &100,
+        &None,
+    );
+    s.escrow.release(&s.controller, &escrow_id, &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_duplicate_escrow_id() {
+    let env = Env::default();
+    let s = setup(&env);
+    let escrow_id = create_locked(&env, &s, 100);
+
+    s.escrow.create_escrow(
+        &s.controller,
+        &escrow_id,
+        &s.depositor,
+        &s.beneficiary,
+        &s.token.address,
+        &100,
+        &None,
+    );
+}
+
+#[test]
+fn test_lock_funded_only_counts_unbacked_tokens() {
+    let env = Env::default();
+    let s = setup(&env);
+    create_locked(&env, &s, 300);
+
+    // The controller pushes the funds in itself, then locks the escrow
+    StellarAssetClient::new(&env, &s.token.address).mint(&s.controller, &200);
+    s.token.transfer(&s.controller, &s.escrow.address, &200);
+
+    let escrow_id = BytesN::from_array(&env, &[2u8; 32]);
+    s.escrow.create_escrow(
+        &s.controller,
+        &escrow_id,
+        &s.depositor,
+        &s.beneficiary,
+        &s.token.address,
+        &200,
+        &None,
+    );
+    s.escrow.lock_funded(&s.controller, &escrow_id);
+
+    assert_eq!(s.escrow.get_total_locked(&s.token.address), 500);
+    // Refunds still go to the depositor
+    s.escrow.refund(&s.controller, &escrow_id, &200);
+    assert_eq!(s.token.balance(&s.depositor), 900);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #6)")]
+fn test_lock_funded_without_funds() {
+    let env = Env::default();
+    let s = setup(&env);
+    create_locked(&env, &s, 300);
+
+    let escrow_id = BytesN::from_array(&env, &[2u8; 32]);
+    s.escrow.create_escrow(
+        &s.controller,
+        &escrow_id,
+        &s.depositor,
+        &s.beneficiary,
+        &s.token.address,
+        &200,
+        &None,
+    );
+    s.escrow.lock_funded(&s.controller, &escrow_id);
+}
+
+#[test]
+fn test_set_depositor_redirects_refunds() {
+    let env = Env::default();
+    let s = setup(&env);
+    let escrow_id = create_locked(&env, &s, 400);
+    let buyer = Address::generate(&env);
+
+    s.escrow.set_depositor(&s.controller, &escrow_id, &buyer);
+    s.escrow.refund(&s.controller, &escrow_id, &400);
+
+    assert_eq!(s.token.balance(&buyer), 400);
+    assert_eq!(s.token.balance(&s.depositor), 600);
+}
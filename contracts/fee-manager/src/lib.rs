@@ -0,0 +1,193 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, Env,
+    panic_with_error, symbol_short
+};
+
+/// Basis-point denominator (100% = 10_000 bps)
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+#[contract]
+pub struct FeeManagerContract;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub platform_fee_bps: u32,          // Platform cut of every settled order
+    pub cancellation_fee_bps: u32,      // Percentage kept when a user cancels
+    pub cancellation_fee_flat: i128,    // Flat amount kept when a user cancels
+    pub listing_fee: i128,              // Charged to providers for listing a DePIN
+    pub referral_bps: u32,              // Share of spend credited to referrers
+}
+
+#[contracttype]
+pub enum DataKey {
+    Governance,                 // Address allowed to change fees (admin or timelock)
+    FeeConfig,                  // Current FeeConfig
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotGovernance = 3,
+    InvalidFee = 4,
+}
+
+#[contractimpl]
+impl FeeManagerContract {
+    /// Initialize with the governance address and an all-zero fee schedule
+    pub fn initialize(env: Env, governance: Address) -> bool {
+        if env.storage().persistent().has(&DataKey::Governance) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+
+        env.storage().persistent().set(&DataKey::Governance, &governance);
+        env.storage().persistent().set(&DataKey::FeeConfig, &FeeConfig {
+            platform_fee_bps: 0,
+            cancellation_fee_bps: 0,
+            cancellation_fee_flat: 0,
+            listing_fee: 0,
+            referral_bps: 0,
+        });
+        true
+    }
+
+    /// Hand fee control to a new governance address, e.g. a timelock (governance only)
+    pub fn set_governance(env: Env, governance: Address, new_governance: Address) -> bool {
+        Self::assert_governance(&env, &governance);
+        env.storage().persistent().set(&DataKey::Governance, &new_governance);
+
+        env.events().publish((symbol_short!("gov"),), (governance, new_governance));
+        true
+    }
+
+    /// Replace the whole fee schedule (governance only)
+    pub fn set_fee_config(env: Env, governance: Address, config: FeeConfig) -> bool {
+        Self::assert_governance(&env, &governance);
+        Self::store_config(&env, &config);
+        true
+    }
+
+    /// Set the platform fee in basis points (governance only)
+    pub fn set_platform_fee_bps(env: Env, governance: Address, bps: u32) -> bool {
+        Self::assert_governance(&env, &governance);
+        let mut config = Self::config(&env);
+        config.platform_fee_bps = bps;
+        Self::store_config(&env, &config);
+        true
+    }
+
+    /// Set the cancellation fee as a percentage plus a flat amount (governance only)
+    pub fn set_cancellation_fee(env: Env, governance: Address, bps: u32, flat: i128) -> bool {
+        Self::assert_governance(&env, &governance);
+        let mut config = Self::config(&env);
+        config.cancellation_fee_bps = bps;
+        config.cancellation_fee_flat = flat;
+        Self::store_config(&env, &config);
+        true
+    }
+
+    /// Set the DePIN listing fee (governance only)
+    pub fn set_listing_fee(env: Env, governance: Address, fee: i128) -> bool {
+        Self::assert_governance(&env, &governance);
+        let mut config = Self::config(&env);
+        config.listing_fee = fee;
+        Self::store_config(&env, &config);
+        true
+    }
+
+    /// Set the referral reward in basis points (governance only)
+    pub fn set_referral_bps(env: Env, governance: Address, bps: u32) -> bool {
+        Self::assert_governance(&env, &governance);
+        let mut config = Self::config(&env);
+        config.referral_bps = bps;
+        Self::store_config(&env, &config);
+        true
+    }
+
+    /// Get the full fee schedule
+    pub fn get_fee_config(env: Env) -> FeeConfig {
+        Self::config(&env)
+    }
+
+    /// Get the governance address
+    pub fn get_governance(env: Env) -> Address {
+        env.storage().persistent()
+            .get(&DataKey::Governance)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+    }
+
+    /// Get the platform fee in basis points
+    pub fn get_platform_fee_bps(env: Env) -> u32 {
+        Self::config(&env).platform_fee_bps
+    }
+
+    /// Get the DePIN listing fee
+    pub fn get_listing_fee(env: Env) -> i128 {
+        Self::config(&env).listing_fee
+    }
+
+    /// Get the referral reward in basis points
+    pub fn get_referral_bps(env: Env) -> u32 {
+        Self::config(&env).referral_bps
+    }
+
+    /// Platform fee owed on a settled amount
+    pub fn quote_platform_fee(env: Env, amount: i128) -> i128 {
+        Self::apply_bps(amount, Self::config(&env).platform_fee_bps)
+    }
+
+    /// Cancellation fee owed on an escrowed amount, never more than the amount itself
+    pub fn quote_cancellation_fee(env: Env, amount: i128) -> i128 {
+        let config = Self::config(&env);
+        let fee = config.cancellation_fee_flat + Self::apply_bps(amount, config.cancellation_fee_bps);
+        fee.min(amount)
+    }
+
+    /// Referral reward owed on an amount spent by a referred user
+    pub fn quote_referral_reward(env: Env, amount: i128) -> i128 {
+        Self::apply_bps(amount, Self::config(&env).referral_bps)
+    }
+
+    // Helper functions
+    fn assert_governance(env: &Env, governance: &Address) {
+        let stored: Address = env.storage().persistent()
+            .get(&DataKey::Governance)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *governance != stored {
+            panic_with_error!(env, Error::NotGovernance);
+        }
+        governance.require_auth();
+    }
+
+    fn config(env: &Env) -> FeeConfig {
+        env.storage().persistent()
+            .get(&DataKey::FeeConfig)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized))
+    }
+
+    fn store_config(env: &Env, config: &FeeConfig) {
+        if config.platform_fee_bps > BPS_DENOMINATOR
+            || config.cancellation_fee_bps > BPS_DENOMINATOR
+            || config.referral_bps > BPS_DENOMINATOR
+            || config.cancellation_fee_flat < 0
+            || config.listing_fee < 0
+        {
+            panic_with_error!(env, Error::InvalidFee);
+        }
+
+        env.storage().persistent().set(&DataKey::FeeConfig, config);
+        env.events().publish((symbol_short!("fees"),), config.clone());
+    }
+
+    fn apply_bps(amount: i128, bps: u32) -> i128 {
+        amount * bps as i128 / BPS_DENOMINATOR as i128
+    }
+}
+
+mod test;
@@ -0,0 +1,100 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{testutils::Address as AddressTestUtils, Address, Env};
+
+fn init_fee_manager<'a>(env: &'a Env, governance: &Address) -> FeeManagerContractClient<'a> {
+    let contract_id = env.register(FeeManagerContract, ());
+    let client = FeeManagerContractClient::new(env, &contract_id);
+    client.initialize(governance);
+    client
+}
+
+#[test]
+fn test_fee_manager_initialization() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let governance = Address::generate(&env);
+    let fees = init_fee_manager(&env, &governance);
+
+    assert_eq!(fees.get_governance(), governance);
+    assert_eq!(fees.get_platform_fee_bps(), 0);
+    assert_eq!(fees.quote_cancellation_fee(&1_000), 0);
+}
+
+#[test]
+fn test_set_and_quote_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let governance = Address::generate(&env);
+    let fees = init_fee_manager(&env, &governance);
+
+    fees.set_platform_fee_bps(&governance, &250);
+    fees.set_cancellation_fee(&governance, &1_000, &5);
+    fees.set_listing_fee(&governance, &100);
+    fees.set_referral_bps(&governance, &50);
+
+    let config = fees.get_fee_config();
+    assert_eq!(config.platform_fee_bps, 250);
+    assert_eq!(config.listing_fee, 100);
+
+    assert_eq!(fees.quote_platform_fee(&10_000), 250);
+    assert_eq!(fees.quote_cancellation_fee(&1_000), 105);
+    // Cancellation fee never exceeds the escrowed amount
+    assert_eq!(fees.quote_cancellation_fee(&4), 4);
+    assert_eq!(fees.quote_referral_reward(&10_000), 50);
+}
+
+#[test]
+fn test_governance_handover() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let governance = Address::generate(&env);
+    let timelock = Address::generate(&env);
+    let fees = init_fee_manager(&env, &governance);
+
+    fees.set_governance(&governance, &timelock);
+    fees.set_listing_fee(&timelock, &10);
+
+    assert_eq!(fees.get_governance(), timelock);
+    assert_eq!(fees.get_listing_fee(), 10);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #3)")]
+fn test_non_governance_cannot_set_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let governance = Address::generate(&env);
+    let fees = init_fee_manager(&env, &governance);
+
+    fees.set_platform_fee_bps(&Address::generate(&env), &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #4)")]
+fn test_bps_above_denominator_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let governance = Address::generate(&env);
+    let fees = init_fee_manager(&env, &governance);
+
+    fees.set_referral_bps(&governance, &10_001);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #2)")]
+fn test_double_initialization() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let governance = Address::generate(&env);
+    let fees = init_fee_manager(&env, &governance);
+
+    fees.initialize(&governance);
+}
@@ -0,0 +1,11 @@
+//! Typed interfaces of the contracts gift cards redeem into.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Subset of the user-profile contract used to credit redeemed value.
+#[allow(dead_code)]
+#[contractclient(name = "UserProfileClient")]
+pub trait UserProfileInterface {
+    fn is_token_whitelisted(env: Env, token_address: Address) -> bool;
+    fn credit_balance(env: Env, source: Address, user_address: Address, token_address: Address, amount: i128);
+}
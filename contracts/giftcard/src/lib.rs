@@ -0,0 +1,225 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, Bytes, BytesN, Env,
+    panic_with_error, symbol_short, token
+};
+
+mod interfaces;
+use interfaces::UserProfileClient;
+
+#[contract]
+pub struct GiftCardContract;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GiftCard {
+    pub code_hash: BytesN<32>,          // sha256 of the secret redemption code
+    pub issuer: Address,                // Party that funded the card
+    pub token: Address,
+    pub amount: i128,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub status: GiftCardStatus,
+    pub redeemed_by: Option<Address>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GiftCardStatus {
+    Active,         // Funded and redeemable
+    Redeemed,       // Credited to a user's profile balance
+    ClawedBack,     // Expired unredeemed, value returned to treasury
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Treasury,                   // Receives clawed-back value
+    UserProfileContract,        // Where redeemed value is credited
+    Card(BytesN<32>),           // code_hash -> GiftCard
+    Outstanding(Address),       // token -> i128 issued but not yet redeemed or clawed back
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    CardExists = 4,
+    CardNotFound = 5,
+    CardNotActive = 6,
+    Expired = 7,
+    NotExpired = 8,
+    InvalidAmount = 9,
+    TokenNotWhitelisted = 10,
+}
+
+#[contractimpl]
+impl GiftCardContract {
+    /// Initialize with admin, treasury and the user-profile contract credits go to
+    pub fn initialize(env: Env, admin: Address, treasury: Address, user_profile_contract: Address) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::Treasury, &treasury);
+        env.storage().persistent().set(&DataKey::UserProfileContract, &user_profile_contract);
+        true
+    }
+
+    /// Set treasury address (admin only)
+    pub fn set_treasury(env: Env, admin: Address, treasury: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::Treasury, &treasury);
+        true
+    }
+
+    /// Issue a prepaid card funded by the issuer in a profile-whitelisted token
+    ///
+    /// Only the hash of the redemption code is stored; the code itself is
+    /// handed to the recipient off-chain.
+    pub fn issue(
+        env: Env,
+        issuer: Address,
+        code_hash: BytesN<32>,
+        token: Address,
+        amount: i128,
+        expires_at: u64,
+    ) -> bool {
+        issuer.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            panic_with_error!(&env, Error::Expired);
+        }
+        if env.storage().persistent().has(&DataKey::Card(code_hash.clone())) {
+            panic_with_error!(&env, Error::CardExists);
+        }
+        if !Self::profile(&env).is_token_whitelisted(&token) {
+            panic_with_error!(&env, Error::TokenNotWhitelisted);
+        }
+
+        token::Client::new(&env, &token)
+            .transfer(&issuer, &env.current_contract_address(), &amount);
+
+        let card = GiftCard {
+            code_hash: code_hash.clone(),
+            issuer,
+            token: token.clone(),
+            amount,
+            issued_at: env.ledger().timestamp(),
+            expires_at,
+            status: GiftCardStatus::Active,
+            redeemed_by: None,
+        };
+        env.storage().persistent().set(&DataKey::Card(code_hash.clone()), &card);
+        Self::adjust_outstanding(&env, &token, amount);
+
+        env.events().publish((symbol_short!("issued"), code_hash), (card.issuer, token, amount));
+        true
+    }
+
+    /// Redeem a card into the user's profile balance by revealing its code
+    pub fn redeem(env: Env, user: Address, code: Bytes) -> i128 {
+        user.require_auth();
+
+        let code_hash: BytesN<32> = env.crypto().sha256(&code).to_bytes();
+        let mut card = Self::load(&env, &code_hash);
+        if card.status != GiftCardStatus::Active {
+            panic_with_error!(&env, Error::CardNotActive);
+        }
+        if env.ledger().timestamp() > card.expires_at {
+            panic_with_error!(&env, Error::Expired);
+        }
+
+        card.status = GiftCardStatus::Redeemed;
+        card.redeemed_by = Some(user.clone());
+        env.storage().persistent().set(&DataKey::Card(code_hash.clone()), &card);
+        Self::adjust_outstanding(&env, &card.token, -card.amount);
+
+        // Move custody to the profile contract, then credit the user's ledger balance
+        let profile = Self::profile(&env);
+        token::Client::new(&env, &card.token)
+            .transfer(&env.current_contract_address(), &profile.address, &card.amount);
+        profile.credit_balance(&env.current_contract_address(), &user, &card.token, &card.amount);
+
+        env.events().publish((symbol_short!("redeemed"), code_hash), (user, card.amount));
+        card.amount
+    }
+
+    /// Return the value of an expired, unredeemed card to the treasury (anyone)
+    pub fn clawback(env: Env, code_hash: BytesN<32>) -> i128 {
+        let mut card = Self::load(&env, &code_hash);
+        if card.status != GiftCardStatus::Active {
+            panic_with_error!(&env, Error::CardNotActive);
+        }
+        if env.ledger().timestamp() <= card.expires_at {
+            panic_with_error!(&env, Error::NotExpired);
+        }
+
+        card.status = GiftCardStatus::ClawedBack;
+        env.storage().persistent().set(&DataKey::Card(code_hash.clone()), &card);
+        Self::adjust_outstanding(&env, &card.token, -card.amount);
+
+        let treasury: Address = env.storage().persistent()
+            .get(&DataKey::Treasury)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        token::Client::new(&env, &card.token)
+            .transfer(&env.current_contract_address(), &treasury, &card.amount);
+
+        env.events().publish((symbol_short!("clawback"), code_hash), (treasury, card.amount));
+        card.amount
+    }
+
+    /// Get card details by code hash
+    pub fn get_card(env: Env, code_hash: BytesN<32>) -> GiftCard {
+        Self::load(&env, &code_hash)
+    }
+
+    /// Get the value of a token held for cards that are still redeemable
+    pub fn get_outstanding(env: Env, token: Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::Outstanding(token))
+            .unwrap_or(0)
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn profile(env: &Env) -> UserProfileClient<'_> {
+        let address: Address = env.storage().persistent()
+            .get(&DataKey::UserProfileContract)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        UserProfileClient::new(env, &address)
+    }
+
+    fn load(env: &Env, code_hash: &BytesN<32>) -> GiftCard {
+        env.storage().persistent()
+            .get(&DataKey::Card(code_hash.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, Error::CardNotFound))
+    }
+
+    fn adjust_outstanding(env: &Env, token: &Address, delta: i128) {
+        let current: i128 = env.storage().persistent()
+            .get(&DataKey::Outstanding(token.clone()))
+            .unwrap_or(0);
+        env.storage().persistent().set(&DataKey::Outstanding(token.clone()), &(current + delta));
+    }
+}
+
+mod test;
@@ -0,0 +1,161 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Bytes, Env, String
+};
+use user_profile::{UserProfileContract, UserProfileContractClient};
+
+struct Setup<'a> {
+    giftcards: GiftCardContractClient<'a>,
+    profile: UserProfileContractClient<'a>,
+    token: TokenClient<'a>,
+    issuer: Address,
+    user: Address,
+    treasury: Address,
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    let admin = Address::generate(env);
+    let issuer = Address::generate(env);
+    let user = Address::generate(env);
+    let treasury = Address::generate(env);
+
+    let token_id = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    StellarAssetClient::new(env, &token_id).mint(&issuer, &1_000);
+
+    let profile_id = env.register(UserProfileContract, ());
+    let profile = UserProfileContractClient::new(env, &profile_id);
+    profile.initialize(&admin, &token_id);
+    profile.create_user_profile(
+        &user,
+        &String::from_str(env, "alice"),
+        &BytesN::from_array(env, &[1u8; 32]),
+        &None,
+    );
+
+    let giftcard_id = env.register(GiftCardContract, ());
+    let giftcards = GiftCardContractClient::new(env, &giftcard_id);
+    giftcards.initialize(&admin, &treasury, &profile_id);
+    profile.set_credit_source(&admin, &giftcard_id, &true);
+
+    Setup {
+        giftcards,
+        profile,
+        token: TokenClient::new(env, &token_id),
+        issuer,
+        user,
+        treasury,
+    }
+}
+
+fn code_hash(env: &Env, code: &Bytes) -> BytesN<32> {
+    env.crypto().sha256(code).to_bytes()
+}
+
+#[test]
+fn test_issue_and_redeem_into_profile() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup(&env);
+
+    let code = Bytes::from_slice(&env, b"GIFT-1234");
+    let hash = code_hash(&env, &code);
+    s.giftcards.issue(&s.issuer, &hash, &s.token.address, &250, &1_000);
+    assert_eq!(s.token.balance(&s.giftcards.address), 250);
+    assert_eq!(s.giftcards.get_outstanding(&s.token.address), 250);
+
+    assert_eq!(s.giftcards.redeem(&s.user, &code), 250);
+
+    let card = s.giftcards.get_card(&hash);
+    assert_eq!(card.status, GiftCardStatus::Redeemed);
+    assert_eq!(card.redeemed_by, Some(s.user.clone()));
+    assert_eq!(s.profile.get_user_balance(&s.user, &s.token.address), 250);
+    assert_eq!(s.token.balance(&s.profile.address), 250);
+    assert_eq!(s.giftcards.get_outstanding(&s.token.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_redeem_twice_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup(&env);
+
+    let code = Bytes::from_slice(&env, b"GIFT-1234");
+    s.giftcards.issue(&s.issuer, &code_hash(&env, &code), &s.token.address, &250, &1_000);
+    s.giftcards.redeem(&s.user, &code);
+    s.giftcards.redeem(&s.user, &code);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_redeem_wrong_code_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup(&env);
+
+    let code = Bytes::from_slice(&env, b"GIFT-1234");
+    s.giftcards.issue(&s.issuer, &code_hash(&env, &code), &s.token.address, &250, &1_000);
+    s.giftcards.redeem(&s.user, &Bytes::from_slice(&env, b"GIFT-0000"));
+}
+
+#[test]
+fn test_clawback_after_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup(&env);
+
+    let code = Bytes::from_slice(&env, b"GIFT-1234");
+    let hash = code_hash(&env, &code);
+    s.giftcards.issue(&s.issuer, &hash, &s.token.address, &250, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_001);
+    assert_eq!(s.giftcards.clawback(&hash), 250);
+
+    assert_eq!(s.giftcards.get_card(&hash).status, GiftCardStatus::ClawedBack);
+    assert_eq!(s.token.balance(&s.treasury), 250);
+    assert_eq!(s.giftcards.get_outstanding(&s.token.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_clawback_before_expiry_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup(&env);
+
+    let code = Bytes::from_slice(&env, b"GIFT-1234");
+    let hash = code_hash(&env, &code);
+    s.giftcards.issue(&s.issuer, &hash, &s.token.address, &250, &1_000);
+    s.giftcards.clawback(&hash);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_redeem_after_expiry_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup(&env);
+
+    let code = Bytes::from_slice(&env, b"GIFT-1234");
+    s.giftcards.issue(&s.issuer, &code_hash(&env, &code), &s.token.address, &250, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_001);
+    s.giftcards.redeem(&s.user, &code);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_issue_requires_whitelisted_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup(&env);
+
+    let other = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    StellarAssetClient::new(&env, &other).mint(&s.issuer, &1_000);
+    let code = Bytes::from_slice(&env, b"GIFT-1234");
+    s.giftcards.issue(&s.issuer, &code_hash(&env, &code), &other, &250, &1_000);
+}
@@ -0,0 +1,144 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, Env, Symbol,
+    panic_with_error, symbol_short
+};
+
+#[contract]
+pub struct GuardianContract;
+
+#[contracttype]
+pub enum DataKey {
+    Admin,                              // Manages guardians, co-signs unpauses
+    Timelock,                           // Co-signs unpauses
+    Guardian(Address),                  // address -> bool, parties allowed to pause
+    GlobalPause,                        // bool, pauses every wired contract
+    ContractPause(Address),             // contract -> bool
+    FunctionPause(Address, Symbol),     // (contract, entry point) -> bool
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    NotGuardian = 4,
+}
+
+#[contractimpl]
+impl GuardianContract {
+    /// Initialize with the admin and the timelock that must co-sign unpauses
+    pub fn initialize(env: Env, admin: Address, timelock: Address) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::Timelock, &timelock);
+        true
+    }
+
+    /// Allow or disallow a guardian (admin only)
+    pub fn set_guardian(env: Env, admin: Address, guardian: Address, allowed: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::Guardian(guardian), &allowed);
+        true
+    }
+
+    /// Pause every wired contract (guardians only)
+    pub fn pause_all(env: Env, guardian: Address) -> bool {
+        Self::assert_guardian(&env, &guardian);
+        env.storage().persistent().set(&DataKey::GlobalPause, &true);
+        env.events().publish((symbol_short!("pause"),), guardian);
+        true
+    }
+
+    /// Pause every guarded entry point of one contract (guardians only)
+    pub fn pause_contract(env: Env, guardian: Address, target: Address) -> bool {
+        Self::assert_guardian(&env, &guardian);
+        env.storage().persistent().set(&DataKey::ContractPause(target.clone()), &true);
+        env.events().publish((symbol_short!("pause"), target), guardian);
+        true
+    }
+
+    /// Pause one entry point of a contract (guardians only)
+    pub fn pause_function(env: Env, guardian: Address, target: Address, function: Symbol) -> bool {
+        Self::assert_guardian(&env, &guardian);
+        env.storage().persistent().set(&DataKey::FunctionPause(target.clone(), function.clone()), &true);
+        env.events().publish((symbol_short!("pause"), target, function), guardian);
+        true
+    }
+
+    /// Lift the global pause (admin and timelock)
+    pub fn unpause_all(env: Env) -> bool {
+        Self::assert_admin_and_timelock(&env);
+        env.storage().persistent().remove(&DataKey::GlobalPause);
+        env.events().publish((symbol_short!("unpause"),), ());
+        true
+    }
+
+    /// Lift a contract-wide pause (admin and timelock)
+    pub fn unpause_contract(env: Env, target: Address) -> bool {
+        Self::assert_admin_and_timelock(&env);
+        env.storage().persistent().remove(&DataKey::ContractPause(target.clone()));
+        env.events().publish((symbol_short!("unpause"), target), ());
+        true
+    }
+
+    /// Lift the pause on one entry point (admin and timelock)
+    pub fn unpause_function(env: Env, target: Address, function: Symbol) -> bool {
+        Self::assert_admin_and_timelock(&env);
+        env.storage().persistent().remove(&DataKey::FunctionPause(target.clone(), function.clone()));
+        env.events().publish((symbol_short!("unpause"), target, function), ());
+        true
+    }
+
+    /// Check whether an entry point of a contract is paused, globally or selectively
+    pub fn is_paused(env: Env, target: Address, function: Symbol) -> bool {
+        let storage = env.storage().persistent();
+        storage.get(&DataKey::GlobalPause).unwrap_or(false)
+            || storage.get(&DataKey::ContractPause(target.clone())).unwrap_or(false)
+            || storage.get(&DataKey::FunctionPause(target, function)).unwrap_or(false)
+    }
+
+    /// Check whether an address is a guardian
+    pub fn is_guardian(env: Env, guardian: Address) -> bool {
+        env.storage().persistent()
+            .get(&DataKey::Guardian(guardian))
+            .unwrap_or(false)
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn assert_guardian(env: &Env, guardian: &Address) {
+        if !Self::is_guardian(env.clone(), guardian.clone()) {
+            panic_with_error!(env, Error::NotGuardian);
+        }
+        guardian.require_auth();
+    }
+
+    fn assert_admin_and_timelock(env: &Env) {
+        let admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        let timelock: Address = env.storage().persistent()
+            .get(&DataKey::Timelock)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        admin.require_auth();
+        timelock.require_auth();
+    }
+}
+
+mod test;
@@ -0,0 +1,72 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils},
+    Address, Env, Symbol
+};
+
+struct Setup<'a> {
+    guardian: GuardianContractClient<'a>,
+    admin: Address,
+    timelock: Address,
+    watcher: Address,
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let timelock = Address::generate(env);
+    let watcher = Address::generate(env);
+    let guardian = GuardianContractClient::new(env, &env.register(GuardianContract, ()));
+    guardian.initialize(&admin, &timelock);
+    guardian.set_guardian(&admin, &watcher, &true);
+
+    Setup { guardian, admin, timelock, watcher }
+}
+
+#[test]
+fn test_selective_pause_scopes() {
+    let env = Env::default();
+    let s = setup(&env);
+    let order = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let create = Symbol::new(&env, "create_order");
+    let complete = Symbol::new(&env, "complete_order");
+
+    s.guardian.pause_function(&s.watcher, &order, &create);
+    assert!(s.guardian.is_paused(&order, &create));
+    assert!(!s.guardian.is_paused(&order, &complete));
+
+    s.guardian.pause_contract(&s.watcher, &order);
+    assert!(s.guardian.is_paused(&order, &complete));
+    assert!(!s.guardian.is_paused(&treasury, &symbol_short!("deposit")));
+
+    s.guardian.pause_all(&s.watcher);
+    assert!(s.guardian.is_paused(&treasury, &symbol_short!("deposit")));
+}
+
+#[test]
+fn test_unpause_needs_admin_and_timelock() {
+    let env = Env::default();
+    let s = setup(&env);
+    let order = Address::generate(&env);
+
+    s.guardian.pause_all(&s.watcher);
+    s.guardian.unpause_all();
+
+    let auths = env.auths();
+    assert!(auths.iter().any(|(address, _)| *address == s.admin));
+    assert!(auths.iter().any(|(address, _)| *address == s.timelock));
+    assert!(!s.guardian.is_paused(&order, &symbol_short!("deposit")));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_only_guardians_pause() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.guardian.pause_all(&s.admin);
+}
@@ -0,0 +1,12 @@
+//! Typed interfaces of the contracts resales go through.
+
+use soroban_sdk::{contractclient, Address, BytesN, Env};
+
+/// Subset of the order contract used to check and move order ownership.
+#[allow(dead_code)]
+#[contractclient(name = "OrderClient")]
+pub trait OrderInterface {
+    fn get_payment_token(env: Env) -> Option<Address>;
+    fn get_order_user(env: Env, order_id: BytesN<32>) -> Address;
+    fn transfer_order(env: Env, agent: Address, order_id: BytesN<32>, from: Address, to: Address) -> bool;
+}
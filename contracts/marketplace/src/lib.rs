@@ -0,0 +1,255 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, BytesN, Env,
+    panic_with_error, symbol_short, token
+};
+
+mod interfaces;
+use interfaces::OrderClient;
+
+/// Basis-point denominator for the resale fee (100% = 10_000 bps)
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+#[contract]
+pub struct MarketplaceContract;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Listing {
+    pub listing_id: u64,
+    pub order_id: BytesN<32>,
+    pub seller: Address,
+    pub token: Address,             // Order payment token at listing time
+    pub price: i128,
+    pub status: ListingStatus,
+    pub buyer: Option<Address>,
+    pub created_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ListingStatus {
+    Active,         // Open for purchase
+    Sold,           // Paid for and order transferred to the buyer
+    Cancelled,      // Withdrawn by the seller, or replaced after the order changed hands
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    OrderContract,              // Order contract whose orders are resold
+    Treasury,                   // Receives resale fees
+    FeeBps,                     // u32 resale fee in basis points
+    ListingCounter,             // u64 counter for listing IDs
+    Listing(u64),               // listing_id -> Listing
+    OrderListing(BytesN<32>),   // order_id -> active listing_id
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    NotOwner = 4,
+    ListingNotFound = 5,
+    ListingClosed = 6,
+    AlreadyListed = 7,
+    InvalidPrice = 8,
+    InvalidFee = 9,
+    PaymentTokenNotSet = 10,
+}
+
+#[contractimpl]
+impl MarketplaceContract {
+    /// Initialize with admin, order contract, fee treasury and resale fee
+    pub fn initialize(env: Env, admin: Address, order_contract: Address, treasury: Address, fee_bps: u32) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+        if fee_bps > BPS_DENOMINATOR {
+            panic_with_error!(&env, Error::InvalidFee);
+        }
+
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::OrderContract, &order_contract);
+        env.storage().persistent().set(&DataKey::Treasury, &treasury);
+        env.storage().persistent().set(&DataKey::FeeBps, &fee_bps);
+        env.storage().persistent().set(&DataKey::ListingCounter, &0u64);
+        true
+    }
+
+    /// Set the resale fee in basis points (admin only)
+    pub fn set_fee_bps(env: Env, admin: Address, fee_bps: u32) -> bool {
+        Self::assert_admin(&env, &admin);
+        if fee_bps > BPS_DENOMINATOR {
+            panic_with_error!(&env, Error::InvalidFee);
+        }
+        env.storage().persistent().set(&DataKey::FeeBps, &fee_bps);
+        true
+    }
+
+    /// Set treasury address (admin only)
+    pub fn set_treasury(env: Env, admin: Address, treasury: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::Treasury, &treasury);
+        true
+    }
+
+    /// List an order the seller owns for sale, replacing any listing a
+    /// previous owner left open
+    pub fn list(env: Env, seller: Address, order_id: BytesN<32>, price: i128) -> u64 {
+        seller.require_auth();
+
+        if price <= 0 {
+            panic_with_error!(&env, Error::InvalidPrice);
+        }
+        let order = Self::order(&env);
+        if order.get_order_user(&order_id) != seller {
+            panic_with_error!(&env, Error::NotOwner);
+        }
+        // A listing left behind by a previous owner, e.g. after a gift, is replaced
+        let existing: Option<u64> = env.storage().persistent().get(&DataKey::OrderListing(order_id.clone()));
+        if let Some(existing_id) = existing {
+            let mut stale = Self::load(&env, existing_id);
+            if stale.seller == seller {
+                panic_with_error!(&env, Error::AlreadyListed);
+            }
+            stale.status = ListingStatus::Cancelled;
+            env.storage().persistent().set(&DataKey::Listing(existing_id), &stale);
+            env.events().publish((symbol_short!("delisted"), existing_id), stale.seller);
+        }
+        let token = order
+            .get_payment_token()
+            .unwrap_or_else(|| panic_with_error!(&env, Error::PaymentTokenNotSet));
+
+        let mut counter: u64 = env.storage().persistent()
+            .get(&DataKey::ListingCounter)
+            .unwrap_or(0);
+        counter += 1;
+        env.storage().persistent().set(&DataKey::ListingCounter, &counter);
+
+        let listing = Listing {
+            listing_id: counter,
+            order_id: order_id.clone(),
+            seller,
+            token,
+            price,
+            status: ListingStatus::Active,
+            buyer: None,
+            created_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&DataKey::Listing(counter), &listing);
+        env.storage().persistent().set(&DataKey::OrderListing(order_id.clone()), &counter);
+
+        env.events().publish((symbol_short!("listed"), counter), (order_id, price));
+        counter
+    }
+
+    /// Withdraw an active listing (seller only)
+    pub fn delist(env: Env, seller: Address, listing_id: u64) -> bool {
+        seller.require_auth();
+
+        let mut listing = Self::load(&env, listing_id);
+        if listing.seller != seller {
+            panic_with_error!(&env, Error::NotOwner);
+        }
+        if listing.status != ListingStatus::Active {
+            panic_with_error!(&env, Error::ListingClosed);
+        }
+
+        listing.status = ListingStatus::Cancelled;
+        env.storage().persistent().set(&DataKey::Listing(listing_id), &listing);
+        env.storage().persistent().remove(&DataKey::OrderListing(listing.order_id));
+
+        env.events().publish((symbol_short!("delisted"), listing_id), seller);
+        true
+    }
+
+    /// Buy a listed order: pay the seller minus the resale fee and take over the order
+    pub fn buy(env: Env, buyer: Address, listing_id: u64) -> bool {
+        buyer.require_auth();
+
+        let mut listing = Self::load(&env, listing_id);
+        if listing.status != ListingStatus::Active {
+            panic_with_error!(&env, Error::ListingClosed);
+        }
+
+        let fee = Self::quote_fee(env.clone(), listing.price);
+        let client = token::Client::new(&env, &listing.token);
+        if fee > 0 {
+            let treasury: Address = env.storage().persistent()
+                .get(&DataKey::Treasury)
+                .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+            client.transfer(&buyer, &treasury, &fee);
+        }
+        client.transfer(&buyer, &listing.seller, &(listing.price - fee));
+
+        // Fails if the seller no longer owns the order or it is already finished
+        Self::order(&env).transfer_order(
+            &env.current_contract_address(),
+            &listing.order_id,
+            &listing.seller,
+            &buyer,
+        );
+
+        listing.status = ListingStatus::Sold;
+        listing.buyer = Some(buyer.clone());
+        env.storage().persistent().set(&DataKey::Listing(listing_id), &listing);
+        env.storage().persistent().remove(&DataKey::OrderListing(listing.order_id));
+
+        env.events().publish((symbol_short!("sold"), listing_id), (buyer, listing.price, fee));
+        true
+    }
+
+    /// Resale fee charged on a sale at `price`
+    pub fn quote_fee(env: Env, price: i128) -> i128 {
+        price * Self::get_fee_bps(env) as i128 / BPS_DENOMINATOR as i128
+    }
+
+    /// Get the resale fee in basis points
+    pub fn get_fee_bps(env: Env) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::FeeBps)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+    }
+
+    /// Get listing details
+    pub fn get_listing(env: Env, listing_id: u64) -> Listing {
+        Self::load(&env, listing_id)
+    }
+
+    /// Get the active listing of an order, if any
+    pub fn get_order_listing(env: Env, order_id: BytesN<32>) -> Option<u64> {
+        env.storage().persistent().get(&DataKey::OrderListing(order_id))
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn order(env: &Env) -> OrderClient<'_> {
+        let address: Address = env.storage().persistent()
+            .get(&DataKey::OrderContract)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        OrderClient::new(env, &address)
+    }
+
+    fn load(env: &Env, listing_id: u64) -> Listing {
+        env.storage().persistent()
+            .get(&DataKey::Listing(listing_id))
+            .unwrap_or_else(|| panic_with_error!(env, Error::ListingNotFound))
+    }
+}
+
+mod test;
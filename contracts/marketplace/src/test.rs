@@ -0,0 +1,190 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::Address as AddressTestUtils,
+    token::{StellarAssetClient, TokenClient},
+    Address, Env, String
+};
+use order::{OrderContract, OrderContractClient, OrderPriority, OrderStatus};
+
+/// Stand-in for the DePIN registry that accepts every DePIN ID, priced at 10 per hour
+#[contract]
+struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn depin_exists(_env: Env, _depin_id: BytesN<32>) -> bool {
+        true
+    }
+
+    pub fn supports_chain(_env: Env, _depin_id: BytesN<32>, _chain: String) -> bool {
+        true
+    }
+
+    pub fn get_payout_address(_env: Env, _depin_id: BytesN<32>) -> Option<Address> {
+        None
+    }
+
+    pub fn get_service_price(_env: Env, _depin_id: BytesN<32>, _service_type: String) -> Option<i128> {
+        Some(10)
+    }
+
+    pub fn reserve_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+
+    pub fn release_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+}
+
+struct Setup<'a> {
+    market: MarketplaceContractClient<'a>,
+    orders: OrderContractClient<'a>,
+    token: TokenClient<'a>,
+    admin: Address,
+    treasury: Address,
+    seller: Address,
+    order_id: BytesN<32>,
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let seller = Address::generate(env);
+    let treasury = Address::generate(env);
+    let token_id = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    StellarAssetClient::new(env, &token_id).mint(&seller, &1_000);
+
+    let orders = OrderContractClient::new(env, &env.register(OrderContract, ()));
+    orders.initialize(&admin);
+    orders.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    orders.set_escrow_contract(&admin, &env.register(escrow::EscrowContract, ()));
+    orders.set_payment_token(&admin, &token_id);
+    orders.set_treasury_wallet(&admin, &Address::generate(env));
+
+    let order_id = orders.create_order(
+        &seller,
+        &BytesN::from_array(env, &[1u8; 32]),
+        &String::from_str(env, "gpu"),
+        &24,
+        &10,
+        &token_id,
+        &String::from_str(env, "ethereum"),
+        &String::from_str(env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+
+    let market = MarketplaceContractClient::new(env, &env.register(MarketplaceContract, ()));
+    market.initialize(&admin, &orders.address, &treasury, &500);
+    orders.set_transfer_agent(&admin, &market.address, &true);
+
+    Setup {
+        market,
+        orders,
+        token: TokenClient::new(env, &token_id),
+        admin,
+        treasury,
+        seller,
+        order_id,
+    }
+}
+
+fn buyer(env: &Env, s: &Setup) -> Address {
+    let buyer = Address::generate(env);
+    StellarAssetClient::new(env, &s.token.address).mint(&buyer, &1_000);
+    buyer
+}
+
+#[test]
+fn test_buy_transfers_order_and_pays_fee() {
+    let env = Env::default();
+    let s = setup(&env);
+    let buyer = buyer(&env, &s);
+
+    let listing_id = s.market.list(&s.seller, &s.order_id, &200);
+    assert_eq!(s.market.get_order_listing(&s.order_id), Some(listing_id));
+
+    s.market.buy(&buyer, &listing_id);
+
+    // 5% resale fee to the treasury, the rest to the seller
+    assert_eq!(s.token.balance(&s.treasury), 10);
+    assert_eq!(s.token.balance(&s.seller), 760 + 190);
+    assert_eq!(s.token.balance(&buyer), 800);
+
+    assert_eq!(s.orders.get_order_user(&s.order_id), buyer);
+    let listing = s.market.get_listing(&listing_id);
+    assert_eq!(listing.status, ListingStatus::Sold);
+    assert_eq!(listing.buyer, Some(buyer));
+    assert_eq!(s.market.get_order_listing(&s.order_id), None);
+}
+
+#[test]
+fn test_delist_allows_relisting() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    let listing_id = s.market.list(&s.seller, &s.order_id, &200);
+    s.market.delist(&s.seller, &listing_id);
+    assert_eq!(s.market.get_listing(&listing_id).status, ListingStatus::Cancelled);
+
+    let relisted = s.market.list(&s.seller, &s.order_id, &150);
+    assert_eq!(s.market.get_listing(&relisted).price, 150);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_only_owner_can_list() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.market.list(&Address::generate(&env), &s.order_id, &200);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_cannot_list_twice() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.market.list(&s.seller, &s.order_id, &200);
+    s.market.list(&s.seller, &s.order_id, &300);
+}
+
+#[test]
+fn test_gifted_order_can_be_relisted_by_new_owner() {
+    let env = Env::default();
+    let s = setup(&env);
+    let friend = Address::generate(&env);
+
+    let stale_id = s.market.list(&s.seller, &s.order_id, &200);
+    s.orders.gift_order(&s.seller, &s.order_id, &friend);
+
+    let listing_id = s.market.list(&friend, &s.order_id, &300);
+    assert_eq!(s.market.get_listing(&stale_id).status, ListingStatus::Cancelled);
+    assert_eq!(s.market.get_order_listing(&s.order_id), Some(listing_id));
+    assert_eq!(s.market.get_listing(&listing_id).seller, friend);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_buy_fails_once_order_is_finished() {
+    let env = Env::default();
+    let s = setup(&env);
+    let buyer = buyer(&env, &s);
+
+    let listing_id = s.market.list(&s.seller, &s.order_id, &200);
+    s.orders.refund_order(&s.admin, &s.order_id);
+    assert_eq!(s.orders.get_order(&s.order_id).status, OrderStatus::Cancelled);
+
+    s.market.buy(&buyer, &listing_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_fee_above_hundred_percent() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.market.set_fee_bps(&s.admin, &10_001);
+}
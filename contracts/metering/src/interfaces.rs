@@ -0,0 +1,12 @@
+//! Typed interfaces of the contracts usage is billed through.
+
+use soroban_sdk::{contractclient, Address, BytesN, Env};
+
+/// Subset of the order contract used to look up orders and stream payment.
+#[allow(dead_code)]
+#[contractclient(name = "OrderClient")]
+pub trait OrderInterface {
+    fn get_order_user(env: Env, order_id: BytesN<32>) -> Address;
+    fn get_order_depin(env: Env, order_id: BytesN<32>) -> BytesN<32>;
+    fn record_usage(env: Env, meter: Address, order_id: BytesN<32>, hours: u64) -> i128;
+}
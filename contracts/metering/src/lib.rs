@@ -0,0 +1,331 @@
+#![no_std]
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, Bytes, BytesN, Env, Vec,
+    panic_with_error, symbol_short
+};
+
+mod interfaces;
+use interfaces::OrderClient;
+
+#[contract]
+pub struct MeteringContract;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UsageReport {
+    pub report_id: u64,
+    pub order_id: BytesN<32>,
+    pub depin_id: BytesN<32>,
+    pub period_start: u64,
+    pub period_end: u64,
+    pub hours_served: u64,
+    pub resources_consumed: u64,    // Provider-defined unit, e.g. GPU-minutes or GB
+    pub submitted_at: u64,
+    pub status: ReportStatus,
+    pub amount_paid: i128,          // Escrow released for this report once finalized
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReportStatus {
+    Pending,        // Within the dispute window
+    Disputed,       // Challenged by the order's user, awaiting admin resolution
+    Finalized,      // Accepted and paid out of escrow
+    Rejected,       // Found inflated, nothing paid
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UsageSummary {
+    pub hours_served: u64,
+    pub resources_consumed: u64,
+    pub amount_paid: i128,
+    pub reports: u32,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    OrderContract,              // Order contract whose escrow usage is paid from
+    DisputeWindow,              // u64 seconds a report can be challenged
+    MeterKey(BytesN<32>),       // depin_id -> ed25519 public key signing its reports
+    ReportCounter,              // u64 counter for report IDs
+    Report(u64),                // report_id -> UsageReport
+    OrderReports(BytesN<32>),   // order_id -> Vec<u64>
+    OrderUsage(BytesN<32>),     // order_id -> UsageSummary of finalized reports
+    LastPeriodEnd(BytesN<32>),  // order_id -> u64 end of the latest reported period
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    MeterKeyNotSet = 4,
+    InvalidPeriod = 5,
+    ReportNotFound = 6,
+    InvalidStatus = 7,
+    DisputeWindowOpen = 8,
+    DisputeWindowClosed = 9,
+    NotOrderUser = 10,
+}
+
+#[contractimpl]
+impl MeteringContract {
+    /// Initialize with admin, the order contract and the dispute window in seconds
+    pub fn initialize(env: Env, admin: Address, order_contract: Address, dispute_window: u64) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::OrderContract, &order_contract);
+        env.storage().persistent().set(&DataKey::DisputeWindow, &dispute_window);
+        env.storage().persistent().set(&DataKey::ReportCounter, &0u64);
+        true
+    }
+
+    /// Register the key a DePIN's provider signs usage reports with (admin only)
+    pub fn set_meter_key(env: Env, admin: Address, depin_id: BytesN<32>, public_key: BytesN<32>) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::MeterKey(depin_id), &public_key);
+        true
+    }
+
+    /// Set how long users can dispute a report (admin only)
+    pub fn set_dispute_window(env: Env, admin: Address, dispute_window: u64) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::DisputeWindow, &dispute_window);
+        true
+    }
+
+    /// Submit a provider-signed usage report for an order (anyone may relay it)
+    ///
+    /// Periods must follow each other, so a signed report cannot be replayed.
+    pub fn submit_report(
+        env: Env,
+        order_id: BytesN<32>,
+        period_start: u64,
+        period_end: u64,
+        hours_served: u64,
+        resources_consumed: u64,
+        signature: BytesN<64>,
+    ) -> u64 {
+        let depin_id = Self::order(&env).get_order_depin(&order_id);
+        let public_key: BytesN<32> = env.storage().persistent()
+            .get(&DataKey::MeterKey(depin_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::MeterKeyNotSet));
+
+        let last_end: u64 = env.storage().persistent()
+            .get(&DataKey::LastPeriodEnd(order_id.clone()))
+            .unwrap_or(0);
+        if period_end <= period_start || period_start < last_end
+            || period_end > env.ledger().timestamp()
+        {
+            panic_with_error!(&env, Error::InvalidPeriod);
+        }
+
+        let payload = Self::report_payload(
+            env.clone(),
+            order_id.clone(),
+            period_start,
+            period_end,
+            hours_served,
+            resources_consumed,
+        );
+        env.crypto().ed25519_verify(&public_key, &payload, &signature);
+
+        let mut counter: u64 = env.storage().persistent()
+            .get(&DataKey::ReportCounter)
+            .unwrap_or(0);
+        counter += 1;
+        env.storage().persistent().set(&DataKey::ReportCounter, &counter);
+
+        let report = UsageReport {
+            report_id: counter,
+            order_id: order_id.clone(),
+            depin_id,
+            period_start,
+            period_end,
+            hours_served,
+            resources_consumed,
+            submitted_at: env.ledger().timestamp(),
+            status: ReportStatus::Pending,
+            amount_paid: 0,
+        };
+        env.storage().persistent().set(&DataKey::Report(counter), &report);
+        env.storage().persistent().set(&DataKey::LastPeriodEnd(order_id.clone()), &period_end);
+
+        let mut reports = Self::list_order_reports(env.clone(), order_id.clone());
+        reports.push_back(counter);
+        env.storage().persistent().set(&DataKey::OrderReports(order_id.clone()), &reports);
+
+        env.events().publish((symbol_short!("reported"), order_id), (counter, hours_served, resources_consumed));
+        counter
+    }
+
+    /// Challenge a report as inflated while its dispute window is open (order user only)
+    pub fn dispute_report(env: Env, user: Address, report_id: u64) -> bool {
+        user.require_auth();
+
+        let mut report = Self::load(&env, report_id);
+        if Self::order(&env).get_order_user(&report.order_id) != user {
+            panic_with_error!(&env, Error::NotOrderUser);
+        }
+        if report.status != ReportStatus::Pending {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if env.ledger().timestamp() > report.submitted_at + Self::get_dispute_window(env.clone()) {
+            panic_with_error!(&env, Error::DisputeWindowClosed);
+        }
+
+        report.status = ReportStatus::Disputed;
+        env.storage().persistent().set(&DataKey::Report(report_id), &report);
+
+        env.events().publish((symbol_short!("disputed"), report.order_id), (report_id, user));
+        true
+    }
+
+    /// Settle a disputed report with the hours found to be genuine (admin only)
+    ///
+    /// Zero accepted hours rejects the report; otherwise it is finalized and
+    /// paid for the accepted hours only.
+    pub fn resolve_dispute(env: Env, admin: Address, report_id: u64, accepted_hours: u64) -> bool {
+        Self::assert_admin(&env, &admin);
+
+        let mut report = Self::load(&env, report_id);
+        if report.status != ReportStatus::Disputed {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        if accepted_hours == 0 {
+            report.status = ReportStatus::Rejected;
+            env.storage().persistent().set(&DataKey::Report(report_id), &report);
+        } else {
+            report.hours_served = report.hours_served.min(accepted_hours);
+            Self::finalize(&env, report);
+        }
+
+        env.events().publish((symbol_short!("resolved"), report_id), accepted_hours);
+        true
+    }
+
+    /// Accept an undisputed report after its window and stream payment for it (anyone)
+    pub fn finalize_report(env: Env, report_id: u64) -> i128 {
+        let report = Self::load(&env, report_id);
+        if report.status != ReportStatus::Pending {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if env.ledger().timestamp() <= report.submitted_at + Self::get_dispute_window(env.clone()) {
+            panic_with_error!(&env, Error::DisputeWindowOpen);
+        }
+
+        Self::finalize(&env, report)
+    }
+
+    /// Canonical bytes a provider signs for a report
+    pub fn report_payload(
+        env: Env,
+        order_id: BytesN<32>,
+        period_start: u64,
+        period_end: u64,
+        hours_served: u64,
+        resources_consumed: u64,
+    ) -> Bytes {
+        let mut payload = Bytes::from_array(&env, &order_id.to_array());
+        payload.extend_from_array(&period_start.to_be_bytes());
+        payload.extend_from_array(&period_end.to_be_bytes());
+        payload.extend_from_array(&hours_served.to_be_bytes());
+        payload.extend_from_array(&resources_consumed.to_be_bytes());
+        payload
+    }
+
+    /// Get report details
+    pub fn get_report(env: Env, report_id: u64) -> UsageReport {
+        Self::load(&env, report_id)
+    }
+
+    /// Get all report IDs submitted for an order
+    pub fn list_order_reports(env: Env, order_id: BytesN<32>) -> Vec<u64> {
+        env.storage().persistent()
+            .get(&DataKey::OrderReports(order_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Get finalized usage totals for an order, e.g. for invoicing
+    pub fn get_order_usage(env: Env, order_id: BytesN<32>) -> UsageSummary {
+        env.storage().persistent()
+            .get(&DataKey::OrderUsage(order_id))
+            .unwrap_or(UsageSummary {
+                hours_served: 0,
+                resources_consumed: 0,
+                amount_paid: 0,
+                reports: 0,
+            })
+    }
+
+    /// Get the key reports for a DePIN must be signed with
+    pub fn get_meter_key(env: Env, depin_id: BytesN<32>) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&DataKey::MeterKey(depin_id))
+    }
+
+    /// Get the dispute window in seconds
+    pub fn get_dispute_window(env: Env) -> u64 {
+        env.storage().persistent()
+            .get(&DataKey::DisputeWindow)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn order(env: &Env) -> OrderClient<'_> {
+        let address: Address = env.storage().persistent()
+            .get(&DataKey::OrderContract)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        OrderClient::new(env, &address)
+    }
+
+    fn load(env: &Env, report_id: u64) -> UsageReport {
+        env.storage().persistent()
+            .get(&DataKey::Report(report_id))
+            .unwrap_or_else(|| panic_with_error!(env, Error::ReportNotFound))
+    }
+
+    fn finalize(env: &Env, mut report: UsageReport) -> i128 {
+        let paid = Self::order(env).record_usage(
+            &env.current_contract_address(),
+            &report.order_id,
+            &report.hours_served,
+        );
+
+        report.status = ReportStatus::Finalized;
+        report.amount_paid = paid;
+        env.storage().persistent().set(&DataKey::Report(report.report_id), &report);
+
+        let mut usage = Self::get_order_usage(env.clone(), report.order_id.clone());
+        usage.hours_served += report.hours_served;
+        usage.resources_consumed += report.resources_consumed;
+        usage.amount_paid += paid;
+        usage.reports += 1;
+        env.storage().persistent().set(&DataKey::OrderUsage(report.order_id.clone()), &usage);
+
+        env.events().publish((symbol_short!("finalized"), report.order_id), (report.report_id, paid));
+        paid
+    }
+}
+
+mod test;
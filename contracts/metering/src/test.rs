@@ -0,0 +1,205 @@
+#![cfg(test)]
+extern crate std;
+
+use super::*;
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as AddressTestUtils, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env, String
+};
+use order::{OrderContract, OrderContractClient, OrderPriority, OrderStatus};
+
+/// Stand-in for the DePIN registry that accepts every DePIN ID, priced at 10 per hour
+#[contract]
+struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn depin_exists(_env: Env, _depin_id: BytesN<32>) -> bool {
+        true
+    }
+
+    pub fn supports_chain(_env: Env, _depin_id: BytesN<32>, _chain: String) -> bool {
+        true
+    }
+
+    pub fn get_payout_address(_env: Env, _depin_id: BytesN<32>) -> Option<Address> {
+        None
+    }
+
+    pub fn get_service_price(_env: Env, _depin_id: BytesN<32>, _service_type: String) -> Option<i128> {
+        Some(10)
+    }
+
+    pub fn reserve_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+
+    pub fn release_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+}
+
+const WINDOW: u64 = 3_600;
+
+struct Setup<'a> {
+    metering: MeteringContractClient<'a>,
+    orders: OrderContractClient<'a>,
+    token: TokenClient<'a>,
+    admin: Address,
+    user: Address,
+    treasury: Address,
+    order_id: BytesN<32>,
+    signer: SigningKey,
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 100_000);
+
+    let admin = Address::generate(env);
+    let user = Address::generate(env);
+    let treasury = Address::generate(env);
+    let depin_id = BytesN::from_array(env, &[1u8; 32]);
+    let token_id = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    StellarAssetClient::new(env, &token_id).mint(&user, &1_000);
+
+    let orders = OrderContractClient::new(env, &env.register(OrderContract, ()));
+    orders.initialize(&admin);
+    orders.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    orders.set_escrow_contract(&admin, &env.register(escrow::EscrowContract, ()));
+    orders.set_payment_token(&admin, &token_id);
+    orders.set_treasury_wallet(&admin, &treasury);
+
+    let order_id = orders.create_order(
+        &user,
+        &depin_id,
+        &String::from_str(env, "gpu"),
+        &24,
+        &10,
+        &token_id,
+        &String::from_str(env, "ethereum"),
+        &String::from_str(env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    orders.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+
+    let metering = MeteringContractClient::new(env, &env.register(MeteringContract, ()));
+    metering.initialize(&admin, &orders.address, &WINDOW);
+    orders.set_usage_meter(&admin, &metering.address, &true);
+
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let public_key = BytesN::from_array(env, &signer.verifying_key().to_bytes());
+    metering.set_meter_key(&admin, &depin_id, &public_key);
+
+    Setup {
+        metering,
+        orders,
+        token: TokenClient::new(env, &token_id),
+        admin,
+        user,
+        treasury,
+        order_id,
+        signer,
+    }
+}
+
+fn submit(env: &Env, s: &Setup, start: u64, end: u64, hours: u64, resources: u64) -> u64 {
+    let payload = s.metering.report_payload(&s.order_id, &start, &end, &hours, &resources);
+    let message: std::vec::Vec<u8> = payload.iter().collect();
+    let signature = BytesN::from_array(env, &s.signer.sign(&message).to_bytes());
+    s.metering.submit_report(&s.order_id, &start, &end, &hours, &resources, &signature)
+}
+
+#[test]
+fn test_finalized_report_streams_escrow() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    let report_id = submit(&env, &s, 0, 6 * 3_600, 6, 360);
+    assert_eq!(s.metering.get_report(&report_id).status, ReportStatus::Pending);
+
+    env.ledger().with_mut(|li| li.timestamp += WINDOW + 1);
+    assert_eq!(s.metering.finalize_report(&report_id), 60);
+
+    assert_eq!(s.token.balance(&s.treasury), 60);
+    assert_eq!(s.orders.get_order(&s.order_id).escrowed_amount, 180);
+
+    let usage = s.metering.get_order_usage(&s.order_id);
+    assert_eq!(usage.hours_served, 6);
+    assert_eq!(usage.resources_consumed, 360);
+    assert_eq!(usage.amount_paid, 60);
+    assert_eq!(usage.reports, 1);
+}
+
+#[test]
+fn test_disputed_report_paid_for_accepted_hours() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    let report_id = submit(&env, &s, 0, 6 * 3_600, 6, 360);
+    s.metering.dispute_report(&s.user, &report_id);
+    s.metering.resolve_dispute(&s.admin, &report_id, &3);
+
+    let report = s.metering.get_report(&report_id);
+    assert_eq!(report.status, ReportStatus::Finalized);
+    assert_eq!(report.hours_served, 3);
+    assert_eq!(report.amount_paid, 30);
+    assert_eq!(s.token.balance(&s.treasury), 30);
+}
+
+#[test]
+fn test_rejected_report_pays_nothing() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    let report_id = submit(&env, &s, 0, 6 * 3_600, 6, 360);
+    s.metering.dispute_report(&s.user, &report_id);
+    s.metering.resolve_dispute(&s.admin, &report_id, &0);
+
+    assert_eq!(s.metering.get_report(&report_id).status, ReportStatus::Rejected);
+    assert_eq!(s.orders.get_order(&s.order_id).escrowed_amount, 240);
+    assert_eq!(s.metering.get_order_usage(&s.order_id).reports, 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_finalize_within_window() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    let report_id = submit(&env, &s, 0, 6 * 3_600, 6, 360);
+    s.metering.finalize_report(&report_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_replayed_period_rejected() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    submit(&env, &s, 0, 6 * 3_600, 6, 360);
+    submit(&env, &s, 0, 6 * 3_600, 6, 360);
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_tampered_report_rejected() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    let payload = s.metering.report_payload(&s.order_id, &0, &3_600, &1, &60);
+    let message: std::vec::Vec<u8> = payload.iter().collect();
+    let signature = BytesN::from_array(&env, &s.signer.sign(&message).to_bytes());
+    // Inflated hours no longer match the signature
+    s.metering.submit_report(&s.order_id, &0, &3_600, &10, &60, &signature);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_only_order_user_can_dispute() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    let report_id = submit(&env, &s, 0, 6 * 3_600, 6, 360);
+    s.metering.dispute_report(&Address::generate(&env), &report_id);
+}
@@ -0,0 +1,152 @@
+//! Typed interfaces of the contracts the order contract drives.
+
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env, String, Symbol, Vec};
+
+/// Subset of the escrow contract used to hold order funds.
+#[allow(dead_code)]
+#[contractclient(name = "EscrowClient")]
+pub trait EscrowInterface {
+    fn create_escrow(
+        env: Env,
+        controller: Address,
+        escrow_id: BytesN<32>,
+        depositor: Address,
+        beneficiary: Address,
+        token: Address,
+        amount: i128,
+        arbiter: Option<Address>,
+    ) -> bool;
+    fn lock(env: Env, caller: Address, escrow_id: BytesN<32>) -> bool;
+    fn lock_funded(env: Env, caller: Address, escrow_id: BytesN<32>) -> bool;
+    fn top_up(env: Env, caller: Address, escrow_id: BytesN<32>, amount: i128) -> bool;
+    fn set_depositor(env: Env, caller: Address, escrow_id: BytesN<32>, depositor: Address) -> bool;
+    fn release(env: Env, caller: Address, escrow_id: BytesN<32>, amount: i128) -> bool;
+    fn refund(env: Env, caller: Address, escrow_id: BytesN<32>, amount: i128) -> bool;
+    fn split(env: Env, caller: Address, escrow_id: BytesN<32>, shares: Vec<(Address, i128)>) -> bool;
+    fn get_remaining(env: Env, escrow_id: BytesN<32>) -> i128;
+}
+
+/// Subset of the fee-manager contract the order contract prices against.
+#[allow(dead_code)]
+#[contractclient(name = "FeeManagerClient")]
+pub trait FeeManagerInterface {
+    fn quote_platform_fee(env: Env, amount: i128) -> i128;
+    fn quote_cancellation_fee(env: Env, amount: i128) -> i128;
+}
+
+/// Subset of the payment-splitter contract used for provider settlements.
+#[allow(dead_code)]
+#[contractclient(name = "SplitterClient")]
+pub trait SplitterInterface {
+    fn has_payees(env: Env, depin_id: BytesN<32>) -> bool;
+    fn allocate(env: Env, depin_id: BytesN<32>, token: Address, amount: i128) -> bool;
+}
+
+/// Subset of the coupon contract redeemed during order creation.
+#[allow(dead_code)]
+#[contractclient(name = "CouponClient")]
+pub trait CouponInterface {
+    fn redeem(
+        env: Env,
+        redeemer: Address,
+        code: String,
+        user: Address,
+        depin_id: BytesN<32>,
+        service_type: String,
+        amount: i128,
+    ) -> i128;
+}
+
+/// Mirror of the DePIN registry's provider trust levels.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrustLevel {
+    Unverified,
+    Verified,
+    Audited,
+}
+
+/// Mirror of the rewards contract's activity categories.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Category {
+    ProviderUptime,
+    ProviderRevenue,
+    UserSpend,
+    UserReviews,
+}
+
+/// Subset of the rewards contract fed with completed order spend.
+#[allow(dead_code)]
+#[contractclient(name = "RewardsClient")]
+pub trait RewardsInterface {
+    fn record(env: Env, feeder: Address, category: Category, account: Address, points: i128) -> bool;
+}
+
+/// Subset of the compliance registry gating high-value orders.
+#[allow(dead_code)]
+#[contractclient(name = "ComplianceClient")]
+pub trait ComplianceInterface {
+    fn is_compliant(env: Env, subject: Address, min_level: u32) -> bool;
+}
+
+/// Subset of the contract directory that resolves platform contracts by name.
+#[allow(dead_code)]
+#[contractclient(name = "DirectoryClient")]
+pub trait DirectoryInterface {
+    fn get_address(env: Env, name: Symbol) -> Option<Address>;
+}
+
+/// Subset of the DePIN registry orders are placed against.
+#[allow(dead_code)]
+#[contractclient(name = "RegistryClient")]
+pub trait RegistryInterface {
+    fn depin_exists(env: Env, depin_id: BytesN<32>) -> bool;
+    fn supports_chain(env: Env, depin_id: BytesN<32>, chain: String) -> bool;
+    fn get_trust_level(env: Env, depin_id: BytesN<32>) -> Option<TrustLevel>;
+    fn get_payout_address(env: Env, depin_id: BytesN<32>) -> Option<Address>;
+    fn get_service_price(env: Env, depin_id: BytesN<32>, service_type: String) -> Option<i128>;
+    fn reserve_capacity(env: Env, caller: Address, depin_id: BytesN<32>, slots: u32);
+    fn release_capacity(env: Env, caller: Address, depin_id: BytesN<32>, slots: u32);
+}
+
+/// Subset of the user-profile contract whose token whitelist and memberships
+/// orders follow.
+#[allow(dead_code)]
+#[contractclient(name = "ProfileClient")]
+pub trait ProfileInterface {
+    fn is_token_whitelisted(env: Env, token_address: Address) -> bool;
+    fn get_subscription_tier(env: Env, user_address: Address) -> u32;
+    fn get_loyalty_points(env: Env, user_address: Address) -> u32;
+}
+
+/// Subset of the pause guardian consulted before state-mutating calls.
+#[allow(dead_code)]
+#[contractclient(name = "GuardianClient")]
+pub trait GuardianInterface {
+    fn is_paused(env: Env, target: Address, function: Symbol) -> bool;
+}
+
+/// Mirror of the SEP-40 price oracle asset identifier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// Mirror of the SEP-40 price record (USD price scaled by `decimals()`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// Subset of a SEP-40 price oracle used to price orders paid in other assets.
+#[allow(dead_code)]
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleInterface {
+    fn decimals(env: Env) -> u32;
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+}
@@ -1,8 +1,16 @@
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 use soroban_sdk::{
     contract, contractimpl, contracttype, contracterror,
-    Address, BytesN, Env, String, Vec, IntoVal,
-    panic_with_error
+    Address, BytesN, Env, Map, String, Symbol, Val, Vec, IntoVal,
+    panic_with_error, symbol_short, token, xdr::ToXdr
+};
+
+mod interfaces;
+use interfaces::{
+    Asset, Category, ComplianceClient, CouponClient, DirectoryClient, EscrowClient, FeeManagerClient,
+    GuardianClient, PriceOracleClient, ProfileClient, RegistryClient, RewardsClient, SplitterClient,
+    TrustLevel,
 };
 
 #[contract]
@@ -17,12 +25,165 @@ pub struct Order {
     pub duration_hours: u64,
     pub price_per_hour: i128,
     pub total_amount: i128,
+    pub payment_token: Address,            // Token the order was paid, and is escrowed, in
     pub status: OrderStatus,
     pub created_at: u64,
     pub external_tx_id: Option<String>,
     pub deployment_chain: String,
     pub service_params: String,
     pub escrowed_amount: i128,
+    pub refunded_amount: i128,             // Returned to the user so far
+    pub released_amount: i128,             // Paid to the treasury and provider so far
+    pub escrow_contract: Option<Address>,  // Escrow contract holding the funds; None if this contract holds them
+    pub coupon_code: Option<String>,
+    pub discount_amount: i128,             // Membership and coupon discounts taken off duration_hours * price_per_hour
+    pub expires_at: u64,                   // Anyone may cancel the order if still Pending by then
+    pub started_at: u64,                   // When the order first left Pending; 0 until then
+    pub completed_at: u64,                 // When the order was completed; 0 until then
+    pub deployment_deadline: u64,          // The user may claim a refund if still Active by then; 0 until Active
+    pub approvals: Vec<Address>,           // Approvers who confirmed completing the order
+    pub priority: OrderPriority,           // How urgently the user wants the order provisioned; chosen at creation
+}
+
+/// Seconds an order may stay Pending unless the admin sets another timeout
+pub const DEFAULT_PENDING_TIMEOUT: u64 = 7 * 24 * 3600;
+
+/// Seconds an order may stay Active before deployment unless the admin sets another timeout
+pub const DEFAULT_DEPLOYMENT_TIMEOUT: u64 = 24 * 3600;
+
+/// Registry capacity slots each open order holds on its DePIN
+pub const SLOTS_PER_ORDER: u32 = 1;
+
+/// Seconds after creation a finished order may be archived unless the admin sets another age
+pub const DEFAULT_ARCHIVE_AGE: u64 = 90 * 24 * 3600;
+
+/// Most orders one `create_orders_batch` call may open
+pub const MAX_BATCH_SIZE: u32 = 20;
+
+/// Most orders returned by one `get_user_orders_page` or `get_depin_orders_page` call
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Most order IDs one page call scans while filtering by status
+pub const MAX_PAGE_SCAN: u32 = 500;
+
+/// Basis-point denominator for slippage tolerances (100% = 10_000 bps)
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Price oracle settings for orders paid in assets other than the payment token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleConfig {
+    pub oracle: Address,        // SEP-40 oracle quoting USD prices
+    pub quote_ttl: u64,         // Seconds a quote stays usable
+    pub max_price_age: u64,     // Oldest oracle price accepted, in seconds
+}
+
+/// Amount of `token` covering a USD-denominated (payment token units) price
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentQuote {
+    pub quote_id: u64,
+    pub user: Address,
+    pub token: Address,
+    pub usd_amount: i128,       // In payment token units
+    pub token_amount: i128,     // At the oracle price when quoted
+    pub max_slippage_bps: u32,  // How far the amount may rise by the time it is used
+    pub expires_at: u64,
+    pub used: bool,
+}
+
+/// What is kept of an order once it is archived
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArchivedOrder {
+    pub order_id: BytesN<32>,
+    pub user: Address,
+    pub depin_id: BytesN<32>,
+    pub status: OrderStatus,
+    pub payment_token: Address,
+    pub total_amount: i128,
+    pub refunded_amount: i128,
+    pub released_amount: i128,
+    pub created_at: u64,
+    pub archived_at: u64,
+}
+
+/// One entry of an order's status history; `actor` is None for
+/// permissionless transitions such as expiry
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatusChange {
+    pub status: OrderStatus,
+    pub timestamp: u64,
+    pub actor: Option<Address>,
+}
+
+/// Inclusive bounds on the hourly price orders may be placed at
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceBand {
+    pub min_price: i128,
+    pub max_price: i128,
+}
+
+/// Completions of orders worth at least `threshold` need `required` approvals
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalPolicy {
+    pub threshold: i128,
+    pub required: u32,
+}
+
+/// Platform-wide order aggregates, kept up to date on every creation and
+/// status change
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrderMetrics {
+    pub total_orders: u32,
+    pub status_counts: Map<OrderStatus, u32>,   // Orders currently in each status
+    pub volume_by_token: Map<Address, i128>,    // Sum of order totals per payment token
+    pub depin_orders: Map<BytesN<32>, u32>,     // Orders placed with each DePIN
+    pub total_duration_hours: u64,
+    pub average_duration_hours: u64,
+}
+
+/// Per-user throttles on order creation; zero disables a limit
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OrderLimits {
+    pub max_open_orders: u32,      // Most Pending or Active orders a user may have at once
+    pub min_ledger_interval: u32,  // Ledgers a user must wait between order creations
+}
+
+/// One order of a `create_orders_batch` call
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrderRequest {
+    pub depin_id: BytesN<32>,
+    pub service_type: String,
+    pub duration_hours: u64,
+    pub price_per_hour: i128,
+    pub deployment_chain: String,
+    pub service_params: String,
+    pub priority: OrderPriority,
+}
+
+/// One page of orders; `next_offset` resumes the scan, None once the list is exhausted
+#[contracttype]
+pub struct OrderPage {
+    pub orders: Vec<Order>,
+    pub next_offset: Option<u32>,
+}
+
+/// Stable subset of an order for other contracts (SLA, metering, ...)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrderTerms {
+    pub user: Address,
+    pub depin_id: BytesN<32>,
+    pub total_amount: i128,
+    pub start_time: u64,        // Order creation
+    pub end_time: u64,          // start_time + duration_hours
 }
 
 #[contracttype]
@@ -34,6 +195,16 @@ pub enum OrderStatus {
     Completed,      // Service completed, payment released to treasury
     Cancelled,      // Cancelled before deployment
     Failed,         // Deployment failed, funds refunded
+    Disputed,       // User disputed the service, funds frozen until arbitration
+}
+
+/// Provisioning priority; High and Urgent orders pay a surge multiplier
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OrderPriority {
+    Normal,
+    High,
+    Urgent,
 }
 
 #[contracttype]
@@ -47,6 +218,60 @@ pub enum DataKey {
     Admin,                     // Admin address
     UserOrders(Address),       // user -> Vec<BytesN<32>> (order IDs)
     DepinOrders(BytesN<32>),   // depin_id -> Vec<BytesN<32>> (order IDs)
+    EscrowContract,            // Address of the standalone escrow contract
+    PaymentToken,              // Token funded orders are paid in, and the only one without a profile contract
+    FeeManagerContract,        // Address of the fee-manager contract
+    PriorityMultiplier(OrderPriority), // priority -> u32 bps of the order total charged, 10_000 if unset
+    PriorityOrders(OrderPriority), // priority -> Vec<BytesN<32>> open orders with that priority above Normal
+    PriceBand(Option<String>), // service_type -> PriceBand its orders' hourly price must fall in; None bounds every order
+    CancellationFeeScaled,     // bool, whether cancellation fees grow with how much of the pending window was used
+    Timelock,                  // Timelock that authorizes admin rotations
+    SplitterContract,          // Address of the provider payment splitter
+    CouponContract,            // Address of the coupon contract
+    OrderSource(Address),      // address -> bool, contracts allowed to open prepaid orders
+    TransferAgent(Address),    // address -> bool, contracts allowed to move orders between users
+    UsageMeter(Address),       // address -> bool, contracts allowed to stream payment for usage
+    DisputeModule(Address),    // address -> bool, contracts allowed to split an order between refund and payout
+    Approver(Address),         // address -> bool, keys whose approvals count towards completing large orders
+    ApprovalPolicy,            // ApprovalPolicy for completing large orders
+    RewardsContract,           // Address of the rewards emission contract
+    ComplianceContract,        // Address of the compliance attestation registry
+    CompliancePolicy,          // (min KYC level, order amount from which it applies)
+    DirectoryContract,         // Directory resolving profile, registry and treasury by name
+    PauseGuardian,             // Guardian that can pause order entry points during incidents
+    Paused,                    // bool, admin emergency stop on the same entry points
+    OracleConfig,              // OracleConfig for orders paid in other assets
+    AcceptedToken(Address),    // token -> bool, assets orders may be paid in at oracle prices
+    QuoteCounter,              // u64 counter for quote IDs
+    Quote(u64),                // quote_id -> PaymentQuote
+    Custody(Address),          // token -> i128 held by this contract for orders without an escrow contract
+    PendingTimeout,            // u64 seconds a new order may stay Pending before it can be expired
+    ArchiveAge,                // u64 seconds after creation a finished order may be archived
+    Archived(BytesN<32>),      // order_id -> ArchivedOrder
+    OrderLimits,               // OrderLimits throttling order creation per user
+    OpenOrders(Address),       // user -> u32 orders Pending or Active
+    LastOrderLedger(Address),  // user -> u32 ledger of their latest order creation
+    TierDiscounts,             // Map<u32, u32> subscription tier -> discount in bps
+    LoyaltyDiscounts,          // Vec<(u32, u32)> (min loyalty points, discount in bps)
+    DeploymentTimeout,         // u64 seconds an order may stay Active before the user can claim a refund
+    DisputeWindow,             // u64 seconds after completion the user may dispute an order
+    Dispute(BytesN<32>),       // order_id -> String reason the user gave
+    OrderHistory(BytesN<32>),  // order_id -> Vec<StatusChange>, every status the order entered
+}
+
+/// Reporting aggregates, kept apart as DataKey is at the contract type case limit
+#[contracttype]
+pub enum StatsKey {
+    EscrowedByToken(Address),  // token -> i128 escrowed for orders paid in it
+    EscrowedByDepin(BytesN<32>), // depin_id -> i128 escrowed for its orders
+    OrderMetrics,              // OrderMetrics across all orders
+    UnsettledDepinOrders(BytesN<32>), // depin_id -> u32 orders Pending, Active, Deployed or Disputed
+}
+
+/// Order policies, kept apart as DataKey is at the contract type case limit
+#[contracttype]
+pub enum PolicyKey {
+    VerifiedProviderThreshold, // i128 order amount from which the DePIN's provider must be Verified or Audited
 }
 
 #[contracterror]
@@ -63,6 +288,24 @@ pub enum Error {
     Unauthorized = 8,
     InvalidAmount = 9,
     ContractNotSet = 10,
+    TimelockNotSet = 11,
+    NotCompliant = 12,
+    Paused = 13,
+    PriceUnavailable = 14,
+    QuoteExpired = 15,
+    SlippageExceeded = 16,
+    NotExpired = 17,
+    TokenNotAccepted = 18,
+    InvalidTransition = 19,
+    DisputeWindowClosed = 20,
+    PriceMismatch = 21,
+    TooManyOpenOrders = 22,
+    RateLimited = 23,
+    AlreadyApproved = 24,
+    ApprovalsMissing = 25,
+    PriceOutOfRange = 26,
+    UnsupportedChain = 27,
+    UnverifiedProvider = 28,
 }
 
 #[contractimpl]
@@ -80,6 +323,25 @@ impl OrderContract {
         true
     }
 
+    /// Set the timelock that authorizes admin rotations
+    ///
+    /// The admin sets it once; afterwards only the timelock can replace itself.
+    pub fn set_timelock(env: Env, caller: Address, timelock: Address) -> bool {
+        match env.storage().persistent().get::<_, Address>(&DataKey::Timelock) {
+            Some(_) => Self::assert_timelock(&env),
+            None => Self::assert_admin(&env, &caller),
+        }
+        env.storage().persistent().set(&DataKey::Timelock, &timelock);
+        true
+    }
+
+    /// Rotate the admin key (timelock only)
+    pub fn set_admin(env: Env, new_admin: Address) -> bool {
+        Self::assert_timelock(&env);
+        env.storage().persistent().set(&DataKey::Admin, &new_admin);
+        true
+    }
+
     /// Set user profile contract address (admin only)
     pub fn set_user_profile_contract(env: Env, admin: Address, contract_address: Address) -> bool {
         Self::assert_admin(&env, &admin);
@@ -101,7 +363,282 @@ impl OrderContract {
         true
     }
 
+    /// Set escrow contract address (admin only)
+    ///
+    /// Once set, new orders lock their funds in the escrow contract instead of
+    /// the internal user-profile ledger.
+    pub fn set_escrow_contract(env: Env, admin: Address, contract_address: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::EscrowContract, &contract_address);
+        true
+    }
+
+    /// Set the token orders are paid and escrowed in (admin only)
+    pub fn set_payment_token(env: Env, admin: Address, token: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::PaymentToken, &token);
+        true
+    }
+
+    /// Set the price oracle used to quote orders paid in other assets (admin only)
+    pub fn set_price_oracle(env: Env, admin: Address, oracle: Address, quote_ttl: u64, max_price_age: u64) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::OracleConfig, &OracleConfig { oracle, quote_ttl, max_price_age });
+        true
+    }
+
+    /// Accept or stop accepting an asset (e.g. XLM) as payment at oracle prices (admin only)
+    pub fn set_accepted_token(env: Env, admin: Address, token: Address, accepted: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::AcceptedToken(token), &accepted);
+        true
+    }
+
+    /// Set fee-manager contract address (admin only)
+    pub fn set_fee_manager(env: Env, admin: Address, contract_address: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::FeeManagerContract, &contract_address);
+        true
+    }
+
+    /// Set what share of an order's total, in bps, a priority costs; at least
+    /// 10_000 so priorities never discount orders (admin only)
+    pub fn set_priority_multiplier(env: Env, admin: Address, priority: OrderPriority, multiplier_bps: u32) -> bool {
+        Self::assert_admin(&env, &admin);
+        if (multiplier_bps as i128) < BPS_DENOMINATOR || priority == OrderPriority::Normal {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        env.storage().persistent().set(&DataKey::PriorityMultiplier(priority), &multiplier_bps);
+        true
+    }
+
+    /// Scale the fee-manager's cancellation fee by the share of the pending
+    /// window an order used before being cancelled, so early cancellations
+    /// cost little and squatting until expiry costs the full fee (admin only)
+    pub fn set_cancellation_fee_scaled(env: Env, admin: Address, scaled: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::CancellationFeeScaled, &scaled);
+        true
+    }
+
+    /// Set payment-splitter contract address (admin only)
+    pub fn set_splitter_contract(env: Env, admin: Address, contract_address: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::SplitterContract, &contract_address);
+        true
+    }
+
+    /// Set coupon contract address (admin only)
+    pub fn set_coupon_contract(env: Env, admin: Address, contract_address: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::CouponContract, &contract_address);
+        true
+    }
+
+    /// Set rewards contract address, fed with spend of completed orders (admin only)
+    pub fn set_rewards_contract(env: Env, admin: Address, contract_address: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::RewardsContract, &contract_address);
+        true
+    }
+
+    /// Set the contract directory, which takes precedence over addresses set here (admin only)
+    pub fn set_directory_contract(env: Env, admin: Address, contract_address: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::DirectoryContract, &contract_address);
+        true
+    }
+
+    /// Set the pause guardian; refunds and cancellations stay available while paused (admin only)
+    pub fn set_pause_guardian(env: Env, admin: Address, guardian: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::PauseGuardian, &guardian);
+        true
+    }
+
+    /// Stop order creation, extension, completion and payouts until `unpause`;
+    /// refunds and cancellations stay available (admin only)
+    pub fn pause(env: Env, admin: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::Paused, &true);
+        env.events().publish((symbol_short!("paused"),), admin);
+        true
+    }
+
+    /// Lift the admin emergency stop (admin only)
+    pub fn unpause(env: Env, admin: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().remove(&DataKey::Paused);
+        env.events().publish((symbol_short!("unpaused"),), admin);
+        true
+    }
+
+    /// Require a compliance attestation of `min_level` for orders of at least `threshold` (admin only)
+    pub fn set_compliance_contract(
+        env: Env,
+        admin: Address,
+        contract_address: Address,
+        min_level: u32,
+        threshold: i128,
+    ) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::ComplianceContract, &contract_address);
+        env.storage().persistent().set(&DataKey::CompliancePolicy, &(min_level, threshold));
+        true
+    }
+
+    /// Restrict orders of at least `threshold` to DePINs whose provider is
+    /// Verified or Audited; None lifts the restriction (admin only)
+    pub fn set_verified_provider_threshold(env: Env, admin: Address, threshold: Option<i128>) -> bool {
+        Self::assert_admin(&env, &admin);
+        match threshold {
+            Some(threshold) => env.storage().persistent().set(&PolicyKey::VerifiedProviderThreshold, &threshold),
+            None => env.storage().persistent().remove(&PolicyKey::VerifiedProviderThreshold),
+        }
+        true
+    }
+
+    /// Get the order amount from which the provider must be verified, if set
+    pub fn get_verified_provider_threshold(env: Env) -> Option<i128> {
+        env.storage().persistent().get(&PolicyKey::VerifiedProviderThreshold)
+    }
+
+    /// Allow or disallow a contract (e.g. the auction) to open prepaid orders (admin only)
+    pub fn set_order_source(env: Env, admin: Address, source: Address, allowed: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::OrderSource(source), &allowed);
+        true
+    }
+
+    /// Allow or disallow a contract (e.g. the resale marketplace) to transfer orders (admin only)
+    pub fn set_transfer_agent(env: Env, admin: Address, agent: Address, allowed: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::TransferAgent(agent), &allowed);
+        true
+    }
+
+    /// Allow or disallow a contract (e.g. usage metering) to release payment for usage (admin only)
+    pub fn set_usage_meter(env: Env, admin: Address, meter: Address, allowed: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::UsageMeter(meter), &allowed);
+        true
+    }
+
+    /// Allow or disallow a dispute-resolution contract to settle orders partially (admin only)
+    pub fn set_dispute_module(env: Env, admin: Address, module: Address, allowed: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::DisputeModule(module), &allowed);
+        true
+    }
+
+    /// Bound the hourly price of orders of `service_type`, or of all orders
+    /// when None, guarding against mispriced listings (admin only)
+    pub fn set_price_band(env: Env, admin: Address, service_type: Option<String>, min_price: i128, max_price: i128) -> bool {
+        Self::assert_admin(&env, &admin);
+        if min_price <= 0 || max_price < min_price {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        env.storage().persistent().set(&DataKey::PriceBand(service_type), &PriceBand { min_price, max_price });
+        true
+    }
+
+    /// Allow or disallow an approver of large order completions (admin only)
+    pub fn set_approver(env: Env, admin: Address, approver: Address, allowed: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::Approver(approver), &allowed);
+        true
+    }
+
+    /// Require `required` approvals before completing orders worth at least
+    /// `threshold`; zero `required` turns the check off (admin only)
+    pub fn set_approval_policy(env: Env, admin: Address, threshold: i128, required: u32) -> bool {
+        Self::assert_admin(&env, &admin);
+        if threshold < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        env.storage().persistent().set(&DataKey::ApprovalPolicy, &ApprovalPolicy { threshold, required });
+        true
+    }
+
+    /// Set how long new orders may stay Pending before anyone can expire them (admin only)
+    pub fn set_pending_timeout(env: Env, admin: Address, timeout: u64) -> bool {
+        Self::assert_admin(&env, &admin);
+        if timeout == 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        env.storage().persistent().set(&DataKey::PendingTimeout, &timeout);
+        true
+    }
+
+    /// Set how long after creation finished orders may be archived (admin only)
+    pub fn set_archive_age(env: Env, admin: Address, age: u64) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::ArchiveAge, &age);
+        true
+    }
+
+    /// Set the per-user caps on open orders and on how often orders may be
+    /// created; zero disables either (admin only)
+    pub fn set_order_limits(env: Env, admin: Address, max_open_orders: u32, min_ledger_interval: u32) -> bool {
+        Self::assert_admin(&env, &admin);
+        let limits = OrderLimits { max_open_orders, min_ledger_interval };
+        env.storage().persistent().set(&DataKey::OrderLimits, &limits);
+        true
+    }
+
+    /// Set the discount, in bps, subscribers of a tier get on list-priced orders (admin only)
+    pub fn set_tier_discount(env: Env, admin: Address, tier: u32, discount_bps: u32) -> bool {
+        Self::assert_admin(&env, &admin);
+        if discount_bps as i128 > BPS_DENOMINATOR {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        let mut discounts = Self::get_tier_discounts(env.clone());
+        discounts.set(tier, discount_bps);
+        env.storage().persistent().set(&DataKey::TierDiscounts, &discounts);
+        true
+    }
+
+    /// Set the loyalty discount levels as (min loyalty points, discount in bps)
+    /// pairs; users get the best level their points reach (admin only)
+    pub fn set_loyalty_discounts(env: Env, admin: Address, levels: Vec<(u32, u32)>) -> bool {
+        Self::assert_admin(&env, &admin);
+        for (_, discount_bps) in levels.iter() {
+            if discount_bps as i128 > BPS_DENOMINATOR {
+                panic_with_error!(&env, Error::InvalidAmount);
+            }
+        }
+        env.storage().persistent().set(&DataKey::LoyaltyDiscounts, &levels);
+        true
+    }
+
+    /// Set how long orders may stay Active before their users can claim a
+    /// refund (admin only)
+    pub fn set_deployment_timeout(env: Env, admin: Address, timeout: u64) -> bool {
+        Self::assert_admin(&env, &admin);
+        if timeout == 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        env.storage().persistent().set(&DataKey::DeploymentTimeout, &timeout);
+        true
+    }
+
+    /// Set how long after completion users may dispute an order (admin only)
+    ///
+    /// While the window is open a completed order keeps its funds escrowed;
+    /// `finalize_order` pays them out once it closes. Zero, the default, pays
+    /// out on completion and disables disputes of completed orders.
+    pub fn set_dispute_window(env: Env, admin: Address, window: u64) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::DisputeWindow, &window);
+        true
+    }
+
     /// Create a new order with escrow mechanism
+    ///
+    /// The total is pulled straight from the user's wallet by a token transfer
+    /// the user authorizes with this call; no user-profile deposit is needed.
+    /// `priority` scales the total by its multiplier before the payment is
+    /// taken, so an expedited order is paid for in full up front.
     pub fn create_order(
         env: Env,
         user: Address,
@@ -109,60 +646,70 @@ impl OrderContract {
         service_type: String,
         duration_hours: u64,
         price_per_hour: i128,
+        payment_token: Address,
         deployment_chain: String,
         service_params: String,
+        coupon_code: Option<String>,
+        priority: OrderPriority,
     ) -> BytesN<32> {
+        Self::assert_not_paused(&env, "create_order");
         // Ensure user is authenticated (they signed the transaction)
         user.require_auth();
+        Self::assert_within_limits(&env, &user, 1);
         
         // Validate inputs
         if duration_hours == 0 || price_per_hour <= 0 {
             panic_with_error!(&env, Error::InvalidAmount);
         }
+        Self::assert_token_accepted(&env, &payment_token);
 
-        // Check if DePIN exists in registry
-        let registry_contract: Address = env.storage().persistent()
-            .get(&DataKey::DepinRegistryContract)
-            .unwrap_or_else(|| panic_with_error!(&env, Error::ContractNotSet));
-
-        let depin_exists: bool = env.invoke_contract(
-            &registry_contract,
-            &soroban_sdk::symbol_short!("exists"),
-            soroban_sdk::vec![&env, depin_id.into_val(&env)]
-        );
-
-        if !depin_exists {
-            panic_with_error!(&env, Error::InvalidDepin);
-        }
+        // Check if DePIN exists in registry and the price is the one it lists
+        Self::assert_depin_exists(&env, &depin_id, &deployment_chain);
+        Self::assert_price(&env, &depin_id, &service_type, price_per_hour);
+        Self::reserve_slot(&env, &depin_id);
 
-        // Calculate total amount
-        let total_amount = (duration_hours as i128) * price_per_hour;
+        // Calculate total amount, less the user's membership discount
+        let list_amount = (duration_hours as i128) * price_per_hour;
+        let member_discount = Self::member_discount(&env, &user, list_amount);
+        let mut total_amount = list_amount - member_discount;
 
-        // Check user balance and deduct from user profile
-        let profile_contract: Address = env.storage().persistent()
-            .get(&DataKey::UserProfileContract)
-            .unwrap_or_else(|| panic_with_error!(&env, Error::ContractNotSet));
+        // Redeem the coupon, if any, in the same transaction as the payment
+        let coupon_discount = match &coupon_code {
+            Some(code) => {
+                let coupon_contract: Address = env.storage().persistent()
+                    .get(&DataKey::CouponContract)
+                    .unwrap_or_else(|| panic_with_error!(&env, Error::ContractNotSet));
+                CouponClient::new(&env, &coupon_contract).redeem(
+                    &env.current_contract_address(),
+                    code,
+                    &user,
+                    &depin_id,
+                    &service_type,
+                    &total_amount,
+                )
+            }
+            None => 0,
+        };
+        total_amount -= coupon_discount;
+        let discount_amount = member_discount + coupon_discount;
+        total_amount += Self::priority_surcharge(&env, priority, total_amount);
 
-        let has_sufficient_balance: bool = env.invoke_contract(
-            &profile_contract,
-            &soroban_sdk::symbol_short!("has_suff"),
-            soroban_sdk::vec![&env, user.into_val(&env), total_amount.into_val(&env)]
-        );
+        // Generate unique order ID
+        let order_id = Self::generate_order_id(&env, &user, &depin_id);
 
-        if !has_sufficient_balance {
-            panic_with_error!(&env, Error::InsufficientBalance);
+        let escrow_contract: Option<Address> = env.storage().persistent().get(&DataKey::EscrowContract);
+        if total_amount > 0 {
+            match &escrow_contract {
+                // Lock the payment in the standalone escrow contract
+                Some(escrow) => {
+                    Self::create_escrow(&env, escrow, &order_id, &user, &payment_token, total_amount)
+                        .lock(&env.current_contract_address(), &order_id);
+                }
+                // Take the payment into this contract's custody
+                None => Self::take_custody(&env, &user, &payment_token, total_amount),
+            }
         }
 
-        // Deduct balance from user
-        let _deduct_result: bool = env.invoke_contract(
-            &profile_contract,
-            &soroban_sdk::symbol_short!("deduct"),
-            soroban_sdk::vec![&env, user.into_val(&env), total_amount.into_val(&env)]
-        );
-
-        // Generate unique order ID
-        let order_id = Self::generate_order_id(&env);
-
         // Create order
         let order = Order {
             order_id: order_id.clone(),
@@ -172,206 +719,1976 @@ impl OrderContract {
             duration_hours,
             price_per_hour,
             total_amount,
+            payment_token,
             status: OrderStatus::Pending,
             created_at: env.ledger().timestamp(),
             external_tx_id: None,
             deployment_chain,
             service_params,
             escrowed_amount: total_amount,
+            refunded_amount: 0,
+            released_amount: 0,
+            escrow_contract,
+            coupon_code,
+            discount_amount,
+            expires_at: Self::pending_deadline(&env),
+            started_at: 0,
+            completed_at: 0,
+            deployment_deadline: 0,
+            approvals: Vec::new(&env),
+            priority,
         };
 
-        // Store order
-        env.storage().persistent().set(&DataKey::Order(order_id.clone()), &order);
+        Self::record_order(&env, &order, &order.user);
+        order_id
+    }
 
-        // Update total escrowed amount
-        let current_escrowed: i128 = env.storage().persistent()
-            .get(&DataKey::TotalEscrowed)
-            .unwrap_or(0);
-        env.storage().persistent().set(&DataKey::TotalEscrowed, &(current_escrowed + total_amount));
+    /// Open several orders paid in the payment token, charging their total in
+    /// one transfer. Returns the new order IDs in request order.
+    ///
+    /// Every request is validated and priced like `create_order` (without
+    /// coupons) and the batch fails as a whole if any of them does.
+    pub fn create_orders_batch(env: Env, user: Address, requests: Vec<OrderRequest>) -> Vec<BytesN<32>> {
+        Self::assert_not_paused(&env, "create_orders_batch");
+        user.require_auth();
 
-        // Add to user's order list
-        Self::add_user_order(&env, &user, &order_id);
+        if requests.is_empty() || requests.len() > MAX_BATCH_SIZE {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        Self::assert_within_limits(&env, &user, requests.len());
 
-        // Add to DePIN's order list
-        Self::add_depin_order(&env, &depin_id, &order_id);
+        let mut amounts: Vec<(i128, i128)> = Vec::new(&env);
+        let mut total_amount: i128 = 0;
+        for request in requests.iter() {
+            if request.duration_hours == 0 || request.price_per_hour <= 0 {
+                panic_with_error!(&env, Error::InvalidAmount);
+            }
+            Self::assert_depin_exists(&env, &request.depin_id, &request.deployment_chain);
+            Self::assert_price(&env, &request.depin_id, &request.service_type, request.price_per_hour);
+            Self::reserve_slot(&env, &request.depin_id);
 
-        order_id
+            let list_amount = request.duration_hours as i128 * request.price_per_hour;
+            let discount = Self::member_discount(&env, &user, list_amount);
+            let amount = list_amount - discount;
+            let amount = amount + Self::priority_surcharge(&env, request.priority, amount);
+            amounts.push_back((amount, discount));
+            total_amount += amount;
+        }
+        // Check the whole batch, not each order, against the compliance threshold
+        Self::assert_compliant(&env, &user, total_amount);
+
+        let payment_token = Self::payment_token(&env);
+        let escrow_contract: Option<Address> = env.storage().persistent().get(&DataKey::EscrowContract);
+        match &escrow_contract {
+            // Pull the total once, then fund each order's escrow from it
+            Some(_) => Self::collect_payment(&env, &user, &payment_token, total_amount),
+            None => Self::take_custody(&env, &user, &payment_token, total_amount),
+        }
+
+        let mut order_ids = Vec::new(&env);
+        for (request, (amount, discount_amount)) in requests.iter().zip(amounts.iter()) {
+            let order_id = Self::generate_order_id(&env, &user, &request.depin_id);
+            if let Some(escrow) = &escrow_contract {
+                Self::open_funded_escrow(&env, escrow, &order_id, &user, &payment_token, amount);
+            }
+
+            let order = Order {
+                order_id: order_id.clone(),
+                user: user.clone(),
+                depin_id: request.depin_id,
+                service_type: request.service_type,
+                duration_hours: request.duration_hours,
+                price_per_hour: request.price_per_hour,
+                total_amount: amount,
+                payment_token: payment_token.clone(),
+                status: OrderStatus::Pending,
+                created_at: env.ledger().timestamp(),
+                external_tx_id: None,
+                deployment_chain: request.deployment_chain,
+                service_params: request.service_params,
+                escrowed_amount: amount,
+                refunded_amount: 0,
+                released_amount: 0,
+                escrow_contract: escrow_contract.clone(),
+                coupon_code: None,
+                discount_amount,
+                expires_at: Self::pending_deadline(&env),
+                started_at: 0,
+                completed_at: 0,
+                deployment_deadline: 0,
+                approvals: Vec::new(&env),
+                priority: request.priority,
+            };
+            Self::record_order(&env, &order, &order.user);
+            order_ids.push_back(order_id);
+        }
+        order_ids
     }
 
-    /// Update order status (admin only)
-    pub fn update_order_status(
+    /// Create an order paid for by an approved source contract
+    ///
+    /// `total_amount` of `payment_token` is pulled from the source (e.g. the
+    /// auction contract settling a winning bid), so a failed call leaves the
+    /// funds with it. That token is escrowed as is, even if the platform
+    /// payment token has changed since the source took the funds. They are
+    /// locked with the user as depositor, so refunds go back to the user.
+    pub fn create_funded_order(
+        env: Env,
+        source: Address,
+        user: Address,
+        depin_id: BytesN<32>,
+        service_type: String,
+        duration_hours: u64,
+        total_amount: i128,
+        payment_token: Address,
+        deployment_chain: String,
+        service_params: String,
+    ) -> BytesN<32> {
+        Self::assert_not_paused(&env, "create_funded_order");
+        source.require_auth();
+        let allowed: bool = env.storage().persistent()
+            .get(&DataKey::OrderSource(source.clone()))
+            .unwrap_or(false);
+        if !allowed {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        if duration_hours == 0 || total_amount <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        Self::assert_depin_exists(&env, &depin_id, &deployment_chain);
+        Self::reserve_slot(&env, &depin_id);
+
+        let escrow: Address = env.storage().persistent()
+            .get(&DataKey::EscrowContract)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::ContractNotSet));
+
+        let order_id = Self::generate_order_id(&env, &user, &depin_id);
+        token::Client::new(&env, &payment_token).transfer(&source, &env.current_contract_address(), &total_amount);
+        Self::open_funded_escrow(&env, &escrow, &order_id, &user, &payment_token, total_amount);
+
+        let order = Order {
+            order_id: order_id.clone(),
+            user,
+            depin_id,
+            service_type,
+            duration_hours,
+            price_per_hour: total_amount / duration_hours as i128,
+            total_amount,
+            payment_token,
+            status: OrderStatus::Pending,
+            created_at: env.ledger().timestamp(),
+            external_tx_id: None,
+            deployment_chain,
+            service_params,
+            escrowed_amount: total_amount,
+            refunded_amount: 0,
+            released_amount: 0,
+            escrow_contract: Some(escrow),
+            coupon_code: None,
+            discount_amount: 0,
+            expires_at: Self::pending_deadline(&env),
+            started_at: 0,
+            completed_at: 0,
+            deployment_deadline: 0,
+            approvals: Vec::new(&env),
+            priority: OrderPriority::Normal,
+        };
+
+        Self::record_order(&env, &order, &source);
+        order_id
+    }
+
+    /// Quote how much of an accepted asset pays for `usd_amount` payment token units
+    ///
+    /// The quote can be used once, before it expires, as long as the amount
+    /// at the then-current price is at most `max_slippage_bps` above it.
+    pub fn quote_payment(
+        env: Env,
+        user: Address,
+        token: Address,
+        usd_amount: i128,
+        max_slippage_bps: u32,
+    ) -> PaymentQuote {
+        user.require_auth();
+        if usd_amount <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        let accepted: bool = env.storage().persistent()
+            .get(&DataKey::AcceptedToken(token.clone()))
+            .unwrap_or(false);
+        if !accepted {
+            panic_with_error!(&env, Error::PriceUnavailable);
+        }
+
+        let config = Self::oracle_config(&env);
+        let quote_id: u64 = env.storage().persistent().get(&DataKey::QuoteCounter).unwrap_or(0) + 1;
+        env.storage().persistent().set(&DataKey::QuoteCounter, &quote_id);
+
+        let quote = PaymentQuote {
+            quote_id,
+            user,
+            token: token.clone(),
+            usd_amount,
+            token_amount: Self::convert_usd(&env, &config, &token, usd_amount),
+            max_slippage_bps,
+            expires_at: env.ledger().timestamp() + config.quote_ttl,
+            used: false,
+        };
+        env.storage().persistent().set(&DataKey::Quote(quote_id), &quote);
+        quote
+    }
+
+    /// Create an order paid in the asset of an earlier quote
+    ///
+    /// The order total (duration x price) must match the quoted USD amount.
+    /// The asset amount is re-priced now and escrowed; the order records its
+    /// amounts in that asset.
+    pub fn create_order_with_quote(
+        env: Env,
+        user: Address,
+        quote_id: u64,
+        depin_id: BytesN<32>,
+        service_type: String,
+        duration_hours: u64,
+        price_per_hour: i128,
+        deployment_chain: String,
+        service_params: String,
+    ) -> BytesN<32> {
+        Self::assert_not_paused(&env, "create_order_with_quote");
+        user.require_auth();
+        Self::assert_within_limits(&env, &user, 1);
+
+        let mut quote: PaymentQuote = env.storage().persistent()
+            .get(&DataKey::Quote(quote_id))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::QuoteExpired));
+        if quote.user != user {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+        if quote.used || env.ledger().timestamp() > quote.expires_at {
+            panic_with_error!(&env, Error::QuoteExpired);
+        }
+        if duration_hours == 0 || (duration_hours as i128) * price_per_hour != quote.usd_amount {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        Self::assert_depin_exists(&env, &depin_id, &deployment_chain);
+        Self::assert_price(&env, &depin_id, &service_type, price_per_hour);
+        Self::reserve_slot(&env, &depin_id);
+
+        let total_amount = Self::convert_usd(&env, &Self::oracle_config(&env), &quote.token, quote.usd_amount);
+        let max_amount = quote.token_amount * (BPS_DENOMINATOR + quote.max_slippage_bps as i128) / BPS_DENOMINATOR;
+        if total_amount > max_amount {
+            panic_with_error!(&env, Error::SlippageExceeded);
+        }
+        quote.used = true;
+        env.storage().persistent().set(&DataKey::Quote(quote_id), &quote);
+
+        let escrow: Address = env.storage().persistent()
+            .get(&DataKey::EscrowContract)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::ContractNotSet));
+
+        let order_id = Self::generate_order_id(&env, &user, &depin_id);
+        Self::create_escrow(&env, &escrow, &order_id, &user, &quote.token, total_amount)
+            .lock(&env.current_contract_address(), &order_id);
+
+        let order = Order {
+            order_id: order_id.clone(),
+            user,
+            depin_id,
+            service_type,
+            duration_hours,
+            price_per_hour: total_amount / duration_hours as i128,
+            total_amount,
+            payment_token: quote.token,
+            status: OrderStatus::Pending,
+            created_at: env.ledger().timestamp(),
+            external_tx_id: None,
+            deployment_chain,
+            service_params,
+            escrowed_amount: total_amount,
+            refunded_amount: 0,
+            released_amount: 0,
+            escrow_contract: Some(escrow),
+            coupon_code: None,
+            discount_amount: 0,
+            expires_at: Self::pending_deadline(&env),
+            started_at: 0,
+            completed_at: 0,
+            deployment_deadline: 0,
+            approvals: Vec::new(&env),
+            priority: OrderPriority::Normal,
+        };
+
+        Self::record_order(&env, &order, &order.user);
+        order_id
+    }
+
+    /// Move an order along its deployment: Pending -> Active -> Deployed (admin only)
+    ///
+    /// Completion, cancellation and failure move funds and go through
+    /// `complete_order`, `refund_order`, `cancel_order` or `expire_order`.
+    pub fn update_order_status(
         env: Env,
         admin: Address,
         order_id: BytesN<32>,
         new_status: OrderStatus,
         external_tx_id: Option<String>,
     ) -> bool {
+        Self::assert_not_paused(&env, "update_order_status");
+        Self::assert_admin(&env, &admin);
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+
+        if !matches!(new_status, OrderStatus::Active | OrderStatus::Deployed)
+            || !Self::can_transition(&order.status, &new_status)
+        {
+            panic_with_error!(&env, Error::InvalidTransition);
+        }
+
+        let old_status = order.status.clone();
+        if old_status == OrderStatus::Pending {
+            order.started_at = env.ledger().timestamp();
+        }
+        if new_status == OrderStatus::Active {
+            order.deployment_deadline = env.ledger().timestamp() + Self::get_deployment_timeout(env.clone());
+        }
+        Self::set_status(&env, &mut order, new_status, Some(&admin));
+        if external_tx_id.is_some() {
+            order.external_tx_id = external_tx_id;
+        }
+
+        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+
+        Self::publish(&env, symbol_short!("status"), &order, (old_status, order.status.clone()));
+        if order.status == OrderStatus::Deployed {
+            Self::publish(&env, symbol_short!("deployed"), &order, (order.escrowed_amount, order.external_tx_id.clone()));
+        }
+        true
+    }
+
+    /// Mark an Active order Deployed, recording the deployment transaction
+    /// (the DePIN's payout address only)
+    pub fn confirm_deployment(env: Env, provider: Address, order_id: BytesN<32>, external_tx_id: String) -> bool {
+        Self::assert_not_paused(&env, "confirm_deployment");
+        provider.require_auth();
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        Self::assert_provider(&env, &provider, &order);
+        if order.status != OrderStatus::Active {
+            panic_with_error!(&env, Error::InvalidTransition);
+        }
+
+        Self::set_status(&env, &mut order, OrderStatus::Deployed, Some(&provider));
+        order.external_tx_id = Some(external_tx_id);
+        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+
+        Self::publish(&env, symbol_short!("status"), &order, (OrderStatus::Active, OrderStatus::Deployed));
+        Self::publish(&env, symbol_short!("deployed"), &order, (order.escrowed_amount, order.external_tx_id.clone()));
+        true
+    }
+
+    /// Confirm a deployed order may be completed (approvers only). Returns how
+    /// many approvals the order now has.
+    pub fn approve_completion(env: Env, approver: Address, order_id: BytesN<32>) -> u32 {
+        approver.require_auth();
+        if !Self::is_approver(&env, &approver) {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if order.status != OrderStatus::Deployed {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if order.approvals.contains(&approver) {
+            panic_with_error!(&env, Error::AlreadyApproved);
+        }
+
+        order.approvals.push_back(approver.clone());
+        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+        Self::publish(&env, symbol_short!("approved"), &order, approver);
+        order.approvals.len()
+    }
+
+    /// Complete order and transfer funds to treasury
+    ///
+    /// Orders at or above the approval policy threshold first need the
+    /// required number of `approve_completion` calls from current approvers.
+    pub fn complete_order(env: Env, admin: Address, order_id: BytesN<32>) -> bool {
+        Self::assert_not_paused(&env, "complete_order");
+        Self::assert_admin(&env, &admin);
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+
+        if !Self::can_transition(&order.status, &OrderStatus::Completed) {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        Self::assert_approved(&env, &order);
+
+        // Update order status
+        Self::set_status(&env, &mut order, OrderStatus::Completed, Some(&admin));
+        order.completed_at = env.ledger().timestamp();
+        let escrowed_amount = order.escrowed_amount;
+
+        // Release the escrowed funds to the treasury and provider, unless
+        // they stay escrowed while the user may still dispute the order
+        if escrowed_amount > 0 && Self::get_dispute_window(env.clone()) == 0 {
+            Self::pay_out(&env, &mut order, escrowed_amount);
+        }
+        env.storage().persistent().set(&DataKey::Order(order_id.clone()), &order);
+        Self::release_slot(&env, &order);
+        Self::publish(&env, symbol_short!("completed"), &order, escrowed_amount);
+
+        // Count the spend towards the user's rewards for this epoch
+        let rewards: Option<Address> = env.storage().persistent().get(&DataKey::RewardsContract);
+        if let Some(rewards) = rewards {
+            if order.total_amount > 0 {
+                RewardsClient::new(&env, &rewards).record(
+                    &env.current_contract_address(),
+                    &Category::UserSpend,
+                    &order.user,
+                    &order.total_amount,
+                );
+            }
+        }
+
+        true
+    }
+
+    /// Refund order (admin only)
+    ///
+    /// Refunds, here and on cancellation, expiry or SLA claims, are token
+    /// transfers from the escrow contract or this contract's custody to the
+    /// user's wallet, never credits to a user-profile balance.
+    pub fn refund_order(env: Env, admin: Address, order_id: BytesN<32>) -> bool {
         Self::assert_admin(&env, &admin);
+        Self::refund(&env, order_id, &admin)
+    }
+
+    /// Move a Pending or Failed order to another DePIN, repricing it at the
+    /// new DePIN's listed rate (admin only). Returns the new order total.
+    ///
+    /// The order keeps its ID and escrow and reopens as Pending. Escrow above
+    /// the new total goes back to the user; a shortfall is charged to the
+    /// user, who then has to authorize the call as well. Expedited orders keep
+    /// their priority surge on the new price.
+    pub fn reassign_order(env: Env, admin: Address, order_id: BytesN<32>, new_depin_id: BytesN<32>) -> i128 {
+        Self::assert_not_paused(&env, "reassign_order");
+        Self::assert_admin(&env, &admin);
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if !matches!(order.status, OrderStatus::Pending | OrderStatus::Failed) {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        Self::assert_depin_exists(&env, &new_depin_id, &order.deployment_chain);
+        let price_per_hour = Self::listed_price(&env, &new_depin_id, &order.service_type);
+        Self::assert_price_in_band(&env, &order.service_type, price_per_hour);
+        let base_amount = (order.duration_hours as i128 * price_per_hour - order.discount_amount).max(0);
+        // Expedited orders keep paying their surge at the new price
+        let total_amount = base_amount + Self::priority_surcharge(&env, order.priority, base_amount);
+        Self::assert_trusted_provider(&env, &new_depin_id, total_amount);
+
+        // Failed orders already gave their slot back
+        Self::reserve_slot(&env, &new_depin_id);
+        if order.status == OrderStatus::Pending {
+            Self::release_slot(&env, &order);
+        }
+
+        let shortfall = total_amount - order.escrowed_amount;
+        if shortfall > 0 {
+            order.user.require_auth();
+            match &order.escrow_contract {
+                Some(escrow) => {
+                    EscrowClient::new(&env, escrow).top_up(&env.current_contract_address(), &order_id, &shortfall);
+                }
+                None => Self::take_custody(&env, &order.user, &order.payment_token, shortfall),
+            }
+            order.escrowed_amount += shortfall;
+            Self::adjust_escrowed(&env, &order, shortfall);
+        } else if shortfall < 0 {
+            Self::refund_to_user(&env, &mut order, -shortfall);
+        }
+
+        let old_depin_id = order.depin_id.clone();
+        Self::remove_depin_order(&env, &old_depin_id, &order_id);
+        Self::add_depin_order(&env, &new_depin_id, &order_id);
+        if order.status == OrderStatus::Pending {
+            Self::adjust_unsettled_depin_orders(&env, &old_depin_id, -1);
+            Self::adjust_unsettled_depin_orders(&env, &new_depin_id, 1);
+        }
+
+        // The escrow now counts towards the new DePIN
+        Self::adjust_escrowed(&env, &order, -order.escrowed_amount);
+        Self::update_metrics(&env, |metrics| {
+            let volume = metrics.volume_by_token.get(order.payment_token.clone()).unwrap_or(0);
+            metrics.volume_by_token.set(order.payment_token.clone(), volume + total_amount - order.total_amount);
+            let moved_from = metrics.depin_orders.get(old_depin_id.clone()).unwrap_or(0);
+            metrics.depin_orders.set(old_depin_id.clone(), moved_from.saturating_sub(1));
+            let moved_to = metrics.depin_orders.get(new_depin_id.clone()).unwrap_or(0);
+            metrics.depin_orders.set(new_depin_id.clone(), moved_to + 1);
+        });
+        order.depin_id = new_depin_id;
+        Self::adjust_escrowed(&env, &order, order.escrowed_amount);
+        order.price_per_hour = price_per_hour;
+        order.total_amount = total_amount;
+        order.expires_at = Self::pending_deadline(&env);
+        order.started_at = 0;
+        order.deployment_deadline = 0;
+        order.approvals = Vec::new(&env);
+        Self::set_status(&env, &mut order, OrderStatus::Pending, Some(&admin));
+        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+
+        Self::publish(&env, symbol_short!("reassign"), &order, (old_depin_id, total_amount));
+        total_amount
+    }
+
+    /// Split what a terminated order has escrowed: refund `refund_amount` to
+    /// the user and settle the rest as earned (admin or approved dispute module)
+    ///
+    /// For services that stopped partway through; the order ends as Failed.
+    pub fn partial_refund(env: Env, caller: Address, order_id: BytesN<32>, refund_amount: i128) -> bool {
+        Self::assert_arbitrator(&env, &caller);
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if !Self::can_transition(&order.status, &OrderStatus::Failed) {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if refund_amount < 0 || refund_amount > order.escrowed_amount {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let payout_amount = order.escrowed_amount - refund_amount;
+        if refund_amount > 0 {
+            Self::refund_to_user(&env, &mut order, refund_amount);
+        }
+        if payout_amount > 0 {
+            Self::pay_out(&env, &mut order, payout_amount);
+        }
+        Self::set_status(&env, &mut order, OrderStatus::Failed, Some(&caller));
+
+        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+        Self::release_slot(&env, &order);
+        Self::publish(&env, symbol_short!("partial"), &order, (refund_amount, payout_amount));
+        true
+    }
+
+    /// Pay out a completed order's funds once its dispute window has closed
+    /// (permissionless). Returns the amount paid.
+    pub fn finalize_order(env: Env, order_id: BytesN<32>) -> i128 {
+        Self::assert_not_paused(&env, "finalize_order");
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if order.status != OrderStatus::Completed {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if Self::dispute_window_open(&env, &order) {
+            panic_with_error!(&env, Error::NotExpired);
+        }
+
+        let amount = order.escrowed_amount;
+        if amount > 0 {
+            Self::pay_out(&env, &mut order, amount);
+            env.storage().persistent().set(&DataKey::Order(order_id), &order);
+        }
+        amount
+    }
+
+    /// Dispute a running order, or a completed one within the dispute window
+    /// (order owner only)
+    ///
+    /// Whatever the order still has escrowed stays frozen until an arbitrator
+    /// calls `resolve_dispute`.
+    pub fn open_dispute(env: Env, user: Address, order_id: BytesN<32>, reason: String) -> bool {
+        Self::assert_not_paused(&env, "open_dispute");
+        user.require_auth();
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if order.user != user {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+        if !Self::can_transition(&order.status, &OrderStatus::Disputed) {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if order.status == OrderStatus::Completed && !Self::dispute_window_open(&env, &order) {
+            panic_with_error!(&env, Error::DisputeWindowClosed);
+        }
+
+        let old_status = order.status.clone();
+        Self::set_status(&env, &mut order, OrderStatus::Disputed, Some(&user));
+        env.storage().persistent().set(&DataKey::Order(order_id.clone()), &order);
+        env.storage().persistent().set(&DataKey::Dispute(order_id), &reason);
+
+        Self::publish(&env, symbol_short!("status"), &order, (old_status, OrderStatus::Disputed));
+        Self::publish(&env, symbol_short!("disputed"), &order, reason);
+        true
+    }
+
+    /// Settle a disputed order by refunding `user_share` and paying out
+    /// `provider_share` of what it has escrowed (admin or approved dispute module)
+    ///
+    /// The shares must add up to the escrowed amount. The order ends as
+    /// Completed if the user gets nothing back, otherwise as Failed.
+    pub fn resolve_dispute(
+        env: Env,
+        caller: Address,
+        order_id: BytesN<32>,
+        user_share: i128,
+        provider_share: i128,
+    ) -> bool {
+        Self::assert_arbitrator(&env, &caller);
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if order.status != OrderStatus::Disputed {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if user_share < 0 || provider_share < 0 || user_share + provider_share != order.escrowed_amount {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        if user_share > 0 {
+            Self::refund_to_user(&env, &mut order, user_share);
+        }
+        if provider_share > 0 {
+            Self::pay_out(&env, &mut order, provider_share);
+        }
+        let status = if user_share > 0 { OrderStatus::Failed } else { OrderStatus::Completed };
+        Self::set_status(&env, &mut order, status, Some(&caller));
+
+        env.storage().persistent().set(&DataKey::Order(order_id.clone()), &order);
+        env.storage().persistent().remove(&DataKey::Dispute(order_id));
+        // Orders disputed after completion freed their slot then
+        if order.completed_at == 0 {
+            Self::release_slot(&env, &order);
+        }
+        Self::publish(&env, symbol_short!("status"), &order, (OrderStatus::Disputed, order.status.clone()));
+        Self::publish(&env, symbol_short!("resolved"), &order, (user_share, provider_share));
+        true
+    }
+
+    /// Cancel an order still Pending past its deadline and refund the user in full
+    /// (permissionless)
+    pub fn expire_order(env: Env, order_id: BytesN<32>) -> i128 {
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+
+        if order.status != OrderStatus::Pending {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if env.ledger().timestamp() < order.expires_at {
+            panic_with_error!(&env, Error::NotExpired);
+        }
+
+        let refunded = Self::return_funds(&env, &mut order);
+        Self::set_status(&env, &mut order, OrderStatus::Cancelled, None);
+
+        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+        Self::release_slot(&env, &order);
+        Self::publish(&env, symbol_short!("expired"), &order, refunded);
+        refunded
+    }
+
+    /// Fail an order still Active past its deployment deadline and refund the
+    /// user in full (permissionless)
+    pub fn claim_sla_refund(env: Env, order_id: BytesN<32>) -> i128 {
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+
+        if order.status != OrderStatus::Active {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if env.ledger().timestamp() < order.deployment_deadline {
+            panic_with_error!(&env, Error::NotExpired);
+        }
+
+        let refunded = Self::return_funds(&env, &mut order);
+        Self::set_status(&env, &mut order, OrderStatus::Failed, None);
+
+        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+        Self::release_slot(&env, &order);
+        Self::publish(&env, symbol_short!("slarefund"), &order, refunded);
+        refunded
+    }
+
+    /// Cancel order (user only, before deployment)
+    pub fn cancel_order(env: Env, user: Address, order_id: BytesN<32>) -> bool {
+        user.require_auth();
+
+        let order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+
+        if order.user != user {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        if order.status != OrderStatus::Pending {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        // Keep the cancellation fee before refunding the remainder
+        Self::collect_cancellation_fee(&env, order);
+        Self::refund(&env, order_id, &user)
+    }
+
+    /// Pay out the share of a running order covered by `hours` of verified usage
+    /// (approved usage meters only)
+    ///
+    /// The payment is pro-rata to the order total, settled like a completed
+    /// order and capped at what is still escrowed. Returns the amount paid.
+    pub fn record_usage(env: Env, meter: Address, order_id: BytesN<32>, hours: u64) -> i128 {
+        Self::assert_not_paused(&env, "record_usage");
+        meter.require_auth();
+        let allowed: bool = env.storage().persistent()
+            .get(&DataKey::UsageMeter(meter))
+            .unwrap_or(false);
+        if !allowed {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if !matches!(order.status, OrderStatus::Active | OrderStatus::Deployed) {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        let amount = (order.total_amount * hours as i128 / order.duration_hours as i128)
+            .min(order.escrowed_amount);
+        if amount <= 0 {
+            return 0;
+        }
+
+        Self::pay_out(&env, &mut order, amount);
+        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+        amount
+    }
+
+    /// Release the share of a running order earned so far to its provider
+    /// (the DePIN's payout address only)
+    ///
+    /// Earnings accrue linearly from when the order left Pending until its
+    /// duration is up; whatever has not been claimed stays escrowed and is
+    /// refunded if the order fails. Returns the amount released.
+    pub fn claim_earned(env: Env, provider: Address, order_id: BytesN<32>) -> i128 {
+        Self::assert_not_paused(&env, "claim_earned");
+        provider.require_auth();
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        Self::assert_provider(&env, &provider, &order);
+        if !matches!(order.status, OrderStatus::Active | OrderStatus::Deployed) {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        let duration = order.duration_hours * 3600;
+        let elapsed = (env.ledger().timestamp() - order.started_at).min(duration);
+        let earned = order.total_amount * elapsed as i128 / duration as i128;
+        let amount = (earned - order.released_amount).min(order.escrowed_amount);
+        if amount <= 0 {
+            return 0;
+        }
+
+        Self::pay_out(&env, &mut order, amount);
+        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+        Self::publish(&env, symbol_short!("claimed"), &order, amount);
+        amount
+    }
+
+    /// Expedite a Pending order created at Normal priority, charging the surge
+    /// for `priority` on top of its total (order owner only). Orders can also
+    /// be given a priority when created; this is for raising it afterwards,
+    /// once per order. Returns the amount charged.
+    pub fn set_order_priority(env: Env, user: Address, order_id: BytesN<32>, priority: OrderPriority) -> i128 {
+        Self::assert_not_paused(&env, "set_order_priority");
+        user.require_auth();
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if order.user != user {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+        if order.status != OrderStatus::Pending || order.priority != OrderPriority::Normal {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if priority == OrderPriority::Normal {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let charge = Self::priority_surcharge(&env, priority, order.total_amount);
+        if charge > 0 {
+            Self::assert_compliant(&env, &user, order.total_amount + charge);
+            match &order.escrow_contract {
+                Some(escrow) => {
+                    EscrowClient::new(&env, escrow).top_up(&env.current_contract_address(), &order_id, &charge);
+                }
+                None => Self::take_custody(&env, &user, &order.payment_token, charge),
+            }
+            order.total_amount += charge;
+            order.escrowed_amount += charge;
+            Self::adjust_escrowed(&env, &order, charge);
+            Self::update_metrics(&env, |metrics| {
+                let volume = metrics.volume_by_token.get(order.payment_token.clone()).unwrap_or(0);
+                metrics.volume_by_token.set(order.payment_token.clone(), volume + charge);
+            });
+        }
+
+        order.priority = priority;
+        env.storage().persistent().set(&DataKey::Order(order_id.clone()), &order);
+        Self::index_priority(&env, &order, true);
+
+        Self::publish(&env, symbol_short!("priority"), &order, (priority, charge));
+        charge
+    }
+
+    /// Add `extra_hours` to a running order, charging them at its hourly price
+    /// (order owner only). Returns the amount charged.
+    pub fn extend_order(env: Env, user: Address, order_id: BytesN<32>, extra_hours: u64) -> i128 {
+        Self::assert_not_paused(&env, "extend_order");
+        user.require_auth();
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if order.user != user {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+        if !matches!(order.status, OrderStatus::Active | OrderStatus::Deployed) {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        if extra_hours == 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let charge = extra_hours as i128 * order.price_per_hour;
+        Self::assert_compliant(&env, &user, order.total_amount + charge);
+        match &order.escrow_contract {
+            Some(escrow) => {
+                EscrowClient::new(&env, escrow).top_up(&env.current_contract_address(), &order_id, &charge);
+            }
+            None => Self::take_custody(&env, &user, &order.payment_token, charge),
+        }
+
+        order.duration_hours += extra_hours;
+        order.total_amount += charge;
+        Self::update_metrics(&env, |metrics| {
+            metrics.total_duration_hours += extra_hours;
+            let volume = metrics.volume_by_token.get(order.payment_token.clone()).unwrap_or(0);
+            metrics.volume_by_token.set(order.payment_token.clone(), volume + charge);
+        });
+        order.escrowed_amount += charge;
+        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+        Self::adjust_escrowed(&env, &order, charge);
+
+        Self::publish(&env, symbol_short!("extended"), &order, (extra_hours, charge));
+        charge
+    }
+
+    /// Move an unfinished order, and the right to its refunds, to a new user
+    /// (approved transfer agents only)
+    ///
+    /// Agents such as the resale marketplace settle payment between the
+    /// parties themselves and call this once the buyer has paid.
+    pub fn transfer_order(env: Env, agent: Address, order_id: BytesN<32>, from: Address, to: Address) -> bool {
+        Self::assert_not_paused(&env, "transfer_order");
+        agent.require_auth();
+        let allowed: bool = env.storage().persistent()
+            .get(&DataKey::TransferAgent(agent))
+            .unwrap_or(false);
+        if !allowed {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if order.user != from {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+        if !Self::is_transferable(&order) {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        Self::change_owner(&env, &mut order, &to);
+        true
+    }
+
+    /// Give a Pending or Active order, and the right to its refunds, to
+    /// another user (order owner only). The owner-side counterpart of the
+    /// agent-only `transfer_order`.
+    pub fn gift_order(env: Env, user: Address, order_id: BytesN<32>, new_owner: Address) -> bool {
+        Self::assert_not_paused(&env, "gift_order");
+        user.require_auth();
+
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if order.user != user {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+        if !Self::is_open(&order.status) {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        Self::change_owner(&env, &mut order, &new_owner);
+        Self::publish(&env, symbol_short!("gifted"), &order, user);
+        true
+    }
+
+    /// Replace a finished order with a compact archived record and drop it
+    /// from the user and DePIN order lists (permissionless)
+    ///
+    /// Only Completed, Cancelled or Failed orders with nothing left escrowed,
+    /// no open dispute window and older than the archive age qualify.
+    pub fn archive_order(env: Env, order_id: BytesN<32>) -> bool {
+        let order = Self::get_order(env.clone(), order_id);
+        if !Self::is_archivable(&env, &order) {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+        Self::archive(&env, order);
+        true
+    }
+
+    /// Archive every qualifying order of `order_ids`, skipping the rest and
+    /// unknown IDs. Returns how many were archived.
+    pub fn archive_orders(env: Env, order_ids: Vec<BytesN<32>>) -> u32 {
+        if order_ids.len() > MAX_PAGE_SIZE {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let mut archived = 0;
+        for order_id in order_ids.iter() {
+            let order: Option<Order> = env.storage().persistent().get(&DataKey::Order(order_id));
+            if let Some(order) = order.filter(|order| Self::is_archivable(&env, order)) {
+                Self::archive(&env, order);
+                archived += 1;
+            }
+        }
+        archived
+    }
+
+    /// Get the user an order currently belongs to
+    pub fn get_order_user(env: Env, order_id: BytesN<32>) -> Address {
+        Self::get_order(env, order_id).user
+    }
+
+    /// Get the parties, amount and service window of an order
+    pub fn get_order_terms(env: Env, order_id: BytesN<32>) -> OrderTerms {
+        let order = Self::get_order(env, order_id);
+        OrderTerms {
+            user: order.user,
+            depin_id: order.depin_id,
+            total_amount: order.total_amount,
+            start_time: order.created_at,
+            end_time: order.created_at + order.duration_hours * 3600,
+        }
+    }
+
+    /// Get the DePIN an order was placed with
+    pub fn get_order_depin(env: Env, order_id: BytesN<32>) -> BytesN<32> {
+        Self::get_order(env, order_id).depin_id
+    }
+
+    /// Get order details
+    pub fn get_order(env: Env, order_id: BytesN<32>) -> Order {
+        env.storage().persistent()
+            .get(&DataKey::Order(order_id))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound))
+    }
+
+    /// Get the archived record of an order, if it has been archived
+    pub fn get_archived_order(env: Env, order_id: BytesN<32>) -> Option<ArchivedOrder> {
+        env.storage().persistent().get(&DataKey::Archived(order_id))
+    }
+
+    /// Get all orders for a user
+    pub fn list_user_orders(env: Env, user: Address) -> Vec<BytesN<32>> {
+        env.storage().persistent()
+            .get(&DataKey::UserOrders(user))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Get the open orders expedited with `priority`, oldest first, so
+    /// fulfillment can serve Urgent then High before the rest. Normal orders
+    /// are not listed.
+    pub fn list_orders_by_priority(env: Env, priority: OrderPriority) -> Vec<BytesN<32>> {
+        env.storage().persistent()
+            .get(&DataKey::PriorityOrders(priority))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Get the share of an order's total, in bps, that `priority` costs
+    pub fn get_priority_multiplier(env: Env, priority: OrderPriority) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::PriorityMultiplier(priority))
+            .unwrap_or(BPS_DENOMINATOR as u32)
+    }
+
+    /// Get all orders for a DePIN
+    pub fn list_depin_orders(env: Env, depin_id: BytesN<32>) -> Vec<BytesN<32>> {
+        env.storage().persistent()
+            .get(&DataKey::DepinOrders(depin_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Page through a user's orders, oldest first, optionally only those in `status`
+    ///
+    /// Scans at most `MAX_PAGE_SCAN` order IDs from `offset`, collecting up to `limit` matches.
+    pub fn get_user_orders_page(
+        env: Env,
+        user: Address,
+        offset: u32,
+        limit: u32,
+        status: Option<OrderStatus>,
+    ) -> OrderPage {
+        let order_ids = Self::list_user_orders(env.clone(), user);
+        Self::orders_page(&env, &order_ids, offset, limit, status)
+    }
+
+    /// Page through a DePIN's orders, oldest first, optionally only those in `status`
+    ///
+    /// Scans at most `MAX_PAGE_SCAN` order IDs from `offset`, collecting up to `limit` matches.
+    pub fn get_depin_orders_page(
+        env: Env,
+        depin_id: BytesN<32>,
+        offset: u32,
+        limit: u32,
+        status: Option<OrderStatus>,
+    ) -> OrderPage {
+        let order_ids = Self::list_depin_orders(env.clone(), depin_id);
+        Self::orders_page(&env, &order_ids, offset, limit, status)
+    }
+
+    /// Quote what an order for `duration_hours` of a DePIN's service costs at
+    /// the price the registry lists, before any coupon
+    pub fn quote_order(env: Env, depin_id: BytesN<32>, service_type: String, duration_hours: u64) -> i128 {
+        Self::listed_price(&env, &depin_id, &service_type) * duration_hours as i128
+    }
+
+    /// Get total order count
+    pub fn get_order_count(env: Env) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::OrderCounter)
+            .unwrap_or(0)
+    }
+
+    /// Get total escrowed amount
+    pub fn get_total_escrowed(env: Env) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::TotalEscrowed)
+            .unwrap_or(0)
+    }
+
+    /// Get order counts by status and DePIN, volume per payment token and the
+    /// average order duration across the platform
+    pub fn get_order_metrics(env: Env) -> OrderMetrics {
+        env.storage().persistent()
+            .get(&StatsKey::OrderMetrics)
+            .unwrap_or_else(|| OrderMetrics {
+                total_orders: 0,
+                status_counts: Map::new(&env),
+                volume_by_token: Map::new(&env),
+                depin_orders: Map::new(&env),
+                total_duration_hours: 0,
+                average_duration_hours: 0,
+            })
+    }
+
+    /// Get the amount escrowed for orders paid in `token`
+    pub fn get_escrowed_by_token(env: Env, token: Address) -> i128 {
+        env.storage().persistent()
+            .get(&StatsKey::EscrowedByToken(token))
+            .unwrap_or(0)
+    }
+
+    /// Get the amount escrowed for orders on a DePIN
+    pub fn get_escrowed_by_depin(env: Env, depin_id: BytesN<32>) -> i128 {
+        env.storage().persistent()
+            .get(&StatsKey::EscrowedByDepin(depin_id))
+            .unwrap_or(0)
+    }
+
+    /// Get the amount of a token this contract holds for orders without an escrow contract
+    pub fn get_custody(env: Env, token: Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::Custody(token))
+            .unwrap_or(0)
+    }
+
+    /// Get how long new orders may stay Pending
+    pub fn get_pending_timeout(env: Env) -> u64 {
+        env.storage().persistent()
+            .get(&DataKey::PendingTimeout)
+            .unwrap_or(DEFAULT_PENDING_TIMEOUT)
+    }
+
+    /// Get the discount, in bps, of each subscription tier
+    pub fn get_tier_discounts(env: Env) -> Map<u32, u32> {
+        env.storage().persistent()
+            .get(&DataKey::TierDiscounts)
+            .unwrap_or(Map::new(&env))
+    }
+
+    /// Get the loyalty discount levels as (min loyalty points, discount in bps) pairs
+    pub fn get_loyalty_discounts(env: Env) -> Vec<(u32, u32)> {
+        env.storage().persistent()
+            .get(&DataKey::LoyaltyDiscounts)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get how long after creation finished orders may be archived
+    pub fn get_archive_age(env: Env) -> u64 {
+        env.storage().persistent()
+            .get(&DataKey::ArchiveAge)
+            .unwrap_or(DEFAULT_ARCHIVE_AGE)
+    }
+
+    /// Get every status an order entered, oldest first, with when and by whom.
+    /// Empty for unknown and archived orders.
+    pub fn get_order_history(env: Env, order_id: BytesN<32>) -> Vec<StatusChange> {
+        env.storage().persistent()
+            .get(&DataKey::OrderHistory(order_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Get the price band of `service_type`, or the global one when None
+    pub fn get_price_band(env: Env, service_type: Option<String>) -> Option<PriceBand> {
+        env.storage().persistent().get(&DataKey::PriceBand(service_type))
+    }
+
+    /// Get the approval policy for completing large orders, if one is set
+    pub fn get_approval_policy(env: Env) -> Option<ApprovalPolicy> {
+        env.storage().persistent().get(&DataKey::ApprovalPolicy)
+    }
+
+    /// Get the per-user caps on order creation
+    pub fn get_order_limits(env: Env) -> OrderLimits {
+        env.storage().persistent()
+            .get(&DataKey::OrderLimits)
+            .unwrap_or_default()
+    }
+
+    /// Get how many of a user's orders are Pending or Active
+    pub fn get_open_order_count(env: Env, user: Address) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::OpenOrders(user))
+            .unwrap_or(0)
+    }
+
+    /// Get how many of a DePIN's orders still hold it: Pending, Active, Deployed
+    /// or Disputed, or Completed with funds escrowed until `finalize_order`
+    pub fn get_unsettled_depin_order_count(env: Env, depin_id: BytesN<32>) -> u32 {
+        env.storage().persistent()
+            .get(&StatsKey::UnsettledDepinOrders(depin_id))
+            .unwrap_or(0)
+    }
+
+    /// Get how long orders may stay Active before deployment
+    pub fn get_deployment_timeout(env: Env) -> u64 {
+        env.storage().persistent()
+            .get(&DataKey::DeploymentTimeout)
+            .unwrap_or(DEFAULT_DEPLOYMENT_TIMEOUT)
+    }
+
+    /// Get how long after completion users may dispute an order
+    pub fn get_dispute_window(env: Env) -> u64 {
+        env.storage().persistent()
+            .get(&DataKey::DisputeWindow)
+            .unwrap_or(0)
+    }
+
+    /// Get the reason given for an open dispute
+    pub fn get_dispute_reason(env: Env, order_id: BytesN<32>) -> Option<String> {
+        env.storage().persistent().get(&DataKey::Dispute(order_id))
+    }
+
+    /// Whether the admin emergency stop is on
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().persistent().get(&DataKey::Paused).unwrap_or(false)
+    }
+
+    /// Get treasury wallet address
+    pub fn get_treasury_wallet(env: Env) -> Option<Address> {
+        Self::resolve(&env, DataKey::TreasuryWallet, symbol_short!("treasury"))
+    }
+
+    /// Get escrow contract address
+    pub fn get_escrow_contract(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::EscrowContract)
+    }
+
+    /// Get admin address
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+    }
+
+    /// Get timelock address
+    pub fn get_timelock(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Timelock)
+    }
+
+    /// Get fee-manager contract address
+    pub fn get_fee_manager(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::FeeManagerContract)
+    }
+
+    /// Whether cancellation fees scale with the time an order spent Pending
+    pub fn is_cancellation_fee_scaled(env: Env) -> bool {
+        env.storage().persistent().get(&DataKey::CancellationFeeScaled).unwrap_or(false)
+    }
+
+    /// Get payment-splitter contract address
+    pub fn get_splitter_contract(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::SplitterContract)
+    }
+
+    /// Get rewards contract address
+    pub fn get_rewards_contract(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::RewardsContract)
+    }
+
+    /// Get coupon contract address
+    pub fn get_coupon_contract(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::CouponContract)
+    }
+
+    /// Get the token new orders are escrowed in
+    pub fn get_payment_token(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::PaymentToken)
+    }
+
+    /// Get the token an order was paid in
+    pub fn get_order_token(env: Env, order_id: BytesN<32>) -> Address {
+        Self::get_order(env, order_id).payment_token
+    }
+
+    /// Get a payment quote
+    pub fn get_quote(env: Env, quote_id: u64) -> Option<PaymentQuote> {
+        env.storage().persistent().get(&DataKey::Quote(quote_id))
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
 
-        let mut order: Order = env.storage().persistent()
-            .get(&DataKey::Order(order_id.clone()))
-            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+    fn assert_timelock(env: &Env) {
+        let timelock: Address = env.storage().persistent()
+            .get(&DataKey::Timelock)
+            .unwrap_or_else(|| panic_with_error!(env, Error::TimelockNotSet));
+        timelock.require_auth();
+    }
 
-        order.status = new_status;
-        if external_tx_id.is_some() {
-            order.external_tx_id = external_tx_id;
+    /// Look a platform contract up in the directory, falling back to the locally set address
+    fn resolve(env: &Env, key: DataKey, name: Symbol) -> Option<Address> {
+        let directory: Option<Address> = env.storage().persistent().get(&DataKey::DirectoryContract);
+        if let Some(address) = directory.and_then(|directory| DirectoryClient::new(env, &directory).get_address(&name)) {
+            return Some(address);
         }
+        env.storage().persistent().get(&key)
+    }
 
-        env.storage().persistent().set(&DataKey::Order(order_id), &order);
-        true
+    /// Reject DePINs the registry doesn't list, or that don't deploy to `deployment_chain`
+    fn assert_depin_exists(env: &Env, depin_id: &BytesN<32>, deployment_chain: &String) {
+        let registry_contract = Self::resolve(env, DataKey::DepinRegistryContract, symbol_short!("registry"))
+            .unwrap_or_else(|| panic_with_error!(env, Error::ContractNotSet));
+
+        let registry = RegistryClient::new(env, &registry_contract);
+        if !registry.depin_exists(depin_id) {
+            panic_with_error!(env, Error::InvalidDepin);
+        }
+        if !registry.supports_chain(depin_id, deployment_chain) {
+            panic_with_error!(env, Error::UnsupportedChain);
+        }
     }
 
-    /// Complete order and transfer funds to treasury
-    pub fn complete_order(env: Env, admin: Address, order_id: BytesN<32>) -> bool {
-        Self::assert_admin(&env, &admin);
+    /// Discount on a list-priced amount for the user's subscription tier plus
+    /// the best loyalty level their points reach, capped at the whole amount
+    fn member_discount(env: &Env, user: &Address, amount: i128) -> i128 {
+        let tier_discounts = Self::get_tier_discounts(env.clone());
+        let loyalty_discounts = Self::get_loyalty_discounts(env.clone());
+        if tier_discounts.is_empty() && loyalty_discounts.is_empty() {
+            return 0;
+        }
+        let profile = match Self::resolve(env, DataKey::UserProfileContract, symbol_short!("profile")) {
+            Some(profile) => ProfileClient::new(env, &profile),
+            None => return 0,
+        };
 
-        let mut order: Order = env.storage().persistent()
-            .get(&DataKey::Order(order_id.clone()))
-            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        let mut discount_bps = tier_discounts.get(profile.get_subscription_tier(user)).unwrap_or(0);
+        if !loyalty_discounts.is_empty() {
+            let points = profile.get_loyalty_points(user);
+            discount_bps += loyalty_discounts.iter()
+                .filter(|(min_points, _)| points >= *min_points)
+                .map(|(_, level_bps)| level_bps)
+                .max()
+                .unwrap_or(0);
+        }
+        amount * (discount_bps as i128).min(BPS_DENOMINATOR) / BPS_DENOMINATOR
+    }
 
-        if order.status != OrderStatus::Deployed {
-            panic_with_error!(&env, Error::InvalidStatus);
+    /// Hold capacity on the DePIN for a new order
+    fn reserve_slot(env: &Env, depin_id: &BytesN<32>) {
+        let registry_contract = Self::resolve(env, DataKey::DepinRegistryContract, symbol_short!("registry"))
+            .unwrap_or_else(|| panic_with_error!(env, Error::ContractNotSet));
+
+        RegistryClient::new(env, &registry_contract)
+            .reserve_capacity(&env.current_contract_address(), depin_id, &SLOTS_PER_ORDER);
+    }
+
+    /// Free the capacity an order held once it has ended
+    fn release_slot(env: &Env, order: &Order) {
+        if let Some(registry_contract) = Self::resolve(env, DataKey::DepinRegistryContract, symbol_short!("registry")) {
+            RegistryClient::new(env, &registry_contract)
+                .release_capacity(&env.current_contract_address(), &order.depin_id, &SLOTS_PER_ORDER);
         }
+    }
 
-        // Update order status
-        order.status = OrderStatus::Completed;
-        let escrowed_amount = order.escrowed_amount;
-        order.escrowed_amount = 0;
+    /// Reject prices other than the one the registry lists, or outside the
+    /// admin's price bands
+    fn assert_price(env: &Env, depin_id: &BytesN<32>, service_type: &String, price_per_hour: i128) {
+        Self::assert_price_in_band(env, service_type, price_per_hour);
+        if price_per_hour != Self::listed_price(env, depin_id, service_type) {
+            panic_with_error!(env, Error::PriceMismatch);
+        }
+    }
 
-        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+    fn assert_price_in_band(env: &Env, service_type: &String, price_per_hour: i128) {
+        let bands = [
+            Self::get_price_band(env.clone(), None),
+            Self::get_price_band(env.clone(), Some(service_type.clone())),
+        ];
+        for band in bands.into_iter().flatten() {
+            if price_per_hour < band.min_price || price_per_hour > band.max_price {
+                panic_with_error!(env, Error::PriceOutOfRange);
+            }
+        }
+    }
 
-        // Update total escrowed amount
-        let current_escrowed: i128 = env.storage().persistent()
-            .get(&DataKey::TotalEscrowed)
-            .unwrap_or(0);
-        env.storage().persistent().set(&DataKey::TotalEscrowed, &(current_escrowed - escrowed_amount));
+    /// Hourly price the registry lists for a service type of a DePIN
+    fn listed_price(env: &Env, depin_id: &BytesN<32>, service_type: &String) -> i128 {
+        let registry_contract = Self::resolve(env, DataKey::DepinRegistryContract, symbol_short!("registry"))
+            .unwrap_or_else(|| panic_with_error!(env, Error::ContractNotSet));
 
-        // Funds are now considered transferred to treasury
-        // (In a real implementation, you might want to track treasury balance)
+        RegistryClient::new(env, &registry_contract)
+            .get_service_price(depin_id, service_type)
+            .unwrap_or_else(|| panic_with_error!(env, Error::PriceUnavailable))
+    }
 
-        true
+    /// Reject calls while the admin stop is on, or the guardian has paused `function`
+    fn assert_not_paused(env: &Env, function: &str) {
+        if Self::is_paused(env.clone()) {
+            panic_with_error!(env, Error::Paused);
+        }
+        let guardian: Option<Address> = env.storage().persistent().get(&DataKey::PauseGuardian);
+        if let Some(guardian) = guardian {
+            let paused = GuardianClient::new(env, &guardian)
+                .is_paused(&env.current_contract_address(), &Symbol::new(env, function));
+            if paused {
+                panic_with_error!(env, Error::Paused);
+            }
+        }
     }
 
-    /// Refund order (admin only)
-    pub fn refund_order(env: Env, admin: Address, order_id: BytesN<32>) -> bool {
-        Self::assert_admin(&env, &admin);
+    fn assert_compliant(env: &Env, user: &Address, amount: i128) {
+        let compliance: Address = match env.storage().persistent().get(&DataKey::ComplianceContract) {
+            Some(compliance) => compliance,
+            None => return,
+        };
+        let (min_level, threshold): (u32, i128) = env.storage().persistent()
+            .get(&DataKey::CompliancePolicy)
+            .unwrap_or_else(|| panic_with_error!(env, Error::ContractNotSet));
 
-        let mut order: Order = env.storage().persistent()
-            .get(&DataKey::Order(order_id.clone()))
-            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+        if amount >= threshold && !ComplianceClient::new(env, &compliance).is_compliant(user, &min_level) {
+            panic_with_error!(env, Error::NotCompliant);
+        }
+    }
 
-        if order.status == OrderStatus::Completed {
-            panic_with_error!(&env, Error::InvalidStatus);
+    /// Reject orders over the verified provider threshold on DePINs whose provider is unverified
+    fn assert_trusted_provider(env: &Env, depin_id: &BytesN<32>, amount: i128) {
+        if Self::get_verified_provider_threshold(env.clone()).is_none_or(|threshold| amount < threshold) {
+            return;
         }
+        let registry_contract = Self::resolve(env, DataKey::DepinRegistryContract, symbol_short!("registry"))
+            .unwrap_or_else(|| panic_with_error!(env, Error::ContractNotSet));
+        let trust_level = RegistryClient::new(env, &registry_contract).get_trust_level(depin_id);
+        if !matches!(trust_level, Some(TrustLevel::Verified | TrustLevel::Audited)) {
+            panic_with_error!(env, Error::UnverifiedProvider);
+        }
+    }
+
+    fn record_order(env: &Env, order: &Order, actor: &Address) {
+        Self::assert_compliant(env, &order.user, order.total_amount);
+        Self::assert_trusted_provider(env, &order.depin_id, order.total_amount);
+
+        // Store order
+        env.storage().persistent().set(&DataKey::Order(order.order_id.clone()), order);
+        Self::record_transition(env, order, Some(actor));
+
+        // Update escrowed totals and metrics
+        Self::adjust_escrowed(env, order, order.total_amount);
+        Self::update_metrics(env, |metrics| {
+            metrics.total_orders += 1;
+            metrics.total_duration_hours += order.duration_hours;
+            let count = metrics.status_counts.get(order.status.clone()).unwrap_or(0);
+            metrics.status_counts.set(order.status.clone(), count + 1);
+            let volume = metrics.volume_by_token.get(order.payment_token.clone()).unwrap_or(0);
+            metrics.volume_by_token.set(order.payment_token.clone(), volume + order.total_amount);
+            let depin_orders = metrics.depin_orders.get(order.depin_id.clone()).unwrap_or(0);
+            metrics.depin_orders.set(order.depin_id.clone(), depin_orders + 1);
+        });
+
+        // Add to user's order list
+        Self::add_user_order(env, &order.user, &order.order_id);
+        Self::adjust_open_orders(env, &order.user, 1);
+        Self::index_priority(env, order, true);
+        Self::adjust_unsettled_depin_orders(env, &order.depin_id, 1);
+
+        // Add to DePIN's order list
+        Self::add_depin_order(env, &order.depin_id, &order.order_id);
+
+        Self::publish(env, symbol_short!("created"), order, order.total_amount);
+    }
 
+    /// Refund whatever an order still has escrowed to its user, returning the amount
+    fn return_funds(env: &Env, order: &mut Order) -> i128 {
         let escrowed_amount = order.escrowed_amount;
         if escrowed_amount > 0 {
-            // Refund to user profile
-            let profile_contract: Address = env.storage().persistent()
-                .get(&DataKey::UserProfileContract)
-                .unwrap_or_else(|| panic_with_error!(&env, Error::ContractNotSet));
-
-            let _refund_result: bool = env.invoke_contract(
-                &profile_contract,
-                &soroban_sdk::symbol_short!("refund"),
-                soroban_sdk::vec![&env, order.user.into_val(&env), escrowed_amount.into_val(&env)]
-            );
-
-            // Update total escrowed amount
-            let current_escrowed: i128 = env.storage().persistent()
-                .get(&DataKey::TotalEscrowed)
-                .unwrap_or(0);
-            env.storage().persistent().set(&DataKey::TotalEscrowed, &(current_escrowed - escrowed_amount));
+            Self::refund_to_user(env, order, escrowed_amount);
+        }
+        escrowed_amount.max(0)
+    }
 
-            order.escrowed_amount = 0;
+    /// Refund part of what an order has escrowed to its user
+    fn refund_to_user(env: &Env, order: &mut Order, amount: i128) {
+        let was_unsettled = Self::is_unsettled(order);
+        match &order.escrow_contract {
+            // Return the locked tokens to the user's wallet
+            Some(escrow) => {
+                EscrowClient::new(env, escrow)
+                    .refund(&env.current_contract_address(), &order.order_id, &amount);
+            }
+            // Return the tokens held here to the user's wallet
+            None => Self::pay_from_custody(env, order, &order.user, amount),
         }
 
-        order.status = match order.status {
-            OrderStatus::Pending => OrderStatus::Cancelled,
-            _ => OrderStatus::Failed,
-        };
+        order.escrowed_amount -= amount;
+        order.refunded_amount += amount;
+        Self::adjust_escrowed(env, order, -amount);
+        Self::track_unsettled(env, order, was_unsettled);
+    }
 
-        env.storage().persistent().set(&DataKey::Order(order_id), &order);
-        true
+    /// Settle part of what an order has escrowed with the treasury and provider
+    fn pay_out(env: &Env, order: &mut Order, amount: i128) {
+        let was_unsettled = Self::is_unsettled(order);
+        Self::settle(env, order, amount);
+
+        order.escrowed_amount -= amount;
+        order.released_amount += amount;
+        Self::adjust_escrowed(env, order, -amount);
+        Self::track_unsettled(env, order, was_unsettled);
     }
 
-    /// Cancel order (user only, before deployment)
-    pub fn cancel_order(env: Env, user: Address, order_id: BytesN<32>) -> bool {
-        user.require_auth();
+    /// Add `delta` to the global escrow total and to those of the order's
+    /// payment token and DePIN
+    fn adjust_escrowed(env: &Env, order: &Order, delta: i128) {
+        Self::add_to_total(env, &DataKey::TotalEscrowed, delta);
+        Self::add_to_total(env, &StatsKey::EscrowedByToken(order.payment_token.clone()), delta);
+        Self::add_to_total(env, &StatsKey::EscrowedByDepin(order.depin_id.clone()), delta);
+    }
 
-        let order: Order = env.storage().persistent()
-            .get(&DataKey::Order(order_id.clone()))
-            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound));
+    fn add_to_total<K: IntoVal<Env, Val>>(env: &Env, key: &K, delta: i128) {
+        let current: i128 = env.storage().persistent().get(key).unwrap_or(0);
+        env.storage().persistent().set(key, &(current + delta));
+    }
 
-        if order.user != user {
-            panic_with_error!(&env, Error::Unauthorized);
+    /// Apply `update` to the platform order metrics
+    fn update_metrics(env: &Env, update: impl FnOnce(&mut OrderMetrics)) {
+        let mut metrics = Self::get_order_metrics(env.clone());
+        update(&mut metrics);
+        if metrics.total_orders > 0 {
+            metrics.average_duration_hours = metrics.total_duration_hours / metrics.total_orders as u64;
         }
+        env.storage().persistent().set(&StatsKey::OrderMetrics, &metrics);
+    }
 
-        if order.status != OrderStatus::Pending {
-            panic_with_error!(&env, Error::InvalidStatus);
+    /// Reject a user creating `new_orders` orders beyond their open order cap
+    /// or sooner than the minimum interval since their last creation
+    fn assert_within_limits(env: &Env, user: &Address, new_orders: u32) {
+        Self::assert_open_order_cap(env, user, new_orders);
+
+        let limits = Self::get_order_limits(env.clone());
+        let ledger = env.ledger().sequence();
+        if limits.min_ledger_interval > 0 {
+            let last: Option<u32> = env.storage().persistent().get(&DataKey::LastOrderLedger(user.clone()));
+            if let Some(last) = last {
+                if ledger < last + limits.min_ledger_interval {
+                    panic_with_error!(env, Error::RateLimited);
+                }
+            }
+        }
+        env.storage().persistent().set(&DataKey::LastOrderLedger(user.clone()), &ledger);
+    }
+
+    /// Reject a user taking on `new_orders` open orders beyond their cap
+    fn assert_open_order_cap(env: &Env, user: &Address, new_orders: u32) {
+        let limits = Self::get_order_limits(env.clone());
+        if limits.max_open_orders > 0
+            && Self::get_open_order_count(env.clone(), user.clone()) + new_orders > limits.max_open_orders
+        {
+            panic_with_error!(env, Error::TooManyOpenOrders);
+        }
+    }
+
+    /// Move an order to `status`, keeping its user's open order count in step
+    fn set_status(env: &Env, order: &mut Order, status: OrderStatus, actor: Option<&Address>) {
+        let was_unsettled = Self::is_unsettled(order);
+        if Self::is_open(&order.status) && !Self::is_open(&status) {
+            Self::adjust_open_orders(env, &order.user, -1);
+            Self::index_priority(env, order, false);
+        } else if !Self::is_open(&order.status) && Self::is_open(&status) {
+            Self::adjust_open_orders(env, &order.user, 1);
+            Self::index_priority(env, order, true);
+        }
+        Self::update_metrics(env, |metrics| {
+            let count = metrics.status_counts.get(order.status.clone()).unwrap_or(0);
+            metrics.status_counts.set(order.status.clone(), count.saturating_sub(1));
+            let count = metrics.status_counts.get(status.clone()).unwrap_or(0);
+            metrics.status_counts.set(status.clone(), count + 1);
+        });
+        order.status = status;
+        Self::track_unsettled(env, order, was_unsettled);
+        Self::record_transition(env, order, actor);
+    }
+
+    /// Append the order's current status to its history
+    fn record_transition(env: &Env, order: &Order, actor: Option<&Address>) {
+        let key = DataKey::OrderHistory(order.order_id.clone());
+        let mut history: Vec<StatusChange> = env.storage().persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(env));
+        history.push_back(StatusChange {
+            status: order.status.clone(),
+            timestamp: env.ledger().timestamp(),
+            actor: actor.cloned(),
+        });
+        env.storage().persistent().set(&key, &history);
+    }
+
+    /// The surge `priority` adds on top of `amount`
+    fn priority_surcharge(env: &Env, priority: OrderPriority, amount: i128) -> i128 {
+        let multiplier_bps = Self::get_priority_multiplier(env.clone(), priority);
+        amount * (multiplier_bps as i128 - BPS_DENOMINATOR) / BPS_DENOMINATOR
+    }
+
+    /// Add an expedited order to, or drop it from, its priority's open order list
+    fn index_priority(env: &Env, order: &Order, open: bool) {
+        if order.priority == OrderPriority::Normal {
+            return;
+        }
+        let key = DataKey::PriorityOrders(order.priority);
+        let mut order_ids: Vec<BytesN<32>> = env.storage().persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(env));
+        let index = order_ids.first_index_of(&order.order_id);
+        match (open, index) {
+            (true, None) => order_ids.push_back(order.order_id.clone()),
+            (false, Some(index)) => {
+                order_ids.remove(index);
+            }
+            _ => return,
+        }
+        env.storage().persistent().set(&key, &order_ids);
+    }
+
+    fn is_open(status: &OrderStatus) -> bool {
+        matches!(status, OrderStatus::Pending | OrderStatus::Active)
+    }
+
+    fn adjust_open_orders(env: &Env, user: &Address, delta: i32) {
+        let count = Self::get_open_order_count(env.clone(), user.clone());
+        let count = count.saturating_add_signed(delta);
+        env.storage().persistent().set(&DataKey::OpenOrders(user.clone()), &count);
+    }
+
+    /// Whether an order still holds its DePIN: it has not finished, or it
+    /// completed but keeps funds escrowed while it may still be disputed
+    fn is_unsettled(order: &Order) -> bool {
+        match order.status {
+            OrderStatus::Pending | OrderStatus::Active | OrderStatus::Deployed | OrderStatus::Disputed => true,
+            OrderStatus::Completed => order.escrowed_amount > 0,
+            _ => false,
+        }
+    }
+
+    /// Count the order in or out of its DePIN's unsettled orders after a change
+    fn track_unsettled(env: &Env, order: &Order, was_unsettled: bool) {
+        match (was_unsettled, Self::is_unsettled(order)) {
+            (true, false) => Self::adjust_unsettled_depin_orders(env, &order.depin_id, -1),
+            (false, true) => Self::adjust_unsettled_depin_orders(env, &order.depin_id, 1),
+            _ => {}
         }
+    }
+
+    fn adjust_unsettled_depin_orders(env: &Env, depin_id: &BytesN<32>, delta: i32) {
+        let count = Self::get_unsettled_depin_order_count(env.clone(), depin_id.clone());
+        let count = count.saturating_add_signed(delta);
+        env.storage().persistent().set(&StatsKey::UnsettledDepinOrders(depin_id.clone()), &count);
+    }
+
+    /// Reject completing an order the approval policy covers without enough
+    /// approvals from keys that are still approvers
+    fn assert_approved(env: &Env, order: &Order) {
+        let policy: Option<ApprovalPolicy> = env.storage().persistent().get(&DataKey::ApprovalPolicy);
+        if let Some(policy) = policy {
+            if policy.required > 0 && order.total_amount >= policy.threshold {
+                let approvals = order.approvals.iter()
+                    .filter(|approver| Self::is_approver(env, approver))
+                    .count() as u32;
+                if approvals < policy.required {
+                    panic_with_error!(env, Error::ApprovalsMissing);
+                }
+            }
+        }
+    }
+
+    /// Reject callers other than the payout address the registry holds for
+    /// the order's DePIN
+    fn assert_provider(env: &Env, provider: &Address, order: &Order) {
+        let payout = Self::resolve(env, DataKey::DepinRegistryContract, symbol_short!("registry"))
+            .and_then(|registry| RegistryClient::new(env, &registry).get_payout_address(&order.depin_id));
+        if payout.as_ref() != Some(provider) {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+    }
+
+    fn is_approver(env: &Env, approver: &Address) -> bool {
+        env.storage().persistent()
+            .get(&DataKey::Approver(approver.clone()))
+            .unwrap_or(false)
+    }
 
-        // Get admin for refund process
+    /// Admin or an approved dispute module, authorized
+    fn assert_arbitrator(env: &Env, caller: &Address) {
         let admin: Address = env.storage().persistent()
             .get(&DataKey::Admin)
-            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        if *caller != admin {
+            let allowed: bool = env.storage().persistent()
+                .get(&DataKey::DisputeModule(caller.clone()))
+                .unwrap_or(false);
+            if !allowed {
+                panic_with_error!(env, Error::Unauthorized);
+            }
+        }
+        caller.require_auth();
+    }
 
-        Self::refund_order(env, admin, order_id)
+    fn dispute_window_open(env: &Env, order: &Order) -> bool {
+        env.ledger().timestamp() < order.completed_at + Self::get_dispute_window(env.clone())
     }
 
-    /// Get order details
-    pub fn get_order(env: Env, order_id: BytesN<32>) -> Order {
-        env.storage().persistent()
-            .get(&DataKey::Order(order_id))
-            .unwrap_or_else(|| panic_with_error!(&env, Error::OrderNotFound))
+    fn pending_deadline(env: &Env) -> u64 {
+        env.ledger().timestamp() + Self::get_pending_timeout(env.clone())
     }
 
-    /// Get all orders for a user
-    pub fn list_user_orders(env: Env, user: Address) -> Vec<BytesN<32>> {
-        env.storage().persistent()
-            .get(&DataKey::UserOrders(user))
-            .unwrap_or_else(|| Vec::new(&env))
+    /// Move a payment from the user's wallet to this contract
+    fn collect_payment(env: &Env, user: &Address, token: &Address, amount: i128) {
+        let client = token::Client::new(env, token);
+        if client.balance(user) < amount {
+            panic_with_error!(env, Error::InsufficientBalance);
+        }
+        client.transfer(user, &env.current_contract_address(), &amount);
     }
 
-    /// Get all orders for a DePIN
-    pub fn list_depin_orders(env: Env, depin_id: BytesN<32>) -> Vec<BytesN<32>> {
-        env.storage().persistent()
-            .get(&DataKey::DepinOrders(depin_id))
-            .unwrap_or_else(|| Vec::new(&env))
+    /// Pull the payment from the user's wallet into this contract's custody
+    fn take_custody(env: &Env, user: &Address, token: &Address, total_amount: i128) {
+        Self::collect_payment(env, user, token, total_amount);
+
+        let held = Self::get_custody(env.clone(), token.clone());
+        env.storage().persistent().set(&DataKey::Custody(token.clone()), &(held + total_amount));
     }
 
-    /// Get total order count
-    pub fn get_order_count(env: Env) -> u32 {
+    /// Send tokens this contract holds for an order
+    fn pay_from_custody(env: &Env, order: &Order, to: &Address, amount: i128) {
+        let token = &order.payment_token;
+        token::Client::new(env, token).transfer(&env.current_contract_address(), to, &amount);
+
+        let held = Self::get_custody(env.clone(), token.clone());
+        env.storage().persistent().set(&DataKey::Custody(token.clone()), &(held - amount));
+    }
+
+    /// Forward tokens a source already sent here into the escrow and lock them
+    fn open_funded_escrow(
+        env: &Env,
+        escrow: &Address,
+        order_id: &BytesN<32>,
+        user: &Address,
+        token: &Address,
+        total_amount: i128,
+    ) {
+        let client = Self::create_escrow(env, escrow, order_id, user, token, total_amount);
+        let this = env.current_contract_address();
+        token::Client::new(env, token).transfer(&this, escrow, &total_amount);
+        client.lock_funded(&this, order_id);
+    }
+
+    fn create_escrow<'a>(
+        env: &'a Env,
+        escrow: &Address,
+        order_id: &BytesN<32>,
+        user: &Address,
+        token: &Address,
+        total_amount: i128,
+    ) -> EscrowClient<'a> {
+        let treasury = Self::resolve(env, DataKey::TreasuryWallet, symbol_short!("treasury"))
+            .unwrap_or_else(|| panic_with_error!(env, Error::ContractNotSet));
+        let admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        // The order contract controls the escrow; the platform admin arbitrates
+        let client = EscrowClient::new(env, escrow);
+        client.create_escrow(&env.current_contract_address(), order_id, user, &treasury, token, &total_amount, &Some(admin));
+        client
+    }
+
+    /// Pay the platform fee to the treasury and the rest to the provider
+    ///
+    /// The provider share goes through the splitter when the DePIN has payees
+    /// there, otherwise to the payout address the registry records for it.
+    /// Without either the whole amount goes to the treasury, which is the
+    /// escrow beneficiary.
+    fn settle(env: &Env, order: &Order, amount: i128) {
+        let this = env.current_contract_address();
+        let treasury = Self::resolve(env, DataKey::TreasuryWallet, symbol_short!("treasury"))
+            .unwrap_or_else(|| panic_with_error!(env, Error::ContractNotSet));
+
+        let splitter: Option<Address> = env.storage().persistent().get(&DataKey::SplitterContract);
+        let splitter = splitter.filter(|splitter| SplitterClient::new(env, splitter).has_payees(&order.depin_id));
+        let provider = match &splitter {
+            Some(splitter) => Some(splitter.clone()),
+            None => Self::resolve(env, DataKey::DepinRegistryContract, symbol_short!("registry"))
+                .and_then(|registry| RegistryClient::new(env, &registry).get_payout_address(&order.depin_id)),
+        };
+        let provider = match provider {
+            Some(provider) => provider,
+            None => {
+                Self::release(env, order, amount);
+                Self::publish(env, symbol_short!("treasury"), order, (treasury, amount));
+                return;
+            }
+        };
+
+        let fee_manager: Option<Address> = env.storage().persistent().get(&DataKey::FeeManagerContract);
+        let platform_fee = match fee_manager {
+            Some(fee_manager) => FeeManagerClient::new(env, &fee_manager).quote_platform_fee(&amount),
+            None => 0,
+        };
+        let provider_share = amount - platform_fee;
+
+        let mut shares = Vec::new(env);
+        if platform_fee > 0 {
+            shares.push_back((treasury.clone(), platform_fee));
+        }
+        if provider_share > 0 {
+            shares.push_back((provider.clone(), provider_share));
+        }
+        match &order.escrow_contract {
+            Some(escrow) => {
+                EscrowClient::new(env, escrow).split(&this, &order.order_id, &shares);
+            }
+            None => {
+                for (to, share) in shares.iter() {
+                    Self::pay_from_custody(env, order, &to, share);
+                }
+            }
+        }
+
+        if let Some(splitter) = splitter {
+            if provider_share > 0 {
+                SplitterClient::new(env, &splitter).allocate(&order.depin_id, &order.payment_token, &provider_share);
+            }
+        }
+        if platform_fee > 0 {
+            Self::publish(env, symbol_short!("treasury"), order, (treasury, platform_fee));
+        }
+        if provider_share > 0 {
+            Self::publish(env, symbol_short!("payout"), order, (provider, provider_share));
+        }
+    }
+
+    /// Pay part of an order's funds to the treasury
+    fn release(env: &Env, order: &Order, amount: i128) {
+        match &order.escrow_contract {
+            Some(escrow) => {
+                EscrowClient::new(env, escrow).release(&env.current_contract_address(), &order.order_id, &amount);
+            }
+            None => {
+                let treasury = Self::resolve(env, DataKey::TreasuryWallet, symbol_short!("treasury"))
+                    .unwrap_or_else(|| panic_with_error!(env, Error::ContractNotSet));
+                Self::pay_from_custody(env, order, &treasury, amount);
+            }
+        }
+    }
+
+    fn payment_token(env: &Env) -> Address {
         env.storage().persistent()
-            .get(&DataKey::OrderCounter)
-            .unwrap_or(0)
+            .get(&DataKey::PaymentToken)
+            .unwrap_or_else(|| panic_with_error!(env, Error::ContractNotSet))
     }
 
-    /// Get total escrowed amount
-    pub fn get_total_escrowed(env: Env) -> i128 {
+    /// Accept tokens the user-profile contract whitelists, or just the payment token without one
+    fn assert_token_accepted(env: &Env, token: &Address) {
+        let accepted = match Self::resolve(env, DataKey::UserProfileContract, symbol_short!("profile")) {
+            Some(profile) => ProfileClient::new(env, &profile).is_token_whitelisted(token),
+            None => *token == Self::payment_token(env),
+        };
+        if !accepted {
+            panic_with_error!(env, Error::TokenNotAccepted);
+        }
+    }
+
+    fn oracle_config(env: &Env) -> OracleConfig {
         env.storage().persistent()
-            .get(&DataKey::TotalEscrowed)
-            .unwrap_or(0)
+            .get(&DataKey::OracleConfig)
+            .unwrap_or_else(|| panic_with_error!(env, Error::ContractNotSet))
     }
 
-    /// Get treasury wallet address
-    pub fn get_treasury_wallet(env: Env) -> Option<Address> {
-        env.storage().persistent().get(&DataKey::TreasuryWallet)
+    /// Convert payment token units into `token` units at the oracle's USD prices, rounding up
+    fn convert_usd(env: &Env, config: &OracleConfig, token: &Address, usd_amount: i128) -> i128 {
+        let oracle = PriceOracleClient::new(env, &config.oracle);
+        let price = oracle.lastprice(&Asset::Stellar(token.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, Error::PriceUnavailable));
+        if price.price <= 0 || env.ledger().timestamp() > price.timestamp + config.max_price_age {
+            panic_with_error!(env, Error::PriceUnavailable);
+        }
+
+        let usd_decimals = token::Client::new(env, &Self::payment_token(env)).decimals();
+        let token_decimals = token::Client::new(env, token).decimals();
+        let numerator = usd_amount
+            * 10i128.pow(oracle.decimals())
+            * 10i128.pow(token_decimals);
+        let denominator = price.price * 10i128.pow(usd_decimals);
+        (numerator + denominator - 1) / denominator
     }
 
-    // Helper functions
-    fn assert_admin(env: &Env, admin: &Address) {
-        let stored_admin: Address = env.storage().persistent()
-            .get(&DataKey::Admin)
-            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
-        
-        if *admin != stored_admin {
-            panic_with_error!(env, Error::NotAdmin);
+    /// Return what an order has escrowed to its user and close it: Pending
+    /// orders end Cancelled, running ones Failed. Callers check authorization.
+    fn refund(env: &Env, order_id: BytesN<32>, actor: &Address) -> bool {
+        let mut order: Order = env.storage().persistent()
+            .get(&DataKey::Order(order_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, Error::OrderNotFound));
+
+        let (status, action) = match order.status {
+            OrderStatus::Pending => (OrderStatus::Cancelled, symbol_short!("cancelled")),
+            _ => (OrderStatus::Failed, symbol_short!("refunded")),
+        };
+        if !Self::can_transition(&order.status, &status) {
+            panic_with_error!(env, Error::InvalidStatus);
         }
-        admin.require_auth();
+
+        let escrowed_amount = Self::return_funds(env, &mut order);
+        Self::set_status(env, &mut order, status, Some(actor));
+
+        env.storage().persistent().set(&DataKey::Order(order_id), &order);
+        Self::release_slot(env, &order);
+        Self::publish(env, action, &order, escrowed_amount);
+        true
+    }
+
+    fn collect_cancellation_fee(env: &Env, mut order: Order) {
+        let fee_manager: Option<Address> = env.storage().persistent().get(&DataKey::FeeManagerContract);
+        let fee = match fee_manager {
+            Some(fee_manager) => FeeManagerClient::new(env, &fee_manager)
+                .quote_cancellation_fee(&order.escrowed_amount),
+            None => 0,
+        };
+        let window = order.expires_at.saturating_sub(order.created_at);
+        let fee = if Self::is_cancellation_fee_scaled(env.clone()) && window > 0 {
+            let elapsed = (env.ledger().timestamp() - order.created_at).min(window);
+            fee * elapsed as i128 / window as i128
+        } else {
+            fee
+        };
+        if fee <= 0 {
+            return;
+        }
+
+        Self::release(env, &order, fee);
+
+        order.escrowed_amount -= fee;
+        order.released_amount += fee;
+        env.storage().persistent().set(&DataKey::Order(order.order_id.clone()), &order);
+        Self::adjust_escrowed(env, &order, -fee);
+    }
+
+    /// Publish a lifecycle event with topics (action, order_id, user, depin_id)
+    fn publish<D: IntoVal<Env, Val>>(env: &Env, action: Symbol, order: &Order, data: D) {
+        env.events().publish(
+            (action, order.order_id.clone(), order.user.clone(), order.depin_id.clone()),
+            data,
+        );
     }
 
-    fn generate_order_id(env: &Env) -> BytesN<32> {
+    fn orders_page(
+        env: &Env,
+        order_ids: &Vec<BytesN<32>>,
+        offset: u32,
+        limit: u32,
+        status: Option<OrderStatus>,
+    ) -> OrderPage {
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let count = order_ids.len();
+        let scan_end = count.min(offset.saturating_add(MAX_PAGE_SCAN));
+        let mut orders = Vec::new(env);
+        let mut index = offset;
+        while index < scan_end && orders.len() < limit {
+            let order = Self::get_order(env.clone(), order_ids.get(index).unwrap());
+            index += 1;
+
+            if status.as_ref().is_some_and(|status| *status != order.status) {
+                continue;
+            }
+            orders.push_back(order);
+        }
+
+        OrderPage {
+            orders,
+            next_offset: if index < count { Some(index) } else { None },
+        }
+    }
+
+    fn generate_order_id(env: &Env, user: &Address, depin_id: &BytesN<32>) -> BytesN<32> {
         let mut counter: u32 = env.storage().persistent()
             .get(&DataKey::OrderCounter)
             .unwrap_or(0);
@@ -379,19 +2696,11 @@ impl OrderContract {
         counter += 1;
         env.storage().persistent().set(&DataKey::OrderCounter, &counter);
         
-        // Create unique ID with counter in first 4 bytes
-        let mut id_bytes = [0u8; 32];
-        id_bytes[0..4].copy_from_slice(&counter.to_be_bytes());
-        
-        // Fill remaining bytes with timestamp and random data from ledger
-        let timestamp = env.ledger().timestamp();
-        id_bytes[4..12].copy_from_slice(&timestamp.to_be_bytes());
-        
-        // Use sequence number for additional randomness
-        let sequence = env.ledger().sequence();
-        id_bytes[12..16].copy_from_slice(&sequence.to_be_bytes());
-        
-        BytesN::from_array(env, &id_bytes)
+        // Hash the counter together with the order's parties, the time and
+        // PRNG bytes so IDs are uniformly spread and cannot be guessed ahead
+        let nonce: BytesN<32> = env.prng().gen();
+        let preimage = (counter, user.clone(), depin_id.clone(), env.ledger().timestamp(), nonce).to_xdr(env);
+        env.crypto().sha256(&preimage).into()
     }
 
     fn add_user_order(env: &Env, user: &Address, order_id: &BytesN<32>) {
@@ -403,6 +2712,92 @@ impl OrderContract {
         env.storage().persistent().set(&DataKey::UserOrders(user.clone()), &user_orders);
     }
 
+    fn remove_user_order(env: &Env, user: &Address, order_id: &BytesN<32>) {
+        let mut user_orders: Vec<BytesN<32>> = env.storage().persistent()
+            .get(&DataKey::UserOrders(user.clone()))
+            .unwrap_or_else(|| Vec::new(env));
+
+        if let Some(index) = user_orders.first_index_of(order_id) {
+            user_orders.remove(index);
+            env.storage().persistent().set(&DataKey::UserOrders(user.clone()), &user_orders);
+        }
+    }
+
+    fn is_archivable(env: &Env, order: &Order) -> bool {
+        matches!(order.status, OrderStatus::Completed | OrderStatus::Cancelled | OrderStatus::Failed)
+            && order.escrowed_amount == 0
+            && !(order.status == OrderStatus::Completed && Self::dispute_window_open(env, order))
+            && env.ledger().timestamp() >= order.created_at + Self::get_archive_age(env.clone())
+    }
+
+    fn archive(env: &Env, order: Order) {
+        let archived = ArchivedOrder {
+            order_id: order.order_id.clone(),
+            user: order.user.clone(),
+            depin_id: order.depin_id.clone(),
+            status: order.status.clone(),
+            payment_token: order.payment_token.clone(),
+            total_amount: order.total_amount,
+            refunded_amount: order.refunded_amount,
+            released_amount: order.released_amount,
+            created_at: order.created_at,
+            archived_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&DataKey::Archived(order.order_id.clone()), &archived);
+        env.storage().persistent().remove(&DataKey::Order(order.order_id.clone()));
+        env.storage().persistent().remove(&DataKey::OrderHistory(order.order_id.clone()));
+        Self::remove_user_order(env, &order.user, &order.order_id);
+        Self::remove_depin_order(env, &order.depin_id, &order.order_id);
+
+        Self::publish(env, symbol_short!("archived"), &order, (order.total_amount, order.refunded_amount, order.released_amount));
+    }
+
+    /// Allowed order status transitions
+    ///
+    /// Disputed orders only leave that status through `resolve_dispute`.
+    fn can_transition(from: &OrderStatus, to: &OrderStatus) -> bool {
+        matches!(
+            (from, to),
+            (OrderStatus::Pending, OrderStatus::Active)
+                | (OrderStatus::Active, OrderStatus::Deployed)
+                | (OrderStatus::Deployed, OrderStatus::Completed)
+                | (OrderStatus::Pending, OrderStatus::Cancelled)
+                | (OrderStatus::Active, OrderStatus::Failed)
+                | (OrderStatus::Deployed, OrderStatus::Failed)
+                | (OrderStatus::Active, OrderStatus::Disputed)
+                | (OrderStatus::Deployed, OrderStatus::Disputed)
+                | (OrderStatus::Completed, OrderStatus::Disputed)
+        )
+    }
+
+    /// Hand an order, its escrow refunds and its place in the user indexes
+    /// to `to`
+    fn change_owner(env: &Env, order: &mut Order, to: &Address) {
+        Self::assert_compliant(env, to, order.total_amount);
+        if Self::is_open(&order.status) {
+            Self::assert_open_order_cap(env, to, 1);
+        }
+
+        if let Some(escrow) = &order.escrow_contract {
+            EscrowClient::new(env, escrow)
+                .set_depositor(&env.current_contract_address(), &order.order_id, to);
+        }
+
+        let from = order.user.clone();
+        order.user = to.clone();
+        env.storage().persistent().set(&DataKey::Order(order.order_id.clone()), order);
+        Self::remove_user_order(env, &from, &order.order_id);
+        Self::add_user_order(env, to, &order.order_id);
+        if Self::is_open(&order.status) {
+            Self::adjust_open_orders(env, &from, -1);
+            Self::adjust_open_orders(env, to, 1);
+        }
+    }
+
+    fn is_transferable(order: &Order) -> bool {
+        matches!(order.status, OrderStatus::Pending | OrderStatus::Active | OrderStatus::Deployed)
+    }
+
     fn add_depin_order(env: &Env, depin_id: &BytesN<32>, order_id: &BytesN<32>) {
         let mut depin_orders: Vec<BytesN<32>> = env.storage().persistent()
             .get(&DataKey::DepinOrders(depin_id.clone()))
@@ -411,6 +2806,17 @@ impl OrderContract {
         depin_orders.push_back(order_id.clone());
         env.storage().persistent().set(&DataKey::DepinOrders(depin_id.clone()), &depin_orders);
     }
+
+    fn remove_depin_order(env: &Env, depin_id: &BytesN<32>, order_id: &BytesN<32>) {
+        let mut depin_orders: Vec<BytesN<32>> = env.storage().persistent()
+            .get(&DataKey::DepinOrders(depin_id.clone()))
+            .unwrap_or_else(|| Vec::new(env));
+
+        if let Some(index) = depin_orders.first_index_of(order_id) {
+            depin_orders.remove(index);
+            env.storage().persistent().set(&DataKey::DepinOrders(depin_id.clone()), &depin_orders);
+        }
+    }
 }
 
 mod test;
@@ -2,10 +2,70 @@
 
 use super::*;
 use soroban_sdk::{
-    testutils::{Address as AddressTestUtils},
-    Address, Env, String
+    testutils::{Address as AddressTestUtils, Events, Ledger},
+    Address, Env, String, TryFromVal
 };
 
+/// Stand-in for the DePIN registry that accepts every DePIN ID at 10 per
+/// hour and pays them all out to a single address, if one is set
+#[contract]
+struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn set_payout(env: Env, payout: Address) {
+        env.storage().instance().set(&symbol_short!("payout"), &payout);
+    }
+
+    pub fn depin_exists(_env: Env, _depin_id: BytesN<32>) -> bool {
+        true
+    }
+
+    pub fn supports_chain(_env: Env, _depin_id: BytesN<32>, _chain: String) -> bool {
+        true
+    }
+
+    pub fn get_payout_address(env: Env, _depin_id: BytesN<32>) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("payout"))
+    }
+
+    pub fn get_service_price(_env: Env, _depin_id: BytesN<32>, _service_type: String) -> Option<i128> {
+        Some(10)
+    }
+
+    pub fn reserve_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+
+    pub fn release_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+}
+
+/// Stand-in for the user-profile contract whitelisting a single token and
+/// reporting memberships set by the test
+#[contract]
+struct MockProfile;
+
+#[contractimpl]
+impl MockProfile {
+    pub fn set_whitelisted(env: Env, token: Address) {
+        env.storage().instance().set(&symbol_short!("token"), &token);
+    }
+
+    pub fn set_membership(env: Env, user: Address, tier: u32, loyalty_points: u32) {
+        env.storage().instance().set(&user, &(tier, loyalty_points));
+    }
+
+    pub fn is_token_whitelisted(env: Env, token_address: Address) -> bool {
+        env.storage().instance().get::<_, Address>(&symbol_short!("token")) == Some(token_address)
+    }
+
+    pub fn get_subscription_tier(env: Env, user_address: Address) -> u32 {
+        env.storage().instance().get::<_, (u32, u32)>(&user_address).map_or(0, |(tier, _)| tier)
+    }
+
+    pub fn get_loyalty_points(env: Env, user_address: Address) -> u32 {
+        env.storage().instance().get::<_, (u32, u32)>(&user_address).map_or(0, |(_, points)| points)
+    }
+}
+
 fn init_order_contract<'a>(env: &'a Env, admin: &Address) -> OrderContractClient<'a> {
     let contract_id = env.register(OrderContract, ());
     let client = OrderContractClient::new(env, &contract_id);
@@ -63,8 +123,11 @@ fn test_create_order_invalid_amount() {
         &String::from_str(&env, "compute"),
         &0, // Invalid duration
         &10,
+        &Address::generate(&env),
         &String::from_str(&env, "ethereum"),
-        &String::from_str(&env, "{}")
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal
     );
 }
 
@@ -86,8 +149,11 @@ fn test_create_order_invalid_price() {
         &String::from_str(&env, "compute"),
         &24,
         &0, // Invalid price
+        &Address::generate(&env),
         &String::from_str(&env, "ethereum"),
-        &String::from_str(&env, "{}")
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal
     );
 }
 
@@ -109,8 +175,11 @@ fn test_create_order_no_registry_contract() {
         &String::from_str(&env, "compute"),
         &24,
         &10,
+        &Address::generate(&env),
         &String::from_str(&env, "ethereum"),
-        &String::from_str(&env, "{}")
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal
     );
 }
 
@@ -135,12 +204,24 @@ fn test_order_status_updates() {
         duration_hours: 24,
         price_per_hour: 10,
         total_amount: 240,
+        payment_token: Address::generate(&env),
         status: OrderStatus::Pending,
         created_at: env.ledger().timestamp(),
         external_tx_id: None,
         deployment_chain: String::from_str(&env, "ethereum"),
         service_params: String::from_str(&env, "{}"),
         escrowed_amount: 240,
+        refunded_amount: 0,
+        released_amount: 0,
+        escrow_contract: None,
+        coupon_code: None,
+        discount_amount: 0,
+        expires_at: env.ledger().timestamp() + DEFAULT_PENDING_TIMEOUT,
+        started_at: 0,
+        completed_at: 0,
+        deployment_deadline: 0,
+        approvals: Vec::new(&env),
+        priority: OrderPriority::Normal,
     };
 
     // Store order directly using contract context
@@ -172,6 +253,32 @@ fn test_order_status_updates() {
     assert_eq!(updated_order.external_tx_id, Some(String::from_str(&env, "0x123abc")));
 }
 
+#[test]
+fn test_invalid_status_transitions_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, _, order_id, _, _) = setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+    let invalid = Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidTransition as u32)));
+
+    // No skipping Active, and no settling through a bare status change
+    assert_eq!(order_client.try_update_order_status(&admin, &order_id, &OrderStatus::Deployed, &None), invalid);
+    assert_eq!(order_client.try_update_order_status(&admin, &order_id, &OrderStatus::Cancelled, &None), invalid);
+
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Deployed, &None);
+    assert_eq!(order_client.try_update_order_status(&admin, &order_id, &OrderStatus::Pending, &None), invalid);
+    assert_eq!(order_client.try_update_order_status(&admin, &order_id, &OrderStatus::Completed, &None), invalid);
+
+    order_client.complete_order(&admin, &order_id);
+    assert_eq!(order_client.try_update_order_status(&admin, &order_id, &OrderStatus::Active, &None), invalid);
+    assert_eq!(
+        order_client.try_refund_order(&admin, &order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidStatus as u32)))
+    );
+}
+
 #[test]
 fn test_complete_order() {
     let env = Env::default();
@@ -179,7 +286,13 @@ fn test_complete_order() {
 
     let admin = Address::generate(&env);
     
+    let treasury = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    
     let order_client = init_order_contract(&env, &admin);
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_treasury_wallet(&admin, &treasury);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&order_client.address, &240);
 
     // Create a fake order in deployed status
     let order_id = BytesN::from_array(&env, &[1u8; 32]);
@@ -191,18 +304,31 @@ fn test_complete_order() {
         duration_hours: 24,
         price_per_hour: 10,
         total_amount: 240,
+        payment_token: token.clone(),
         status: OrderStatus::Deployed,
         created_at: env.ledger().timestamp(),
         external_tx_id: Some(String::from_str(&env, "0x123abc")),
         deployment_chain: String::from_str(&env, "ethereum"),
         service_params: String::from_str(&env, "{}"),
         escrowed_amount: 240,
+        refunded_amount: 0,
+        released_amount: 0,
+        escrow_contract: None,
+        coupon_code: None,
+        discount_amount: 0,
+        expires_at: env.ledger().timestamp() + DEFAULT_PENDING_TIMEOUT,
+        started_at: 0,
+        completed_at: 0,
+        deployment_deadline: 0,
+        approvals: Vec::new(&env),
+        priority: OrderPriority::Normal,
     };
 
     // Store order and escrow amount using contract context
     env.as_contract(&order_client.address, || {
         env.storage().persistent().set(&DataKey::Order(order_id.clone()), &order);
         env.storage().persistent().set(&DataKey::TotalEscrowed, &240i128);
+        env.storage().persistent().set(&DataKey::Custody(token.clone()), &240i128);
     });
 
     // Complete order
@@ -212,6 +338,7 @@ fn test_complete_order() {
     assert_eq!(completed_order.status, OrderStatus::Completed);
     assert_eq!(completed_order.escrowed_amount, 0);
     assert_eq!(order_client.get_total_escrowed(), 0);
+    assert_eq!(soroban_sdk::token::TokenClient::new(&env, &token).balance(&treasury), 240);
 }
 
 #[test]
@@ -234,12 +361,24 @@ fn test_complete_order_invalid_status() {
         duration_hours: 24,
         price_per_hour: 10,
         total_amount: 240,
+        payment_token: Address::generate(&env),
         status: OrderStatus::Pending, // Invalid for completion
         created_at: env.ledger().timestamp(),
         external_tx_id: None,
         deployment_chain: String::from_str(&env, "ethereum"),
         service_params: String::from_str(&env, "{}"),
         escrowed_amount: 240,
+        refunded_amount: 0,
+        released_amount: 0,
+        escrow_contract: None,
+        coupon_code: None,
+        discount_amount: 0,
+        expires_at: env.ledger().timestamp() + DEFAULT_PENDING_TIMEOUT,
+        started_at: 0,
+        completed_at: 0,
+        deployment_deadline: 0,
+        approvals: Vec::new(&env),
+        priority: OrderPriority::Normal,
     };
 
     // Store order using contract context
@@ -280,3 +419,2162 @@ fn test_list_orders_empty() {
     let depin_orders = order_client.list_depin_orders(&depin_id);
     assert_eq!(depin_orders.len(), 0);
 }
+
+fn setup_escrowed_order<'a>(
+    env: &'a Env,
+    admin: &Address,
+    status: OrderStatus,
+) -> (OrderContractClient<'a>, soroban_sdk::token::TokenClient<'a>, BytesN<32>, Address, Address) {
+    let order_client = init_order_contract(env, admin);
+    let escrow_id = env.register(escrow::EscrowContract, ());
+    let escrow_client = escrow::EscrowContractClient::new(env, &escrow_id);
+    order_client.set_escrow_contract(admin, &escrow_id);
+
+    let user = Address::generate(env);
+    let treasury = Address::generate(env);
+    order_client.set_treasury_wallet(admin, &treasury);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    soroban_sdk::token::StellarAssetClient::new(env, &token).mint(&user, &1_000);
+
+    // Lock the order's funds the same way create_order does
+    let order_id = BytesN::from_array(env, &[1u8; 32]);
+    escrow_client.create_escrow(
+        &order_client.address,
+        &order_id,
+        &user,
+        &treasury,
+        &token,
+        &240,
+        &Some(admin.clone()),
+    );
+    escrow_client.lock(&order_client.address, &order_id);
+
+    let order = Order {
+        order_id: order_id.clone(),
+        user: user.clone(),
+        depin_id: BytesN::from_array(env, &[2u8; 32]),
+        service_type: String::from_str(env, "compute"),
+        duration_hours: 24,
+        price_per_hour: 10,
+        total_amount: 240,
+        payment_token: token.clone(),
+        status,
+        created_at: env.ledger().timestamp(),
+        external_tx_id: None,
+        deployment_chain: String::from_str(env, "ethereum"),
+        service_params: String::from_str(env, "{}"),
+        escrowed_amount: 240,
+        refunded_amount: 0,
+        released_amount: 0,
+        escrow_contract: Some(escrow_id),
+        coupon_code: None,
+        discount_amount: 0,
+        expires_at: env.ledger().timestamp() + DEFAULT_PENDING_TIMEOUT,
+        started_at: 0,
+        completed_at: 0,
+        deployment_deadline: 0,
+        approvals: Vec::new(env),
+        priority: OrderPriority::Normal,
+    };
+    env.as_contract(&order_client.address, || {
+        env.storage().persistent().set(&DataKey::Order(order_id.clone()), &order);
+        env.storage().persistent().set(&DataKey::TotalEscrowed, &240i128);
+        env.storage().persistent().set(&StatsKey::EscrowedByToken(token.clone()), &240i128);
+        env.storage().persistent().set(&StatsKey::EscrowedByDepin(order.depin_id.clone()), &240i128);
+    });
+
+    (order_client, soroban_sdk::token::TokenClient::new(env, &token), order_id, user, treasury)
+}
+
+#[test]
+fn test_complete_order_releases_escrow_to_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, treasury) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Deployed);
+
+    order_client.complete_order(&admin, &order_id);
+
+    assert_eq!(order_client.get_order(&order_id).status, OrderStatus::Completed);
+    assert_eq!(token.balance(&treasury), 240);
+    assert_eq!(token.balance(&user), 760);
+    assert_eq!(order_client.get_total_escrowed(), 0);
+    assert_eq!(order_client.get_escrowed_by_token(&token.address), 0);
+    assert_eq!(order_client.get_escrowed_by_depin(&BytesN::from_array(&env, &[2u8; 32])), 0);
+}
+
+#[test]
+fn test_large_completion_needs_approvals() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, _, treasury) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Deployed);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    order_client.set_approver(&admin, &first, &true);
+    order_client.set_approver(&admin, &second, &true);
+    order_client.set_approval_policy(&admin, &100, &2);
+
+    assert_eq!(
+        order_client.try_approve_completion(&Address::generate(&env), &order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::Unauthorized as u32)))
+    );
+    assert_eq!(order_client.approve_completion(&first, &order_id), 1);
+    assert_eq!(
+        order_client.try_approve_completion(&first, &order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::AlreadyApproved as u32)))
+    );
+    assert_eq!(
+        order_client.try_complete_order(&admin, &order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::ApprovalsMissing as u32)))
+    );
+
+    // Approvals from a removed approver no longer count
+    assert_eq!(order_client.approve_completion(&second, &order_id), 2);
+    order_client.set_approver(&admin, &second, &false);
+    assert!(order_client.try_complete_order(&admin, &order_id).is_err());
+
+    order_client.set_approver(&admin, &second, &true);
+    order_client.complete_order(&admin, &order_id);
+    assert_eq!(order_client.get_order(&order_id).status, OrderStatus::Completed);
+    assert_eq!(token.balance(&treasury), 240);
+}
+
+#[test]
+fn test_order_history_records_each_transition() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, _, order_id, _, _) = setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Deployed, &None);
+    env.ledger().with_mut(|li| li.timestamp = 300);
+    order_client.complete_order(&admin, &order_id);
+
+    let history = order_client.get_order_history(&order_id);
+    assert_eq!(history.len(), 3);
+    assert_eq!(
+        history.get(0).unwrap(),
+        StatusChange { status: OrderStatus::Active, timestamp: 100, actor: Some(admin.clone()) }
+    );
+    assert_eq!(history.get(1).unwrap().status, OrderStatus::Deployed);
+    assert_eq!(history.get(1).unwrap().timestamp, 200);
+    assert_eq!(history.get(2).unwrap().status, OrderStatus::Completed);
+    assert_eq!(history.get(2).unwrap().timestamp, 300);
+}
+
+#[test]
+fn test_refund_order_returns_escrow_to_user() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, treasury) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+
+    order_client.refund_order(&admin, &order_id);
+
+    let order = order_client.get_order(&order_id);
+    assert_eq!(order.status, OrderStatus::Cancelled);
+    assert_eq!(order.escrowed_amount, 0);
+    assert_eq!(token.balance(&user), 1_000);
+    assert_eq!(token.balance(&treasury), 0);
+}
+
+#[test]
+fn test_refunds_pay_out_to_wallet() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+
+    // No user-profile contract to credit; refunds leave this contract's custody
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_treasury_wallet(&admin, &Address::generate(&env));
+    let create = || order_client.create_order(
+        &user,
+        &BytesN::from_array(&env, &[2u8; 32]),
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    let refunded = create();
+    let cancelled = create();
+    let split = create();
+    assert_eq!(token_client.balance(&user), 280);
+
+    order_client.refund_order(&admin, &refunded);
+    assert_eq!(token_client.balance(&user), 520);
+
+    order_client.cancel_order(&user, &cancelled);
+    assert_eq!(token_client.balance(&user), 760);
+
+    order_client.update_order_status(&admin, &split, &OrderStatus::Active, &None);
+    order_client.update_order_status(&admin, &split, &OrderStatus::Deployed, &None);
+    order_client.partial_refund(&admin, &split, &90);
+    assert_eq!(token_client.balance(&user), 850);
+    assert_eq!(order_client.get_custody(&token), 0);
+}
+
+#[test]
+fn test_order_without_escrow_contract_holds_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_treasury_wallet(&admin, &treasury);
+
+    let create = || order_client.create_order(
+        &user,
+        &BytesN::from_array(&env, &[2u8; 32]),
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    let completed = create();
+    let refunded = create();
+    assert_eq!(token_client.balance(&order_client.address), 480);
+    assert_eq!(token_client.balance(&user), 520);
+    assert_eq!(order_client.get_custody(&token), 480);
+
+    order_client.update_order_status(&admin, &completed, &OrderStatus::Active, &None);
+    order_client.update_order_status(&admin, &completed, &OrderStatus::Deployed, &None);
+    order_client.complete_order(&admin, &completed);
+    order_client.refund_order(&admin, &refunded);
+
+    assert_eq!(token_client.balance(&treasury), 240);
+    assert_eq!(token_client.balance(&user), 760);
+    assert_eq!(order_client.get_custody(&token), 0);
+    assert_eq!(order_client.get_total_escrowed(), 0);
+}
+
+#[test]
+fn test_create_order_pays_from_wallet_in_one_call() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+
+    // No user-profile contract and no prior deposit
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_payment_token(&admin, &token);
+    let order_id = order_client.create_order(
+        &user,
+        &BytesN::from_array(&env, &[2u8; 32]),
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+
+    // The user's one signature on create_order covers the token transfer
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    let (signer, invocation) = &auths[0];
+    assert_eq!(signer, &user);
+    assert_eq!(invocation.sub_invocations.len(), 1);
+    match &invocation.sub_invocations.first().unwrap().function {
+        soroban_sdk::testutils::AuthorizedFunction::Contract((contract, function, _)) => {
+            assert_eq!(contract, &token);
+            assert_eq!(function, &Symbol::new(&env, "transfer"));
+        }
+        _ => panic!("expected a token transfer"),
+    }
+
+    assert_eq!(token_client.balance(&user), 760);
+    assert_eq!(token_client.balance(&order_client.address), 240);
+    assert_eq!(order_client.get_order(&order_id).escrowed_amount, 240);
+
+    // A wallet that cannot cover the total gets no order
+    let broke = Address::generate(&env);
+    assert!(order_client.try_create_order(
+        &broke,
+        &BytesN::from_array(&env, &[2u8; 32]),
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    ).is_err());
+    assert_eq!(order_client.list_user_orders(&broke).len(), 0);
+}
+
+#[test]
+fn test_order_lifecycle_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let depin_id = BytesN::from_array(&env, &[2u8; 32]);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_treasury_wallet(&admin, &Address::generate(&env));
+
+    let order_id = order_client.create_order(
+        &user,
+        &depin_id,
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    let last_event = |action: Symbol| {
+        let (contract, topics, data) = env.events().all().last().unwrap();
+        assert_eq!(contract, order_client.address);
+        assert_eq!(
+            topics,
+            (action, order_id.clone(), user.clone(), depin_id.clone()).into_val(&env)
+        );
+        data
+    };
+    let data = last_event(symbol_short!("created"));
+    assert_eq!(i128::try_from_val(&env, &data).unwrap(), 240);
+
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Deployed, &Some(String::from_str(&env, "0xabc")));
+    let data = last_event(symbol_short!("deployed"));
+    assert_eq!(
+        <(i128, Option<String>)>::try_from_val(&env, &data).unwrap(),
+        (240, Some(String::from_str(&env, "0xabc")))
+    );
+
+    order_client.refund_order(&admin, &order_id);
+    let data = last_event(symbol_short!("refunded"));
+    assert_eq!(i128::try_from_val(&env, &data).unwrap(), 240);
+}
+
+#[test]
+fn test_expire_pending_order_after_timeout() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_pending_timeout(&admin, &3600);
+
+    let order_id = order_client.create_order(
+        &user,
+        &BytesN::from_array(&env, &[2u8; 32]),
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    assert_eq!(order_client.get_order(&order_id).expires_at, env.ledger().timestamp() + 3600);
+    assert_eq!(
+        order_client.try_expire_order(&order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::NotExpired as u32)))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    assert_eq!(order_client.expire_order(&order_id), 240);
+
+    let order = order_client.get_order(&order_id);
+    assert_eq!(order.status, OrderStatus::Cancelled);
+    assert_eq!(order.escrowed_amount, 0);
+    assert_eq!(token_client.balance(&user), 1_000);
+    assert_eq!(order_client.get_total_escrowed(), 0);
+    assert_eq!(
+        order_client.try_expire_order(&order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidStatus as u32)))
+    );
+}
+
+#[test]
+fn test_claim_sla_refund_after_deployment_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, _) = setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+    order_client.set_deployment_timeout(&admin, &3600);
+
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    assert_eq!(order_client.get_order(&order_id).deployment_deadline, env.ledger().timestamp() + 3600);
+    assert_eq!(
+        order_client.try_claim_sla_refund(&order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::NotExpired as u32)))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    assert_eq!(order_client.claim_sla_refund(&order_id), 240);
+
+    let order = order_client.get_order(&order_id);
+    assert_eq!(order.status, OrderStatus::Failed);
+    assert_eq!(order.refunded_amount, 240);
+    assert_eq!(token.balance(&user), 1_000);
+}
+
+#[test]
+fn test_order_paid_in_token_whitelisted_by_profile() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let eurc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &eurc).mint(&user, &1_000);
+
+    let profile = MockProfileClient::new(&env, &env.register(MockProfile, ()));
+    profile.set_whitelisted(&eurc);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_payment_token(&admin, &usdc);
+    order_client.set_user_profile_contract(&admin, &profile.address);
+
+    let create = |token: &Address| order_client.try_create_order(
+        &user,
+        &BytesN::from_array(&env, &[2u8; 32]),
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    assert_eq!(
+        create(&usdc),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::TokenNotAccepted as u32)))
+    );
+
+    let order_id = create(&eurc).unwrap().unwrap();
+    assert_eq!(order_client.get_order_token(&order_id), eurc);
+    assert_eq!(order_client.get_custody(&eurc), 240);
+
+    order_client.refund_order(&admin, &order_id);
+    assert_eq!(soroban_sdk::token::TokenClient::new(&env, &eurc).balance(&user), 1_000);
+}
+
+#[test]
+fn test_member_discounts_applied_at_creation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+
+    let profile = MockProfileClient::new(&env, &env.register(MockProfile, ()));
+    profile.set_whitelisted(&token);
+    profile.set_membership(&user, &1, &500);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_user_profile_contract(&admin, &profile.address);
+
+    // Premium gets 10%, and 500 points reach the 5% loyalty level
+    order_client.set_tier_discount(&admin, &1, &1_000);
+    order_client.set_loyalty_discounts(&admin, &soroban_sdk::vec![&env, (100u32, 500u32), (1_000u32, 1_000u32)]);
+
+    let order_id = order_client.create_order(
+        &user,
+        &BytesN::from_array(&env, &[2u8; 32]),
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+
+    let order = order_client.get_order(&order_id);
+    assert_eq!(order.discount_amount, 36);
+    assert_eq!(order.total_amount, 204);
+    assert_eq!(soroban_sdk::token::TokenClient::new(&env, &token).balance(&user), 796);
+}
+
+#[test]
+fn test_order_limits_cap_open_orders_and_rate() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_order_limits(&admin, &2, &10);
+
+    let create = || order_client.try_create_order(
+        &user,
+        &BytesN::from_array(&env, &[2u8; 32]),
+        &String::from_str(&env, "compute"),
+        &1,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    let first = create().unwrap().unwrap();
+    assert_eq!(
+        create(),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::RateLimited as u32)))
+    );
+
+    env.ledger().with_mut(|li| li.sequence_number += 10);
+    create().unwrap().unwrap();
+    assert_eq!(order_client.get_open_order_count(&user), 2);
+
+    env.ledger().with_mut(|li| li.sequence_number += 10);
+    assert_eq!(
+        create(),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::TooManyOpenOrders as u32)))
+    );
+
+    // Deployed orders no longer count as open
+    order_client.update_order_status(&admin, &first, &OrderStatus::Active, &None);
+    order_client.update_order_status(&admin, &first, &OrderStatus::Deployed, &None);
+    assert_eq!(order_client.get_open_order_count(&user), 1);
+    create().unwrap().unwrap();
+}
+
+#[test]
+fn test_create_order_checks_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+
+    let registry = depin_registry::ContractClient::new(&env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    let depin_id = registry.add_depin(&admin, &String::from_str(&env, "GPU"), &String::from_str(&env, "A100"), &99, &95, &10);
+    registry.set_service_price(&admin, &depin_id, &String::from_str(&env, "compute"), &10);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+    registry.set_order_contract(&admin, &order_client.address);
+    order_client.set_payment_token(&admin, &token);
+
+    let create = |depin_id: &BytesN<32>| order_client.try_create_order(
+        &user,
+        depin_id,
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    assert_eq!(
+        create(&BytesN::from_array(&env, &[9u8; 32])),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidDepin as u32)))
+    );
+    let order_id = create(&depin_id).unwrap().unwrap();
+    assert_eq!(order_client.get_order_depin(&order_id), depin_id);
+}
+
+#[test]
+fn test_create_order_uses_listed_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+
+    let registry = depin_registry::ContractClient::new(&env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    let depin_id = registry.add_depin(&admin, &String::from_str(&env, "GPU"), &String::from_str(&env, "A100"), &99, &95, &10);
+    registry.set_service_price(&admin, &depin_id, &String::from_str(&env, "compute"), &10);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+    registry.set_order_contract(&admin, &order_client.address);
+    order_client.set_payment_token(&admin, &token);
+    assert_eq!(order_client.quote_order(&depin_id, &String::from_str(&env, "compute"), &24), 240);
+
+    let create = |service_type: &str, price_per_hour: i128| order_client.try_create_order(
+        &user,
+        &depin_id,
+        &String::from_str(&env, service_type),
+        &24,
+        &price_per_hour,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    assert_eq!(
+        create("compute", 5),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::PriceMismatch as u32)))
+    );
+    assert_eq!(
+        create("storage", 10),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::PriceUnavailable as u32)))
+    );
+    let order_id = create("compute", 10).unwrap().unwrap();
+    assert_eq!(order_client.get_order(&order_id).total_amount, 240);
+}
+
+#[test]
+fn test_reassign_order_reprices_and_keeps_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+
+    let registry = depin_registry::ContractClient::new(&env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    let compute = String::from_str(&env, "compute");
+    let first = registry.add_depin(&admin, &String::from_str(&env, "GPU"), &String::from_str(&env, "A100"), &99, &95, &10);
+    let second = registry.add_depin(&admin, &String::from_str(&env, "GPU2"), &String::from_str(&env, "H100"), &99, &95, &10);
+    registry.set_service_price(&admin, &first, &compute, &10);
+    registry.set_service_price(&admin, &second, &compute, &8);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+    registry.set_order_contract(&admin, &order_client.address);
+    order_client.set_payment_token(&admin, &token);
+    let order_id = order_client.create_order(
+        &user,
+        &first,
+        &compute,
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+
+    // The cheaper DePIN refunds the difference
+    assert_eq!(order_client.reassign_order(&admin, &order_id, &second), 192);
+    let order = order_client.get_order(&order_id);
+    assert_eq!((order.depin_id, order.price_per_hour, order.escrowed_amount), (second.clone(), 8, 192));
+    assert_eq!(token_client.balance(&user), 808);
+    assert_eq!(order_client.list_depin_orders(&first).len(), 0);
+    assert_eq!(order_client.list_depin_orders(&second), soroban_sdk::vec![&env, order_id.clone()]);
+    assert_eq!(order_client.get_escrowed_by_depin(&first), 0);
+    assert_eq!(order_client.get_escrowed_by_depin(&second), 192);
+    assert_eq!(order_client.get_unsettled_depin_order_count(&first), 0);
+    assert_eq!(order_client.get_unsettled_depin_order_count(&second), 1);
+    assert_eq!(order_client.get_escrowed_by_token(&token), 192);
+
+    // A failed order reopens and the user pays for it again
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    order_client.refund_order(&admin, &order_id);
+    assert_eq!(order_client.get_open_order_count(&user), 0);
+    assert_eq!(order_client.reassign_order(&admin, &order_id, &first), 240);
+    let order = order_client.get_order(&order_id);
+    assert_eq!((order.status, order.escrowed_amount), (OrderStatus::Pending, 240));
+    assert_eq!(token_client.balance(&user), 760);
+    assert_eq!(order_client.get_open_order_count(&user), 1);
+    assert_eq!(order_client.get_unsettled_depin_order_count(&first), 1);
+    assert_eq!(order_client.get_unsettled_depin_order_count(&second), 0);
+    assert_eq!(order_client.get_total_escrowed(), 240);
+}
+
+#[test]
+fn test_reassign_order_keeps_priority_surge() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+
+    let registry = depin_registry::ContractClient::new(&env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    let compute = String::from_str(&env, "compute");
+    let first = registry.add_depin(&admin, &String::from_str(&env, "GPU"), &String::from_str(&env, "A100"), &99, &95, &10);
+    let second = registry.add_depin(&admin, &String::from_str(&env, "GPU2"), &String::from_str(&env, "H100"), &99, &95, &10);
+    registry.set_service_price(&admin, &first, &compute, &10);
+    registry.set_service_price(&admin, &second, &compute, &8);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+    registry.set_order_contract(&admin, &order_client.address);
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_priority_multiplier(&admin, &OrderPriority::Urgent, &15_000);
+    let order_id = order_client.create_order(
+        &user,
+        &first,
+        &compute,
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    assert_eq!(order_client.get_order(&order_id).priority, OrderPriority::Normal);
+    order_client.set_order_priority(&user, &order_id, &OrderPriority::Urgent);
+    assert_eq!(order_client.get_order(&order_id).total_amount, 360);
+
+    // 24h at 8/hour plus the 50% urgent surge
+    assert_eq!(order_client.reassign_order(&admin, &order_id, &second), 288);
+    let order = order_client.get_order(&order_id);
+    assert_eq!((order.priority, order.total_amount, order.escrowed_amount), (OrderPriority::Urgent, 288, 288));
+    assert_eq!(token_client.balance(&user), 1_000 - 288);
+}
+
+#[test]
+fn test_priority_chosen_at_creation_is_paid_up_front() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+
+    let registry = depin_registry::ContractClient::new(&env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    let compute = String::from_str(&env, "compute");
+    let depin_id = registry.add_depin(&admin, &String::from_str(&env, "GPU"), &String::from_str(&env, "A100"), &99, &95, &10);
+    registry.set_service_price(&admin, &depin_id, &compute, &10);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+    registry.set_order_contract(&admin, &order_client.address);
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_priority_multiplier(&admin, &OrderPriority::High, &12_000);
+    order_client.set_priority_multiplier(&admin, &OrderPriority::Urgent, &15_000);
+
+    // 24h at 10/hour plus the 50% urgent surge, taken with the order
+    let order_id = order_client.create_order(
+        &user,
+        &depin_id,
+        &compute,
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Urgent,
+    );
+    let order = order_client.get_order(&order_id);
+    assert_eq!((order.priority, order.total_amount, order.escrowed_amount), (OrderPriority::Urgent, 360, 360));
+    assert_eq!(token_client.balance(&user), 640);
+    assert_eq!(order_client.list_orders_by_priority(&OrderPriority::Urgent), soroban_sdk::vec![&env, order_id.clone()]);
+    assert!(order_client.try_set_order_priority(&user, &order_id, &OrderPriority::High).is_err());
+
+    // Batches price each request at its own priority
+    let request = |priority: OrderPriority| OrderRequest {
+        depin_id: depin_id.clone(),
+        service_type: compute.clone(),
+        duration_hours: 10,
+        price_per_hour: 10,
+        deployment_chain: String::from_str(&env, "ethereum"),
+        service_params: String::from_str(&env, "{}"),
+        priority,
+    };
+    let order_ids = order_client.create_orders_batch(
+        &user,
+        &soroban_sdk::vec![&env, request(OrderPriority::Normal), request(OrderPriority::High)],
+    );
+    assert_eq!(order_client.get_order(&order_ids.get(0).unwrap()).total_amount, 100);
+    assert_eq!(order_client.get_order(&order_ids.get(1).unwrap()).total_amount, 120);
+    assert_eq!(token_client.balance(&user), 640 - 220);
+    assert_eq!(order_client.list_orders_by_priority(&OrderPriority::High), soroban_sdk::vec![&env, order_ids.get(1).unwrap()]);
+}
+
+#[test]
+fn test_deployed_orders_block_depin_retirement() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+
+    let registry = depin_registry::ContractClient::new(&env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    let compute = String::from_str(&env, "compute");
+    let depin_id = registry.add_depin(&admin, &String::from_str(&env, "GPU"), &String::from_str(&env, "A100"), &99, &95, &10);
+    registry.set_service_price(&admin, &depin_id, &compute, &10);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+    registry.set_order_contract(&admin, &order_client.address);
+    order_client.set_payment_token(&admin, &token);
+    let order_id = order_client.create_order(
+        &user,
+        &depin_id,
+        &compute,
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Deployed, &Some(String::from_str(&env, "0xabc")));
+
+    // Deployed orders still run on the DePIN, so it cannot be retired
+    assert_eq!(order_client.get_unsettled_depin_order_count(&depin_id), 1);
+    registry.deprecate_depin(&admin, &depin_id);
+    assert!(registry.try_retire_depin(&admin, &depin_id).is_err());
+
+    // Nor while a completed order keeps its funds escrowed for disputes
+    order_client.set_treasury_wallet(&admin, &Address::generate(&env));
+    order_client.set_dispute_window(&admin, &100);
+    order_client.complete_order(&admin, &order_id);
+    assert_eq!(order_client.get_unsettled_depin_order_count(&depin_id), 1);
+    assert!(registry.try_retire_depin(&admin, &depin_id).is_err());
+
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    order_client.finalize_order(&order_id);
+    assert_eq!(order_client.get_unsettled_depin_order_count(&depin_id), 0);
+    registry.retire_depin(&admin, &depin_id);
+}
+
+#[test]
+fn test_create_order_reserves_capacity() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+
+    let registry = depin_registry::ContractClient::new(&env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    let depin_id = registry.add_depin(&admin, &String::from_str(&env, "GPU"), &String::from_str(&env, "A100"), &99, &95, &10);
+    registry.set_service_price(&admin, &depin_id, &String::from_str(&env, "compute"), &10);
+    registry.set_capacity(&admin, &depin_id, &1);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+    registry.set_order_contract(&admin, &order_client.address);
+    order_client.set_payment_token(&admin, &token);
+
+    let create = || order_client.try_create_order(
+        &user,
+        &depin_id,
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    let order_id = create().unwrap().unwrap();
+    assert_eq!(registry.get_available_capacity(&depin_id), Some(0));
+    assert!(create().is_err());
+
+    // Cancelling frees the slot for the next order
+    order_client.cancel_order(&user, &order_id);
+    assert_eq!(registry.get_available_capacity(&depin_id), Some(1));
+    assert!(create().is_ok());
+}
+
+#[test]
+fn test_order_pages_filter_by_status() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let depin_id = BytesN::from_array(&env, &[2u8; 32]);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_payment_token(&admin, &token);
+
+    let mut order_ids = Vec::new(&env);
+    for _ in 0..3 {
+        order_ids.push_back(order_client.create_order(
+            &user,
+            &depin_id,
+            &String::from_str(&env, "compute"),
+            &1,
+            &10,
+            &token,
+            &String::from_str(&env, "ethereum"),
+            &String::from_str(&env, "{}"),
+            &None,
+            &OrderPriority::Normal,
+        ));
+    }
+    order_client.update_order_status(&admin, &order_ids.get(1).unwrap(), &OrderStatus::Active, &None);
+
+    let page = order_client.get_user_orders_page(&user, &0, &2, &None);
+    assert_eq!(page.orders.len(), 2);
+    assert_eq!(page.orders.get(1).unwrap().order_id, order_ids.get(1).unwrap());
+    assert_eq!(page.next_offset, Some(2));
+
+    let page = order_client.get_user_orders_page(&user, &2, &2, &None);
+    assert_eq!(page.orders.len(), 1);
+    assert_eq!(page.next_offset, None);
+
+    let pending = order_client.get_depin_orders_page(&depin_id, &0, &10, &Some(OrderStatus::Pending));
+    assert_eq!(pending.orders.len(), 2);
+    assert_eq!(pending.orders.get(1).unwrap().order_id, order_ids.get(2).unwrap());
+    assert_eq!(pending.next_offset, None);
+}
+
+#[test]
+fn test_archive_finished_orders() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, _, order_id, user, _) = setup_escrowed_order(&env, &admin, OrderStatus::Deployed);
+    order_client.set_archive_age(&admin, &3600);
+    env.as_contract(&order_client.address, || {
+        env.storage().persistent().set(&DataKey::UserOrders(user.clone()), &soroban_sdk::vec![&env, order_id.clone()]);
+    });
+
+    // Running orders are never archived
+    assert_eq!(
+        order_client.try_archive_order(&order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidStatus as u32)))
+    );
+    order_client.complete_order(&admin, &order_id);
+    assert_eq!(order_client.archive_orders(&soroban_sdk::vec![&env, order_id.clone()]), 0);
+
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    assert_eq!(order_client.archive_orders(&soroban_sdk::vec![&env, order_id.clone()]), 1);
+
+    let archived = order_client.get_archived_order(&order_id).unwrap();
+    assert_eq!(archived.status, OrderStatus::Completed);
+    assert_eq!(archived.released_amount, 240);
+    assert_eq!(order_client.list_user_orders(&user).len(), 0);
+    assert!(order_client.try_get_order(&order_id).is_err());
+}
+
+#[test]
+fn test_dispute_freezes_completed_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, treasury) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Deployed);
+    order_client.set_dispute_window(&admin, &3600);
+
+    // Funds stay escrowed while the order may still be disputed
+    order_client.complete_order(&admin, &order_id);
+    assert_eq!(order_client.get_order(&order_id).escrowed_amount, 240);
+    assert_eq!(token.balance(&treasury), 0);
+
+    let reason = String::from_str(&env, "node never came online");
+    order_client.open_dispute(&user, &order_id, &reason);
+    assert_eq!(order_client.get_order(&order_id).status, OrderStatus::Disputed);
+    assert_eq!(order_client.get_dispute_reason(&order_id), Some(reason));
+    assert_eq!(
+        order_client.try_refund_order(&admin, &order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidStatus as u32)))
+    );
+    assert_eq!(
+        order_client.try_resolve_dispute(&user, &order_id, &240, &0),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::Unauthorized as u32)))
+    );
+    assert_eq!(
+        order_client.try_resolve_dispute(&admin, &order_id, &100, &100),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidAmount as u32)))
+    );
+
+    order_client.resolve_dispute(&admin, &order_id, &90, &150);
+
+    let order = order_client.get_order(&order_id);
+    assert_eq!(order.status, OrderStatus::Failed);
+    assert_eq!(order.escrowed_amount, 0);
+    assert_eq!(token.balance(&user), 850);
+    assert_eq!(token.balance(&treasury), 150);
+    assert_eq!(order_client.get_dispute_reason(&order_id), None);
+}
+
+#[test]
+fn test_finalize_order_after_dispute_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, treasury) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Deployed);
+    order_client.set_dispute_window(&admin, &3600);
+    order_client.complete_order(&admin, &order_id);
+
+    assert_eq!(
+        order_client.try_finalize_order(&order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::NotExpired as u32)))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    assert_eq!(
+        order_client.try_open_dispute(&user, &order_id, &String::from_str(&env, "too late")),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::DisputeWindowClosed as u32)))
+    );
+    assert_eq!(order_client.finalize_order(&order_id), 240);
+    assert_eq!(token.balance(&treasury), 240);
+    assert_eq!(order_client.get_total_escrowed(), 0);
+}
+
+#[test]
+fn test_partial_refund_splits_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, treasury) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Deployed);
+    let dispute = Address::generate(&env);
+
+    assert_eq!(
+        order_client.try_partial_refund(&dispute, &order_id, &90),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::Unauthorized as u32)))
+    );
+    order_client.set_dispute_module(&admin, &dispute, &true);
+    assert_eq!(
+        order_client.try_partial_refund(&dispute, &order_id, &241),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidAmount as u32)))
+    );
+
+    order_client.partial_refund(&dispute, &order_id, &90);
+
+    let order = order_client.get_order(&order_id);
+    assert_eq!(order.status, OrderStatus::Failed);
+    assert_eq!(order.escrowed_amount, 0);
+    assert_eq!(order.refunded_amount, 90);
+    assert_eq!(order.released_amount, 150);
+    assert_eq!(token.balance(&user), 850);
+    assert_eq!(token.balance(&treasury), 150);
+    assert_eq!(order_client.get_total_escrowed(), 0);
+}
+
+#[test]
+fn test_claim_earned_streams_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, _) = setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+    let provider = Address::generate(&env);
+    let registry = MockRegistryClient::new(&env, &env.register(MockRegistry, ()));
+    registry.set_payout(&provider);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    assert_eq!(
+        order_client.try_claim_earned(&user, &order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::Unauthorized as u32)))
+    );
+
+    // A quarter of the 24 hours has passed
+    env.ledger().with_mut(|li| li.timestamp += 6 * 3600);
+    assert_eq!(order_client.claim_earned(&provider, &order_id), 60);
+    assert_eq!(order_client.claim_earned(&provider, &order_id), 0);
+    assert_eq!(token.balance(&provider), 60);
+
+    // The unearned remainder goes back to the user if the order fails
+    order_client.refund_order(&admin, &order_id);
+    let order = order_client.get_order(&order_id);
+    assert_eq!(order.released_amount, 60);
+    assert_eq!(order.refunded_amount, 180);
+    assert_eq!(token.balance(&user), 940);
+}
+
+#[test]
+fn test_provider_confirms_deployment() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, _, order_id, user, _) = setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+    let provider = Address::generate(&env);
+    let registry = MockRegistryClient::new(&env, &env.register(MockRegistry, ()));
+    registry.set_payout(&provider);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+    let tx_id = String::from_str(&env, "0xabc");
+
+    // Only Active orders can be confirmed
+    assert_eq!(
+        order_client.try_confirm_deployment(&provider, &order_id, &tx_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidTransition as u32)))
+    );
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    assert_eq!(
+        order_client.try_confirm_deployment(&user, &order_id, &tx_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::Unauthorized as u32)))
+    );
+
+    order_client.confirm_deployment(&provider, &order_id, &tx_id);
+    let order = order_client.get_order(&order_id);
+    assert_eq!(order.status, OrderStatus::Deployed);
+    assert_eq!(order.external_tx_id, Some(tx_id));
+    assert_eq!(order_client.get_order_history(&order_id).last().unwrap().actor, Some(provider));
+}
+
+#[test]
+fn test_cancel_order_charges_cancellation_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, treasury) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+
+    let fee_manager_id = env.register(fee_manager::FeeManagerContract, ());
+    let fee_manager = fee_manager::FeeManagerContractClient::new(&env, &fee_manager_id);
+    fee_manager.initialize(&admin);
+    fee_manager.set_cancellation_fee(&admin, &1_000, &0); // 10%
+    order_client.set_fee_manager(&admin, &fee_manager_id);
+
+    order_client.cancel_order(&user, &order_id);
+
+    assert_eq!(order_client.get_order(&order_id).status, OrderStatus::Cancelled);
+    assert_eq!(token.balance(&treasury), 24);
+    assert_eq!(token.balance(&user), 1_000 - 24);
+    assert_eq!(order_client.get_total_escrowed(), 0);
+}
+
+#[test]
+fn test_scaled_cancellation_fee_grows_towards_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, treasury) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+
+    let fee_manager_id = env.register(fee_manager::FeeManagerContract, ());
+    let fee_manager = fee_manager::FeeManagerContractClient::new(&env, &fee_manager_id);
+    fee_manager.initialize(&admin);
+    fee_manager.set_cancellation_fee(&admin, &1_000, &0); // 10%
+    order_client.set_fee_manager(&admin, &fee_manager_id);
+    order_client.set_cancellation_fee_scaled(&admin, &true);
+
+    // Half the pending window has gone: half the fee
+    env.ledger().with_mut(|li| li.timestamp += DEFAULT_PENDING_TIMEOUT / 2);
+    order_client.cancel_order(&user, &order_id);
+
+    assert_eq!(token.balance(&treasury), 12);
+    assert_eq!(token.balance(&user), 1_000 - 12);
+}
+
+#[test]
+fn test_cancel_order_needs_only_user_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, _) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+
+    order_client.cancel_order(&user, &order_id);
+
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, user);
+    assert_eq!(order_client.get_order(&order_id).status, OrderStatus::Cancelled);
+    assert_eq!(token.balance(&user), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #11)")]
+fn test_set_admin_requires_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let order_client = init_order_contract(&env, &admin);
+
+    order_client.set_admin(&Address::generate(&env));
+}
+
+#[test]
+fn test_complete_order_routes_provider_share_through_splitter() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, _user, treasury) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Deployed);
+    order_client.set_treasury_wallet(&admin, &treasury);
+    order_client.set_payment_token(&admin, &token.address);
+
+    let fee_manager_id = env.register(fee_manager::FeeManagerContract, ());
+    let fee_manager = fee_manager::FeeManagerContractClient::new(&env, &fee_manager_id);
+    fee_manager.initialize(&admin);
+    fee_manager.set_platform_fee_bps(&admin, &1_000); // 10%
+    order_client.set_fee_manager(&admin, &fee_manager_id);
+
+    let splitter_id = env.register(splitter::SplitterContract, ());
+    let splitter_client = splitter::SplitterContractClient::new(&env, &splitter_id);
+    splitter_client.initialize(&admin);
+    let operator = Address::generate(&env);
+    let financier = Address::generate(&env);
+    splitter_client.set_payees(
+        &admin,
+        &BytesN::from_array(&env, &[2u8; 32]),
+        &soroban_sdk::vec![
+            &env,
+            splitter::Payee { address: operator.clone(), weight: 1 },
+            splitter::Payee { address: financier.clone(), weight: 1 },
+        ],
+    );
+    order_client.set_splitter_contract(&admin, &splitter_id);
+
+    order_client.complete_order(&admin, &order_id);
+
+    assert_eq!(token.balance(&treasury), 24);
+    assert_eq!(token.balance(&splitter_id), 216);
+    assert_eq!(splitter_client.get_claimable(&operator, &token.address), 108);
+    assert_eq!(splitter_client.get_claimable(&financier, &token.address), 108);
+}
+
+#[test]
+fn test_complete_order_pays_provider_payout_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+
+    let registry = depin_registry::ContractClient::new(&env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    let depin_id = registry.add_depin(&admin, &String::from_str(&env, "GPU"), &String::from_str(&env, "A100"), &99, &95, &10);
+    registry.set_service_price(&admin, &depin_id, &String::from_str(&env, "compute"), &10);
+    registry.set_payout_address(&admin, &depin_id, &provider);
+
+    let fee_manager = fee_manager::FeeManagerContractClient::new(&env, &env.register(fee_manager::FeeManagerContract, ()));
+    fee_manager.initialize(&admin);
+    fee_manager.set_platform_fee_bps(&admin, &1_000); // 10%
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+    registry.set_order_contract(&admin, &order_client.address);
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_treasury_wallet(&admin, &treasury);
+    order_client.set_fee_manager(&admin, &fee_manager.address);
+
+    let order_id = order_client.create_order(
+        &user,
+        &depin_id,
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Deployed, &None);
+    order_client.complete_order(&admin, &order_id);
+
+    // complete_order publishes the treasury leg, the provider leg, then `completed`
+    let mut legs = Vec::new(&env);
+    for (contract, topics, data) in env.events().all().iter() {
+        let action = Symbol::try_from_val(&env, &topics.get(0).unwrap()).unwrap();
+        if contract == order_client.address && action != symbol_short!("completed") {
+            let (to, amount) = <(Address, i128)>::try_from_val(&env, &data).unwrap();
+            legs.push_back((action, to, amount));
+        }
+    }
+    assert_eq!(
+        legs,
+        soroban_sdk::vec![
+            &env,
+            (symbol_short!("treasury"), treasury.clone(), 24),
+            (symbol_short!("payout"), provider.clone(), 216),
+        ]
+    );
+
+    assert_eq!(token_client.balance(&treasury), 24);
+    assert_eq!(token_client.balance(&provider), 216);
+    assert_eq!(order_client.get_custody(&token), 0);
+}
+
+#[test]
+fn test_create_order_with_coupon_locks_discounted_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let order_client = init_order_contract(&env, &admin);
+
+    let registry_id = env.register(MockRegistry, ());
+    let escrow_id = env.register(escrow::EscrowContract, ());
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    order_client.set_depin_registry_contract(&admin, &registry_id);
+    order_client.set_escrow_contract(&admin, &escrow_id);
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_treasury_wallet(&admin, &Address::generate(&env));
+
+    let coupon_id = env.register(coupon::CouponContract, ());
+    let coupon_client = coupon::CouponContractClient::new(&env, &coupon_id);
+    coupon_client.initialize(&admin);
+    coupon_client.set_redeemer(&admin, &order_client.address, &true);
+    let code = String::from_str(&env, "LAUNCH10");
+    coupon_client.create_coupon(&admin, &code, &coupon::Discount::Percent(1_000), &0, &0, &1, &None, &None);
+    order_client.set_coupon_contract(&admin, &coupon_id);
+
+    let order_id = order_client.create_order(
+        &user,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &Some(code.clone()),
+        &OrderPriority::Normal
+    );
+
+    let order = order_client.get_order(&order_id);
+    assert_eq!(order.discount_amount, 24);
+    assert_eq!(order.total_amount, 216);
+    assert_eq!(order.escrowed_amount, 216);
+    assert_eq!(order.coupon_code, Some(code.clone()));
+    assert_eq!(soroban_sdk::token::TokenClient::new(&env, &token).balance(&user), 784);
+    assert_eq!(coupon_client.get_user_uses(&code, &user), 1);
+}
+
+#[test]
+fn test_create_orders_batch_charges_total_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let order_client = init_order_contract(&env, &admin);
+
+    let escrow_id = env.register(escrow::EscrowContract, ());
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_escrow_contract(&admin, &escrow_id);
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_treasury_wallet(&admin, &Address::generate(&env));
+
+    let request = |duration_hours: u64, price_per_hour: i128| OrderRequest {
+        depin_id: BytesN::from_array(&env, &[1u8; 32]),
+        service_type: String::from_str(&env, "compute"),
+        duration_hours,
+        price_per_hour,
+        deployment_chain: String::from_str(&env, "ethereum"),
+        service_params: String::from_str(&env, "{}"),
+        priority: OrderPriority::Normal,
+    };
+
+    // One bad request rejects the whole batch
+    assert_eq!(
+        order_client.try_create_orders_batch(&user, &soroban_sdk::vec![&env, request(24, 10), request(12, 5)]),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::PriceMismatch as u32)))
+    );
+    assert_eq!(order_client.get_order_count(), 0);
+
+    let order_ids = order_client.create_orders_batch(&user, &soroban_sdk::vec![&env, request(24, 10), request(12, 10)]);
+    assert_eq!(order_ids.len(), 2);
+    // Orders opened in the same ledger still get distinct IDs
+    assert_ne!(order_ids.get(0), order_ids.get(1));
+    let created = order_client.get_order_history(&order_ids.get(0).unwrap()).get(0).unwrap();
+    assert_eq!((created.status, created.actor), (OrderStatus::Pending, Some(user.clone())));
+    assert_eq!(order_client.get_order(&order_ids.get(0).unwrap()).total_amount, 240);
+    assert_eq!(order_client.get_order(&order_ids.get(1).unwrap()).total_amount, 120);
+    assert_eq!(order_client.list_user_orders(&user), order_ids);
+    assert_eq!(order_client.get_total_escrowed(), 360);
+    assert_eq!(token_client.balance(&user), 640);
+    assert_eq!(token_client.balance(&escrow_id), 360);
+    assert_eq!(token_client.balance(&order_client.address), 0);
+
+    // Each order refunds on its own
+    order_client.refund_order(&admin, &order_ids.get(1).unwrap());
+    assert_eq!(token_client.balance(&user), 760);
+}
+
+#[test]
+fn test_price_bands_reject_out_of_range_orders() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let order_client = init_order_contract(&env, &admin);
+
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_payment_token(&admin, &token);
+
+    let compute = String::from_str(&env, "compute");
+    let create = || order_client.try_create_order(
+        &user,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &compute,
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    let out_of_range = Err(Ok(soroban_sdk::Error::from_contract_error(Error::PriceOutOfRange as u32)));
+
+    // Both the global and the service type's band apply
+    order_client.set_price_band(&admin, &None, &1, &9);
+    assert_eq!(create(), out_of_range);
+    order_client.set_price_band(&admin, &None, &1, &100);
+    order_client.set_price_band(&admin, &Some(compute.clone()), &12, &50);
+    assert_eq!(create(), out_of_range);
+
+    order_client.set_price_band(&admin, &Some(compute.clone()), &5, &50);
+    assert!(create().is_ok());
+    assert_eq!(order_client.get_price_band(&Some(compute)), Some(PriceBand { min_price: 5, max_price: 50 }));
+}
+
+#[test]
+fn test_create_order_rejects_unsupported_chain() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+
+    let registry = depin_registry::ContractClient::new(&env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    let compute = String::from_str(&env, "compute");
+    let depin_id = registry.add_depin(&admin, &String::from_str(&env, "GPU"), &String::from_str(&env, "A100"), &99, &95, &10);
+    registry.set_service_price(&admin, &depin_id, &compute, &10);
+    registry.set_supported_chains(&admin, &depin_id, &soroban_sdk::vec![&env, String::from_str(&env, "stellar")]);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+    registry.set_order_contract(&admin, &order_client.address);
+    order_client.set_payment_token(&admin, &token);
+    let create = |chain: &str| order_client.try_create_order(
+        &user,
+        &depin_id,
+        &compute,
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, chain),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+
+    assert_eq!(
+        create("ethereum"),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::UnsupportedChain as u32)))
+    );
+    assert!(create("stellar").is_ok());
+}
+
+#[test]
+fn test_large_orders_need_verified_provider() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+
+    let registry = depin_registry::ContractClient::new(&env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    let compute = String::from_str(&env, "compute");
+    let depin_id = registry.add_depin(&admin, &String::from_str(&env, "GPU"), &String::from_str(&env, "A100"), &99, &95, &10);
+    registry.set_service_price(&admin, &depin_id, &compute, &10);
+
+    let order_client = init_order_contract(&env, &admin);
+    order_client.set_depin_registry_contract(&admin, &registry.address);
+    registry.set_order_contract(&admin, &order_client.address);
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_verified_provider_threshold(&admin, &Some(200));
+    let create = |hours: u64| order_client.try_create_order(
+        &user,
+        &depin_id,
+        &compute,
+        &hours,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+
+    // Orders below the threshold may use any provider
+    assert!(create(10).is_ok());
+    assert_eq!(
+        create(24),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::UnverifiedProvider as u32)))
+    );
+
+    registry.set_trust_level(&admin, &depin_id, &depin_registry::TrustLevel::Verified);
+    assert!(create(24).is_ok());
+}
+
+#[test]
+fn test_order_metrics_follow_creation_and_transitions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let order_client = init_order_contract(&env, &admin);
+
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    order_client.set_depin_registry_contract(&admin, &env.register(MockRegistry, ()));
+    order_client.set_payment_token(&admin, &token);
+
+    let depin_id = BytesN::from_array(&env, &[1u8; 32]);
+    let create = |duration_hours: u64| order_client.create_order(
+        &user,
+        &depin_id,
+        &String::from_str(&env, "compute"),
+        &duration_hours,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    create(24);
+    let order_id = create(12);
+    order_client.cancel_order(&user, &order_id);
+
+    let metrics = order_client.get_order_metrics();
+    assert_eq!(metrics.total_orders, 2);
+    assert_eq!(metrics.status_counts.get(OrderStatus::Pending), Some(1));
+    assert_eq!(metrics.status_counts.get(OrderStatus::Cancelled), Some(1));
+    assert_eq!(metrics.volume_by_token.get(token), Some(360));
+    assert_eq!(metrics.depin_orders.get(depin_id), Some(2));
+    assert_eq!(metrics.average_duration_hours, 18);
+}
+
+#[test]
+fn test_create_funded_order_locks_source_funds_for_user() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let source = Address::generate(&env);
+    let order_client = init_order_contract(&env, &admin);
+
+    let registry_id = env.register(MockRegistry, ());
+    let escrow_id = env.register(escrow::EscrowContract, ());
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    order_client.set_depin_registry_contract(&admin, &registry_id);
+    order_client.set_escrow_contract(&admin, &escrow_id);
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_treasury_wallet(&admin, &Address::generate(&env));
+    order_client.set_order_source(&admin, &source, &true);
+
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&source, &300);
+    let order_id = order_client.create_funded_order(
+        &source,
+        &user,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &String::from_str(&env, "gpu"),
+        &12,
+        &300,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+    );
+
+    let order = order_client.get_order(&order_id);
+    assert_eq!(order.user, user);
+    assert_eq!(order.total_amount, 300);
+    assert_eq!(order.price_per_hour, 25);
+    assert_eq!(order_client.get_total_escrowed(), 300);
+    assert_eq!(soroban_sdk::token::TokenClient::new(&env, &token).balance(&escrow_id), 300);
+    assert_eq!(soroban_sdk::token::TokenClient::new(&env, &token).balance(&source), 0);
+
+    // Refunds go to the user, not the source
+    order_client.refund_order(&admin, &order_id);
+    assert_eq!(soroban_sdk::token::TokenClient::new(&env, &token).balance(&user), 300);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #8)")]
+fn test_create_funded_order_requires_approved_source() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let order_client = init_order_contract(&env, &admin);
+
+    order_client.create_funded_order(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &String::from_str(&env, "gpu"),
+        &12,
+        &300,
+        &Address::generate(&env),
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+    );
+}
+
+#[test]
+fn test_transfer_order_moves_ownership_and_refund_rights() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, _) = setup_escrowed_order(&env, &admin, OrderStatus::Active);
+    let agent = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    env.as_contract(&order_client.address, || {
+        env.storage().persistent().set(&DataKey::UserOrders(user.clone()), &soroban_sdk::vec![&env, order_id.clone()]);
+    });
+    order_client.set_transfer_agent(&admin, &agent, &true);
+
+    order_client.transfer_order(&agent, &order_id, &user, &buyer);
+
+    assert_eq!(order_client.get_order_user(&order_id), buyer);
+    assert_eq!(order_client.list_user_orders(&user).len(), 0);
+    assert_eq!(order_client.list_user_orders(&buyer), soroban_sdk::vec![&env, order_id.clone()]);
+
+    order_client.refund_order(&admin, &order_id);
+    assert_eq!(token.balance(&buyer), 240);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_transfer_completed_order_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, _, order_id, user, _) = setup_escrowed_order(&env, &admin, OrderStatus::Completed);
+    let agent = Address::generate(&env);
+    order_client.set_transfer_agent(&admin, &agent, &true);
+
+    order_client.transfer_order(&agent, &order_id, &user, &Address::generate(&env));
+}
+
+#[test]
+fn test_gift_order_to_another_user() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, _) = setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+    let friend = Address::generate(&env);
+    env.as_contract(&order_client.address, || {
+        env.storage().persistent().set(&DataKey::UserOrders(user.clone()), &soroban_sdk::vec![&env, order_id.clone()]);
+    });
+
+    assert_eq!(
+        order_client.try_gift_order(&friend, &order_id, &friend),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::Unauthorized as u32)))
+    );
+    order_client.gift_order(&user, &order_id, &friend);
+    assert_eq!(env.auths()[0].0, user);
+
+    assert_eq!(order_client.get_order_user(&order_id), friend);
+    assert_eq!(order_client.list_user_orders(&user).len(), 0);
+    assert_eq!(order_client.list_user_orders(&friend), soroban_sdk::vec![&env, order_id.clone()]);
+
+    // Deployed orders can no longer be gifted
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Deployed, &None);
+    assert_eq!(
+        order_client.try_gift_order(&friend, &order_id, &user),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidStatus as u32)))
+    );
+
+    order_client.refund_order(&admin, &order_id);
+    assert_eq!(token.balance(&friend), 240);
+}
+
+#[test]
+fn test_gift_order_respects_recipient_open_order_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, _, order_id, user, _) = setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+    let friend = Address::generate(&env);
+    order_client.set_order_limits(&admin, &1, &0);
+    env.as_contract(&order_client.address, || {
+        env.storage().persistent().set(&DataKey::OpenOrders(friend.clone()), &1u32);
+    });
+
+    assert_eq!(
+        order_client.try_gift_order(&user, &order_id, &friend),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::TooManyOpenOrders as u32)))
+    );
+    assert_eq!(order_client.get_order_user(&order_id), user);
+
+    env.as_contract(&order_client.address, || {
+        env.storage().persistent().set(&DataKey::OpenOrders(friend.clone()), &0u32);
+    });
+    order_client.gift_order(&user, &order_id, &friend);
+    assert_eq!(order_client.get_open_order_count(&friend), 1);
+}
+
+#[test]
+fn test_order_priority_charges_surge_and_lists_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, _) = setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+    order_client.set_priority_multiplier(&admin, &OrderPriority::Urgent, &15_000);
+
+    assert_eq!(order_client.set_order_priority(&user, &order_id, &OrderPriority::Urgent), 120);
+    let order = order_client.get_order(&order_id);
+    assert_eq!((order.priority, order.total_amount, order.escrowed_amount), (OrderPriority::Urgent, 360, 360));
+    assert_eq!(token.balance(&user), 1_000 - 360);
+    assert_eq!(order_client.list_orders_by_priority(&OrderPriority::Urgent), soroban_sdk::vec![&env, order_id.clone()]);
+    assert_eq!(
+        order_client.try_set_order_priority(&user, &order_id, &OrderPriority::High),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidStatus as u32)))
+    );
+
+    // Closed orders leave the priority list
+    order_client.cancel_order(&user, &order_id);
+    assert_eq!(order_client.list_orders_by_priority(&OrderPriority::Urgent).len(), 0);
+    assert_eq!(token.balance(&user), 1_000);
+}
+
+#[test]
+fn test_record_usage_streams_pro_rata_payment() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, _, treasury) = setup_escrowed_order(&env, &admin, OrderStatus::Active);
+    let meter = Address::generate(&env);
+    order_client.set_usage_meter(&admin, &meter, &true);
+
+    // 6 of 24 hours served
+    assert_eq!(order_client.record_usage(&meter, &order_id, &6), 60);
+    assert_eq!(token.balance(&treasury), 60);
+    assert_eq!(order_client.get_order(&order_id).escrowed_amount, 180);
+    assert_eq!(order_client.get_total_escrowed(), 180);
+
+    // Over-reported usage is capped at what is left
+    assert_eq!(order_client.record_usage(&meter, &order_id, &100), 180);
+    assert_eq!(order_client.get_order(&order_id).escrowed_amount, 0);
+}
+
+#[test]
+fn test_extend_running_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, _) = setup_escrowed_order(&env, &admin, OrderStatus::Pending);
+    assert_eq!(
+        order_client.try_extend_order(&user, &order_id, &12),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidStatus as u32)))
+    );
+
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    assert_eq!(order_client.extend_order(&user, &order_id, &12), 120);
+
+    let order = order_client.get_order(&order_id);
+    assert_eq!(order.duration_hours, 36);
+    assert_eq!(order.total_amount, 360);
+    assert_eq!(order.escrowed_amount, 360);
+    assert_eq!(order_client.get_total_escrowed(), 360);
+    assert_eq!(token.balance(&user), 640);
+
+    // Refunds cover the extension too
+    order_client.refund_order(&admin, &order_id);
+    assert_eq!(token.balance(&user), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #8)")]
+fn test_record_usage_requires_approved_meter() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, _, order_id, _, _) = setup_escrowed_order(&env, &admin, OrderStatus::Active);
+
+    order_client.record_usage(&Address::generate(&env), &order_id, &6);
+}
+
+#[test]
+fn test_get_order_terms() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, _, order_id, user, _) = setup_escrowed_order(&env, &admin, OrderStatus::Active);
+
+    let terms = order_client.get_order_terms(&order_id);
+    assert_eq!(terms.user, user);
+    assert_eq!(terms.total_amount, 240);
+    assert_eq!(terms.end_time - terms.start_time, 24 * 3600);
+}
+
+#[test]
+fn test_high_value_orders_require_compliance_attestation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let attestor = Address::generate(&env);
+    let order_client = init_order_contract(&env, &admin);
+
+    let registry_id = env.register(MockRegistry, ());
+    let escrow_id = env.register(escrow::EscrowContract, ());
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    order_client.set_depin_registry_contract(&admin, &registry_id);
+    order_client.set_escrow_contract(&admin, &escrow_id);
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_treasury_wallet(&admin, &Address::generate(&env));
+
+    let compliance_id = env.register(compliance::ComplianceContract, ());
+    let compliance_client = compliance::ComplianceContractClient::new(&env, &compliance_id);
+    compliance_client.initialize(&admin);
+    compliance_client.set_attestor(&admin, &attestor, &true);
+    order_client.set_compliance_contract(&admin, &compliance_id, &1, &500);
+
+    let create = |duration_hours: u64| order_client.try_create_order(
+        &user,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &String::from_str(&env, "compute"),
+        &duration_hours,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal
+    );
+
+    // Below the threshold no attestation is needed
+    assert!(create(24).is_ok());
+    assert_eq!(create(50), Err(Ok(soroban_sdk::Error::from_contract_error(Error::NotCompliant as u32))));
+
+    compliance_client.attest(&attestor, &user, &String::from_str(&env, "DE"), &1, &(env.ledger().timestamp() + 3600));
+    assert!(create(50).is_ok());
+}
+
+#[test]
+fn test_contracts_resolved_through_directory() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let order_client = init_order_contract(&env, &admin);
+
+    let escrow_id = env.register(escrow::EscrowContract, ());
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    order_client.set_escrow_contract(&admin, &escrow_id);
+    order_client.set_payment_token(&admin, &token);
+    order_client.set_treasury_wallet(&admin, &Address::generate(&env));
+
+    // Only the directory knows the registry; its treasury overrides the local one
+    let treasury = Address::generate(&env);
+    let directory_id = env.register(directory::DirectoryContract, ());
+    let directory_client = directory::DirectoryContractClient::new(&env, &directory_id);
+    directory_client.initialize(&admin);
+    directory_client.set_contract(&symbol_short!("registry"), &env.register(MockRegistry, ()), &1);
+    directory_client.set_contract(&symbol_short!("treasury"), &treasury, &1);
+    order_client.set_directory_contract(&admin, &directory_id);
+
+    let order_id = order_client.create_order(
+        &user,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &String::from_str(&env, "compute"),
+        &24,
+        &10,
+        &token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal
+    );
+    assert_eq!(order_client.get_treasury_wallet(), Some(treasury.clone()));
+
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Deployed, &None);
+    order_client.complete_order(&admin, &order_id);
+    assert_eq!(soroban_sdk::token::TokenClient::new(&env, &token).balance(&treasury), 240);
+}
+
+#[test]
+fn test_paused_order_still_refunds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let watcher = Address::generate(&env);
+    let (order_client, token, order_id, user, _) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Deployed);
+
+    let guardian_id = env.register(guardian::GuardianContract, ());
+    let guardian_client = guardian::GuardianContractClient::new(&env, &guardian_id);
+    guardian_client.initialize(&admin, &Address::generate(&env));
+    guardian_client.set_guardian(&admin, &watcher, &true);
+    order_client.set_pause_guardian(&admin, &guardian_id);
+    guardian_client.pause_contract(&watcher, &order_client.address);
+
+    assert_eq!(
+        order_client.try_complete_order(&admin, &order_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::Paused as u32)))
+    );
+
+    order_client.refund_order(&admin, &order_id);
+    assert_eq!(token.balance(&user), 1_000);
+}
+
+#[test]
+fn test_admin_pause_blocks_orders_but_not_refunds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, token, order_id, user, _) =
+        setup_escrowed_order(&env, &admin, OrderStatus::Active);
+
+    order_client.pause(&admin);
+    assert!(order_client.is_paused());
+    assert_eq!(
+        order_client.try_extend_order(&user, &order_id, &1),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::Paused as u32)))
+    );
+    assert_eq!(
+        order_client.try_update_order_status(&admin, &order_id, &OrderStatus::Deployed, &None),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::Paused as u32)))
+    );
+
+    order_client.unpause(&admin);
+    order_client.extend_order(&user, &order_id, &1);
+
+    order_client.pause(&admin);
+    order_client.refund_order(&admin, &order_id);
+    assert_eq!(token.balance(&user), 1_000);
+}
+
+/// SEP-40 style oracle quoting every asset at a settable USD price (14 decimals)
+#[contract]
+struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, price: i128) {
+        env.storage().instance().set(&symbol_short!("price"), &price);
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        14
+    }
+
+    pub fn lastprice(env: Env, _asset: interfaces::Asset) -> Option<interfaces::PriceData> {
+        Some(interfaces::PriceData {
+            price: env.storage().instance().get(&symbol_short!("price")).unwrap(),
+            timestamp: env.ledger().timestamp(),
+        })
+    }
+}
+
+fn setup_quoted_order<'a>(
+    env: &'a Env,
+    admin: &Address,
+) -> (OrderContractClient<'a>, MockOracleClient<'a>, Address, Address, Address) {
+    let order_client = init_order_contract(env, admin);
+    let user = Address::generate(env);
+    let treasury = Address::generate(env);
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    let xlm = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    soroban_sdk::token::StellarAssetClient::new(env, &xlm).mint(&user, &10_000);
+
+    order_client.set_depin_registry_contract(admin, &env.register(MockRegistry, ()));
+    order_client.set_escrow_contract(admin, &env.register(escrow::EscrowContract, ()));
+    order_client.set_payment_token(admin, &usdc);
+    order_client.set_treasury_wallet(admin, &treasury);
+
+    // XLM at $0.10
+    let oracle = MockOracleClient::new(env, &env.register(MockOracle, ()));
+    oracle.set_price(&10_000_000_000_000);
+    order_client.set_price_oracle(admin, &oracle.address, &300, &60);
+    order_client.set_accepted_token(admin, &xlm, &true);
+
+    (order_client, oracle, user, treasury, xlm)
+}
+
+fn create_quoted_order(env: &Env, order_client: &OrderContractClient, user: &Address, quote_id: u64) -> BytesN<32> {
+    order_client.create_order_with_quote(
+        user,
+        &quote_id,
+        &BytesN::from_array(env, &[1u8; 32]),
+        &String::from_str(env, "compute"),
+        &24,
+        &10,
+        &String::from_str(env, "ethereum"),
+        &String::from_str(env, "{}"),
+    )
+}
+
+#[test]
+fn test_order_paid_in_xlm_at_oracle_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, _, user, treasury, xlm) = setup_quoted_order(&env, &admin);
+
+    let quote = order_client.quote_payment(&user, &xlm, &240, &500);
+    assert_eq!(quote.token_amount, 2_400);
+
+    let order_id = create_quoted_order(&env, &order_client, &user, quote.quote_id);
+    assert_eq!(order_client.get_order(&order_id).total_amount, 2_400);
+    assert_eq!(order_client.get_order_token(&order_id), xlm.clone());
+    assert!(order_client.get_quote(&quote.quote_id).unwrap().used);
+
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Active, &None);
+    order_client.update_order_status(&admin, &order_id, &OrderStatus::Deployed, &None);
+    order_client.complete_order(&admin, &order_id);
+    let xlm_client = soroban_sdk::token::TokenClient::new(&env, &xlm);
+    assert_eq!(xlm_client.balance(&treasury), 2_400);
+    assert_eq!(xlm_client.balance(&user), 7_600);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #16)")]
+fn test_quoted_order_rejects_price_drop_beyond_slippage() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, oracle, user, _, xlm) = setup_quoted_order(&env, &admin);
+
+    let quote = order_client.quote_payment(&user, &xlm, &240, &500);
+    // XLM falls to $0.08: 3_000 needed, more than 5% above the quoted 2_400
+    oracle.set_price(&8_000_000_000_000);
+    create_quoted_order(&env, &order_client, &user, quote.quote_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #15)")]
+fn test_expired_quote_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (order_client, _, user, _, xlm) = setup_quoted_order(&env, &admin);
+
+    let quote = order_client.quote_payment(&user, &xlm, &240, &500);
+    env.ledger().with_mut(|li| li.timestamp += 301);
+    create_quoted_order(&env, &order_client, &user, quote.quote_id);
+}
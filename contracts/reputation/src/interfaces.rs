@@ -0,0 +1,10 @@
+//! Typed interfaces of the contracts the reputation system queries.
+
+use soroban_sdk::{contractclient, Address, Env, Symbol};
+
+/// Subset of the pause guardian consulted before new reviews.
+#[allow(dead_code)]
+#[contractclient(name = "GuardianClient")]
+pub trait GuardianInterface {
+    fn is_paused(env: Env, target: Address, function: Symbol) -> bool;
+}
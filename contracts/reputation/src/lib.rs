@@ -1,11 +1,16 @@
 #![no_std]
-use soroban_sdk::{contracttype, contract, contractimpl, Env, String, Vec, Address, Map};
+use soroban_sdk::{contracttype, contract, contractimpl, Env, String, Symbol, Vec, Address, Map};
+
+mod interfaces;
+use interfaces::GuardianClient;
 
 #[contracttype]
 pub enum DataKey {
     Admin,
     Ratings,
     DepinRegistry, // Store the address of the DePIN registry contract
+    Timelock, // Timelock that authorizes admin rotations
+    PauseGuardian, // Guardian that can pause reviews during incidents
 }
 
 #[contract]
@@ -19,6 +24,19 @@ impl ReputationContract {
         }
     }
 
+    fn assert_timelock(env: &Env) {
+        let timelock: Address = env.storage().persistent().get(&DataKey::Timelock).expect("Timelock not set");
+        timelock.require_auth();
+    }
+
+    fn assert_not_paused(env: &Env, function: &str) {
+        if let Some(guardian) = env.storage().persistent().get::<_, Address>(&DataKey::PauseGuardian) {
+            let paused = GuardianClient::new(env, &guardian)
+                .is_paused(&env.current_contract_address(), &Symbol::new(env, function));
+            assert!(!paused, "Contract is paused");
+        }
+    }
+
     fn assert_depin_exists(_env: &Env, _depin_id: soroban_sdk::BytesN<32>) {
         // In a real implementation, you would call the DePIN registry contract
         // to verify the DePIN exists. For now, we'll assume it's validated externally.
@@ -41,10 +59,38 @@ impl ReputationContract {
         env.storage().persistent().set(&DataKey::DepinRegistry, &depin_registry_address);
     }
 
+    // Set the timelock that authorizes admin rotations (admin once, then timelock only)
+    pub fn set_timelock(env: Env, invoker: Address, timelock: Address) {
+        if env.storage().persistent().has(&DataKey::Timelock) {
+            Self::assert_timelock(&env);
+        } else {
+            Self::assert_admin(&env, &invoker);
+        }
+        env.storage().persistent().set(&DataKey::Timelock, &timelock);
+    }
+
+    // Rotate the admin key (timelock only)
+    pub fn set_admin(env: Env, new_admin: Address) {
+        Self::assert_timelock(&env);
+        env.storage().persistent().set(&DataKey::Admin, &new_admin);
+    }
+
+    // Get the admin address
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().persistent().get(&DataKey::Admin).unwrap()
+    }
+
+    // Set the pause guardian; review moderation stays available while paused (admin only)
+    pub fn set_pause_guardian(env: Env, invoker: Address, guardian: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::PauseGuardian, &guardian);
+    }
+
     // User: Rate and review a DePIN
     pub fn rate_and_review_depin(env: Env, invoker: Address, depin_id: soroban_sdk::BytesN<32>, rating: i32, review: String) {
+        Self::assert_not_paused(&env, "rate_and_review_depin");
         // Validate inputs
-        assert!(rating >= 1 && rating <= 5, "Rating must be 1-5");
+        assert!((1..=5).contains(&rating), "Rating must be 1-5");
         assert!(!review.is_empty(), "Review cannot be empty");
         
         // Verify that the DePIN exists (placeholder for cross-contract call)
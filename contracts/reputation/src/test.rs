@@ -244,3 +244,24 @@ fn test_empty_review_text() {
     // Try to add review with empty text
     client.rate_and_review_depin(&user, &depin_id, &4, &String::from_str(&env, ""));
 }
+
+#[test]
+#[should_panic(expected = "Contract is paused")]
+fn test_paused_reviews_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client = ReputationContractClient::new(&env, &env.register(ReputationContract, ()));
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &Address::generate(&env));
+
+    let guardian_id = env.register(guardian::GuardianContract, ());
+    let guardian = guardian::GuardianContractClient::new(&env, &guardian_id);
+    guardian.initialize(&admin, &Address::generate(&env));
+    guardian.set_guardian(&admin, &admin, &true);
+    client.set_pause_guardian(&admin, &guardian_id);
+    guardian.pause_all(&admin);
+
+    let depin_id = soroban_sdk::BytesN::from_array(&env, &[1u8; 32]);
+    client.rate_and_review_depin(&Address::generate(&env), &depin_id, &4, &String::from_str(&env, "Great service!"));
+}
@@ -0,0 +1,292 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, Env,
+    panic_with_error, symbol_short, token
+};
+
+/// Basis-point denominator for category weights (100% = 10_000 bps)
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+#[contract]
+pub struct RewardsContract;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Category {
+    ProviderUptime,     // Fed by uptime oracles / SLA
+    ProviderRevenue,    // Fed by settlements
+    UserSpend,          // Fed by the order contract on completion
+    UserReviews,        // Fed by the reputation system
+}
+
+const CATEGORIES: [Category; 4] = [
+    Category::ProviderUptime,
+    Category::ProviderRevenue,
+    Category::UserSpend,
+    Category::UserReviews,
+];
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmissionWeights {
+    pub provider_uptime: u32,
+    pub provider_revenue: u32,
+    pub user_spend: u32,
+    pub user_reviews: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EpochSnapshot {
+    pub emission: i128,
+    pub weights: EmissionWeights,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    RewardToken,
+    GenesisTime,                        // u64 start of epoch 0
+    EpochLength,                        // u64 seconds
+    EmissionPerEpoch,                   // i128 reward tokens per epoch
+    Weights,                            // EmissionWeights applied to new epochs
+    Feeder(Address),                    // address -> bool, contracts allowed to record activity
+    Snapshot(u64),                      // epoch -> EpochSnapshot, frozen on first activity
+    Score(u64, Category, Address),      // (epoch, category, account) -> i128 points
+    CategoryTotal(u64, Category),       // (epoch, category) -> i128 points
+    Claimed(u64, Address),              // (epoch, account) -> bool
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    NotFeeder = 4,
+    InvalidWeights = 5,
+    InvalidAmount = 6,
+    EpochNotEnded = 7,
+    AlreadyClaimed = 8,
+    NothingToClaim = 9,
+}
+
+#[contractimpl]
+impl RewardsContract {
+    /// Initialize with the reward token, epoch length and per-epoch emission
+    ///
+    /// Epoch 0 starts at initialization; weights default to an even split.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        reward_token: Address,
+        epoch_length: u64,
+        emission_per_epoch: i128,
+    ) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+        if epoch_length == 0 || emission_per_epoch < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::RewardToken, &reward_token);
+        env.storage().persistent().set(&DataKey::GenesisTime, &env.ledger().timestamp());
+        env.storage().persistent().set(&DataKey::EpochLength, &epoch_length);
+        env.storage().persistent().set(&DataKey::EmissionPerEpoch, &emission_per_epoch);
+        env.storage().persistent().set(&DataKey::Weights, &EmissionWeights {
+            provider_uptime: 2_500,
+            provider_revenue: 2_500,
+            user_spend: 2_500,
+            user_reviews: 2_500,
+        });
+        true
+    }
+
+    /// Set how an epoch's emission is split across categories (admin only)
+    ///
+    /// Applies to epochs without activity yet; earlier epochs keep their snapshot.
+    pub fn set_weights(env: Env, admin: Address, weights: EmissionWeights) -> bool {
+        Self::assert_admin(&env, &admin);
+
+        let total = weights.provider_uptime + weights.provider_revenue
+            + weights.user_spend + weights.user_reviews;
+        if total != BPS_DENOMINATOR {
+            panic_with_error!(&env, Error::InvalidWeights);
+        }
+        env.storage().persistent().set(&DataKey::Weights, &weights);
+        true
+    }
+
+    /// Set the reward tokens emitted per epoch (admin only)
+    pub fn set_emission(env: Env, admin: Address, emission_per_epoch: i128) -> bool {
+        Self::assert_admin(&env, &admin);
+        if emission_per_epoch < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        env.storage().persistent().set(&DataKey::EmissionPerEpoch, &emission_per_epoch);
+        true
+    }
+
+    /// Allow or disallow a contract to record activity (admin only)
+    pub fn set_feeder(env: Env, admin: Address, feeder: Address, allowed: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::Feeder(feeder), &allowed);
+        true
+    }
+
+    /// Add activity points for an account in the current epoch (approved feeders only)
+    pub fn record(env: Env, feeder: Address, category: Category, account: Address, points: i128) -> bool {
+        feeder.require_auth();
+        let allowed: bool = env.storage().persistent()
+            .get(&DataKey::Feeder(feeder))
+            .unwrap_or(false);
+        if !allowed {
+            panic_with_error!(&env, Error::NotFeeder);
+        }
+        if points <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let epoch = Self::current_epoch(env.clone());
+        if !env.storage().persistent().has(&DataKey::Snapshot(epoch)) {
+            let snapshot = EpochSnapshot {
+                emission: env.storage().persistent().get(&DataKey::EmissionPerEpoch).unwrap_or(0),
+                weights: Self::get_weights(env.clone()),
+            };
+            env.storage().persistent().set(&DataKey::Snapshot(epoch), &snapshot);
+        }
+
+        let score = Self::get_score(env.clone(), epoch, category, account.clone()) + points;
+        env.storage().persistent().set(&DataKey::Score(epoch, category, account.clone()), &score);
+        let total = Self::get_category_total(env.clone(), epoch, category) + points;
+        env.storage().persistent().set(&DataKey::CategoryTotal(epoch, category), &total);
+
+        env.events().publish((symbol_short!("record"), account), (epoch, category, points));
+        true
+    }
+
+    /// Claim an account's rewards for a finished epoch
+    pub fn claim(env: Env, account: Address, epoch: u64) -> i128 {
+        account.require_auth();
+
+        if epoch >= Self::current_epoch(env.clone()) {
+            panic_with_error!(&env, Error::EpochNotEnded);
+        }
+        if Self::is_claimed(env.clone(), epoch, account.clone()) {
+            panic_with_error!(&env, Error::AlreadyClaimed);
+        }
+
+        let amount = Self::pending_reward(env.clone(), account.clone(), epoch);
+        if amount <= 0 {
+            panic_with_error!(&env, Error::NothingToClaim);
+        }
+
+        env.storage().persistent().set(&DataKey::Claimed(epoch, account.clone()), &true);
+        token::Client::new(&env, &Self::reward_token(&env))
+            .transfer(&env.current_contract_address(), &account, &amount);
+
+        env.events().publish((symbol_short!("claim"), account), (epoch, amount));
+        amount
+    }
+
+    /// Reward an account earned in an epoch (0 once claimed)
+    pub fn pending_reward(env: Env, account: Address, epoch: u64) -> i128 {
+        if Self::is_claimed(env.clone(), epoch, account.clone()) {
+            return 0;
+        }
+        let snapshot: EpochSnapshot = match env.storage().persistent().get(&DataKey::Snapshot(epoch)) {
+            Some(snapshot) => snapshot,
+            None => return 0,
+        };
+
+        let mut reward: i128 = 0;
+        for category in CATEGORIES {
+            let score = Self::get_score(env.clone(), epoch, category, account.clone());
+            if score == 0 {
+                continue;
+            }
+            let total = Self::get_category_total(env.clone(), epoch, category);
+            let pool = snapshot.emission * Self::weight_of(&snapshot.weights, category) as i128
+                / BPS_DENOMINATOR as i128;
+            reward += pool * score / total;
+        }
+        reward
+    }
+
+    /// Get the epoch the ledger is currently in
+    pub fn current_epoch(env: Env) -> u64 {
+        let genesis: u64 = env.storage().persistent()
+            .get(&DataKey::GenesisTime)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let length: u64 = env.storage().persistent()
+            .get(&DataKey::EpochLength)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        (env.ledger().timestamp() - genesis) / length
+    }
+
+    /// Get the weights applied to new epochs
+    pub fn get_weights(env: Env) -> EmissionWeights {
+        env.storage().persistent()
+            .get(&DataKey::Weights)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+    }
+
+    /// Get the emission and weights frozen for an epoch
+    pub fn get_snapshot(env: Env, epoch: u64) -> Option<EpochSnapshot> {
+        env.storage().persistent().get(&DataKey::Snapshot(epoch))
+    }
+
+    /// Get an account's points in a category for an epoch
+    pub fn get_score(env: Env, epoch: u64, category: Category, account: Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::Score(epoch, category, account))
+            .unwrap_or(0)
+    }
+
+    /// Get all points recorded in a category for an epoch
+    pub fn get_category_total(env: Env, epoch: u64, category: Category) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::CategoryTotal(epoch, category))
+            .unwrap_or(0)
+    }
+
+    /// Check whether an account has claimed an epoch
+    pub fn is_claimed(env: Env, epoch: u64, account: Address) -> bool {
+        env.storage().persistent()
+            .get(&DataKey::Claimed(epoch, account))
+            .unwrap_or(false)
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn reward_token(env: &Env) -> Address {
+        env.storage().persistent()
+            .get(&DataKey::RewardToken)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized))
+    }
+
+    fn weight_of(weights: &EmissionWeights, category: Category) -> u32 {
+        match category {
+            Category::ProviderUptime => weights.provider_uptime,
+            Category::ProviderRevenue => weights.provider_revenue,
+            Category::UserSpend => weights.user_spend,
+            Category::UserReviews => weights.user_reviews,
+        }
+    }
+}
+
+mod test;
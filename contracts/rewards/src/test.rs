@@ -0,0 +1,195 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as AddressTestUtils, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, BytesN, Env, String
+};
+use order::{OrderContract, OrderContractClient, OrderPriority, OrderStatus};
+
+/// Stand-in for the DePIN registry that accepts every DePIN ID, priced at 10 per hour
+#[contract]
+struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn depin_exists(_env: Env, _depin_id: BytesN<32>) -> bool {
+        true
+    }
+
+    pub fn supports_chain(_env: Env, _depin_id: BytesN<32>, _chain: String) -> bool {
+        true
+    }
+
+    pub fn get_payout_address(_env: Env, _depin_id: BytesN<32>) -> Option<Address> {
+        None
+    }
+
+    pub fn get_service_price(_env: Env, _depin_id: BytesN<32>, _service_type: String) -> Option<i128> {
+        Some(10)
+    }
+
+    pub fn reserve_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+
+    pub fn release_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+}
+
+const EPOCH: u64 = 7 * 24 * 3600;
+
+struct Setup<'a> {
+    rewards: RewardsContractClient<'a>,
+    token: TokenClient<'a>,
+    admin: Address,
+    feeder: Address,
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let feeder = Address::generate(env);
+    let token_id = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+
+    let rewards = RewardsContractClient::new(env, &env.register(RewardsContract, ()));
+    rewards.initialize(&admin, &token_id, &EPOCH, &10_000);
+    rewards.set_feeder(&admin, &feeder, &true);
+    StellarAssetClient::new(env, &token_id).mint(&rewards.address, &1_000_000);
+
+    Setup {
+        rewards,
+        token: TokenClient::new(env, &token_id),
+        admin,
+        feeder,
+    }
+}
+
+#[test]
+fn test_rewards_split_by_weight_and_share() {
+    let env = Env::default();
+    let s = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let provider = Address::generate(&env);
+
+    s.rewards.record(&s.feeder, &Category::UserSpend, &alice, &300);
+    s.rewards.record(&s.feeder, &Category::UserSpend, &bob, &100);
+    s.rewards.record(&s.feeder, &Category::UserReviews, &bob, &1);
+    s.rewards.record(&s.feeder, &Category::ProviderUptime, &provider, &9_900);
+
+    env.ledger().with_mut(|li| li.timestamp += EPOCH);
+
+    // 2_500 per category: alice gets 3/4 of spend, bob 1/4 plus all reviews
+    assert_eq!(s.rewards.pending_reward(&alice, &0), 1_875);
+    assert_eq!(s.rewards.pending_reward(&bob, &0), 625 + 2_500);
+    assert_eq!(s.rewards.claim(&provider, &0), 2_500);
+    assert_eq!(s.token.balance(&provider), 2_500);
+    assert_eq!(s.rewards.pending_reward(&provider, &0), 0);
+}
+
+#[test]
+fn test_weights_snapshotted_per_epoch() {
+    let env = Env::default();
+    let s = setup(&env);
+    let alice = Address::generate(&env);
+
+    s.rewards.record(&s.feeder, &Category::UserSpend, &alice, &100);
+    s.rewards.set_weights(&s.admin, &EmissionWeights {
+        provider_uptime: 0,
+        provider_revenue: 0,
+        user_spend: 10_000,
+        user_reviews: 0,
+    });
+
+    env.ledger().with_mut(|li| li.timestamp += EPOCH);
+    s.rewards.record(&s.feeder, &Category::UserSpend, &alice, &100);
+    env.ledger().with_mut(|li| li.timestamp += EPOCH);
+
+    assert_eq!(s.rewards.pending_reward(&alice, &0), 2_500);
+    assert_eq!(s.rewards.pending_reward(&alice, &1), 10_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_claim_current_epoch_fails() {
+    let env = Env::default();
+    let s = setup(&env);
+    let alice = Address::generate(&env);
+
+    s.rewards.record(&s.feeder, &Category::UserSpend, &alice, &100);
+    s.rewards.claim(&alice, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_claim_twice_fails() {
+    let env = Env::default();
+    let s = setup(&env);
+    let alice = Address::generate(&env);
+
+    s.rewards.record(&s.feeder, &Category::UserSpend, &alice, &100);
+    env.ledger().with_mut(|li| li.timestamp += EPOCH);
+    s.rewards.claim(&alice, &0);
+    s.rewards.claim(&alice, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_only_feeders_record() {
+    let env = Env::default();
+    let s = setup(&env);
+    let alice = Address::generate(&env);
+
+    s.rewards.record(&alice, &Category::UserSpend, &alice, &100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_weights_must_sum_to_total() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.rewards.set_weights(&s.admin, &EmissionWeights {
+        provider_uptime: 5_000,
+        provider_revenue: 0,
+        user_spend: 0,
+        user_reviews: 0,
+    });
+}
+
+#[test]
+fn test_completed_orders_feed_user_spend() {
+    let env = Env::default();
+    let s = setup(&env);
+    let user = Address::generate(&env);
+    let payment_token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    StellarAssetClient::new(&env, &payment_token).mint(&user, &1_000);
+
+    let orders = OrderContractClient::new(&env, &env.register(OrderContract, ()));
+    orders.initialize(&s.admin);
+    orders.set_depin_registry_contract(&s.admin, &env.register(MockRegistry, ()));
+    orders.set_escrow_contract(&s.admin, &env.register(escrow::EscrowContract, ()));
+    orders.set_payment_token(&s.admin, &payment_token);
+    orders.set_treasury_wallet(&s.admin, &Address::generate(&env));
+    orders.set_rewards_contract(&s.admin, &s.rewards.address);
+    s.rewards.set_feeder(&s.admin, &orders.address, &true);
+
+    let order_id = orders.create_order(
+        &user,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &String::from_str(&env, "gpu"),
+        &24,
+        &10,
+        &payment_token,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+    orders.update_order_status(&s.admin, &order_id, &OrderStatus::Active, &None);
+    orders.update_order_status(&s.admin, &order_id, &OrderStatus::Deployed, &None);
+    orders.complete_order(&s.admin, &order_id);
+
+    assert_eq!(s.rewards.get_score(&0, &Category::UserSpend, &user), 240);
+}
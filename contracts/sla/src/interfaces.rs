@@ -0,0 +1,69 @@
+//! Typed interfaces of the contracts SLA penalties are computed from.
+
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env, String, Vec};
+
+/// Mirror of the DePIN registry's `DePIN`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DePIN {
+    pub id: BytesN<32>,
+    pub name: String,
+    pub description: String,
+    pub active: bool,
+    pub uptime: i32,
+    pub reliability: i32,
+    pub cost: i32,
+    pub owner: Option<Address>,
+    pub approved: bool,
+    pub trust_level: TrustLevel,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub region: Option<String>,
+    pub country: Option<String>,
+    pub lifecycle: DepinLifecycle,
+    pub metadata_uri: Option<String>,
+    pub metadata_hash: Option<BytesN<32>>,
+}
+
+/// Mirror of the DePIN registry's `TrustLevel`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrustLevel {
+    Unverified,
+    Verified,
+    Audited,
+}
+
+/// Mirror of the DePIN registry's `DepinLifecycle`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DepinLifecycle {
+    Active,
+    Deprecated,
+    Retired,
+}
+
+/// Mirror of the order contract's `OrderTerms`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrderTerms {
+    pub user: Address,
+    pub depin_id: BytesN<32>,
+    pub total_amount: i128,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+/// Subset of the DePIN registry holding each DePIN's committed uptime.
+#[allow(dead_code)]
+#[contractclient(name = "RegistryClient")]
+pub trait RegistryInterface {
+    fn get_depin(env: Env, depin_id: BytesN<32>) -> Option<DePIN>;
+}
+
+/// Subset of the order contract used to look up the service window.
+#[allow(dead_code)]
+#[contractclient(name = "OrderClient")]
+pub trait OrderInterface {
+    fn get_order_terms(env: Env, order_id: BytesN<32>) -> OrderTerms;
+}
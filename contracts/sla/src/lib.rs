@@ -0,0 +1,364 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, BytesN, Env, Vec,
+    panic_with_error, symbol_short, token
+};
+
+mod interfaces;
+use interfaces::{OrderClient, RegistryClient};
+
+/// Basis-point denominator for uptime and penalty rates (100% = 10_000 bps)
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Time a provider must wait between requesting and withdrawing stake (7 days)
+pub const UNBONDING_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+#[contract]
+pub struct SlaContract;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UptimeReport {
+    pub period_start: u64,
+    pub period_end: u64,
+    pub uptime_bps: u32,
+    pub oracle: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProviderStake {
+    pub provider: Address,
+    pub amount: i128,
+    pub unbonding: i128,            // Requested for withdrawal, still slashable
+    pub unbonding_since: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PenaltyClaim {
+    pub order_id: BytesN<32>,
+    pub user: Address,
+    pub sla_bps: u32,
+    pub measured_bps: u32,
+    pub refund: i128,               // Slashed from the provider's stake and paid to the user
+    pub claimed_at: u64,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    OrderContract,
+    RegistryContract,
+    StakeToken,                     // Token providers stake and penalties are paid in
+    PenaltyMultiplier,              // u32 bps of the uptime shortfall refunded (10_000 = 1x)
+    Oracle(Address),                // address -> bool, allowed to report uptime
+    UptimeReports(BytesN<32>),      // depin_id -> Vec<UptimeReport>, non-overlapping
+    Stake(BytesN<32>),              // depin_id -> ProviderStake
+    Claim(BytesN<32>),              // order_id -> PenaltyClaim
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    NotOracle = 4,
+    InvalidReport = 5,
+    DepinNotFound = 6,
+    NotProvider = 7,
+    InvalidAmount = 8,
+    InsufficientStake = 9,
+    StillUnbonding = 10,
+    OrderNotEnded = 11,
+    AlreadyClaimed = 12,
+    NoUptimeData = 13,
+}
+
+#[contractimpl]
+impl SlaContract {
+    /// Initialize with admin, the order and registry contracts and the stake token
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        order_contract: Address,
+        registry_contract: Address,
+        stake_token: Address,
+    ) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::OrderContract, &order_contract);
+        env.storage().persistent().set(&DataKey::RegistryContract, &registry_contract);
+        env.storage().persistent().set(&DataKey::StakeToken, &stake_token);
+        env.storage().persistent().set(&DataKey::PenaltyMultiplier, &(BPS_DENOMINATOR as u32));
+        true
+    }
+
+    /// Allow or disallow an oracle to report uptime (admin only)
+    pub fn set_oracle(env: Env, admin: Address, oracle: Address, allowed: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::Oracle(oracle), &allowed);
+        true
+    }
+
+    /// Set how much of the uptime shortfall is refunded, in bps (admin only)
+    pub fn set_penalty_multiplier(env: Env, admin: Address, multiplier_bps: u32) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::PenaltyMultiplier, &multiplier_bps);
+        true
+    }
+
+    /// Record measured uptime of a DePIN over a period (approved oracles only)
+    pub fn report_uptime(
+        env: Env,
+        oracle: Address,
+        depin_id: BytesN<32>,
+        period_start: u64,
+        period_end: u64,
+        uptime_bps: u32,
+    ) -> bool {
+        oracle.require_auth();
+        let allowed: bool = env.storage().persistent()
+            .get(&DataKey::Oracle(oracle.clone()))
+            .unwrap_or(false);
+        if !allowed {
+            panic_with_error!(&env, Error::NotOracle);
+        }
+
+        let mut reports = Self::get_uptime_reports(env.clone(), depin_id.clone());
+        let last_end = reports.last().map(|r| r.period_end).unwrap_or(0);
+        if period_end <= period_start || period_start < last_end
+            || period_end > env.ledger().timestamp()
+            || uptime_bps as i128 > BPS_DENOMINATOR
+        {
+            panic_with_error!(&env, Error::InvalidReport);
+        }
+
+        reports.push_back(UptimeReport { period_start, period_end, uptime_bps, oracle });
+        env.storage().persistent().set(&DataKey::UptimeReports(depin_id.clone()), &reports);
+
+        env.events().publish((symbol_short!("uptime"), depin_id), (period_start, period_end, uptime_bps));
+        true
+    }
+
+    /// Put up stake backing a DePIN's SLA (first staker becomes its provider)
+    pub fn stake(env: Env, provider: Address, depin_id: BytesN<32>, amount: i128) -> bool {
+        provider.require_auth();
+        if amount <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let mut stake = Self::get_stake(env.clone(), depin_id.clone()).unwrap_or(ProviderStake {
+            provider: provider.clone(),
+            amount: 0,
+            unbonding: 0,
+            unbonding_since: 0,
+        });
+        if stake.provider != provider {
+            panic_with_error!(&env, Error::NotProvider);
+        }
+
+        token::Client::new(&env, &Self::stake_token(&env))
+            .transfer(&provider, &env.current_contract_address(), &amount);
+        stake.amount += amount;
+        env.storage().persistent().set(&DataKey::Stake(depin_id.clone()), &stake);
+
+        env.events().publish((symbol_short!("staked"), depin_id), (provider, amount));
+        true
+    }
+
+    /// Start the unbonding period for part of the stake (provider only)
+    pub fn request_unstake(env: Env, provider: Address, depin_id: BytesN<32>, amount: i128) -> bool {
+        provider.require_auth();
+
+        let mut stake = Self::load_stake(&env, &depin_id, &provider);
+        if amount <= 0 || amount > stake.amount {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        stake.unbonding = amount;
+        stake.unbonding_since = env.ledger().timestamp();
+        env.storage().persistent().set(&DataKey::Stake(depin_id), &stake);
+        true
+    }
+
+    /// Withdraw unbonded stake once the unbonding period has passed (provider only)
+    pub fn withdraw_stake(env: Env, provider: Address, depin_id: BytesN<32>) -> i128 {
+        provider.require_auth();
+
+        let mut stake = Self::load_stake(&env, &depin_id, &provider);
+        if stake.unbonding <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        if env.ledger().timestamp() < stake.unbonding_since + UNBONDING_PERIOD {
+            panic_with_error!(&env, Error::StillUnbonding);
+        }
+
+        // Slashes during unbonding reduce what can be withdrawn
+        let amount = stake.unbonding.min(stake.amount);
+        stake.amount -= amount;
+        stake.unbonding = 0;
+        env.storage().persistent().set(&DataKey::Stake(depin_id.clone()), &stake);
+
+        token::Client::new(&env, &Self::stake_token(&env))
+            .transfer(&env.current_contract_address(), &provider, &amount);
+
+        env.events().publish((symbol_short!("unstaked"), depin_id), (provider, amount));
+        amount
+    }
+
+    /// Measured vs committed uptime over an order's service window, and the refund owed
+    pub fn quote_penalty(env: Env, order_id: BytesN<32>) -> PenaltyClaim {
+        let terms = Self::order(&env).get_order_terms(&order_id);
+
+        let depin = Self::registry(&env)
+            .get_depin(&terms.depin_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::DepinNotFound));
+        let sla_bps = depin.uptime as u32 * 100;
+        let measured_bps = Self::measured_uptime(&env, &terms.depin_id, terms.start_time, terms.end_time);
+
+        let shortfall = sla_bps.saturating_sub(measured_bps) as i128;
+        let multiplier: u32 = env.storage().persistent()
+            .get(&DataKey::PenaltyMultiplier)
+            .unwrap_or(BPS_DENOMINATOR as u32);
+        let refund = (terms.total_amount * shortfall / BPS_DENOMINATOR * multiplier as i128 / BPS_DENOMINATOR)
+            .min(terms.total_amount);
+
+        PenaltyClaim {
+            order_id,
+            user: terms.user,
+            sla_bps,
+            measured_bps,
+            refund,
+            claimed_at: env.ledger().timestamp(),
+        }
+    }
+
+    /// Pay the SLA refund for a finished order out of the provider's stake (anyone)
+    ///
+    /// The refund always goes to the order's user; the slash is capped at the
+    /// stake available. Each order can be claimed once.
+    pub fn claim_penalty(env: Env, order_id: BytesN<32>) -> i128 {
+        if env.storage().persistent().has(&DataKey::Claim(order_id.clone())) {
+            panic_with_error!(&env, Error::AlreadyClaimed);
+        }
+
+        let terms = Self::order(&env).get_order_terms(&order_id);
+        if env.ledger().timestamp() < terms.end_time {
+            panic_with_error!(&env, Error::OrderNotEnded);
+        }
+
+        let mut claim = Self::quote_penalty(env.clone(), order_id.clone());
+        let mut stake = Self::get_stake(env.clone(), terms.depin_id.clone())
+            .unwrap_or_else(|| panic_with_error!(&env, Error::InsufficientStake));
+        claim.refund = claim.refund.min(stake.amount);
+        if claim.refund <= 0 {
+            return 0;
+        }
+
+        stake.amount -= claim.refund;
+        env.storage().persistent().set(&DataKey::Stake(terms.depin_id.clone()), &stake);
+        env.storage().persistent().set(&DataKey::Claim(order_id.clone()), &claim);
+
+        token::Client::new(&env, &Self::stake_token(&env))
+            .transfer(&env.current_contract_address(), &claim.user, &claim.refund);
+
+        env.events().publish(
+            (symbol_short!("slashed"), terms.depin_id),
+            (order_id, claim.user.clone(), claim.refund),
+        );
+        claim.refund
+    }
+
+    /// Get the uptime reports recorded for a DePIN
+    pub fn get_uptime_reports(env: Env, depin_id: BytesN<32>) -> Vec<UptimeReport> {
+        env.storage().persistent()
+            .get(&DataKey::UptimeReports(depin_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Get the stake backing a DePIN's SLA
+    pub fn get_stake(env: Env, depin_id: BytesN<32>) -> Option<ProviderStake> {
+        env.storage().persistent().get(&DataKey::Stake(depin_id))
+    }
+
+    /// Get the penalty paid for an order, if claimed
+    pub fn get_claim(env: Env, order_id: BytesN<32>) -> Option<PenaltyClaim> {
+        env.storage().persistent().get(&DataKey::Claim(order_id))
+    }
+
+    /// Check whether an address may report uptime
+    pub fn is_oracle(env: Env, oracle: Address) -> bool {
+        env.storage().persistent()
+            .get(&DataKey::Oracle(oracle))
+            .unwrap_or(false)
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn order(env: &Env) -> OrderClient<'_> {
+        let address: Address = env.storage().persistent()
+            .get(&DataKey::OrderContract)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        OrderClient::new(env, &address)
+    }
+
+    fn registry(env: &Env) -> RegistryClient<'_> {
+        let address: Address = env.storage().persistent()
+            .get(&DataKey::RegistryContract)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        RegistryClient::new(env, &address)
+    }
+
+    fn stake_token(env: &Env) -> Address {
+        env.storage().persistent()
+            .get(&DataKey::StakeToken)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized))
+    }
+
+    fn load_stake(env: &Env, depin_id: &BytesN<32>, provider: &Address) -> ProviderStake {
+        let stake = Self::get_stake(env.clone(), depin_id.clone())
+            .unwrap_or_else(|| panic_with_error!(env, Error::InsufficientStake));
+        if stake.provider != *provider {
+            panic_with_error!(env, Error::NotProvider);
+        }
+        stake
+    }
+
+    /// Time-weighted uptime of the reports overlapping [start, end)
+    fn measured_uptime(env: &Env, depin_id: &BytesN<32>, start: u64, end: u64) -> u32 {
+        let mut covered: i128 = 0;
+        let mut weighted: i128 = 0;
+        for report in Self::get_uptime_reports(env.clone(), depin_id.clone()).iter() {
+            let from = report.period_start.max(start);
+            let to = report.period_end.min(end);
+            if to > from {
+                let overlap = (to - from) as i128;
+                covered += overlap;
+                weighted += overlap * report.uptime_bps as i128;
+            }
+        }
+        if covered == 0 {
+            panic_with_error!(env, Error::NoUptimeData);
+        }
+        (weighted / covered) as u32
+    }
+}
+
+mod test;
@@ -0,0 +1,279 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as AddressTestUtils, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env, String
+};
+use interfaces::DePIN;
+use order::{OrderContract, OrderContractClient, OrderPriority};
+
+/// Stand-in for the DePIN registry: every DePIN exists, costs 100 per hour
+/// and commits to 99% uptime
+#[contract]
+struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn depin_exists(_env: Env, _depin_id: BytesN<32>) -> bool {
+        true
+    }
+
+    pub fn supports_chain(_env: Env, _depin_id: BytesN<32>, _chain: String) -> bool {
+        true
+    }
+
+    pub fn get_payout_address(_env: Env, _depin_id: BytesN<32>) -> Option<Address> {
+        None
+    }
+
+    pub fn get_service_price(_env: Env, _depin_id: BytesN<32>, _service_type: String) -> Option<i128> {
+        Some(100)
+    }
+
+    pub fn reserve_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+
+    pub fn release_capacity(_env: Env, _caller: Address, _depin_id: BytesN<32>, _slots: u32) {}
+
+    pub fn get_depin(env: Env, depin_id: BytesN<32>) -> Option<DePIN> {
+        Some(DePIN {
+            id: depin_id,
+            name: String::from_str(&env, "GPU Cloud"),
+            description: String::from_str(&env, "A100 capacity"),
+            active: true,
+            uptime: 99,
+            reliability: 95,
+            cost: 100,
+            owner: None,
+            approved: true,
+            trust_level: interfaces::TrustLevel::Verified,
+            category: None,
+            tags: Vec::new(&env),
+            region: None,
+            country: None,
+            lifecycle: interfaces::DepinLifecycle::Active,
+            metadata_uri: None,
+            metadata_hash: None,
+        })
+    }
+}
+
+const START: u64 = 100_000;
+const DAY: u64 = 24 * 3600;
+
+struct Setup<'a> {
+    sla: SlaContractClient<'a>,
+    token: TokenClient<'a>,
+    admin: Address,
+    user: Address,
+    provider: Address,
+    oracle: Address,
+    depin_id: BytesN<32>,
+    order_id: BytesN<32>,
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = START);
+
+    let admin = Address::generate(env);
+    let user = Address::generate(env);
+    let provider = Address::generate(env);
+    let oracle = Address::generate(env);
+    let depin_id = BytesN::from_array(env, &[1u8; 32]);
+    let token_id = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    StellarAssetClient::new(env, &token_id).mint(&user, &10_000);
+    StellarAssetClient::new(env, &token_id).mint(&provider, &10_000);
+
+    let registry = env.register(MockRegistry, ());
+    let orders = OrderContractClient::new(env, &env.register(OrderContract, ()));
+    orders.initialize(&admin);
+    orders.set_depin_registry_contract(&admin, &registry);
+    orders.set_escrow_contract(&admin, &env.register(escrow::EscrowContract, ()));
+    orders.set_payment_token(&admin, &token_id);
+    orders.set_treasury_wallet(&admin, &Address::generate(env));
+
+    // 24 hours at 100 per hour
+    let order_id = orders.create_order(
+        &user,
+        &depin_id,
+        &String::from_str(env, "gpu"),
+        &24,
+        &100,
+        &token_id,
+        &String::from_str(env, "ethereum"),
+        &String::from_str(env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+
+    let sla = SlaContractClient::new(env, &env.register(SlaContract, ()));
+    sla.initialize(&admin, &orders.address, &registry, &token_id);
+    sla.set_oracle(&admin, &oracle, &true);
+    sla.stake(&provider, &depin_id, &1_000);
+
+    Setup {
+        sla,
+        token: TokenClient::new(env, &token_id),
+        admin,
+        user,
+        provider,
+        oracle,
+        depin_id,
+        order_id,
+    }
+}
+
+fn report_day(env: &Env, s: &Setup, first_half_bps: u32, second_half_bps: u32) {
+    env.ledger().with_mut(|li| li.timestamp = START + DAY);
+    s.sla.report_uptime(&s.oracle, &s.depin_id, &START, &(START + DAY / 2), &first_half_bps);
+    s.sla.report_uptime(&s.oracle, &s.depin_id, &(START + DAY / 2), &(START + DAY), &second_half_bps);
+}
+
+#[test]
+fn test_breach_refunds_user_from_stake() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    // 95% measured against a 99% commitment
+    report_day(&env, &s, 10_000, 9_000);
+    let quote = s.sla.quote_penalty(&s.order_id);
+    assert_eq!(quote.sla_bps, 9_900);
+    assert_eq!(quote.measured_bps, 9_500);
+    assert_eq!(quote.refund, 96);
+
+    let user_before = s.token.balance(&s.user);
+    assert_eq!(s.sla.claim_penalty(&s.order_id), 96);
+    assert_eq!(s.token.balance(&s.user), user_before + 96);
+    assert_eq!(s.sla.get_stake(&s.depin_id).unwrap().amount, 904);
+    assert_eq!(s.sla.get_claim(&s.order_id).unwrap().refund, 96);
+}
+
+#[test]
+fn test_met_sla_pays_nothing() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    report_day(&env, &s, 10_000, 9_900);
+    assert_eq!(s.sla.claim_penalty(&s.order_id), 0);
+    assert_eq!(s.sla.get_stake(&s.depin_id).unwrap().amount, 1_000);
+    assert_eq!(s.sla.get_claim(&s.order_id), None);
+}
+
+#[test]
+fn test_penalty_multiplier_scales_refund() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.sla.set_penalty_multiplier(&s.admin, &20_000);
+    report_day(&env, &s, 10_000, 9_000);
+    assert_eq!(s.sla.claim_penalty(&s.order_id), 192);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn test_claim_only_once() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    report_day(&env, &s, 10_000, 9_000);
+    s.sla.claim_penalty(&s.order_id);
+    s.sla.claim_penalty(&s.order_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn test_claim_before_order_ends() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = START + 3600);
+    s.sla.report_uptime(&s.oracle, &s.depin_id, &START, &(START + 3600), &5_000);
+    s.sla.claim_penalty(&s.order_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_only_oracles_report() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = START + DAY);
+    s.sla.report_uptime(&s.user, &s.depin_id, &START, &(START + DAY), &10_000);
+}
+
+#[test]
+fn test_slash_during_unbonding_reduces_withdrawal() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.sla.request_unstake(&s.provider, &s.depin_id, &1_000);
+    report_day(&env, &s, 10_000, 9_000);
+    s.sla.claim_penalty(&s.order_id);
+
+    env.ledger().with_mut(|li| li.timestamp += UNBONDING_PERIOD);
+    assert_eq!(s.sla.withdraw_stake(&s.provider, &s.depin_id), 904);
+    assert_eq!(s.token.balance(&s.provider), 9_904);
+}
+
+#[test]
+fn test_registry_reports_keep_committed_uptime() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = START);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let reporter = Address::generate(&env);
+    let token_id = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    StellarAssetClient::new(&env, &token_id).mint(&user, &10_000);
+    StellarAssetClient::new(&env, &token_id).mint(&provider, &10_000);
+
+    let registry = depin_registry::ContractClient::new(&env, &env.register(depin_registry::Contract, ()));
+    registry.initialize(&admin);
+    let gpu = String::from_str(&env, "gpu");
+    let depin_id = registry.add_depin(&admin, &String::from_str(&env, "GPU Cloud"), &String::from_str(&env, "A100 capacity"), &99, &95, &100);
+    registry.set_service_price(&admin, &depin_id, &gpu, &100);
+
+    let orders = OrderContractClient::new(&env, &env.register(OrderContract, ()));
+    orders.initialize(&admin);
+    orders.set_depin_registry_contract(&admin, &registry.address);
+    registry.set_order_contract(&admin, &orders.address);
+    orders.set_escrow_contract(&admin, &env.register(escrow::EscrowContract, ()));
+    orders.set_payment_token(&admin, &token_id);
+    orders.set_treasury_wallet(&admin, &Address::generate(&env));
+    let order_id = orders.create_order(
+        &user,
+        &depin_id,
+        &gpu,
+        &24,
+        &100,
+        &token_id,
+        &String::from_str(&env, "ethereum"),
+        &String::from_str(&env, "{}"),
+        &None,
+        &OrderPriority::Normal,
+    );
+
+    let sla = SlaContractClient::new(&env, &env.register(SlaContract, ()));
+    sla.initialize(&admin, &orders.address, &registry.address, &token_id);
+    sla.set_oracle(&admin, &oracle, &true);
+    sla.stake(&provider, &depin_id, &1_000);
+
+    // A poor measurement goes to the metrics history, not the commitment
+    env.ledger().with_mut(|li| li.timestamp = START + DAY);
+    registry.add_reporter(&admin, &reporter);
+    registry.report_metrics(&reporter, &depin_id, &50, &40, &(START + DAY));
+    assert_eq!(registry.get_depin(&depin_id).unwrap().uptime, 99);
+    assert_eq!(registry.get_rolling_metrics(&depin_id).unwrap().uptime, 50);
+
+    sla.report_uptime(&oracle, &depin_id, &START, &(START + DAY / 2), &10_000);
+    sla.report_uptime(&oracle, &depin_id, &(START + DAY / 2), &(START + DAY), &9_000);
+    let quote = sla.quote_penalty(&order_id);
+    assert_eq!(quote.sla_bps, 9_900);
+    assert_eq!(quote.refund, 96);
+}
@@ -0,0 +1,216 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, BytesN, Env, Vec,
+    panic_with_error, symbol_short, token
+};
+
+/// Upper bound on payees per DePIN so allocation stays within resource limits
+pub const MAX_PAYEES: u32 = 16;
+
+#[contract]
+pub struct SplitterContract;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Payee {
+    pub address: Address,
+    pub weight: u32,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    DepinManager(BytesN<32>),           // depin_id -> Address allowed to manage payees
+    Payees(BytesN<32>),                 // depin_id -> Vec<Payee>
+    Claimable(Address, Address),        // (payee, token) -> i128 ready to pull
+    Accounted(Address),                 // token -> i128 allocated but not yet claimed
+    TotalAllocated(BytesN<32>, Address), // (depin_id, token) -> i128 lifetime allocations
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    Unauthorized = 4,
+    InvalidPayees = 5,
+    NoPayees = 6,
+    InsufficientFunds = 7,
+    NothingToClaim = 8,
+    InvalidAmount = 9,
+}
+
+#[contractimpl]
+impl SplitterContract {
+    /// Initialize the contract with admin address
+    pub fn initialize(env: Env, admin: Address) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        true
+    }
+
+    /// Let an address (typically the provider) manage a DePIN's payees (admin only)
+    pub fn set_depin_manager(env: Env, admin: Address, depin_id: BytesN<32>, manager: Address) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::DepinManager(depin_id), &manager);
+        true
+    }
+
+    /// Replace the weighted payee list of a DePIN (admin or DePIN manager)
+    pub fn set_payees(env: Env, caller: Address, depin_id: BytesN<32>, payees: Vec<Payee>) -> bool {
+        caller.require_auth();
+
+        let admin = Self::admin(&env);
+        let manager: Option<Address> = env.storage().persistent().get(&DataKey::DepinManager(depin_id.clone()));
+        if caller != admin && manager != Some(caller.clone()) {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        if payees.is_empty() || payees.len() > MAX_PAYEES {
+            panic_with_error!(&env, Error::InvalidPayees);
+        }
+        for payee in payees.iter() {
+            if payee.weight == 0 {
+                panic_with_error!(&env, Error::InvalidPayees);
+            }
+        }
+
+        env.storage().persistent().set(&DataKey::Payees(depin_id.clone()), &payees);
+
+        env.events().publish((symbol_short!("payees"), depin_id), payees.len());
+        true
+    }
+
+    /// Split funds already sent to this contract across a DePIN's payees
+    ///
+    /// Callers transfer `amount` of `token` here first (e.g. as an escrow
+    /// payout leg) and allocate it in the same transaction. Only tokens that
+    /// are not already owed to payees can be allocated, so anyone may call it.
+    pub fn allocate(env: Env, depin_id: BytesN<32>, token: Address, amount: i128) -> bool {
+        if amount <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let payees = Self::payees(&env, &depin_id);
+        if payees.is_empty() {
+            panic_with_error!(&env, Error::NoPayees);
+        }
+
+        let accounted = Self::get_accounted(&env, &token);
+        let balance = token::Client::new(&env, &token).balance(&env.current_contract_address());
+        if balance - accounted < amount {
+            panic_with_error!(&env, Error::InsufficientFunds);
+        }
+
+        let mut total_weight: i128 = 0;
+        for payee in payees.iter() {
+            total_weight += payee.weight as i128;
+        }
+
+        // Rounding dust goes to the first payee
+        let mut distributed: i128 = 0;
+        for payee in payees.iter() {
+            let share = amount * payee.weight as i128 / total_weight;
+            Self::credit(&env, &payee.address, &token, share);
+            distributed += share;
+        }
+        if distributed < amount {
+            Self::credit(&env, &payees.get_unchecked(0).address, &token, amount - distributed);
+        }
+
+        env.storage().persistent().set(&DataKey::Accounted(token.clone()), &(accounted + amount));
+        let total = Self::get_total_allocated(env.clone(), depin_id.clone(), token.clone()) + amount;
+        env.storage().persistent().set(&DataKey::TotalAllocated(depin_id.clone(), token.clone()), &total);
+
+        env.events().publish((symbol_short!("allocate"), depin_id), (token, amount));
+        true
+    }
+
+    /// Pull everything owed to a payee in a token
+    pub fn claim(env: Env, payee: Address, token: Address) -> i128 {
+        payee.require_auth();
+
+        let amount = Self::get_claimable(env.clone(), payee.clone(), token.clone());
+        if amount <= 0 {
+            panic_with_error!(&env, Error::NothingToClaim);
+        }
+
+        env.storage().persistent().set(&DataKey::Claimable(payee.clone(), token.clone()), &0i128);
+        let accounted = Self::get_accounted(&env, &token);
+        env.storage().persistent().set(&DataKey::Accounted(token.clone()), &(accounted - amount));
+
+        token::Client::new(&env, &token)
+            .transfer(&env.current_contract_address(), &payee, &amount);
+
+        env.events().publish((symbol_short!("claim"), payee), (token, amount));
+        amount
+    }
+
+    /// Get the weighted payees of a DePIN
+    pub fn get_payees(env: Env, depin_id: BytesN<32>) -> Vec<Payee> {
+        Self::payees(&env, &depin_id)
+    }
+
+    /// Check whether a DePIN has payees registered
+    pub fn has_payees(env: Env, depin_id: BytesN<32>) -> bool {
+        !Self::payees(&env, &depin_id).is_empty()
+    }
+
+    /// Get the manager of a DePIN's payees
+    pub fn get_depin_manager(env: Env, depin_id: BytesN<32>) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::DepinManager(depin_id))
+    }
+
+    /// Get the amount a payee can pull in a token
+    pub fn get_claimable(env: Env, payee: Address, token: Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::Claimable(payee, token))
+            .unwrap_or(0)
+    }
+
+    /// Get the lifetime amount allocated to a DePIN's payees in a token
+    pub fn get_total_allocated(env: Env, depin_id: BytesN<32>, token: Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::TotalAllocated(depin_id, token))
+            .unwrap_or(0)
+    }
+
+    // Helper functions
+    fn admin(env: &Env) -> Address {
+        env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized))
+    }
+
+    fn assert_admin(env: &Env, admin: &Address) {
+        if *admin != Self::admin(env) {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn payees(env: &Env, depin_id: &BytesN<32>) -> Vec<Payee> {
+        env.storage().persistent()
+            .get(&DataKey::Payees(depin_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn get_accounted(env: &Env, token: &Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::Accounted(token.clone()))
+            .unwrap_or(0)
+    }
+
+    fn credit(env: &Env, payee: &Address, token: &Address, amount: i128) {
+        let key = DataKey::Claimable(payee.clone(), token.clone());
+        let current: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(current + amount));
+    }
+}
+
+mod test;
@@ -0,0 +1,139 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::Address as AddressTestUtils,
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, Env
+};
+
+fn init_splitter<'a>(env: &'a Env, admin: &Address) -> SplitterContractClient<'a> {
+    let contract_id = env.register(SplitterContract, ());
+    let client = SplitterContractClient::new(env, &contract_id);
+    client.initialize(admin);
+    client
+}
+
+fn create_token<'a>(env: &'a Env) -> (TokenClient<'a>, StellarAssetClient<'a>) {
+    let address = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    (TokenClient::new(env, &address), StellarAssetClient::new(env, &address))
+}
+
+#[test]
+fn test_allocate_and_claim_by_weight() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let splitter = init_splitter(&env, &admin);
+    let (token, token_admin) = create_token(&env);
+
+    let depin_id = BytesN::from_array(&env, &[1u8; 32]);
+    let operator = Address::generate(&env);
+    let financier = Address::generate(&env);
+    splitter.set_payees(
+        &admin,
+        &depin_id,
+        &vec![
+            &env,
+            Payee { address: operator.clone(), weight: 3 },
+            Payee { address: financier.clone(), weight: 1 },
+        ],
+    );
+    assert!(splitter.has_payees(&depin_id));
+
+    token_admin.mint(&splitter.address, &401);
+    splitter.allocate(&depin_id, &token.address, &401);
+
+    // 401 * 3/4 = 300 (+1 dust), 401 * 1/4 = 100
+    assert_eq!(splitter.get_claimable(&operator, &token.address), 301);
+    assert_eq!(splitter.get_claimable(&financier, &token.address), 100);
+    assert_eq!(splitter.get_total_allocated(&depin_id, &token.address), 401);
+
+    // Payees pull independently
+    assert_eq!(splitter.claim(&financier, &token.address), 100);
+    assert_eq!(token.balance(&financier), 100);
+    assert_eq!(splitter.get_claimable(&financier, &token.address), 0);
+    assert_eq!(splitter.get_claimable(&operator, &token.address), 301);
+}
+
+#[test]
+fn test_depin_manager_sets_payees() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let splitter = init_splitter(&env, &admin);
+    let depin_id = BytesN::from_array(&env, &[1u8; 32]);
+
+    splitter.set_depin_manager(&admin, &depin_id, &provider);
+    splitter.set_payees(&provider, &depin_id, &vec![&env, Payee { address: provider.clone(), weight: 1 }]);
+
+    assert_eq!(splitter.get_depin_manager(&depin_id), Some(provider));
+    assert_eq!(splitter.get_payees(&depin_id).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #4)")]
+fn test_stranger_cannot_set_payees() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let splitter = init_splitter(&env, &admin);
+    let stranger = Address::generate(&env);
+
+    splitter.set_payees(
+        &stranger,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &vec![&env, Payee { address: stranger.clone(), weight: 1 }],
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #5)")]
+fn test_zero_weight_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let splitter = init_splitter(&env, &admin);
+
+    splitter.set_payees(
+        &admin,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &vec![&env, Payee { address: Address::generate(&env), weight: 0 }],
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_cannot_allocate_unfunded_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let splitter = init_splitter(&env, &admin);
+    let (token, token_admin) = create_token(&env);
+    let depin_id = BytesN::from_array(&env, &[1u8; 32]);
+    splitter.set_payees(&admin, &depin_id, &vec![&env, Payee { address: admin.clone(), weight: 1 }]);
+
+    token_admin.mint(&splitter.address, &100);
+    splitter.allocate(&depin_id, &token.address, &100);
+    // The same tokens cannot be allocated twice
+    splitter.allocate(&depin_id, &token.address, &1);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #8)")]
+fn test_claim_nothing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let splitter = init_splitter(&env, &admin);
+    let (token, _) = create_token(&env);
+
+    splitter.claim(&admin, &token.address);
+}
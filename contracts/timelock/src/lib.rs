@@ -0,0 +1,237 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contracterror,
+    Address, Env, Symbol, Val, Vec,
+    panic_with_error, symbol_short
+};
+
+/// How long a ready operation stays executable before it expires (14 days)
+pub const GRACE_PERIOD: u64 = 14 * 24 * 60 * 60;
+
+#[contract]
+pub struct TimelockContract;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Operation {
+    pub id: u64,
+    pub target: Address,        // Contract the call is made on
+    pub function: Symbol,       // Privileged entry point, e.g. set_admin or upgrade
+    pub args: Vec<Val>,
+    pub proposer: Address,
+    pub eta: u64,               // Earliest execution timestamp
+    pub status: OperationStatus,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OperationStatus {
+    Queued,         // Waiting out the delay, can still be cancelled
+    Executed,
+    Cancelled,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,                      // Proposer and default canceller
+    MinDelay,                   // u64 seconds every operation must wait
+    OperationCounter,           // u64 counter for operation IDs
+    Operation(u64),             // id -> Operation
+    Canceller(Address),         // address -> bool, extra parties allowed to cancel
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    OperationNotFound = 4,
+    DelayTooShort = 5,
+    NotReady = 6,
+    Expired = 7,
+    InvalidStatus = 8,
+    Unauthorized = 9,
+}
+
+#[contractimpl]
+impl TimelockContract {
+    /// Initialize with the proposing admin and the mandatory delay in seconds
+    pub fn initialize(env: Env, admin: Address, min_delay: u64) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::MinDelay, &min_delay);
+        env.storage().persistent().set(&DataKey::OperationCounter, &0u64);
+        true
+    }
+
+    /// Queue a privileged call to be executed after `delay` seconds (admin only)
+    pub fn schedule(
+        env: Env,
+        admin: Address,
+        target: Address,
+        function: Symbol,
+        args: Vec<Val>,
+        delay: u64,
+    ) -> u64 {
+        Self::assert_admin(&env, &admin);
+
+        if delay < Self::get_min_delay(env.clone()) {
+            panic_with_error!(&env, Error::DelayTooShort);
+        }
+
+        let mut counter: u64 = env.storage().persistent()
+            .get(&DataKey::OperationCounter)
+            .unwrap_or(0);
+        counter += 1;
+        env.storage().persistent().set(&DataKey::OperationCounter, &counter);
+
+        let operation = Operation {
+            id: counter,
+            target,
+            function,
+            args,
+            proposer: admin,
+            eta: env.ledger().timestamp() + delay,
+            status: OperationStatus::Queued,
+        };
+        env.storage().persistent().set(&DataKey::Operation(counter), &operation);
+
+        env.events().publish(
+            (symbol_short!("queued"), counter),
+            (operation.target, operation.function, operation.eta),
+        );
+        counter
+    }
+
+    /// Cancel a queued operation (admin or an approved canceller)
+    pub fn cancel(env: Env, caller: Address, id: u64) -> bool {
+        caller.require_auth();
+
+        let admin = Self::admin(&env);
+        let is_canceller: bool = env.storage().persistent()
+            .get(&DataKey::Canceller(caller.clone()))
+            .unwrap_or(false);
+        if caller != admin && !is_canceller {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        let mut operation = Self::load(&env, id);
+        if operation.status != OperationStatus::Queued {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        operation.status = OperationStatus::Cancelled;
+        env.storage().persistent().set(&DataKey::Operation(id), &operation);
+
+        env.events().publish((symbol_short!("cancelled"), id), caller);
+        true
+    }
+
+    /// Execute a queued operation once its delay has passed (anyone)
+    pub fn execute(env: Env, id: u64) -> Val {
+        let mut operation = Self::load(&env, id);
+        if operation.status != OperationStatus::Queued {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        let now = env.ledger().timestamp();
+        if now < operation.eta {
+            panic_with_error!(&env, Error::NotReady);
+        }
+        if now > operation.eta + GRACE_PERIOD {
+            panic_with_error!(&env, Error::Expired);
+        }
+
+        operation.status = OperationStatus::Executed;
+        env.storage().persistent().set(&DataKey::Operation(id), &operation);
+
+        // The target sees this contract as the direct invoker, so its
+        // `timelock.require_auth()` checks pass without extra signatures
+        let result: Val = env.invoke_contract(&operation.target, &operation.function, operation.args);
+
+        env.events().publish((symbol_short!("executed"), id), operation.target);
+        result
+    }
+
+    /// Allow or disallow an address to cancel queued operations (admin only)
+    pub fn set_canceller(env: Env, admin: Address, canceller: Address, enabled: bool) -> bool {
+        Self::assert_admin(&env, &admin);
+        env.storage().persistent().set(&DataKey::Canceller(canceller), &enabled);
+        true
+    }
+
+    /// Change the mandatory delay (only through a queued operation on this contract)
+    pub fn set_min_delay(env: Env, min_delay: u64) -> bool {
+        env.current_contract_address().require_auth();
+        env.storage().persistent().set(&DataKey::MinDelay, &min_delay);
+        true
+    }
+
+    /// Rotate the proposing admin (only through a queued operation on this contract)
+    pub fn set_admin(env: Env, new_admin: Address) -> bool {
+        env.current_contract_address().require_auth();
+        env.storage().persistent().set(&DataKey::Admin, &new_admin);
+        true
+    }
+
+    /// Get operation details
+    pub fn get_operation(env: Env, id: u64) -> Operation {
+        Self::load(&env, id)
+    }
+
+    /// Check whether an operation can be executed right now
+    pub fn is_ready(env: Env, id: u64) -> bool {
+        let operation = Self::load(&env, id);
+        let now = env.ledger().timestamp();
+        operation.status == OperationStatus::Queued
+            && now >= operation.eta
+            && now <= operation.eta + GRACE_PERIOD
+    }
+
+    /// Get the mandatory delay in seconds
+    pub fn get_min_delay(env: Env) -> u64 {
+        env.storage().persistent()
+            .get(&DataKey::MinDelay)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+    }
+
+    /// Get the proposing admin
+    pub fn get_admin(env: Env) -> Address {
+        Self::admin(&env)
+    }
+
+    /// Check whether an address may cancel queued operations
+    pub fn is_canceller(env: Env, address: Address) -> bool {
+        address == Self::admin(&env)
+            || env.storage().persistent()
+                .get(&DataKey::Canceller(address))
+                .unwrap_or(false)
+    }
+
+    // Helper functions
+    fn admin(env: &Env) -> Address {
+        env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized))
+    }
+
+    fn assert_admin(env: &Env, admin: &Address) {
+        if *admin != Self::admin(env) {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn load(env: &Env, id: u64) -> Operation {
+        env.storage().persistent()
+            .get(&DataKey::Operation(id))
+            .unwrap_or_else(|| panic_with_error!(env, Error::OperationNotFound))
+    }
+}
+
+mod test;
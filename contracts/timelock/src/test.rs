@@ -0,0 +1,186 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    vec, Address, Env, IntoVal
+};
+
+const DELAY: u64 = 2 * 24 * 60 * 60;
+
+fn init_timelock<'a>(env: &'a Env, admin: &Address) -> TimelockContractClient<'a> {
+    let contract_id = env.register(TimelockContract, ());
+    let client = TimelockContractClient::new(env, &contract_id);
+    client.initialize(admin, &DELAY);
+    client
+}
+
+/// Fee manager governed by the timelock
+fn init_fee_manager<'a>(env: &'a Env, timelock: &Address) -> fee_manager::FeeManagerContractClient<'a> {
+    let contract_id = env.register(fee_manager::FeeManagerContract, ());
+    let client = fee_manager::FeeManagerContractClient::new(env, &contract_id);
+    client.initialize(timelock);
+    client
+}
+
+fn schedule_fee_change(env: &Env, timelock: &TimelockContractClient, admin: &Address, fee_manager: &Address) -> u64 {
+    timelock.schedule(
+        admin,
+        fee_manager,
+        &Symbol::new(env, "set_platform_fee_bps"),
+        &vec![env, timelock.address.into_val(env), 250u32.into_val(env)],
+        &DELAY,
+    )
+}
+
+fn advance(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+}
+
+#[test]
+fn test_schedule_and_execute_fee_change() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let timelock = init_timelock(&env, &admin);
+    let fees = init_fee_manager(&env, &timelock.address);
+
+    let id = schedule_fee_change(&env, &timelock, &admin, &fees.address);
+    let operation = timelock.get_operation(&id);
+    assert_eq!(operation.status, OperationStatus::Queued);
+    assert_eq!(operation.eta, env.ledger().timestamp() + DELAY);
+    assert!(!timelock.is_ready(&id));
+
+    advance(&env, DELAY);
+    assert!(timelock.is_ready(&id));
+    timelock.execute(&id);
+
+    assert_eq!(fees.get_platform_fee_bps(), 250);
+    assert_eq!(timelock.get_operation(&id).status, OperationStatus::Executed);
+}
+
+#[test]
+fn test_admin_rotation_through_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let timelock = init_timelock(&env, &admin);
+
+    let order_id = env.register(order::OrderContract, ());
+    let order_client = order::OrderContractClient::new(&env, &order_id);
+    order_client.initialize(&admin);
+    order_client.set_timelock(&admin, &timelock.address);
+
+    let id = timelock.schedule(
+        &admin,
+        &order_id,
+        &Symbol::new(&env, "set_admin"),
+        &vec![&env, new_admin.into_val(&env)],
+        &DELAY,
+    );
+    advance(&env, DELAY);
+    timelock.execute(&id);
+
+    assert_eq!(order_client.get_admin(), new_admin);
+}
+
+#[test]
+fn test_canceller_can_cancel_during_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let timelock = init_timelock(&env, &admin);
+    let fees = init_fee_manager(&env, &timelock.address);
+
+    timelock.set_canceller(&admin, &guardian, &true);
+    assert!(timelock.is_canceller(&guardian));
+
+    let id = schedule_fee_change(&env, &timelock, &admin, &fees.address);
+    timelock.cancel(&guardian, &id);
+
+    assert_eq!(timelock.get_operation(&id).status, OperationStatus::Cancelled);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #8)")]
+fn test_cancelled_operation_cannot_execute() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let timelock = init_timelock(&env, &admin);
+    let fees = init_fee_manager(&env, &timelock.address);
+
+    let id = schedule_fee_change(&env, &timelock, &admin, &fees.address);
+    timelock.cancel(&admin, &id);
+    advance(&env, DELAY);
+    timelock.execute(&id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #9)")]
+fn test_stranger_cannot_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let timelock = init_timelock(&env, &admin);
+    let fees = init_fee_manager(&env, &timelock.address);
+
+    let id = schedule_fee_change(&env, &timelock, &admin, &fees.address);
+    timelock.cancel(&Address::generate(&env), &id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #6)")]
+fn test_execute_before_eta() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let timelock = init_timelock(&env, &admin);
+    let fees = init_fee_manager(&env, &timelock.address);
+
+    let id = schedule_fee_change(&env, &timelock, &admin, &fees.address);
+    advance(&env, DELAY - 1);
+    timelock.execute(&id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_execute_after_grace_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let timelock = init_timelock(&env, &admin);
+    let fees = init_fee_manager(&env, &timelock.address);
+
+    let id = schedule_fee_change(&env, &timelock, &admin, &fees.address);
+    advance(&env, DELAY + GRACE_PERIOD + 1);
+    timelock.execute(&id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #5)")]
+fn test_delay_below_minimum() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let timelock = init_timelock(&env, &admin);
+    let fees = init_fee_manager(&env, &timelock.address);
+
+    timelock.schedule(
+        &admin,
+        &fees.address,
+        &Symbol::new(&env, "set_listing_fee"),
+        &vec![&env, timelock.address.into_val(&env), 10i128.into_val(&env)],
+        &(DELAY - 1),
+    );
+}
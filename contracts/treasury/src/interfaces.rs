@@ -0,0 +1,24 @@
+//! Typed interfaces of the contracts the treasury queries.
+
+use soroban_sdk::{contractclient, Address, Env, Symbol};
+
+/// Subset of the fee-manager contract used to size the platform's share.
+#[allow(dead_code)]
+#[contractclient(name = "FeeManagerClient")]
+pub trait FeeManagerInterface {
+    fn quote_platform_fee(env: Env, amount: i128) -> i128;
+}
+
+/// Subset of the compliance registry gating large user withdrawals.
+#[allow(dead_code)]
+#[contractclient(name = "ComplianceClient")]
+pub trait ComplianceInterface {
+    fn is_compliant(env: Env, subject: Address, min_level: u32) -> bool;
+}
+
+/// Subset of the pause guardian consulted before moving funds.
+#[allow(dead_code)]
+#[contractclient(name = "GuardianClient")]
+pub trait GuardianInterface {
+    fn is_paused(env: Env, target: Address, function: Symbol) -> bool;
+}
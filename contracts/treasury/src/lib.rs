@@ -1,10 +1,13 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, contracterror, Env, Address, Symbol, IntoVal,
+    contract, contractimpl, contracttype, contracterror, Env, Address, Symbol,
     symbol_short,
     panic_with_error,
 };
 
+mod interfaces;
+use interfaces::{ComplianceClient, FeeManagerClient, GuardianClient};
+
 #[contracttype]
 pub enum DataKey {
     AdminContract,                // Address of the admin contract
@@ -12,6 +15,11 @@ pub enum DataKey {
     TotalReceived(Address),       // token address -> i128
     TotalWithdrawn(Address),      // token address -> i128
     LastWithdrawal(u64),          // timestamp
+    FeeManager,                   // Address of the fee-manager contract
+    Timelock,                     // Timelock that authorizes admin rotations
+    ComplianceContract,           // Address of the compliance attestation registry
+    CompliancePolicy,             // (min KYC level, withdrawal amount from which it applies)
+    PauseGuardian,                // Guardian that can freeze deposits and withdrawals
 }
 
 #[contracterror]
@@ -22,6 +30,9 @@ pub enum Error {
     NotAdmin = 2,
     InsufficientBalance = 3,
     InvalidAmount = 4,
+    TimelockNotSet = 5,
+    NotCompliant = 6,
+    Paused = 7,
 }
 
 #[contract]
@@ -39,6 +50,7 @@ impl TreasuryContract {
 
     // Deposit tokens into treasury (called by order contract)
     pub fn deposit(env: Env, token: Address, from: Address, amount: i128) {
+        Self::assert_not_paused(&env, symbol_short!("deposit"));
         if amount <= 0 {
             panic_with_error!(&env, Error::InvalidAmount);
         }
@@ -61,6 +73,7 @@ impl TreasuryContract {
 
     // Withdraw tokens (user can withdraw their own, admin contract can withdraw platform funds)
     pub fn withdraw(env: Env, token: Address, to: Address, amount: i128, is_admin: bool) {
+        Self::assert_not_paused(&env, symbol_short!("withdraw"));
         if amount <= 0 {
             panic_with_error!(&env, Error::InvalidAmount);
         }
@@ -77,6 +90,7 @@ impl TreasuryContract {
         } else {
             // User withdrawal: only the recipient can withdraw
             to.require_auth();
+            Self::assert_compliant(&env, &to, amount);
         }
 
         let new_bal = bal - amount;
@@ -97,6 +111,53 @@ impl TreasuryContract {
         );
     }
 
+    // Set the timelock that authorizes admin rotations (admin contract once, then timelock only)
+    pub fn set_timelock(env: Env, timelock: Address) {
+        if env.storage().persistent().has(&DataKey::Timelock) {
+            Self::assert_timelock(&env);
+        } else {
+            let admin_contract: Address = env.storage().persistent().get(&DataKey::AdminContract).expect("admin not set");
+            admin_contract.require_auth();
+        }
+        env.storage().persistent().set(&DataKey::Timelock, &timelock);
+    }
+
+    // Rotate the admin contract (timelock only)
+    pub fn set_admin_contract(env: Env, admin_contract: Address) {
+        Self::assert_timelock(&env);
+        env.storage().persistent().set(&DataKey::AdminContract, &admin_contract);
+    }
+
+    // Point the treasury at the fee-manager contract (admin contract only)
+    pub fn set_fee_manager(env: Env, fee_manager: Address) {
+        let admin_contract: Address = env.storage().persistent().get(&DataKey::AdminContract).expect("admin not set");
+        admin_contract.require_auth();
+        env.storage().persistent().set(&DataKey::FeeManager, &fee_manager);
+    }
+
+    // Require a compliance attestation of `min_level` for user withdrawals of at least `threshold` (admin contract only)
+    pub fn set_compliance(env: Env, compliance: Address, min_level: u32, threshold: i128) {
+        let admin_contract: Address = env.storage().persistent().get(&DataKey::AdminContract).expect("admin not set");
+        admin_contract.require_auth();
+        env.storage().persistent().set(&DataKey::ComplianceContract, &compliance);
+        env.storage().persistent().set(&DataKey::CompliancePolicy, &(min_level, threshold));
+    }
+
+    // Set the pause guardian that can freeze deposits and withdrawals (admin contract only)
+    pub fn set_pause_guardian(env: Env, guardian: Address) {
+        let admin_contract: Address = env.storage().persistent().get(&DataKey::AdminContract).expect("admin not set");
+        admin_contract.require_auth();
+        env.storage().persistent().set(&DataKey::PauseGuardian, &guardian);
+    }
+
+    // Platform share of a settled amount, as configured in the fee manager
+    pub fn quote_platform_fee(env: Env, amount: i128) -> i128 {
+        match env.storage().persistent().get::<_, Address>(&DataKey::FeeManager) {
+            Some(fee_manager) => FeeManagerClient::new(&env, &fee_manager).quote_platform_fee(&amount),
+            None => 0,
+        }
+    }
+
     // Getters
     pub fn get_balance(env: Env, token: Address) -> i128 {
         Self::get_balance_internal(&env, &token)
@@ -114,7 +175,35 @@ impl TreasuryContract {
         env.storage().persistent().get(&DataKey::AdminContract).expect("admin not set")
     }
 
-    // Internal helper
+    // Internal helpers
+    fn assert_timelock(env: &Env) {
+        let timelock: Address = env.storage().persistent()
+            .get(&DataKey::Timelock)
+            .unwrap_or_else(|| panic_with_error!(env, Error::TimelockNotSet));
+        timelock.require_auth();
+    }
+
+    fn assert_not_paused(env: &Env, function: Symbol) {
+        if let Some(guardian) = env.storage().persistent().get::<_, Address>(&DataKey::PauseGuardian) {
+            if GuardianClient::new(env, &guardian).is_paused(&env.current_contract_address(), &function) {
+                panic_with_error!(env, Error::Paused);
+            }
+        }
+    }
+
+    fn assert_compliant(env: &Env, to: &Address, amount: i128) {
+        let compliance: Address = match env.storage().persistent().get(&DataKey::ComplianceContract) {
+            Some(compliance) => compliance,
+            None => return,
+        };
+        let (min_level, threshold): (u32, i128) = env.storage().persistent()
+            .get(&DataKey::CompliancePolicy)
+            .expect("compliance policy not set");
+        if amount >= threshold && !ComplianceClient::new(env, &compliance).is_compliant(to, &min_level) {
+            panic_with_error!(env, Error::NotCompliant);
+        }
+    }
+
     fn get_balance_internal(env: &Env, token: &Address) -> i128 {
         env.storage().persistent().get(&DataKey::AssetBalance(token.clone())).unwrap_or(0)
     }
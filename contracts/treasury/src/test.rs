@@ -3,16 +3,16 @@
 
 #[cfg(test)]
 mod tests {
-    use soroban_sdk::{Address, Env, BytesN, String};
+    use soroban_sdk::{testutils::Address as _, token::StellarAssetClient, Address, Env};
     use crate::{TreasuryContract, TreasuryContractClient};
 
-    fn setup(env: &Env) -> (TreasuryContractClient, Address, Address, Address) {
-        // Use fixed addresses for test determinism
-        let admin_contract = Address::from_string(&String::from_str(env, "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF"));
-        let user = Address::from_string(&String::from_str(env, "GBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBR4"));
-        let token = Address::from_string(&String::from_str(env, "GCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCC"));
-        // Use a fixed contract id for testing
-        let contract_id = Address::from_string(&String::from_str(env, "GDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDD"));
+    fn setup(env: &Env) -> (TreasuryContractClient<'_>, Address, Address, Address) {
+        env.mock_all_auths_allowing_non_root_auth();
+        let admin_contract = Address::generate(env);
+        let user = Address::generate(env);
+        let token = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+        StellarAssetClient::new(env, &token).mint(&user, &1_000);
+        let contract_id = env.register(TreasuryContract, ());
         let client = TreasuryContractClient::new(env, &contract_id);
         client.initialize(&admin_contract);
         (client, admin_contract, user, token)
@@ -24,6 +24,9 @@ mod tests {
         let (treasury, admin_contract, _, _) = setup(&env);
         assert_eq!(treasury.get_admin_contract(), admin_contract);
     }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #3)")]
     fn test_withdraw_insufficient_balance() {
         let env = Env::default();
         let (treasury, _, user, token) = setup(&env);
@@ -31,4 +34,52 @@ mod tests {
         // Try to withdraw more than balance
         treasury.withdraw(&token, &user, &200, &false);
     }
+
+    #[test]
+    fn test_quote_platform_fee_from_fee_manager() {
+        let env = Env::default();
+        let (treasury, admin_contract, _, _) = setup(&env);
+        assert_eq!(treasury.quote_platform_fee(&10_000), 0);
+
+        let fee_manager_id = env.register(fee_manager::FeeManagerContract, ());
+        let fee_manager = fee_manager::FeeManagerContractClient::new(&env, &fee_manager_id);
+        fee_manager.initialize(&admin_contract);
+        fee_manager.set_platform_fee_bps(&admin_contract, &300);
+
+        treasury.set_fee_manager(&fee_manager_id);
+        assert_eq!(treasury.quote_platform_fee(&10_000), 300);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #6)")]
+    fn test_large_user_withdrawal_requires_compliance() {
+        let env = Env::default();
+        let (treasury, admin_contract, user, token) = setup(&env);
+        treasury.deposit(&token, &user, &1_000);
+
+        let compliance_id = env.register(compliance::ComplianceContract, ());
+        compliance::ComplianceContractClient::new(&env, &compliance_id).initialize(&admin_contract);
+        treasury.set_compliance(&compliance_id, &1, &500);
+
+        // Small withdrawals stay open, large ones need an attestation
+        treasury.withdraw(&token, &user, &100, &false);
+        treasury.withdraw(&token, &user, &500, &false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #7)")]
+    fn test_paused_treasury_rejects_withdrawals() {
+        let env = Env::default();
+        let (treasury, admin_contract, user, token) = setup(&env);
+        treasury.deposit(&token, &user, &100);
+
+        let guardian_id = env.register(guardian::GuardianContract, ());
+        let guardian = guardian::GuardianContractClient::new(&env, &guardian_id);
+        guardian.initialize(&admin_contract, &Address::generate(&env));
+        guardian.set_guardian(&admin_contract, &admin_contract, &true);
+        treasury.set_pause_guardian(&guardian_id);
+        guardian.pause_all(&admin_contract);
+
+        treasury.withdraw(&token, &user, &100, &false);
+    }
 }
@@ -0,0 +1,41 @@
+//! Typed interfaces of the contracts the user profile queries.
+
+use soroban_sdk::{contractclient, contracttype, Address, Env, Symbol};
+
+/// Subset of the compliance registry that replaces the profile's own verification flag.
+#[allow(dead_code)]
+#[contractclient(name = "ComplianceClient")]
+pub trait ComplianceInterface {
+    fn is_compliant(env: Env, subject: Address, min_level: u32) -> bool;
+}
+
+/// Subset of the pause guardian consulted before balance and profile changes.
+#[allow(dead_code)]
+#[contractclient(name = "GuardianClient")]
+pub trait GuardianInterface {
+    fn is_paused(env: Env, target: Address, function: Symbol) -> bool;
+}
+
+/// Mirror of the SEP-40 price oracle asset identifier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// Mirror of the SEP-40 price record (USD price scaled by `decimals()`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// Subset of a SEP-40 price oracle used to price subscriptions paid in other tokens.
+#[allow(dead_code)]
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleInterface {
+    fn decimals(env: Env) -> u32;
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+}
@@ -1,19 +1,220 @@
 #![no_std]
-use soroban_sdk::{contracttype, contract, contractimpl, Env, String, Vec, Address, Map};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{contracttype, contracterror, contract, contractimpl, panic_with_error, symbol_short, vec, Bytes, BytesN, Env, String, Symbol, Val, Vec, Address, Map};
+
+mod interfaces;
+use interfaces::{Asset, ComplianceClient, GuardianClient, PriceOracleClient};
 
 #[contracttype]
 pub enum DataKey {
     Admin,
-    UserProfiles,
+    Profile(Address),       // user -> UserProfile
+    UserCount,              // u32 number of registered users
+    UserIndex(u32),         // registration index -> user, for enumeration
     UserBalances,
     PlatformStats,
     WhitelistedTokens,
     ReferralSystem,
+    Timelock,
+    CreditSources,
+    BalanceCallers,             // Map<Address, bool> of contracts allowed to deduct and refund balances
+    ComplianceContract,
+    CompliancePolicy,
+    PauseGuardian,
+    Paused,                     // bool, emergency stop on balance and subscription changes
+    ReferralProgram,            // ReferralProgram paying referrers
+    ReferralStats(Address),     // referrer -> ReferralStats
+    ReferralMilestone(Address), // referee -> u32 milestones already paid out
+    LoyaltyMultiplier(u32),     // subscription tier -> u32 points multiplier in bps
+    BillingPeriod,              // u64 seconds a paid subscription lasts per payment
+    Operator(Address),          // address -> bool, may verify users and manage the token whitelist
+    Username(String),           // username -> Address, keeps usernames unique
+    EmailSalt,                  // BytesN<32> prefixed to emails before hashing
+    Frozen(Address),            // user -> bool, blocked from moving funds
+    WithdrawalLimit(u32),       // verification level -> i128 max per withdrawal
+    DailyWithdrawalLimit(u32),  // verification level -> i128 max per rolling 24h window
+    WithdrawalWindow(Address, Address), // (user, token) -> Vec<(u64, i128)> withdrawals of the last 24h
+    WithdrawalDelay,            // u64 seconds a queued withdrawal stays locked
+    WithdrawalRequestCounter,   // u64 last issued withdrawal request ID
+    WithdrawalRequest(u64),     // request ID -> WithdrawalRequest
+    Version,                    // u32 contract version storage was last migrated to
+    ProfileSchema(Address),     // user -> u32 layout version of the stored profile
+    ProfileMetadata(Address),   // user -> Map<Symbol, String> free-form frontend fields
+    InterestPool(Address),      // token -> InterestPool
+    InterestCheckpoint(Address, Address), // (user, token) -> InterestCheckpoint
+    Allowance(Address, Address, Address), // (user, spender, token) -> i128 the spender may still pull
+    RelayNonce(Address),        // user -> u64 nonce the next signed action must carry
+    ReferralAliases(Address),   // user -> Vec<String> earlier referral codes that still resolve to them
+    Referees(Address),          // referrer -> Vec<Address> users they referred directly
+    ReferralCounts(Address),    // referrer -> Vec<u32> referees per level, direct ones first
+    BalanceHistory(Address, u32), // (user, slot) -> BalanceOperation, ring buffer of BALANCE_HISTORY_LEN slots
+    BalanceHistoryCount(Address), // user -> u32 balance operations ever recorded
+    TokenDecimals(Address),     // token -> u32 decimals, set when whitelisting
+    MinDeposit(Address),        // token -> i128 smallest accepted deposit
+    MinWithdrawal(Address),     // token -> i128 smallest accepted withdrawal short of the full balance
+    UsdToken,                   // Token subscription prices are quoted in
+    PriceOracle,                // OracleConfig pricing subscriptions paid in other tokens
+    SubscriptionPayment(Address), // user -> SubscriptionPayment for the paid time still ahead
+}
+
+/// Verification levels, from unverified to full KYC
+pub const LEVEL_NONE: u32 = 0;
+pub const LEVEL_EMAIL: u32 = 1;
+pub const LEVEL_KYC_LIGHT: u32 = 2;
+pub const LEVEL_KYC_FULL: u32 = 3;
+
+/// Length of the rolling window daily withdrawal limits apply to
+const WITHDRAWAL_WINDOW: u64 = 24 * 3600;
+
+/// Version of this code; `migrate` brings storage written by older versions up to it
+pub const CONTRACT_VERSION: u32 = 1;
+
+/// Layout of `UserProfile` written by this code
+///
+/// 1: plaintext email and `is_verified` flag (`LegacyUserProfile`)
+/// 2: salted email hash and verification levels
+pub const PROFILE_SCHEMA_VERSION: u32 = 2;
+
+/// Ledgers (~30 days at 5s each) persistent entries are kept alive for when bumped
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Remaining ledgers (~7 days) below which a touched entry gets bumped
+pub const TTL_THRESHOLD: u32 = 120_960;
+
+/// Default time a queued withdrawal waits for fraud review
+const DEFAULT_WITHDRAWAL_DELAY: u64 = 24 * 3600;
+
+/// Most profiles returned by one `get_users_page` or `get_user_profiles` call
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Most index entries one `get_users_page` call scans while filtering
+pub const MAX_PAGE_SCAN: u32 = 500;
+
+/// Fixed-point scale of interest indices
+const INTEREST_INDEX_SCALE: i128 = 1_000_000_000_000_000_000;
+
+/// Seconds interest rates are quoted over
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 3600;
+
+/// Most metadata fields one profile may carry
+pub const MAX_METADATA_ENTRIES: u32 = 16;
+
+/// Longest metadata value in bytes
+pub const MAX_METADATA_VALUE_LEN: u32 = 256;
+
+/// Decimals assumed for tokens whitelisted without them, such as the initial USDC
+pub const DEFAULT_TOKEN_DECIMALS: u32 = 6;
+
+/// Most decimals a whitelisted token may have
+pub const MAX_TOKEN_DECIMALS: u32 = 18;
+
+/// Balance operations kept per user; older ones are overwritten
+pub const BALANCE_HISTORY_LEN: u32 = 50;
+
+/// Deepest referral level counted and returned by `get_referral_tree`
+pub const MAX_REFERRAL_DEPTH: u32 = 5;
+
+/// Most nodes one `get_referral_tree` call returns
+pub const MAX_REFERRAL_TREE_SIZE: u32 = 200;
+
+/// Length bounds of custom referral codes
+pub const MIN_REFERRAL_CODE_LEN: u32 = 4;
+pub const MAX_REFERRAL_CODE_LEN: u32 = 16;
+
+/// Longest email address accepted when migrating plaintext emails (RFC 5321)
+const MAX_EMAIL_LEN: usize = 254;
+
+/// Basis-point denominator for loyalty multipliers (1x = 10_000 bps)
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Default loyalty multipliers for Basic, Premium and Enterprise (1x / 1.5x / 2x)
+const DEFAULT_LOYALTY_MULTIPLIERS: [u32; 3] = [10_000, 15_000, 20_000];
+
+/// Price per billing period of Basic, Premium and Enterprise (Free / 10 USDC / 50 USDC),
+/// in units of the USD token; other tokens pay the equivalent at oracle prices
+const SUBSCRIPTION_COSTS: [i128; 3] = [0, 10_000_000, 50_000_000];
+
+/// Default billing period of paid subscriptions
+const DEFAULT_BILLING_PERIOD: u64 = 30 * 24 * 3600;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    TimelockNotSet = 4,
+    UserExists = 5,
+    UserNotFound = 6,
+    InvalidUsername = 7,
+    InvalidEmail = 8,
+    NotWhitelisted = 9,
+    InvalidAmount = 10,
+    InsufficientBalance = 11,
+    InvalidTier = 12,
+    NotCreditSource = 13,
+    ComplianceRequired = 14,
+    Paused = 15,
+    InvalidReferralProgram = 16,
+    NotOperator = 17,
+    UsernameTaken = 18,
+    EmailSaltNotSet = 19,
+    AccountInactive = 20,
+    NonZeroBalance = 21,
+    UserFrozen = 22,
+    InvalidVerificationLevel = 23,
+    WithdrawalLimitExceeded = 24,
+    NotBalanceCaller = 25,
+    DailyLimitExceeded = 26,
+    WithdrawalRequestNotFound = 27,
+    WithdrawalLocked = 28,
+    MetadataTooLarge = 29,
+    InsufficientAllowance = 30,
+    SignatureExpired = 31,
+    InvalidNonce = 32,
+    InvalidReferralCode = 33,
+    ReferralCodeTaken = 34,
+    BatchTooLarge = 35,
+    BelowMinDeposit = 36,
+    BelowMinWithdrawal = 37,
+    PriceUnavailable = 38,
+    SubscriptionTokenMismatch = 39,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UserProfile {
+    pub user_address: Address,
+    pub username: String,
+    pub email_hash: BytesN<32>, // sha256(email salt || email)
+    pub created_at: u64,
+    pub is_active: bool,
+    pub verification_level: u32, // 0: None, 1: Email, 2: KYC-light, 3: KYC-full
+    pub verified_at: u64,        // When the level was last set or revoked
+    pub referral_code: String,
+    pub referred_by: Option<Address>,
+    pub total_spent: i128,
+    pub loyalty_points: u32,
+    pub subscription_tier: u32, // 0: Basic, 1: Premium, 2: Enterprise
+    pub subscription_expires_at: u64, // 0 while on Basic
+}
+
+/// What a user paid for their current subscription, so a downgrade refunds
+/// the unused part in the token it was paid in
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionPayment {
+    pub token: Address,
+    pub amount: i128,         // Paid for the time from `paid_from` to the subscription expiry
+    pub loyalty_points: u32,  // Points earned on `amount`
+    pub paid_from: u64,
+}
+
+/// Profile layout version 1, which stored the email in plaintext; read only when upgrading
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyUserProfile {
     pub user_address: Address,
     pub username: String,
     pub email: String,
@@ -24,7 +225,90 @@ pub struct UserProfile {
     pub referred_by: Option<Address>,
     pub total_spent: i128,
     pub loyalty_points: u32,
-    pub subscription_tier: u32, // 0: Basic, 1: Premium, 2: Enterprise
+    pub subscription_tier: u32,
+    pub subscription_expires_at: u64,
+}
+
+/// Criteria a profile must meet to be returned by `get_users_page`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserFilter {
+    pub verified_only: bool,
+    pub active_only: bool,
+    pub tier: Option<u32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserPage {
+    pub users: Vec<UserProfile>,
+    pub next_offset: Option<u32>,   // Where to resume; None once the index is exhausted
+}
+
+/// Price oracle settings for subscriptions paid in tokens other than the USD token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleConfig {
+    pub oracle: Address,        // SEP-40 oracle quoting USD prices
+    pub max_price_age: u64,     // Oldest oracle price accepted, in seconds
+}
+
+/// Withdrawal waiting out the review delay
+///
+/// The amount leaves the user's balance when the request is made and is paid
+/// out by `execute_withdrawal` once `unlocks_at` has passed, or returned to
+/// the balance if the admin cancels the request.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalRequest {
+    pub id: u64,
+    pub user_address: Address,
+    pub token_address: Address,
+    pub amount: i128,
+    pub requested_at: u64,
+    pub unlocks_at: u64,
+}
+
+/// Interest paid on idle balances of one token
+///
+/// `index` is the interest one unit of balance has earned since the pool was
+/// created, scaled by `INTEREST_INDEX_SCALE`; it grows linearly with time at
+/// `rate_bps` per year and is brought up to date before every balance change.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestPool {
+    pub rate_bps: u32,          // Simple annual rate
+    pub index: i128,
+    pub updated_at: u64,
+    pub total_claimed: i128,
+}
+
+/// Interest a user has earned on one token up to `index`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestCheckpoint {
+    pub index: i128,
+    pub accrued: i128,          // Earned but not yet claimed
+}
+
+/// Action a user can have a relayer submit for them
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MetaAction {
+    CreateProfile(String, BytesN<32>, Option<String>), // username, email hash, referral code
+    Deposit(Address, i128),                            // token, amount
+}
+
+/// Payload a user signs with their account key for `relay`
+///
+/// The signature covers the XDR of `(contract address, SignedAction)`, so a
+/// payload cannot be replayed against another deployment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignedAction {
+    pub action: MetaAction,
+    pub nonce: u64,             // Must equal `get_relay_nonce` of the signer
+    pub expiry: u64,            // Last ledger timestamp the payload is valid at
 }
 
 #[contracttype]
@@ -36,230 +320,1707 @@ pub struct PlatformStats {
     pub active_subscriptions: u32,
 }
 
+/// Reward paid to a referrer once their referee's total spend reaches `spend_threshold`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralMilestone {
+    pub spend_threshold: i128,
+    pub loyalty_points: u32,
+    pub token_bonus: i128,      // Credited to the referrer's balance in the program token
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralProgram {
+    pub token: Address,
+    pub milestones: Vec<ReferralMilestone>, // Ascending by spend_threshold
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralStats {
+    pub referrals: u32,
+    pub milestones_reached: u32,
+    pub points_earned: u32,
+    pub bonus_earned: i128,
+}
+
+/// Entry of a user's balance history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BalanceOperation {
+    pub kind: Symbol,           // Same reason as the `balance` event, e.g. deposit, deduct, sub_fee
+    pub token_address: Address,
+    pub amount: i128,           // Signed change, negative for debits
+    pub timestamp: u64,
+    pub counterparty: Option<Address>, // Contract or source on the other side, if any
+}
+
+/// One user in a referral tree
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralNode {
+    pub user_address: Address,
+    pub referrer: Address,
+    pub depth: u32,             // 1 for direct referees of the root
+}
+
 #[contract]
 pub struct UserProfileContract;
 
 impl UserProfileContract {
     fn assert_admin(env: &Env, invoker: &Address) {
-        let admin: Address = env.storage().persistent().get(&DataKey::Admin).unwrap();
+        let admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
         if invoker != &admin {
-            panic!("Only admin can perform this action");
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        invoker.require_auth();
+    }
+
+    // Operators cover day-to-day administration; the admin always qualifies
+    fn assert_operator(env: &Env, invoker: &Address) {
+        let admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        if invoker != &admin && !Self::is_operator(env.clone(), invoker.clone()) {
+            panic_with_error!(env, Error::NotOperator);
+        }
+        invoker.require_auth();
+    }
+
+    fn assert_timelock(env: &Env) {
+        let timelock: Address = env.storage().persistent()
+            .get(&DataKey::Timelock)
+            .unwrap_or_else(|| panic_with_error!(env, Error::TimelockNotSet));
+        timelock.require_auth();
+    }
+
+    fn assert_user_exists(env: &Env, user_address: &Address) {
+        if !env.storage().persistent().has(&DataKey::Profile(user_address.clone())) {
+            panic_with_error!(env, Error::UserNotFound);
+        }
+    }
+
+    fn assert_balance_caller(env: &Env, caller: &Address) {
+        caller.require_auth();
+        let callers: Map<Address, bool> = env.storage().persistent().get(&DataKey::BalanceCallers).unwrap_or(Map::new(env));
+        if !callers.get(caller.clone()).unwrap_or(false) {
+            panic_with_error!(env, Error::NotBalanceCaller);
+        }
+    }
+
+    fn assert_not_frozen(env: &Env, user_address: &Address) {
+        if Self::is_frozen(env.clone(), user_address.clone()) {
+            panic_with_error!(env, Error::UserFrozen);
+        }
+    }
+
+    fn assert_user_active(env: &Env, user_address: &Address) {
+        if !Self::load_profile(env, user_address).is_active {
+            panic_with_error!(env, Error::AccountInactive);
+        }
+    }
+
+    fn load_profile(env: &Env, user_address: &Address) -> UserProfile {
+        let profile = Self::read_profile(env, user_address)
+            .unwrap_or_else(|| panic_with_error!(env, Error::UserNotFound));
+        Self::bump(env, &DataKey::Profile(user_address.clone()));
+        Self::expire_subscription(env, profile)
+    }
+
+    // Read a stored profile, upgrading it first if it was written in an older layout
+    fn read_profile(env: &Env, user_address: &Address) -> Option<UserProfile> {
+        let key = DataKey::Profile(user_address.clone());
+        if !env.storage().persistent().has(&key) {
+            return None;
+        }
+        if Self::profile_schema(env, user_address) < PROFILE_SCHEMA_VERSION {
+            return Some(Self::upgrade_profile(env, user_address));
+        }
+        env.storage().persistent().get(&key)
+    }
+
+    // Layout version of a stored profile
+    // Profiles saved before the marker existed are told apart by their fields
+    fn profile_schema(env: &Env, user_address: &Address) -> u32 {
+        let marker = DataKey::ProfileSchema(user_address.clone());
+        if let Some(version) = env.storage().persistent().get(&marker) {
+            return version;
+        }
+        // Only version 1 entries carry an `email` field
+        let fields: Map<Symbol, Val> = env.storage().persistent()
+            .get(&DataKey::Profile(user_address.clone()))
+            .unwrap();
+        if fields.contains_key(symbol_short!("email")) {
+            1
+        } else {
+            env.storage().persistent().set(&marker, &PROFILE_SCHEMA_VERSION);
+            PROFILE_SCHEMA_VERSION
+        }
+    }
+
+    // Rewrite a stored profile in the current layout, one version step at a time
+    // New steps go below, each reading the previous layout and saving the next
+    fn upgrade_profile(env: &Env, user_address: &Address) -> UserProfile {
+        let key = DataKey::Profile(user_address.clone());
+        let from = Self::profile_schema(env, user_address);
+        if from < 2 {
+            let legacy: LegacyUserProfile = env.storage().persistent().get(&key).unwrap();
+            let profile = Self::upgrade_profile_v1(env, legacy);
+            Self::save_profile(env, &profile);
+        }
+        env.events().publish(
+            (symbol_short!("profile"), symbol_short!("upgraded"), user_address.clone()),
+            (from, PROFILE_SCHEMA_VERSION),
+        );
+        env.storage().persistent().get(&key).unwrap()
+    }
+
+    // Version 1 -> 2: hash the plaintext email with the salt and map the verified flag to a level
+    fn upgrade_profile_v1(env: &Env, legacy: LegacyUserProfile) -> UserProfile {
+        let salt: BytesN<32> = env.storage().persistent()
+            .get(&DataKey::EmailSalt)
+            .unwrap_or_else(|| panic_with_error!(env, Error::EmailSaltNotSet));
+        if legacy.email.len() as usize > MAX_EMAIL_LEN {
+            panic_with_error!(env, Error::InvalidEmail);
+        }
+
+        let mut buffer = [0u8; MAX_EMAIL_LEN];
+        let email = &mut buffer[..legacy.email.len() as usize];
+        legacy.email.copy_into_slice(email);
+        let mut preimage = Bytes::from_array(env, &salt.to_array());
+        preimage.extend_from_slice(email);
+
+        UserProfile {
+            user_address: legacy.user_address,
+            username: legacy.username,
+            email_hash: env.crypto().sha256(&preimage).into(),
+            created_at: legacy.created_at,
+            is_active: legacy.is_active,
+            verification_level: if legacy.is_verified { LEVEL_KYC_FULL } else { LEVEL_NONE },
+            verified_at: 0,
+            referral_code: legacy.referral_code,
+            referred_by: legacy.referred_by,
+            total_spent: legacy.total_spent,
+            loyalty_points: legacy.loyalty_points,
+            subscription_tier: legacy.subscription_tier,
+            subscription_expires_at: legacy.subscription_expires_at,
+        }
+    }
+
+    // Keep a persistent entry from being archived while it is in use
+    fn bump(env: &Env, key: &DataKey) {
+        env.storage().persistent().extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    // Bump a user's profile and username entries; false if the user has no profile
+    fn bump_profile(env: &Env, user_address: &Address) -> bool {
+        let key = DataKey::Profile(user_address.clone());
+        let profile = match Self::read_profile(env, user_address) {
+            Some(profile) => profile,
+            None => return false,
+        };
+        env.storage().persistent().extend_ttl(&key, TTL_EXTEND_TO, TTL_EXTEND_TO);
+        env.storage().persistent().extend_ttl(&DataKey::ProfileSchema(user_address.clone()), TTL_EXTEND_TO, TTL_EXTEND_TO);
+        let metadata = DataKey::ProfileMetadata(user_address.clone());
+        if env.storage().persistent().has(&metadata) {
+            env.storage().persistent().extend_ttl(&metadata, TTL_EXTEND_TO, TTL_EXTEND_TO);
+        }
+        let username = DataKey::Username(profile.username);
+        if env.storage().persistent().has(&username) {
+            env.storage().persistent().extend_ttl(&username, TTL_EXTEND_TO, TTL_EXTEND_TO);
+        }
+        true
+    }
+
+    // Downgrade a lapsed paid subscription to Basic the first time it is read
+    fn expire_subscription(env: &Env, mut profile: UserProfile) -> UserProfile {
+        if profile.subscription_tier == 0 || env.ledger().timestamp() < profile.subscription_expires_at {
+            return profile;
+        }
+
+        let old_tier = profile.subscription_tier;
+        profile.subscription_tier = 0;
+        profile.subscription_expires_at = 0;
+        Self::save_profile(env, &profile);
+        env.storage().persistent().remove(&DataKey::SubscriptionPayment(profile.user_address.clone()));
+
+        let mut stats: PlatformStats = env.storage().persistent().get(&DataKey::PlatformStats).unwrap();
+        stats.active_subscriptions -= 1;
+        env.storage().persistent().set(&DataKey::PlatformStats, &stats);
+
+        env.events().publish((symbol_short!("sub"), symbol_short!("expired"), profile.user_address.clone()), old_tier);
+        profile
+    }
+
+    // Price of a tier in `token_address` units
+    // Deployments from before the USD token was recorded charge the USD amounts in any token
+    fn subscription_price(env: &Env, tier: u32, token_address: &Address) -> i128 {
+        let usd_amount = SUBSCRIPTION_COSTS[tier as usize];
+        if !Self::is_token_whitelisted(env.clone(), token_address.clone()) {
+            panic_with_error!(env, Error::NotWhitelisted);
+        }
+        let usd_token: Address = match env.storage().persistent().get(&DataKey::UsdToken) {
+            Some(usd_token) => usd_token,
+            None => return usd_amount,
+        };
+        if usd_amount == 0 || *token_address == usd_token {
+            return usd_amount;
+        }
+
+        let config: OracleConfig = env.storage().persistent()
+            .get(&DataKey::PriceOracle)
+            .unwrap_or_else(|| panic_with_error!(env, Error::PriceUnavailable));
+        let oracle = PriceOracleClient::new(env, &config.oracle);
+        let price = oracle.lastprice(&Asset::Stellar(token_address.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, Error::PriceUnavailable));
+        if price.price <= 0 || env.ledger().timestamp() > price.timestamp + config.max_price_age {
+            panic_with_error!(env, Error::PriceUnavailable);
+        }
+
+        // Round up so paying in another token never undercuts the USD price
+        let usd_decimals = Self::get_token_decimals(env.clone(), usd_token);
+        let token_decimals = Self::get_token_decimals(env.clone(), token_address.clone());
+        let numerator = usd_amount
+            * 10i128.pow(oracle.decimals())
+            * 10i128.pow(token_decimals);
+        let denominator = price.price * 10i128.pow(usd_decimals);
+        (numerator + denominator - 1) / denominator
+    }
+
+    // Take a subscription payment from the user's internal balance
+    // The recorded subscription payment with the amount, and the points earned on it,
+    // that cover the paid time still ahead
+    fn unused_subscription(env: &Env, profile: &UserProfile) -> Option<(SubscriptionPayment, i128, u32)> {
+        let payment: SubscriptionPayment = env.storage().persistent()
+            .get(&DataKey::SubscriptionPayment(profile.user_address.clone()))?;
+        let span = profile.subscription_expires_at.saturating_sub(payment.paid_from) as i128;
+        if span == 0 {
+            return None;
+        }
+        let unused = profile.subscription_expires_at
+            .saturating_sub(env.ledger().timestamp().max(payment.paid_from)) as i128;
+        let amount = payment.amount * unused / span;
+        let points = (payment.loyalty_points as i128 * unused / span) as u32;
+        Some((payment, amount, points))
+    }
+
+    fn charge_subscription(env: &Env, user_address: &Address, token_address: &Address, cost: i128) {
+        if Self::get_user_balance(env.clone(), user_address.clone(), token_address.clone()) < cost {
+            panic_with_error!(env, Error::InsufficientBalance);
+        }
+        Self::change_balance(env, user_address, token_address, -cost, symbol_short!("sub_fee"), None);
+    }
+
+    // Apply a signed change to a user's internal balance and return the new balance
+    // Every balance mutation goes through here so the `balance` events form a complete
+    // ledger for off-chain reconciliation; callers check sufficiency beforehand
+    fn change_balance(
+        env: &Env,
+        user_address: &Address,
+        token_address: &Address,
+        delta: i128,
+        reason: Symbol,
+        counterparty: Option<Address>,
+    ) -> i128 {
+        Self::accrue_interest(env, user_address, token_address);
+        let mut user_balances: Map<(Address, Address), i128> = env.storage().persistent().get(&DataKey::UserBalances).unwrap();
+        let balance_key = (user_address.clone(), token_address.clone());
+        let new_balance = user_balances.get(balance_key.clone()).unwrap_or(0) + delta;
+        user_balances.set(balance_key, new_balance);
+        env.storage().persistent().set(&DataKey::UserBalances, &user_balances);
+        Self::bump(env, &DataKey::UserBalances);
+
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("balance"), user_address.clone()),
+            (token_address.clone(), delta, reason.clone(), new_balance),
+        );
+        Self::record_history(env, user_address, BalanceOperation {
+            kind: reason,
+            token_address: token_address.clone(),
+            amount: delta,
+            timestamp: env.ledger().timestamp(),
+            counterparty,
+        });
+        new_balance
+    }
+
+    // Append to the user's ring buffer of recent balance operations
+    fn record_history(env: &Env, user_address: &Address, operation: BalanceOperation) {
+        let count_key = DataKey::BalanceHistoryCount(user_address.clone());
+        let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        let slot = DataKey::BalanceHistory(user_address.clone(), count % BALANCE_HISTORY_LEN);
+        env.storage().persistent().set(&slot, &operation);
+        env.storage().persistent().set(&count_key, &(count + 1));
+        Self::bump(env, &slot);
+        Self::bump(env, &count_key);
+    }
+
+    fn save_profile(env: &Env, profile: &UserProfile) {
+        let key = DataKey::Profile(profile.user_address.clone());
+        let marker = DataKey::ProfileSchema(profile.user_address.clone());
+        env.storage().persistent().set(&key, profile);
+        env.storage().persistent().set(&marker, &PROFILE_SCHEMA_VERSION);
+        Self::bump(env, &key);
+        Self::bump(env, &marker);
+    }
+
+    // Emergency stop set by the admin; profile reads and edits stay available
+    fn assert_not_halted(env: &Env) {
+        if Self::is_paused(env.clone()) {
+            panic_with_error!(env, Error::Paused);
+        }
+    }
+
+    fn assert_not_paused(env: &Env, function: &str) {
+        if let Some(guardian) = env.storage().persistent().get::<_, Address>(&DataKey::PauseGuardian) {
+            let paused = GuardianClient::new(env, &guardian)
+                .is_paused(&env.current_contract_address(), &Symbol::new(env, function));
+            if paused {
+                panic_with_error!(env, Error::Paused);
+            }
+        }
+    }
+
+    fn assert_compliant(env: &Env, user_address: &Address, amount: i128) {
+        let policy: Option<(u32, i128)> = env.storage().persistent().get(&DataKey::CompliancePolicy);
+        if let Some((_, threshold)) = policy {
+            if amount >= threshold && !Self::is_user_verified(env.clone(), user_address.clone()) {
+                panic_with_error!(env, Error::ComplianceRequired);
+            }
+        }
+    }
+
+    // Pay the referrer every milestone the referee's spend has newly crossed
+    fn reward_referrer(env: &Env, referee: &UserProfile) {
+        let referrer = match &referee.referred_by {
+            Some(referrer) => referrer.clone(),
+            None => return,
+        };
+        let program: ReferralProgram = match env.storage().persistent().get(&DataKey::ReferralProgram) {
+            Some(program) => program,
+            None => return,
+        };
+
+        let milestone_key = DataKey::ReferralMilestone(referee.user_address.clone());
+        let paid: u32 = env.storage().persistent().get(&milestone_key).unwrap_or(0);
+        let mut reached = paid;
+        let mut points = 0u32;
+        let mut bonus = 0i128;
+        while let Some(milestone) = program.milestones.get(reached) {
+            if referee.total_spent < milestone.spend_threshold {
+                break;
+            }
+            points += milestone.loyalty_points;
+            bonus += milestone.token_bonus;
+            reached += 1;
+        }
+        if reached == paid {
+            return;
+        }
+        env.storage().persistent().set(&milestone_key, &reached);
+
+        let mut profile = Self::load_profile(env, &referrer);
+        profile.loyalty_points += points;
+        Self::save_profile(env, &profile);
+
+        if bonus > 0 {
+            Self::change_balance(env, &referrer, &program.token, bonus, symbol_short!("referral"), Some(referee.user_address.clone()));
+        }
+
+        let mut stats = Self::get_referral_stats(env.clone(), referrer.clone());
+        stats.milestones_reached += reached - paid;
+        stats.points_earned += points;
+        stats.bonus_earned += bonus;
+        env.storage().persistent().set(&DataKey::ReferralStats(referrer.clone()), &stats);
+
+        env.events().publish(
+            (symbol_short!("referral"), symbol_short!("reward"), referrer),
+            (referee.user_address.clone(), reached, points, program.token, bonus),
+        );
+    }
+
+    fn assert_email_hash(env: &Env, email_hash: &BytesN<32>) {
+        if email_hash.to_array() == [0u8; 32] {
+            panic_with_error!(env, Error::InvalidEmail);
+        }
+    }
+
+    fn claim_username(env: &Env, username: &String, user_address: &Address) {
+        let key = DataKey::Username(username.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(env, Error::UsernameTaken);
+        }
+        env.storage().persistent().set(&key, user_address);
+    }
+
+    fn apply_deposit(env: &Env, user_address: &Address, token_address: &Address, amount: i128) {
+        Self::assert_not_frozen(env, user_address);
+        Self::assert_user_active(env, user_address);
+        
+        // Check if token is whitelisted
+        if !Self::is_token_whitelisted(env.clone(), token_address.clone()) {
+            panic_with_error!(env, Error::NotWhitelisted);
+        }
+        if amount <= 0 {
+            panic_with_error!(env, Error::InvalidAmount);
+        }
+        if amount < Self::get_min_deposit(env.clone(), token_address.clone()) {
+            panic_with_error!(env, Error::BelowMinDeposit);
+        }
+
+        let new_balance = Self::change_balance(env, user_address, token_address, amount, symbol_short!("deposit"), None);
+
+        // Update platform stats
+        let mut stats: PlatformStats = env.storage().persistent().get(&DataKey::PlatformStats).unwrap();
+        stats.total_deposits += amount;
+        env.storage().persistent().set(&DataKey::PlatformStats, &stats);
+        Self::bump(env, &DataKey::PlatformStats);
+
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("deposit"), user_address.clone()),
+            (token_address.clone(), amount, new_balance),
+        );
+    }
+
+    fn apply_withdrawal(env: &Env, user_address: &Address, token_address: &Address, amount: i128) {
+        let new_balance = Self::debit_withdrawal(env, user_address, token_address, amount);
+        Self::settle_withdrawal(env, user_address, token_address, amount, new_balance);
+    }
+
+    // Run every withdrawal check and take the amount off the user's balance
+    fn debit_withdrawal(env: &Env, user_address: &Address, token_address: &Address, amount: i128) -> i128 {
+        Self::assert_not_frozen(env, user_address);
+        Self::assert_user_exists(env, user_address);
+        
+        let current_balance = Self::get_user_balance(env.clone(), user_address.clone(), token_address.clone());
+        if current_balance < amount {
+            panic_with_error!(env, Error::InsufficientBalance);
+        }
+        if amount <= 0 {
+            panic_with_error!(env, Error::InvalidAmount);
+        }
+        // Emptying the balance is always allowed so dust cannot get stuck
+        if amount < Self::get_min_withdrawal(env.clone(), token_address.clone()) && amount != current_balance {
+            panic_with_error!(env, Error::BelowMinWithdrawal);
+        }
+        Self::assert_compliant(env, user_address, amount);
+        let level = Self::get_verification_level(env.clone(), user_address.clone());
+        if let Some(limit) = Self::get_withdrawal_limit(env.clone(), level) {
+            if amount > limit {
+                panic_with_error!(env, Error::WithdrawalLimitExceeded);
+            }
+        }
+        Self::record_daily_withdrawal(env, user_address, token_address, level, amount);
+
+        Self::change_balance(env, user_address, token_address, -amount, symbol_short!("withdraw"), None)
+    }
+
+    // Count a debited withdrawal as paid out
+    fn settle_withdrawal(env: &Env, user_address: &Address, token_address: &Address, amount: i128, new_balance: i128) {
+        // Update platform stats
+        let mut stats: PlatformStats = env.storage().persistent().get(&DataKey::PlatformStats).unwrap();
+        stats.total_withdrawals += amount;
+        env.storage().persistent().set(&DataKey::PlatformStats, &stats);
+
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("withdraw"), user_address.clone()),
+            (token_address.clone(), amount, new_balance),
+        );
+    }
+
+    fn load_withdrawal_request(env: &Env, request_id: u64) -> WithdrawalRequest {
+        env.storage().persistent()
+            .get(&DataKey::WithdrawalRequest(request_id))
+            .unwrap_or_else(|| panic_with_error!(env, Error::WithdrawalRequestNotFound))
+    }
+
+    // Withdrawals of a user in a token still inside the rolling window
+    fn recent_withdrawals(env: &Env, user_address: &Address, token_address: &Address) -> Vec<(u64, i128)> {
+        let now = env.ledger().timestamp();
+        let window: Vec<(u64, i128)> = env.storage().persistent()
+            .get(&DataKey::WithdrawalWindow(user_address.clone(), token_address.clone()))
+            .unwrap_or(Vec::new(env));
+        let mut recent = Vec::new(env);
+        for (timestamp, amount) in window.iter() {
+            if timestamp + WITHDRAWAL_WINDOW > now {
+                recent.push_back((timestamp, amount));
+            }
+        }
+        recent
+    }
+
+    fn record_daily_withdrawal(env: &Env, user_address: &Address, token_address: &Address, level: u32, amount: i128) {
+        let limit = match Self::get_daily_withdrawal_limit(env.clone(), level) {
+            Some(limit) => limit,
+            None => return,
+        };
+
+        let mut recent = Self::recent_withdrawals(env, user_address, token_address);
+        let withdrawn: i128 = recent.iter().map(|(_, amount)| amount).sum();
+        if withdrawn + amount > limit {
+            panic_with_error!(env, Error::DailyLimitExceeded);
+        }
+
+        recent.push_back((env.ledger().timestamp(), amount));
+        env.storage().persistent().set(&DataKey::WithdrawalWindow(user_address.clone(), token_address.clone()), &recent);
+    }
+
+    // Give back the daily allowance a withdrawal recorded at `timestamp` used
+    fn release_daily_withdrawal(env: &Env, user_address: &Address, token_address: &Address, timestamp: u64, amount: i128) {
+        let mut recent = Self::recent_withdrawals(env, user_address, token_address);
+        if let Some(index) = recent.first_index_of((timestamp, amount)) {
+            recent.remove(index);
+            env.storage().persistent().set(&DataKey::WithdrawalWindow(user_address.clone(), token_address.clone()), &recent);
+        }
+    }
+
+    // Pool index as of now, without writing it
+    fn current_interest_index(env: &Env, pool: &InterestPool) -> i128 {
+        let elapsed = env.ledger().timestamp().saturating_sub(pool.updated_at) as i128;
+        pool.index + pool.rate_bps as i128 * elapsed * INTEREST_INDEX_SCALE
+            / (BPS_DENOMINATOR as i128 * SECONDS_PER_YEAR as i128)
+    }
+
+    // Interest checkpoint of a user brought up to `index`
+    fn interest_checkpoint(env: &Env, user_address: &Address, token_address: &Address, index: i128) -> InterestCheckpoint {
+        let mut checkpoint = env.storage().persistent()
+            .get(&DataKey::InterestCheckpoint(user_address.clone(), token_address.clone()))
+            .unwrap_or(InterestCheckpoint { index: 0, accrued: 0 });
+        let balance = Self::get_user_balance(env.clone(), user_address.clone(), token_address.clone());
+        checkpoint.accrued += balance * (index - checkpoint.index) / INTEREST_INDEX_SCALE;
+        checkpoint.index = index;
+        checkpoint
+    }
+
+    // Settle interest earned on the current balance; call before every balance change
+    fn accrue_interest(env: &Env, user_address: &Address, token_address: &Address) {
+        let mut pool: InterestPool = match env.storage().persistent().get(&DataKey::InterestPool(token_address.clone())) {
+            Some(pool) => pool,
+            None => return,
+        };
+        pool.index = Self::current_interest_index(env, &pool);
+        pool.updated_at = env.ledger().timestamp();
+        env.storage().persistent().set(&DataKey::InterestPool(token_address.clone()), &pool);
+
+        let checkpoint = Self::interest_checkpoint(env, user_address, token_address, pool.index);
+        env.storage().persistent().set(&DataKey::InterestCheckpoint(user_address.clone(), token_address.clone()), &checkpoint);
+    }
+
+    // Take a purchase off the user's balance and credit spending and loyalty points
+    // Returns false, changing nothing, if the balance is short
+    fn apply_spend(env: &Env, user_address: &Address, token_address: &Address, amount: i128, spender: &Address) -> bool {
+        if Self::get_user_balance(env.clone(), user_address.clone(), token_address.clone()) < amount {
+            return false;
+        }
+        let new_balance = Self::change_balance(env, user_address, token_address, -amount, symbol_short!("deduct"), Some(spender.clone()));
+
+        // Update user profile spending and loyalty points
+        let mut profile = Self::load_profile(env, user_address);
+        profile.total_spent += amount;
+        profile.loyalty_points += Self::calculate_loyalty_points(env, token_address, amount, profile.subscription_tier);
+        Self::save_profile(env, &profile);
+        Self::reward_referrer(env, &profile);
+
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("deduct"), user_address.clone()),
+            (token_address.clone(), amount, new_balance),
+        );
+        true
+    }
+
+    // Register a profile for a user whose authorization has already been checked
+    fn register_profile(
+        env: &Env,
+        user_address: Address,
+        username: String,
+        email_hash: BytesN<32>,
+        referral_code: Option<String>
+    ) -> String {
+        // Check if user already exists
+        if Self::user_exists(env.clone(), user_address.clone()) {
+            panic_with_error!(env, Error::UserExists);
+        }
+        
+        // Validate inputs
+        if username.is_empty() {
+            panic_with_error!(env, Error::InvalidUsername);
+        }
+        Self::assert_email_hash(env, &email_hash);
+        Self::claim_username(env, &username, &user_address);
+
+        let current_time = env.ledger().timestamp();
+        let user_referral_code = Self::generate_referral_code(env);
+        
+        let mut referred_by = None;
+        if let Some(ref_code) = referral_code {
+            let referral_map: Map<String, Address> = env.storage().persistent().get(&DataKey::ReferralSystem).unwrap();
+            referred_by = referral_map.get(ref_code);
+        }
+
+        let profile = UserProfile {
+            user_address: user_address.clone(),
+            username: username.clone(),
+            email_hash,
+            created_at: current_time,
+            is_active: true,
+            verification_level: LEVEL_NONE,
+            verified_at: 0,
+            referral_code: user_referral_code.clone(),
+            referred_by: referred_by.clone(),
+            total_spent: 0,
+            loyalty_points: 0,
+            subscription_tier: 0,
+            subscription_expires_at: 0,
+        };
+
+        Self::save_profile(env, &profile);
+
+        // Index the user for enumeration
+        let count: u32 = env.storage().persistent().get(&DataKey::UserCount).unwrap_or(0);
+        env.storage().persistent().set(&DataKey::UserIndex(count), &user_address);
+        env.storage().persistent().set(&DataKey::UserCount, &(count + 1));
+
+        // Store referral mapping
+        let mut referral_map: Map<String, Address> = env.storage().persistent().get(&DataKey::ReferralSystem).unwrap();
+        referral_map.set(user_referral_code.clone(), user_address.clone());
+        env.storage().persistent().set(&DataKey::ReferralSystem, &referral_map);
+
+        if let Some(referrer) = referred_by.clone() {
+            let mut referral_stats = Self::get_referral_stats(env.clone(), referrer.clone());
+            referral_stats.referrals += 1;
+            env.storage().persistent().set(&DataKey::ReferralStats(referrer.clone()), &referral_stats);
+            Self::record_referee(env, &referrer, &user_address);
+        }
+
+        // Update platform stats
+        let mut stats: PlatformStats = env.storage().persistent().get(&DataKey::PlatformStats).unwrap();
+        stats.total_users += 1;
+        env.storage().persistent().set(&DataKey::PlatformStats, &stats);
+
+        env.events().publish(
+            (symbol_short!("profile"), symbol_short!("created"), user_address),
+            (username, user_referral_code.clone(), referred_by),
+        );
+        user_referral_code
+    }
+
+    // Stellar account address (G... strkey) controlled by an Ed25519 public key
+    fn account_address(env: &Env, public_key: &BytesN<32>) -> Address {
+        const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+        // Version byte for account IDs, key, then CRC16-XModem of both, little endian
+        let mut raw = [0u8; 35];
+        raw[0] = 6 << 3;
+        raw[1..33].copy_from_slice(&public_key.to_array());
+        let mut crc: u16 = 0;
+        for byte in &raw[..33] {
+            crc ^= (*byte as u16) << 8;
+            for _ in 0..8 {
+                crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+            }
+        }
+        raw[33..].copy_from_slice(&crc.to_le_bytes());
+
+        // Base32, 8 characters per 5 bytes
+        let mut strkey = [0u8; 56];
+        for (group, chunk) in raw.chunks(5).enumerate() {
+            let bits = chunk.iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+            for i in 0..8 {
+                strkey[group * 8 + i] = ALPHABET[((bits >> (35 - 5 * i)) & 31) as usize];
+            }
+        }
+        Address::from_string_bytes(&Bytes::from_array(env, &strkey))
+    }
+
+    // Add a new referee to its referrer's list and to the level counts of every upline
+    fn record_referee(env: &Env, referrer: &Address, referee: &Address) {
+        let mut referees = Self::get_referees(env.clone(), referrer.clone());
+        referees.push_back(referee.clone());
+        env.storage().persistent().set(&DataKey::Referees(referrer.clone()), &referees);
+
+        let mut upline = Some(referrer.clone());
+        for level in 0..MAX_REFERRAL_DEPTH {
+            let ancestor = match upline {
+                Some(ancestor) => ancestor,
+                None => break,
+            };
+            let mut counts = Self::get_referral_counts(env.clone(), ancestor.clone());
+            while counts.len() <= level {
+                counts.push_back(0);
+            }
+            counts.set(level, counts.get(level).unwrap() + 1);
+            env.storage().persistent().set(&DataKey::ReferralCounts(ancestor.clone()), &counts);
+            upline = Self::read_profile(env, &ancestor).and_then(|profile| profile.referred_by);
+        }
+    }
+
+    fn generate_referral_code(env: &Env) -> String {
+        // Simple referral code generation - in production, this would be more sophisticated
+        String::from_str(env, "NF123456")
+    }
+
+    fn calculate_loyalty_points(env: &Env, token_address: &Address, amount: i128, tier: u32) -> u32 {
+        // 1 point per whole token spent, scaled by the tier's multiplier
+        let multiplier = Self::get_loyalty_multiplier(env.clone(), tier) as i128;
+        let unit = 10i128.pow(Self::get_token_decimals(env.clone(), token_address.clone()));
+        (amount * multiplier / BPS_DENOMINATOR as i128 / unit) as u32
+    }
+}
+
+#[contractimpl]
+impl UserProfileContract {
+    // Initialize contract
+    pub fn initialize(env: Env, admin: Address, usdc_token: Address) {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::Version, &CONTRACT_VERSION);
+        env.storage().persistent().set(&DataKey::UsdToken, &usdc_token);
+        env.storage().persistent().set(&DataKey::UserBalances, &Map::<(Address, Address), i128>::new(&env));
+        env.storage().persistent().set(&DataKey::WhitelistedTokens, &Map::<Address, bool>::new(&env));
+        env.storage().persistent().set(&DataKey::ReferralSystem, &Map::<String, Address>::new(&env));
+        
+        // Initialize platform stats
+        let stats = PlatformStats {
+            total_users: 0,
+            total_deposits: 0,
+            total_withdrawals: 0,
+            active_subscriptions: 0,
+        };
+        env.storage().persistent().set(&DataKey::PlatformStats, &stats);
+
+        // Whitelist USDC by default
+        let mut whitelisted_tokens: Map<Address, bool> = env.storage().persistent().get(&DataKey::WhitelistedTokens).unwrap();
+        whitelisted_tokens.set(usdc_token.clone(), true);
+        env.storage().persistent().set(&DataKey::WhitelistedTokens, &whitelisted_tokens);
+
+        env.events().publish((symbol_short!("config"), symbol_short!("init")), (admin, usdc_token));
+    }
+
+    // Replace the contract code, keeping storage (admin only)
+    // Call `migrate` from the new code afterwards to upgrade storage it reads differently
+    pub fn upgrade(env: Env, invoker: Address, new_wasm_hash: BytesN<32>) {
+        Self::assert_admin(&env, &invoker);
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish((symbol_short!("config"), symbol_short!("upgrade")), new_wasm_hash);
+    }
+
+    // Run the storage migrations between the stored version and this code's (admin only)
+    pub fn migrate(env: Env, invoker: Address) -> u32 {
+        Self::assert_admin(&env, &invoker);
+        let from = Self::get_version(env.clone());
+        // Migration steps go here, one `if from < N` block per version that needs one
+        if from < CONTRACT_VERSION {
+            env.storage().persistent().set(&DataKey::Version, &CONTRACT_VERSION);
+            env.events().publish((symbol_short!("config"), symbol_short!("migrate")), (from, CONTRACT_VERSION));
+        }
+        CONTRACT_VERSION
+    }
+
+    // Version storage was last migrated to; deployments from before versioning count as 1
+    pub fn get_version(env: Env) -> u32 {
+        env.storage().persistent().get(&DataKey::Version).unwrap_or(1)
+    }
+
+    // Stop every balance and subscription change until `unpause` (admin only)
+    pub fn pause(env: Env, invoker: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::Paused, &true);
+        env.events().publish((symbol_short!("config"), symbol_short!("paused")), invoker);
+    }
+
+    pub fn unpause(env: Env, invoker: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().remove(&DataKey::Paused);
+        env.events().publish((symbol_short!("config"), symbol_short!("unpaused")), invoker);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().persistent().get(&DataKey::Paused).unwrap_or(false)
+    }
+
+    // Set the timelock that authorizes admin rotations (admin once, then timelock only)
+    pub fn set_timelock(env: Env, invoker: Address, timelock: Address) {
+        if env.storage().persistent().has(&DataKey::Timelock) {
+            Self::assert_timelock(&env);
+        } else {
+            Self::assert_admin(&env, &invoker);
+        }
+        env.storage().persistent().set(&DataKey::Timelock, &timelock);
+        env.events().publish((symbol_short!("config"), symbol_short!("timelock")), timelock);
+    }
+
+    // Rotate the admin key (timelock only)
+    pub fn set_admin(env: Env, new_admin: Address) {
+        Self::assert_timelock(&env);
+        env.storage().persistent().set(&DataKey::Admin, &new_admin);
+        env.events().publish((symbol_short!("config"), symbol_short!("admin")), new_admin);
+    }
+
+    // Grant the operator role, e.g. to a backend hot wallet (admin only)
+    pub fn add_operator(env: Env, invoker: Address, operator: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::Operator(operator.clone()), &true);
+        env.events().publish((symbol_short!("config"), symbol_short!("operator"), operator), true);
+    }
+
+    // Revoke the operator role (admin only)
+    pub fn remove_operator(env: Env, invoker: Address, operator: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().remove(&DataKey::Operator(operator.clone()));
+        env.events().publish((symbol_short!("config"), symbol_short!("operator"), operator), false);
+    }
+
+    pub fn is_operator(env: Env, address: Address) -> bool {
+        env.storage().persistent().get(&DataKey::Operator(address)).unwrap_or(false)
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+    }
+
+    // User Management
+    pub fn create_user_profile(
+        env: Env, 
+        user_address: Address, 
+        username: String, 
+        email_hash: BytesN<32>,
+        referral_code: Option<String>
+    ) -> String {
+        Self::assert_not_paused(&env, "create_user_profile");
+        user_address.require_auth();
+        Self::register_profile(&env, user_address, username, email_hash, referral_code)
+    }
+
+    pub fn update_user_profile(
+        env: Env,
+        user_address: Address,
+        username: Option<String>,
+        email_hash: Option<BytesN<32>>
+    ) {
+        Self::assert_not_paused(&env, "update_user_profile");
+        user_address.require_auth();
+        Self::assert_user_active(&env, &user_address);
+        
+        let mut profile = Self::load_profile(&env, &user_address);
+
+        if let Some(new_username) = username {
+            if new_username.is_empty() {
+                panic_with_error!(&env, Error::InvalidUsername);
+            }
+            if new_username != profile.username {
+                Self::claim_username(&env, &new_username, &user_address);
+                env.storage().persistent().remove(&DataKey::Username(profile.username.clone()));
+            }
+            profile.username = new_username;
+        }
+
+        if let Some(new_email_hash) = email_hash {
+            Self::assert_email_hash(&env, &new_email_hash);
+            profile.email_hash = new_email_hash;
+        }
+
+        Self::save_profile(&env, &profile);
+
+        env.events().publish((symbol_short!("profile"), symbol_short!("updated"), user_address), profile.username);
+    }
+
+    // Close the account; every internal balance must be withdrawn first
+    pub fn deactivate_account(env: Env, user_address: Address) {
+        user_address.require_auth();
+        let mut profile = Self::load_profile(&env, &user_address);
+        if !profile.is_active {
+            panic_with_error!(&env, Error::AccountInactive);
+        }
+
+        let whitelisted_tokens: Map<Address, bool> = env.storage().persistent().get(&DataKey::WhitelistedTokens).unwrap();
+        for token_address in whitelisted_tokens.keys().iter() {
+            if Self::get_user_balance(env.clone(), user_address.clone(), token_address) != 0 {
+                panic_with_error!(&env, Error::NonZeroBalance);
+            }
+        }
+
+        profile.is_active = false;
+        Self::save_profile(&env, &profile);
+        env.events().publish((symbol_short!("profile"), symbol_short!("closed"), user_address), ());
+    }
+
+    // Reopen a deactivated account that has not been purged
+    pub fn reactivate_account(env: Env, user_address: Address) {
+        user_address.require_auth();
+        let mut profile = Self::load_profile(&env, &user_address);
+        if profile.is_active || profile.username.is_empty() {
+            panic_with_error!(&env, Error::AccountInactive);
+        }
+
+        profile.is_active = true;
+        Self::save_profile(&env, &profile);
+        env.events().publish((symbol_short!("profile"), symbol_short!("reopened"), user_address), ());
+    }
+
+    // Erase the personal data of a deactivated account (admin only)
+    // Spend, loyalty and subscription history stay for accounting; the account cannot be reopened
+    pub fn purge_profile(env: Env, invoker: Address, user_address: Address) {
+        Self::assert_admin(&env, &invoker);
+        let mut profile = Self::load_profile(&env, &user_address);
+        if profile.is_active {
+            panic_with_error!(&env, Error::AccountInactive);
+        }
+
+        env.storage().persistent().remove(&DataKey::Username(profile.username.clone()));
+        env.storage().persistent().remove(&DataKey::ProfileMetadata(user_address.clone()));
+        let mut referral_map: Map<String, Address> = env.storage().persistent().get(&DataKey::ReferralSystem).unwrap();
+        let mut codes = Self::get_referral_aliases(env.clone(), user_address.clone());
+        codes.push_back(profile.referral_code.clone());
+        for code in codes.iter() {
+            if referral_map.get(code.clone()) == Some(user_address.clone()) {
+                referral_map.remove(code);
+            }
+        }
+        env.storage().persistent().set(&DataKey::ReferralSystem, &referral_map);
+        env.storage().persistent().remove(&DataKey::ReferralAliases(user_address.clone()));
+
+        profile.username = String::from_str(&env, "");
+        profile.email_hash = BytesN::from_array(&env, &[0u8; 32]);
+        profile.referral_code = String::from_str(&env, "");
+        profile.verification_level = LEVEL_NONE;
+        profile.verified_at = env.ledger().timestamp();
+        Self::save_profile(&env, &profile);
+        env.events().publish((symbol_short!("profile"), symbol_short!("purged"), user_address), invoker);
+    }
+
+    // Block a compromised or fraudulent account from moving funds (admin only)
+    pub fn freeze_user(env: Env, invoker: Address, user_address: Address) {
+        Self::assert_admin(&env, &invoker);
+        Self::assert_user_exists(&env, &user_address);
+        env.storage().persistent().set(&DataKey::Frozen(user_address.clone()), &true);
+        env.events().publish((symbol_short!("profile"), symbol_short!("frozen"), user_address), true);
+    }
+
+    pub fn unfreeze_user(env: Env, invoker: Address, user_address: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().remove(&DataKey::Frozen(user_address.clone()));
+        env.events().publish((symbol_short!("profile"), symbol_short!("frozen"), user_address), false);
+    }
+
+    pub fn is_frozen(env: Env, user_address: Address) -> bool {
+        env.storage().persistent().get(&DataKey::Frozen(user_address)).unwrap_or(false)
+    }
+
+    // Mark a user fully KYC-verified
+    pub fn verify_user(env: Env, invoker: Address, user_address: Address) {
+        Self::set_verification_level(env, invoker, user_address, LEVEL_KYC_FULL);
+    }
+
+    // Set a user's verification level, stamping when it changed (admin or operator)
+    pub fn set_verification_level(env: Env, invoker: Address, user_address: Address, level: u32) {
+        Self::assert_operator(&env, &invoker);
+        if level > LEVEL_KYC_FULL {
+            panic_with_error!(&env, Error::InvalidVerificationLevel);
+        }
+
+        let mut profile = Self::load_profile(&env, &user_address);
+        profile.verification_level = level;
+        profile.verified_at = env.ledger().timestamp();
+        Self::save_profile(&env, &profile);
+
+        env.events().publish((symbol_short!("profile"), symbol_short!("verified"), user_address), (level, invoker));
+    }
+
+    // Drop a user back to unverified (admin or operator)
+    pub fn revoke_verification(env: Env, invoker: Address, user_address: Address) {
+        Self::set_verification_level(env, invoker, user_address, LEVEL_NONE);
+    }
+
+    pub fn get_verification_level(env: Env, user_address: Address) -> u32 {
+        Self::get_user_profile(env, user_address)
+            .map(|profile| profile.verification_level)
+            .unwrap_or(LEVEL_NONE)
+    }
+
+    // Cap single withdrawals of users at a verification level (admin only)
+    pub fn set_withdrawal_limit(env: Env, invoker: Address, level: u32, limit: i128) {
+        Self::assert_admin(&env, &invoker);
+        if level > LEVEL_KYC_FULL {
+            panic_with_error!(&env, Error::InvalidVerificationLevel);
+        }
+        if limit < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        env.storage().persistent().set(&DataKey::WithdrawalLimit(level), &limit);
+        env.events().publish((symbol_short!("config"), symbol_short!("wd_limit"), level), limit);
+    }
+
+    // Cap what users at a verification level may withdraw per token in any 24h window (admin only)
+    pub fn set_daily_withdrawal_limit(env: Env, invoker: Address, level: u32, limit: i128) {
+        Self::assert_admin(&env, &invoker);
+        if level > LEVEL_KYC_FULL {
+            panic_with_error!(&env, Error::InvalidVerificationLevel);
+        }
+        if limit < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        env.storage().persistent().set(&DataKey::DailyWithdrawalLimit(level), &limit);
+        env.events().publish((symbol_short!("config"), symbol_short!("day_limit"), level), limit);
+    }
+
+    pub fn get_daily_withdrawal_limit(env: Env, level: u32) -> Option<i128> {
+        env.storage().persistent().get(&DataKey::DailyWithdrawalLimit(level))
+    }
+
+    // What the user may still withdraw in a token within the current 24h window; None means unlimited
+    pub fn get_remaining_daily_limit(env: Env, user_address: Address, token_address: Address) -> Option<i128> {
+        let level = Self::get_verification_level(env.clone(), user_address.clone());
+        let limit = Self::get_daily_withdrawal_limit(env.clone(), level)?;
+        let withdrawn: i128 = Self::recent_withdrawals(&env, &user_address, &token_address)
+            .iter()
+            .map(|(_, amount)| amount)
+            .sum();
+        Some((limit - withdrawn).max(0))
+    }
+
+    // Largest single withdrawal allowed at a level; None means unlimited
+    pub fn get_withdrawal_limit(env: Env, level: u32) -> Option<i128> {
+        env.storage().persistent().get(&DataKey::WithdrawalLimit(level))
+    }
+
+    // Delegate verification to the compliance registry (admin only)
+    // Withdrawals of at least `threshold` then need an attestation of `min_level`
+    pub fn set_compliance_contract(env: Env, invoker: Address, compliance: Address, min_level: u32, threshold: i128) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::ComplianceContract, &compliance);
+        env.storage().persistent().set(&DataKey::CompliancePolicy, &(min_level, threshold));
+        env.events().publish((symbol_short!("config"), symbol_short!("kyc")), (compliance, min_level, threshold));
+    }
+
+    // Set the pause guardian; refunds stay available while paused (admin only)
+    pub fn set_pause_guardian(env: Env, invoker: Address, guardian: Address) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::PauseGuardian, &guardian);
+        env.events().publish((symbol_short!("config"), symbol_short!("guardian")), guardian);
+    }
+
+    // Check if user is verified: by the compliance registry when set, else by holding at least KYC-light
+    pub fn is_user_verified(env: Env, user_address: Address) -> bool {
+        let compliance: Option<Address> = env.storage().persistent().get(&DataKey::ComplianceContract);
+        match compliance {
+            Some(compliance) => {
+                let (min_level, _): (u32, i128) = env.storage().persistent().get(&DataKey::CompliancePolicy).unwrap();
+                ComplianceClient::new(&env, &compliance).is_compliant(&user_address, &min_level)
+            }
+            None => Self::get_verification_level(env, user_address) >= LEVEL_KYC_LIGHT,
+        }
+    }
+
+    // Wallet Management
+    pub fn deposit_funds(
+        env: Env,
+        user_address: Address,
+        token_address: Address,
+        amount: i128
+    ) {
+        Self::assert_not_paused(&env, "deposit_funds");
+        Self::assert_not_halted(&env);
+        user_address.require_auth();
+        Self::apply_deposit(&env, &user_address, &token_address, amount);
+    }
+
+    // Execute an action signed by a user's account key, letting a relayer pay the fees
+    // The signer's profile address is the account of `public_key`
+    pub fn relay(env: Env, public_key: BytesN<32>, payload: SignedAction, signature: BytesN<64>) {
+        if env.ledger().timestamp() > payload.expiry {
+            panic_with_error!(&env, Error::SignatureExpired);
+        }
+        let user_address = Self::account_address(&env, &public_key);
+        let nonce = Self::get_relay_nonce(env.clone(), user_address.clone());
+        if payload.nonce != nonce {
+            panic_with_error!(&env, Error::InvalidNonce);
+        }
+        let message = (env.current_contract_address(), payload.clone()).to_xdr(&env);
+        env.crypto().ed25519_verify(&public_key, &message, &signature);
+        env.storage().persistent().set(&DataKey::RelayNonce(user_address.clone()), &(nonce + 1));
+
+        match payload.action {
+            MetaAction::CreateProfile(username, email_hash, referral_code) => {
+                Self::assert_not_paused(&env, "create_user_profile");
+                Self::register_profile(&env, user_address.clone(), username, email_hash, referral_code);
+            }
+            MetaAction::Deposit(token_address, amount) => {
+                Self::assert_not_paused(&env, "deposit_funds");
+                Self::assert_not_halted(&env);
+                Self::apply_deposit(&env, &user_address, &token_address, amount);
+            }
+        }
+        env.events().publish((symbol_short!("wallet"), symbol_short!("relayed"), user_address), nonce);
+    }
+
+    pub fn get_relay_nonce(env: Env, user_address: Address) -> u64 {
+        env.storage().persistent().get(&DataKey::RelayNonce(user_address)).unwrap_or(0)
+    }
+
+    pub fn withdraw_funds(
+        env: Env,
+        user_address: Address,
+        token_address: Address,
+        amount: i128
+    ) {
+        Self::assert_not_paused(&env, "withdraw_funds");
+        Self::assert_not_halted(&env);
+        user_address.require_auth();
+        Self::apply_withdrawal(&env, &user_address, &token_address, amount);
+    }
+
+    // Queue a withdrawal for fraud review; the amount is locked until executed or cancelled
+    pub fn request_withdrawal(env: Env, user_address: Address, token_address: Address, amount: i128) -> u64 {
+        Self::assert_not_paused(&env, "withdraw_funds");
+        Self::assert_not_halted(&env);
+        user_address.require_auth();
+        Self::debit_withdrawal(&env, &user_address, &token_address, amount);
+
+        let id = env.storage().persistent().get(&DataKey::WithdrawalRequestCounter).unwrap_or(0u64) + 1;
+        env.storage().persistent().set(&DataKey::WithdrawalRequestCounter, &id);
+
+        let now = env.ledger().timestamp();
+        let request = WithdrawalRequest {
+            id,
+            user_address: user_address.clone(),
+            token_address: token_address.clone(),
+            amount,
+            requested_at: now,
+            unlocks_at: now + Self::get_withdrawal_delay(env.clone()),
+        };
+        env.storage().persistent().set(&DataKey::WithdrawalRequest(id), &request);
+
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("wd_queued"), user_address),
+            (id, token_address, amount, request.unlocks_at),
+        );
+        id
+    }
+
+    // Pay out a queued withdrawal once its delay has passed
+    pub fn execute_withdrawal(env: Env, user_address: Address, request_id: u64) {
+        Self::assert_not_paused(&env, "withdraw_funds");
+        Self::assert_not_halted(&env);
+        user_address.require_auth();
+        let request = Self::load_withdrawal_request(&env, request_id);
+        if request.user_address != user_address {
+            panic_with_error!(&env, Error::WithdrawalRequestNotFound);
+        }
+        Self::assert_not_frozen(&env, &user_address);
+        if env.ledger().timestamp() < request.unlocks_at {
+            panic_with_error!(&env, Error::WithdrawalLocked);
+        }
+
+        env.storage().persistent().remove(&DataKey::WithdrawalRequest(request_id));
+        let balance = Self::get_user_balance(env.clone(), user_address.clone(), request.token_address.clone());
+        Self::settle_withdrawal(&env, &user_address, &request.token_address, request.amount, balance);
+    }
+
+    // Drop a queued withdrawal of a flagged account and return the amount to its balance
+    // and daily allowance (admin only)
+    pub fn cancel_withdrawal_request(env: Env, invoker: Address, request_id: u64) {
+        Self::assert_admin(&env, &invoker);
+        let request = Self::load_withdrawal_request(&env, request_id);
+        env.storage().persistent().remove(&DataKey::WithdrawalRequest(request_id));
+        Self::release_daily_withdrawal(&env, &request.user_address, &request.token_address, request.requested_at, request.amount);
+
+        Self::change_balance(&env, &request.user_address, &request.token_address, request.amount, symbol_short!("wd_cancel"), None);
+
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("wd_cancel"), request.user_address),
+            (request_id, request.token_address, request.amount),
+        );
+    }
+
+    pub fn get_withdrawal_request(env: Env, request_id: u64) -> Option<WithdrawalRequest> {
+        env.storage().persistent().get(&DataKey::WithdrawalRequest(request_id))
+    }
+
+    // Set how long queued withdrawals stay locked (admin only)
+    pub fn set_withdrawal_delay(env: Env, invoker: Address, delay: u64) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::WithdrawalDelay, &delay);
+        env.events().publish((symbol_short!("config"), symbol_short!("wd_delay")), delay);
+    }
+
+    pub fn get_withdrawal_delay(env: Env) -> u64 {
+        env.storage().persistent().get(&DataKey::WithdrawalDelay).unwrap_or(DEFAULT_WITHDRAWAL_DELAY)
+    }
+
+    // Set the annual interest rate paid on idle balances of a token (admin only)
+    // Interest earned so far keeps the old rate
+    pub fn set_interest_rate(env: Env, invoker: Address, token_address: Address, rate_bps: u32) {
+        Self::assert_admin(&env, &invoker);
+        if !Self::is_token_whitelisted(env.clone(), token_address.clone()) {
+            panic_with_error!(&env, Error::NotWhitelisted);
+        }
+
+        let key = DataKey::InterestPool(token_address.clone());
+        let now = env.ledger().timestamp();
+        let mut pool = match env.storage().persistent().get::<_, InterestPool>(&key) {
+            Some(pool) => InterestPool { index: Self::current_interest_index(&env, &pool), ..pool },
+            None => InterestPool { rate_bps: 0, index: 0, updated_at: now, total_claimed: 0 },
+        };
+        pool.rate_bps = rate_bps;
+        pool.updated_at = now;
+        env.storage().persistent().set(&key, &pool);
+        env.events().publish((symbol_short!("config"), symbol_short!("interest"), token_address), rate_bps);
+    }
+
+    pub fn get_interest_pool(env: Env, token_address: Address) -> Option<InterestPool> {
+        env.storage().persistent().get(&DataKey::InterestPool(token_address))
+    }
+
+    // Interest a user could claim in a token right now
+    pub fn get_accrued_interest(env: Env, user_address: Address, token_address: Address) -> i128 {
+        let pool: InterestPool = match env.storage().persistent().get(&DataKey::InterestPool(token_address.clone())) {
+            Some(pool) => pool,
+            None => return 0,
+        };
+        let index = Self::current_interest_index(&env, &pool);
+        Self::interest_checkpoint(&env, &user_address, &token_address, index).accrued
+    }
+
+    // Credit the interest earned in a token to the user's balance; returns the amount
+    pub fn claim_interest(env: Env, user_address: Address, token_address: Address) -> i128 {
+        Self::assert_not_halted(&env);
+        user_address.require_auth();
+        Self::assert_not_frozen(&env, &user_address);
+        Self::accrue_interest(&env, &user_address, &token_address);
+
+        let checkpoint_key = DataKey::InterestCheckpoint(user_address.clone(), token_address.clone());
+        let mut checkpoint: InterestCheckpoint = match env.storage().persistent().get(&checkpoint_key) {
+            Some(checkpoint) => checkpoint,
+            None => return 0,
+        };
+        let amount = checkpoint.accrued;
+        if amount == 0 {
+            return 0;
+        }
+        checkpoint.accrued = 0;
+        env.storage().persistent().set(&checkpoint_key, &checkpoint);
+
+        let new_balance = Self::change_balance(&env, &user_address, &token_address, amount, symbol_short!("interest"), None);
+
+        let mut pool: InterestPool = env.storage().persistent().get(&DataKey::InterestPool(token_address.clone())).unwrap();
+        pool.total_claimed += amount;
+        env.storage().persistent().set(&DataKey::InterestPool(token_address.clone()), &pool);
+
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("interest"), user_address),
+            (token_address, amount, new_balance),
+        );
+        amount
+    }
+
+    // Settle many (user, token, amount) deposits at once for the custodial backend (admin or operator)
+    // Every entry gets the same checks and event as `deposit_funds`; one failure reverts the batch
+    pub fn batch_deposit(env: Env, invoker: Address, entries: Vec<(Address, Address, i128)>) {
+        Self::assert_not_paused(&env, "deposit_funds");
+        Self::assert_not_halted(&env);
+        Self::assert_operator(&env, &invoker);
+        for (user_address, token_address, amount) in entries.iter() {
+            Self::apply_deposit(&env, &user_address, &token_address, amount);
+        }
+    }
+
+    // Settle many (user, token, amount) withdrawals at once for the custodial backend (admin or operator)
+    pub fn batch_withdraw(env: Env, invoker: Address, entries: Vec<(Address, Address, i128)>) {
+        Self::assert_not_paused(&env, "withdraw_funds");
+        Self::assert_not_halted(&env);
+        Self::assert_operator(&env, &invoker);
+        for (user_address, token_address, amount) in entries.iter() {
+            Self::apply_withdrawal(&env, &user_address, &token_address, amount);
+        }
+    }
+
+    pub fn get_user_balance(env: Env, user_address: Address, token_address: Address) -> i128 {
+        let user_balances: Map<(Address, Address), i128> = env.storage().persistent().get(&DataKey::UserBalances).unwrap();
+        let balance_key = (user_address, token_address);
+        user_balances.get(balance_key).unwrap_or(0)
+    }
+
+    // Get user profile
+    pub fn get_user_profile(env: Env, user_address: Address) -> Option<UserProfile> {
+        Self::read_profile(&env, &user_address)
+            .map(|profile| Self::expire_subscription(&env, profile))
+    }
+
+    // Current subscription tier of a user (0 for Basic, lapsed or unknown users)
+    pub fn get_subscription_tier(env: Env, user_address: Address) -> u32 {
+        Self::get_user_profile(env, user_address).map_or(0, |profile| profile.subscription_tier)
+    }
+
+    // Loyalty points of a user (0 for unknown users)
+    pub fn get_loyalty_points(env: Env, user_address: Address) -> u32 {
+        Self::read_profile(&env, &user_address).map_or(0, |profile| profile.loyalty_points)
+    }
+
+    // Recent balance operations of a user, newest first; `offset` 0 is the latest
+    // Only the last `BALANCE_HISTORY_LEN` operations are kept
+    pub fn get_balance_history(env: Env, user_address: Address, offset: u32, limit: u32) -> Vec<BalanceOperation> {
+        let count: u32 = env.storage().persistent()
+            .get(&DataKey::BalanceHistoryCount(user_address.clone()))
+            .unwrap_or(0);
+        let retained = count.min(BALANCE_HISTORY_LEN);
+        let end = offset.saturating_add(limit).min(retained);
+
+        let mut history = Vec::new(&env);
+        for i in offset..end {
+            let slot = (count - 1 - i) % BALANCE_HISTORY_LEN;
+            history.push_back(env.storage().persistent().get(&DataKey::BalanceHistory(user_address.clone(), slot)).unwrap());
+        }
+        history
+    }
+
+    // Get up to `MAX_PAGE_SIZE` profiles at once, in the order asked, None for unknown users
+    pub fn get_user_profiles(env: Env, users: Vec<Address>) -> Vec<Option<UserProfile>> {
+        if users.len() > MAX_PAGE_SIZE {
+            panic_with_error!(&env, Error::BatchTooLarge);
+        }
+        let mut profiles = Vec::new(&env);
+        for user_address in users.iter() {
+            profiles.push_back(Self::get_user_profile(env.clone(), user_address));
+        }
+        profiles
+    }
+
+    // Set or, with None, clear a free-form field such as an avatar hash or locale
+    pub fn set_profile_metadata(env: Env, user_address: Address, key: Symbol, value: Option<String>) {
+        user_address.require_auth();
+        Self::assert_user_exists(&env, &user_address);
+
+        let mut metadata = Self::get_profile_metadata(env.clone(), user_address.clone());
+        match value.clone() {
+            Some(value) => {
+                if value.len() > MAX_METADATA_VALUE_LEN {
+                    panic_with_error!(&env, Error::MetadataTooLarge);
+                }
+                metadata.set(key.clone(), value);
+                if metadata.len() > MAX_METADATA_ENTRIES {
+                    panic_with_error!(&env, Error::MetadataTooLarge);
+                }
+            }
+            None => {
+                metadata.remove(key.clone());
+            }
+        }
+
+        let storage_key = DataKey::ProfileMetadata(user_address.clone());
+        if metadata.is_empty() {
+            env.storage().persistent().remove(&storage_key);
+        } else {
+            env.storage().persistent().set(&storage_key, &metadata);
+            Self::bump(&env, &storage_key);
+        }
+        env.events().publish((symbol_short!("profile"), symbol_short!("metadata"), user_address), (key, value));
+    }
+
+    pub fn get_profile_metadata(env: Env, user_address: Address) -> Map<Symbol, String> {
+        env.storage().persistent()
+            .get(&DataKey::ProfileMetadata(user_address))
+            .unwrap_or(Map::new(&env))
+    }
+
+    // Check a candidate sha256(email salt || email) against the stored hash
+    pub fn verify_email_hash(env: Env, user_address: Address, candidate_hash: BytesN<32>) -> bool {
+        Self::get_user_profile(env, user_address)
+            .map(|profile| profile.email_hash == candidate_hash)
+            .unwrap_or(false)
+    }
+
+    // Set the salt clients prefix to emails before hashing (admin only)
+    pub fn set_email_salt(env: Env, invoker: Address, salt: BytesN<32>) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::EmailSalt, &salt);
+        env.events().publish((symbol_short!("config"), symbol_short!("salt")), salt);
+    }
+
+    pub fn get_email_salt(env: Env) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&DataKey::EmailSalt)
+    }
+
+    // Push back archival of a user's profile (anyone may pay for the bump)
+    pub fn extend_profile_ttl(env: Env, user_address: Address) {
+        if !Self::bump_profile(&env, &user_address) {
+            panic_with_error!(&env, Error::UserNotFound);
+        }
+    }
+
+    // Push back archival of the contract-wide entries and of the given users' profiles
+    // (admin only); returns how many profiles were extended, skipping unknown users
+    pub fn bulk_extend_ttl(env: Env, invoker: Address, users: Vec<Address>) -> u32 {
+        Self::assert_admin(&env, &invoker);
+        for key in [
+            DataKey::Admin,
+            DataKey::UserCount,
+            DataKey::UserBalances,
+            DataKey::PlatformStats,
+            DataKey::WhitelistedTokens,
+        ] {
+            if env.storage().persistent().has(&key) {
+                env.storage().persistent().extend_ttl(&key, TTL_EXTEND_TO, TTL_EXTEND_TO);
+            }
+        }
+
+        let mut extended = 0u32;
+        for user_address in users.iter() {
+            if Self::bump_profile(&env, &user_address) {
+                extended += 1;
+            }
+        }
+        extended
+    }
+
+    // Upgrade the given users' profiles to the current layout ahead of their next read
+    // (admin only); returns how many were upgraded, skipping unknown and current ones
+    pub fn migrate_profiles(env: Env, invoker: Address, users: Vec<Address>) -> u32 {
+        Self::assert_admin(&env, &invoker);
+        let mut migrated = 0u32;
+        for user_address in users.iter() {
+            if !env.storage().persistent().has(&DataKey::Profile(user_address.clone())) {
+                continue;
+            }
+            if Self::profile_schema(&env, &user_address) < PROFILE_SCHEMA_VERSION {
+                Self::upgrade_profile(&env, &user_address);
+                migrated += 1;
+            }
+        }
+
+        env.events().publish((symbol_short!("profile"), symbol_short!("migrated")), migrated);
+        migrated
+    }
+
+    // Replace plaintext emails of profiles stored in the legacy layout with their
+    // salted hash (admin only); kept for existing tooling, same as `migrate_profiles`
+    pub fn migrate_email_hashes(env: Env, invoker: Address, users: Vec<Address>) -> u32 {
+        if !env.storage().persistent().has(&DataKey::EmailSalt) {
+            panic_with_error!(&env, Error::EmailSaltNotSet);
+        }
+        Self::migrate_profiles(env, invoker, users)
+    }
+
+    // Resolve a username to its owner
+    pub fn get_address_by_username(env: Env, username: String) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Username(username))
+    }
+
+    // Admin functions
+    // Accept deposits in a token with the given number of decimals (admin or operator)
+    pub fn whitelist_token(env: Env, invoker: Address, token_address: Address, decimals: u32) {
+        Self::assert_operator(&env, &invoker);
+        if decimals > MAX_TOKEN_DECIMALS {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        
+        let mut whitelisted_tokens: Map<Address, bool> = env.storage().persistent().get(&DataKey::WhitelistedTokens).unwrap();
+        whitelisted_tokens.set(token_address.clone(), true);
+        env.storage().persistent().set(&DataKey::WhitelistedTokens, &whitelisted_tokens);
+        env.storage().persistent().set(&DataKey::TokenDecimals(token_address.clone()), &decimals);
+
+        env.events().publish((symbol_short!("token"), symbol_short!("whitelist"), token_address), true);
+    }
+
+    // Set the smallest deposit and withdrawal accepted in a token (admin only)
+    pub fn set_min_amounts(env: Env, invoker: Address, token_address: Address, min_deposit: i128, min_withdrawal: i128) {
+        Self::assert_admin(&env, &invoker);
+        if min_deposit < 0 || min_withdrawal < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
         }
+        env.storage().persistent().set(&DataKey::MinDeposit(token_address.clone()), &min_deposit);
+        env.storage().persistent().set(&DataKey::MinWithdrawal(token_address.clone()), &min_withdrawal);
+        env.events().publish((symbol_short!("token"), symbol_short!("minimums"), token_address), (min_deposit, min_withdrawal));
     }
 
-    fn assert_user_exists(env: &Env, user_address: &Address) {
-        let user_profiles: Map<Address, UserProfile> = env.storage().persistent().get(&DataKey::UserProfiles).unwrap();
-        assert!(user_profiles.contains_key(user_address.clone()), "User profile not found");
+    pub fn get_min_deposit(env: Env, token_address: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::MinDeposit(token_address)).unwrap_or(0)
     }
 
-    fn generate_referral_code(env: &Env) -> String {
-        // Simple referral code generation - in production, this would be more sophisticated
-        String::from_str(env, "NF123456")
+    pub fn get_min_withdrawal(env: Env, token_address: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::MinWithdrawal(token_address)).unwrap_or(0)
     }
 
-    fn calculate_loyalty_points(amount: i128) -> u32 {
-        // 1 point per 1 USDC spent (assuming 6 decimal places)
-        (amount / 1_000_000) as u32
+    pub fn get_token_decimals(env: Env, token_address: Address) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::TokenDecimals(token_address))
+            .unwrap_or(DEFAULT_TOKEN_DECIMALS)
     }
-}
 
-#[contractimpl]
-impl UserProfileContract {
-    // Initialize contract
-    pub fn initialize(env: Env, admin: Address, usdc_token: Address) {
-        env.storage().persistent().set(&DataKey::Admin, &admin);
-        env.storage().persistent().set(&DataKey::UserProfiles, &Map::<Address, UserProfile>::new(&env));
-        env.storage().persistent().set(&DataKey::UserBalances, &Map::<(Address, Address), i128>::new(&env));
-        env.storage().persistent().set(&DataKey::WhitelistedTokens, &Map::<Address, bool>::new(&env));
-        env.storage().persistent().set(&DataKey::ReferralSystem, &Map::<String, Address>::new(&env));
+    pub fn remove_token_whitelist(env: Env, invoker: Address, token_address: Address) {
+        Self::assert_operator(&env, &invoker);
         
-        // Initialize platform stats
-        let stats = PlatformStats {
-            total_users: 0,
-            total_deposits: 0,
-            total_withdrawals: 0,
-            active_subscriptions: 0,
-        };
-        env.storage().persistent().set(&DataKey::PlatformStats, &stats);
-
-        // Whitelist USDC by default
         let mut whitelisted_tokens: Map<Address, bool> = env.storage().persistent().get(&DataKey::WhitelistedTokens).unwrap();
-        whitelisted_tokens.set(usdc_token, true);
+        whitelisted_tokens.set(token_address.clone(), false);
         env.storage().persistent().set(&DataKey::WhitelistedTokens, &whitelisted_tokens);
+
+        env.events().publish((symbol_short!("token"), symbol_short!("whitelist"), token_address), false);
     }
 
-    // User Management
-    pub fn create_user_profile(
-        env: Env, 
-        user_address: Address, 
-        username: String, 
-        email: String,
-        referral_code: Option<String>
-    ) -> String {
-        let mut user_profiles: Map<Address, UserProfile> = env.storage().persistent().get(&DataKey::UserProfiles).unwrap();
-        
-        // Check if user already exists
-        assert!(!user_profiles.contains_key(user_address.clone()), "User profile already exists");
-        
-        // Validate inputs
-        assert!(!username.is_empty(), "Username cannot be empty");
-        assert!(!email.is_empty(), "Email cannot be empty");
+    pub fn is_token_whitelisted(env: Env, token_address: Address) -> bool {
+        let whitelisted_tokens: Map<Address, bool> = env.storage().persistent().get(&DataKey::WhitelistedTokens).unwrap();
+        whitelisted_tokens.get(token_address).unwrap_or(false)
+    }
 
-        let current_time = env.ledger().timestamp();
-        let user_referral_code = Self::generate_referral_code(&env);
-        
-        let mut referred_by = None;
-        if let Some(ref_code) = referral_code {
-            let referral_map: Map<String, Address> = env.storage().persistent().get(&DataKey::ReferralSystem).unwrap();
-            referred_by = referral_map.get(ref_code);
-        }
+    // Allow or disallow a contract (e.g. gift cards) to credit user balances
+    pub fn set_credit_source(env: Env, invoker: Address, source: Address, allowed: bool) {
+        Self::assert_admin(&env, &invoker);
 
-        let profile = UserProfile {
-            user_address: user_address.clone(),
-            username,
-            email,
-            created_at: current_time,
-            is_active: true,
-            is_verified: false,
-            referral_code: user_referral_code.clone(),
-            referred_by,
-            total_spent: 0,
-            loyalty_points: 0,
-            subscription_tier: 0,
-        };
+        let mut sources: Map<Address, bool> = env.storage().persistent().get(&DataKey::CreditSources).unwrap_or(Map::new(&env));
+        sources.set(source.clone(), allowed);
+        env.storage().persistent().set(&DataKey::CreditSources, &sources);
 
-        user_profiles.set(user_address.clone(), profile);
-        env.storage().persistent().set(&DataKey::UserProfiles, &user_profiles);
+        env.events().publish((symbol_short!("config"), symbol_short!("credit"), source), allowed);
+    }
 
-        // Store referral mapping
-        let mut referral_map: Map<String, Address> = env.storage().persistent().get(&DataKey::ReferralSystem).unwrap();
-        referral_map.set(user_referral_code.clone(), user_address);
-        env.storage().persistent().set(&DataKey::ReferralSystem, &referral_map);
+    // Allow or disallow a contract (e.g. orders) to deduct and refund user balances
+    pub fn set_balance_caller(env: Env, invoker: Address, caller: Address, allowed: bool) {
+        Self::assert_admin(&env, &invoker);
 
-        // Update platform stats
-        let mut stats: PlatformStats = env.storage().persistent().get(&DataKey::PlatformStats).unwrap();
-        stats.total_users += 1;
-        env.storage().persistent().set(&DataKey::PlatformStats, &stats);
+        let mut callers: Map<Address, bool> = env.storage().persistent().get(&DataKey::BalanceCallers).unwrap_or(Map::new(&env));
+        callers.set(caller.clone(), allowed);
+        env.storage().persistent().set(&DataKey::BalanceCallers, &callers);
 
-        user_referral_code
+        env.events().publish((symbol_short!("config"), symbol_short!("caller"), caller), allowed);
     }
 
-    pub fn update_user_profile(
-        env: Env,
-        user_address: Address,
-        username: Option<String>,
-        email: Option<String>
-    ) {
-        Self::assert_user_exists(&env, &user_address);
-        
-        let mut user_profiles: Map<Address, UserProfile> = env.storage().persistent().get(&DataKey::UserProfiles).unwrap();
-        let mut profile = user_profiles.get(user_address.clone()).unwrap();
-
-        if let Some(new_username) = username {
-            assert!(!new_username.is_empty(), "Username cannot be empty");
-            profile.username = new_username;
-        }
+    pub fn is_balance_caller(env: Env, caller: Address) -> bool {
+        let callers: Map<Address, bool> = env.storage().persistent().get(&DataKey::BalanceCallers).unwrap_or(Map::new(&env));
+        callers.get(caller).unwrap_or(false)
+    }
 
-        if let Some(new_email) = email {
-            assert!(!new_email.is_empty(), "Email cannot be empty");
-            profile.email = new_email;
+    // Set the loyalty points multiplier of a subscription tier in bps (admin only)
+    pub fn set_loyalty_multiplier(env: Env, invoker: Address, tier: u32, multiplier_bps: u32) {
+        Self::assert_admin(&env, &invoker);
+        if tier > 2 {
+            panic_with_error!(&env, Error::InvalidTier);
         }
+        env.storage().persistent().set(&DataKey::LoyaltyMultiplier(tier), &multiplier_bps);
+        env.events().publish((symbol_short!("config"), symbol_short!("loyalty"), tier), multiplier_bps);
+    }
 
-        user_profiles.set(user_address, profile);
-        env.storage().persistent().set(&DataKey::UserProfiles, &user_profiles);
+    pub fn get_loyalty_multiplier(env: Env, tier: u32) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::LoyaltyMultiplier(tier))
+            .unwrap_or(DEFAULT_LOYALTY_MULTIPLIERS[tier.min(2) as usize])
     }
 
-    pub fn verify_user(env: Env, invoker: Address, user_address: Address) {
+    // Configure the milestones paying referrers as their referees spend (admin only)
+    pub fn set_referral_program(env: Env, invoker: Address, token: Address, milestones: Vec<ReferralMilestone>) {
         Self::assert_admin(&env, &invoker);
-        Self::assert_user_exists(&env, &user_address);
+        if !Self::is_token_whitelisted(env.clone(), token.clone()) {
+            panic_with_error!(&env, Error::NotWhitelisted);
+        }
 
-        let mut user_profiles: Map<Address, UserProfile> = env.storage().persistent().get(&DataKey::UserProfiles).unwrap();
-        let mut profile = user_profiles.get(user_address.clone()).unwrap();
-        profile.is_verified = true;
+        let mut previous = 0i128;
+        for milestone in milestones.iter() {
+            if milestone.spend_threshold <= previous || milestone.token_bonus < 0 {
+                panic_with_error!(&env, Error::InvalidReferralProgram);
+            }
+            previous = milestone.spend_threshold;
+        }
 
-        user_profiles.set(user_address, profile);
-        env.storage().persistent().set(&DataKey::UserProfiles, &user_profiles);
+        let program = ReferralProgram { token: token.clone(), milestones: milestones.clone() };
+        env.storage().persistent().set(&DataKey::ReferralProgram, &program);
+        env.events().publish((symbol_short!("config"), symbol_short!("referral")), (token, milestones.len()));
     }
 
-    // Wallet Management
-    pub fn deposit_funds(
-        env: Env,
-        user_address: Address,
-        token_address: Address,
-        amount: i128
-    ) {
-        Self::assert_user_exists(&env, &user_address);
-        
-        // Check if token is whitelisted
-        let whitelisted_tokens: Map<Address, bool> = env.storage().persistent().get(&DataKey::WhitelistedTokens).unwrap();
-        assert!(whitelisted_tokens.get(token_address.clone()).unwrap_or(false), "Token not whitelisted");
-        
-        assert!(amount > 0, "Deposit amount must be positive");
+    pub fn get_referral_program(env: Env) -> Option<ReferralProgram> {
+        env.storage().persistent().get(&DataKey::ReferralProgram)
+    }
 
-        let mut user_balances: Map<(Address, Address), i128> = env.storage().persistent().get(&DataKey::UserBalances).unwrap();
-        let balance_key = (user_address.clone(), token_address.clone());
-        let current_balance = user_balances.get(balance_key.clone()).unwrap_or(0);
-        
-        user_balances.set(balance_key, current_balance + amount);
-        env.storage().persistent().set(&DataKey::UserBalances, &user_balances);
+    // Replace the user's referral code with a branded one of 4-16 uppercase letters and digits
+    // The previous code keeps resolving to the user as an alias
+    pub fn set_custom_referral_code(env: Env, user_address: Address, code: String) {
+        user_address.require_auth();
+        let mut profile = Self::load_profile(&env, &user_address);
+        if code.len() < MIN_REFERRAL_CODE_LEN || code.len() > MAX_REFERRAL_CODE_LEN {
+            panic_with_error!(&env, Error::InvalidReferralCode);
+        }
+        let mut buffer = [0u8; MAX_REFERRAL_CODE_LEN as usize];
+        let chars = &mut buffer[..code.len() as usize];
+        code.copy_into_slice(chars);
+        if !chars.iter().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+            panic_with_error!(&env, Error::InvalidReferralCode);
+        }
 
-        // Update platform stats
-        let mut stats: PlatformStats = env.storage().persistent().get(&DataKey::PlatformStats).unwrap();
-        stats.total_deposits += amount;
-        env.storage().persistent().set(&DataKey::PlatformStats, &stats);
-    }
+        let mut referral_map: Map<String, Address> = env.storage().persistent().get(&DataKey::ReferralSystem).unwrap();
+        if referral_map.contains_key(code.clone()) {
+            panic_with_error!(&env, Error::ReferralCodeTaken);
+        }
+        referral_map.set(code.clone(), user_address.clone());
+        env.storage().persistent().set(&DataKey::ReferralSystem, &referral_map);
 
-    pub fn withdraw_funds(
-        env: Env,
-        user_address: Address,
-        token_address: Address,
-        amount: i128
-    ) {
-        Self::assert_user_exists(&env, &user_address);
-        
-        let mut user_balances: Map<(Address, Address), i128> = env.storage().persistent().get(&DataKey::UserBalances).unwrap();
-        let balance_key = (user_address.clone(), token_address.clone());
-        let current_balance = user_balances.get(balance_key.clone()).unwrap_or(0);
-        
-        assert!(current_balance >= amount, "Insufficient balance");
-        assert!(amount > 0, "Withdrawal amount must be positive");
+        let mut aliases = Self::get_referral_aliases(env.clone(), user_address.clone());
+        aliases.push_back(profile.referral_code.clone());
+        env.storage().persistent().set(&DataKey::ReferralAliases(user_address.clone()), &aliases);
 
-        user_balances.set(balance_key, current_balance - amount);
-        env.storage().persistent().set(&DataKey::UserBalances, &user_balances);
+        let old_code = profile.referral_code;
+        profile.referral_code = code.clone();
+        Self::save_profile(&env, &profile);
+        env.events().publish((symbol_short!("referral"), symbol_short!("code"), user_address), (old_code, code));
+    }
 
-        // Update platform stats
-        let mut stats: PlatformStats = env.storage().persistent().get(&DataKey::PlatformStats).unwrap();
-        stats.total_withdrawals += amount;
-        env.storage().persistent().set(&DataKey::PlatformStats, &stats);
+    // Users an address referred directly, in signup order
+    pub fn get_referees(env: Env, user_address: Address) -> Vec<Address> {
+        env.storage().persistent()
+            .get(&DataKey::Referees(user_address))
+            .unwrap_or(Vec::new(&env))
     }
 
-    pub fn get_user_balance(env: Env, user_address: Address, token_address: Address) -> i128 {
-        let user_balances: Map<(Address, Address), i128> = env.storage().persistent().get(&DataKey::UserBalances).unwrap();
-        let balance_key = (user_address, token_address);
-        user_balances.get(balance_key).unwrap_or(0)
+    // Referees of an address per level, direct ones first, up to `MAX_REFERRAL_DEPTH` levels
+    pub fn get_referral_counts(env: Env, user_address: Address) -> Vec<u32> {
+        env.storage().persistent()
+            .get(&DataKey::ReferralCounts(user_address))
+            .unwrap_or(Vec::new(&env))
     }
 
-    // Get user profile
-    pub fn get_user_profile(env: Env, user_address: Address) -> Option<UserProfile> {
-        let user_profiles: Map<Address, UserProfile> = env.storage().persistent().get(&DataKey::UserProfiles).unwrap();
-        user_profiles.get(user_address)
+    // Breadth-first list of everyone an address brought in, directly or through
+    // their referees, up to `max_depth` levels (capped at `MAX_REFERRAL_DEPTH`)
+    // Stops after `MAX_REFERRAL_TREE_SIZE` nodes
+    pub fn get_referral_tree(env: Env, user_address: Address, max_depth: u32) -> Vec<ReferralNode> {
+        let max_depth = max_depth.min(MAX_REFERRAL_DEPTH);
+        let mut tree: Vec<ReferralNode> = Vec::new(&env);
+        let mut level = vec![&env, user_address];
+        for depth in 1..=max_depth {
+            let mut next = Vec::new(&env);
+            for referrer in level.iter() {
+                for referee in Self::get_referees(env.clone(), referrer.clone()).iter() {
+                    if tree.len() == MAX_REFERRAL_TREE_SIZE {
+                        return tree;
+                    }
+                    tree.push_back(ReferralNode { user_address: referee.clone(), referrer: referrer.clone(), depth });
+                    next.push_back(referee);
+                }
+            }
+            level = next;
+        }
+        tree
     }
 
-    // Admin functions
-    pub fn whitelist_token(env: Env, invoker: Address, token_address: Address) {
-        Self::assert_admin(&env, &invoker);
-        
-        let mut whitelisted_tokens: Map<Address, bool> = env.storage().persistent().get(&DataKey::WhitelistedTokens).unwrap();
-        whitelisted_tokens.set(token_address, true);
-        env.storage().persistent().set(&DataKey::WhitelistedTokens, &whitelisted_tokens);
+    // Earlier referral codes of a user, oldest first
+    pub fn get_referral_aliases(env: Env, user_address: Address) -> Vec<String> {
+        env.storage().persistent()
+            .get(&DataKey::ReferralAliases(user_address))
+            .unwrap_or(Vec::new(&env))
     }
 
-    pub fn remove_token_whitelist(env: Env, invoker: Address, token_address: Address) {
-        Self::assert_admin(&env, &invoker);
-        
-        let mut whitelisted_tokens: Map<Address, bool> = env.storage().persistent().get(&DataKey::WhitelistedTokens).unwrap();
-        whitelisted_tokens.set(token_address, false);
-        env.storage().persistent().set(&DataKey::WhitelistedTokens, &whitelisted_tokens);
+    // Referrals made by an address and the rewards they earned
+    pub fn get_referral_stats(env: Env, user_address: Address) -> ReferralStats {
+        env.storage().persistent()
+            .get(&DataKey::ReferralStats(user_address))
+            .unwrap_or(ReferralStats {
+                referrals: 0,
+                milestones_reached: 0,
+                points_earned: 0,
+                bonus_earned: 0,
+            })
     }
 
     pub fn get_platform_stats(env: Env) -> PlatformStats {
@@ -267,106 +2028,346 @@ impl UserProfileContract {
     }
 
     // Utility functions for order contract integration
+    // Spending from the internal balance needs an allowlisted caller and the user's authorization
     pub fn deduct_balance(
         env: Env,
+        caller: Address,
         user_address: Address,
         token_address: Address,
         amount: i128
     ) -> bool {
-        Self::assert_user_exists(&env, &user_address);
-        
-        let mut user_balances: Map<(Address, Address), i128> = env.storage().persistent().get(&DataKey::UserBalances).unwrap();
-        let balance_key = (user_address.clone(), token_address.clone());
-        let current_balance = user_balances.get(balance_key.clone()).unwrap_or(0);
+        Self::assert_not_paused(&env, "deduct_balance");
+        Self::assert_not_halted(&env);
+        Self::assert_balance_caller(&env, &caller);
+        user_address.require_auth();
+        Self::assert_not_frozen(&env, &user_address);
+        Self::assert_user_active(&env, &user_address);
         
-        if current_balance >= amount {
-            user_balances.set(balance_key, current_balance - amount);
-            env.storage().persistent().set(&DataKey::UserBalances, &user_balances);
-            
-            // Update user profile spending and loyalty points
-            let mut user_profiles: Map<Address, UserProfile> = env.storage().persistent().get(&DataKey::UserProfiles).unwrap();
-            let mut profile = user_profiles.get(user_address.clone()).unwrap();
-            profile.total_spent += amount;
-            profile.loyalty_points += Self::calculate_loyalty_points(amount);
-            user_profiles.set(user_address.clone(), profile);
-            env.storage().persistent().set(&DataKey::UserProfiles, &user_profiles);
-            
-            true
+        Self::apply_spend(&env, &user_address, &token_address, amount, &caller)
+    }
+
+    // Let a contract pull up to `amount` of the user's internal balance through `spend_from`
+    // Replaces any previous allowance; zero revokes it
+    pub fn approve(env: Env, user_address: Address, spender: Address, token_address: Address, amount: i128) {
+        user_address.require_auth();
+        Self::assert_user_exists(&env, &user_address);
+        if amount < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let key = DataKey::Allowance(user_address.clone(), spender.clone(), token_address.clone());
+        if amount == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &amount);
+        }
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("approve"), user_address),
+            (spender, token_address, amount),
+        );
+    }
+
+    pub fn get_allowance(env: Env, user_address: Address, spender: Address, token_address: Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::Allowance(user_address, spender, token_address))
+            .unwrap_or(0)
+    }
+
+    // Spend from a user's balance against the allowance they granted the spender
+    // Counts towards spending and loyalty like `deduct_balance`
+    pub fn spend_from(env: Env, spender: Address, user_address: Address, token_address: Address, amount: i128) {
+        Self::assert_not_paused(&env, "spend_from");
+        Self::assert_not_halted(&env);
+        spender.require_auth();
+        Self::assert_not_frozen(&env, &user_address);
+        Self::assert_user_active(&env, &user_address);
+        if amount <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let allowance = Self::get_allowance(env.clone(), user_address.clone(), spender.clone(), token_address.clone());
+        if allowance < amount {
+            panic_with_error!(&env, Error::InsufficientAllowance);
+        }
+        if !Self::apply_spend(&env, &user_address, &token_address, amount, &spender) {
+            panic_with_error!(&env, Error::InsufficientBalance);
+        }
+
+        let key = DataKey::Allowance(user_address, spender, token_address);
+        if allowance == amount {
+            env.storage().persistent().remove(&key);
         } else {
-            false
+            env.storage().persistent().set(&key, &(allowance - amount));
         }
     }
 
     pub fn refund_balance(
         env: Env,
+        caller: Address,
         user_address: Address,
         token_address: Address,
         amount: i128
     ) {
+        Self::assert_balance_caller(&env, &caller);
         Self::assert_user_exists(&env, &user_address);
         
-        let mut user_balances: Map<(Address, Address), i128> = env.storage().persistent().get(&DataKey::UserBalances).unwrap();
-        let balance_key = (user_address.clone(), token_address.clone());
-        let current_balance = user_balances.get(balance_key.clone()).unwrap_or(0);
-        
-        user_balances.set(balance_key, current_balance + amount);
-        env.storage().persistent().set(&DataKey::UserBalances, &user_balances);
+        let new_balance = Self::change_balance(&env, &user_address, &token_address, amount, symbol_short!("refund"), Some(caller.clone()));
+
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("refund"), user_address),
+            (token_address, amount, new_balance),
+        );
+    }
+
+    // Credit a user's balance from an approved source that has already
+    // transferred the tokens to this contract
+    pub fn credit_balance(
+        env: Env,
+        source: Address,
+        user_address: Address,
+        token_address: Address,
+        amount: i128
+    ) {
+        Self::assert_not_paused(&env, "credit_balance");
+        Self::assert_not_halted(&env);
+        source.require_auth();
+        let sources: Map<Address, bool> = env.storage().persistent().get(&DataKey::CreditSources).unwrap_or(Map::new(&env));
+        if !sources.get(source.clone()).unwrap_or(false) {
+            panic_with_error!(&env, Error::NotCreditSource);
+        }
+        Self::assert_user_active(&env, &user_address);
+        if !Self::is_token_whitelisted(env.clone(), token_address.clone()) {
+            panic_with_error!(&env, Error::NotWhitelisted);
+        }
+        if amount <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let new_balance = Self::change_balance(&env, &user_address, &token_address, amount, symbol_short!("credit"), Some(source.clone()));
+
+        // Credited value counts as a deposit in platform stats
+        let mut stats: PlatformStats = env.storage().persistent().get(&DataKey::PlatformStats).unwrap();
+        stats.total_deposits += amount;
+        env.storage().persistent().set(&DataKey::PlatformStats, &stats);
+
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("credit"), user_address),
+            (source, token_address, amount, new_balance),
+        );
     }
 
     // Subscription Management
+    // Move to a dearer tier for a fresh billing period; the unused share of the current
+    // payment is credited back in the token it was paid in, with the points it earned.
+    // Renewals and cheaper tiers go through renew_subscription and downgrade_subscription
     pub fn upgrade_subscription(
         env: Env,
         user_address: Address,
         tier: u32,
         token_address: Address
     ) {
-        Self::assert_user_exists(&env, &user_address);
-        assert!(tier <= 2, "Invalid subscription tier");
-        
-        let subscription_costs = [0i128, 10_000_000, 50_000_000]; // Basic: Free, Premium: 10 USDC, Enterprise: 50 USDC
-        let cost = subscription_costs[tier as usize];
-        
-        if cost > 0 {
-            let balance = Self::get_user_balance(env.clone(), user_address.clone(), token_address.clone());
-            assert!(balance >= cost, "Insufficient balance for subscription upgrade");
-            
-            // Deduct subscription cost
-            let mut user_balances: Map<(Address, Address), i128> = env.storage().persistent().get(&DataKey::UserBalances).unwrap();
-            let balance_key = (user_address.clone(), token_address.clone());
-            let current_balance = user_balances.get(balance_key.clone()).unwrap_or(0);
-            
-            user_balances.set(balance_key, current_balance - cost);
-            env.storage().persistent().set(&DataKey::UserBalances, &user_balances);
-        }
-
-        // Update user profile
-        let mut user_profiles: Map<Address, UserProfile> = env.storage().persistent().get(&DataKey::UserProfiles).unwrap();
-        let mut profile = user_profiles.get(user_address.clone()).unwrap();
+        Self::assert_not_paused(&env, "upgrade_subscription");
+        Self::assert_not_halted(&env);
+        user_address.require_auth();
+        Self::assert_not_frozen(&env, &user_address);
+        Self::assert_user_active(&env, &user_address);
+        let mut profile = Self::load_profile(&env, &user_address);
         let old_tier = profile.subscription_tier;
-        profile.subscription_tier = tier;
-        
-        if cost > 0 {
-            profile.total_spent += cost;
-            profile.loyalty_points += Self::calculate_loyalty_points(cost);
+        if tier > 2 || tier <= old_tier {
+            panic_with_error!(&env, Error::InvalidTier);
+        }
+
+        // Credit the unused paid time before charging the new tier
+        let mut credit = 0;
+        if let Some((payment, unused_amount, unused_points)) = Self::unused_subscription(&env, &profile) {
+            if unused_amount > 0 {
+                Self::change_balance(&env, &user_address, &payment.token, unused_amount, symbol_short!("sub_cred"), None);
+            }
+            profile.loyalty_points = profile.loyalty_points.saturating_sub(unused_points);
+            profile.total_spent = (profile.total_spent - unused_amount).max(0);
+            credit = unused_amount;
         }
+
+        let cost = Self::subscription_price(&env, tier, &token_address);
+        Self::charge_subscription(&env, &user_address, &token_address, cost);
+
+        // Update user profile; the new tier starts a fresh billing period
+        profile.subscription_tier = tier;
+        profile.subscription_expires_at = env.ledger().timestamp() + Self::get_billing_period(env.clone());
+        let points = Self::calculate_loyalty_points(&env, &token_address, cost, tier);
+        profile.total_spent += cost;
+        profile.loyalty_points += points;
+        let payment = SubscriptionPayment {
+            token: token_address.clone(),
+            amount: cost,
+            loyalty_points: points,
+            paid_from: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&DataKey::SubscriptionPayment(user_address.clone()), &payment);
         
-        user_profiles.set(user_address.clone(), profile);
-        env.storage().persistent().set(&DataKey::UserProfiles, &user_profiles);
+        Self::save_profile(&env, &profile);
+        Self::reward_referrer(&env, &profile);
 
         // Update platform stats
-        let mut stats: PlatformStats = env.storage().persistent().get(&DataKey::PlatformStats).unwrap();
-        if old_tier == 0 && tier > 0 {
+        if old_tier == 0 {
+            let mut stats: PlatformStats = env.storage().persistent().get(&DataKey::PlatformStats).unwrap();
             stats.active_subscriptions += 1;
-        } else if old_tier > 0 && tier == 0 {
+            env.storage().persistent().set(&DataKey::PlatformStats, &stats);
+        }
+
+        env.events().publish(
+            (symbol_short!("sub"), symbol_short!("upgrade"), user_address),
+            (old_tier, tier, token_address, cost, credit),
+        );
+    }
+
+    // Pay for another billing period of the current paid tier, in the token it was paid in
+    // Renewing early extends from the current expiry, so no paid time is lost
+    pub fn renew_subscription(env: Env, user_address: Address, token_address: Address) -> u64 {
+        Self::assert_not_paused(&env, "renew_subscription");
+        Self::assert_not_halted(&env);
+        user_address.require_auth();
+        Self::assert_not_frozen(&env, &user_address);
+        let mut profile = Self::load_profile(&env, &user_address);
+        if !profile.is_active {
+            panic_with_error!(&env, Error::AccountInactive);
+        }
+        if profile.subscription_tier == 0 {
+            panic_with_error!(&env, Error::InvalidTier);
+        }
+
+        // Subscriptions recorded before payments were kept count from now
+        let payment_key = DataKey::SubscriptionPayment(user_address.clone());
+        let mut payment: SubscriptionPayment = env.storage().persistent()
+            .get(&payment_key)
+            .unwrap_or(SubscriptionPayment {
+                token: token_address.clone(),
+                amount: 0,
+                loyalty_points: 0,
+                paid_from: env.ledger().timestamp(),
+            });
+        if payment.token != token_address {
+            panic_with_error!(&env, Error::SubscriptionTokenMismatch);
+        }
+
+        let cost = Self::subscription_price(&env, profile.subscription_tier, &token_address);
+        Self::charge_subscription(&env, &user_address, &token_address, cost);
+
+        let points = Self::calculate_loyalty_points(&env, &token_address, cost, profile.subscription_tier);
+        profile.subscription_expires_at += Self::get_billing_period(env.clone());
+        profile.total_spent += cost;
+        profile.loyalty_points += points;
+        Self::save_profile(&env, &profile);
+        payment.amount += cost;
+        payment.loyalty_points += points;
+        env.storage().persistent().set(&payment_key, &payment);
+        Self::reward_referrer(&env, &profile);
+
+        env.events().publish(
+            (symbol_short!("sub"), symbol_short!("renew"), user_address),
+            (profile.subscription_tier, token_address, cost, profile.subscription_expires_at),
+        );
+        profile.subscription_expires_at
+    }
+
+    // Move to a cheaper tier, refunding the price difference for the unused paid time in
+    // the token the subscription was paid in, less the loyalty points earned on it
+    // A paid target tier keeps the current expiry; Basic ends the subscription
+    pub fn downgrade_subscription(env: Env, user_address: Address, tier: u32) -> i128 {
+        Self::assert_not_paused(&env, "downgrade_subscription");
+        Self::assert_not_halted(&env);
+        user_address.require_auth();
+        Self::assert_not_frozen(&env, &user_address);
+        let mut profile = Self::load_profile(&env, &user_address);
+        let old_tier = profile.subscription_tier;
+        if tier >= old_tier {
+            panic_with_error!(&env, Error::InvalidTier);
+        }
+
+        // Refund the share of the unused payment the cheaper tier no longer covers
+        let payment_key = DataKey::SubscriptionPayment(user_address.clone());
+        let mut refund = 0;
+        let mut refund_token = None;
+        if let Some((payment, unused_amount, unused_points)) = Self::unused_subscription(&env, &profile) {
+            let old_cost = SUBSCRIPTION_COSTS[old_tier as usize];
+            refund = unused_amount * (old_cost - SUBSCRIPTION_COSTS[tier as usize]) / old_cost;
+            let clawback = (payment.loyalty_points as i128 * refund / payment.amount) as u32;
+            if refund > 0 {
+                Self::change_balance(&env, &user_address, &payment.token, refund, symbol_short!("sub_rfnd"), None);
+            }
+            profile.loyalty_points = profile.loyalty_points.saturating_sub(clawback);
+            if tier > 0 {
+                let remaining = SubscriptionPayment {
+                    token: payment.token.clone(),
+                    amount: unused_amount - refund,
+                    loyalty_points: unused_points.saturating_sub(clawback),
+                    paid_from: env.ledger().timestamp(),
+                };
+                env.storage().persistent().set(&payment_key, &remaining);
+            }
+            refund_token = Some(payment.token);
+        }
+        if tier == 0 {
+            env.storage().persistent().remove(&payment_key);
+        }
+
+        profile.subscription_tier = tier;
+        if tier == 0 {
+            profile.subscription_expires_at = 0;
+        }
+        profile.total_spent = (profile.total_spent - refund).max(0);
+        Self::save_profile(&env, &profile);
+
+        if tier == 0 {
+            let mut stats: PlatformStats = env.storage().persistent().get(&DataKey::PlatformStats).unwrap();
             stats.active_subscriptions -= 1;
+            env.storage().persistent().set(&DataKey::PlatformStats, &stats);
         }
-        env.storage().persistent().set(&DataKey::PlatformStats, &stats);
+
+        env.events().publish(
+            (symbol_short!("sub"), symbol_short!("downgrade"), user_address),
+            (old_tier, tier, refund_token, refund),
+        );
+        refund
+    }
+
+    // Get what the user paid for their current subscription, if it is recorded
+    pub fn get_subscription_payment(env: Env, user_address: Address) -> Option<SubscriptionPayment> {
+        env.storage().persistent().get(&DataKey::SubscriptionPayment(user_address))
+    }
+
+    // Set the oracle pricing subscriptions paid in tokens other than the USD token (admin only)
+    pub fn set_price_oracle(env: Env, invoker: Address, oracle: Address, max_price_age: u64) {
+        Self::assert_admin(&env, &invoker);
+        env.storage().persistent().set(&DataKey::PriceOracle, &OracleConfig { oracle: oracle.clone(), max_price_age });
+        env.events().publish((symbol_short!("config"), symbol_short!("oracle")), (oracle, max_price_age));
+    }
+
+    // Price of a subscription tier per billing period when paid in a token
+    pub fn get_subscription_price(env: Env, tier: u32, token_address: Address) -> i128 {
+        if tier > 2 {
+            panic_with_error!(&env, Error::InvalidTier);
+        }
+        Self::subscription_price(&env, tier, &token_address)
+    }
+
+    // Set how long one subscription payment lasts (admin only)
+    pub fn set_billing_period(env: Env, invoker: Address, period: u64) {
+        Self::assert_admin(&env, &invoker);
+        if period == 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        env.storage().persistent().set(&DataKey::BillingPeriod, &period);
+        env.events().publish((symbol_short!("config"), symbol_short!("billing")), period);
+    }
+
+    pub fn get_billing_period(env: Env) -> u64 {
+        env.storage().persistent().get(&DataKey::BillingPeriod).unwrap_or(DEFAULT_BILLING_PERIOD)
     }
 
     // Check if user exists (for order contract)
     pub fn user_exists(env: Env, user_address: Address) -> bool {
-        let user_profiles: Map<Address, UserProfile> = env.storage().persistent().get(&DataKey::UserProfiles).unwrap();
-        user_profiles.contains_key(user_address)
+        env.storage().persistent().has(&DataKey::Profile(user_address))
     }
 
     // Check if user has sufficient balance (for order contract)
@@ -379,22 +2380,50 @@ impl UserProfileContract {
     pub fn get_all_users(env: Env, invoker: Address) -> Vec<UserProfile> {
         Self::assert_admin(&env, &invoker);
         
-        let user_profiles: Map<Address, UserProfile> = env.storage().persistent().get(&DataKey::UserProfiles).unwrap();
         let mut users = Vec::new(&env);
-        
-        for i in 0..user_profiles.len() {
-            if let Some(profile) = user_profiles.values().get(i) {
-                users.push_back(profile);
-            }
+        for i in 0..Self::get_user_count(env.clone()) {
+            let user_address: Address = env.storage().persistent().get(&DataKey::UserIndex(i)).unwrap();
+            users.push_back(Self::load_profile(&env, &user_address));
         }
-        
         users
     }
 
+    // Page through users in registration order (admin or operator)
+    // Scans at most MAX_PAGE_SCAN index entries from `offset`, collecting up to `limit` matches
+    pub fn get_users_page(env: Env, invoker: Address, offset: u32, limit: u32, filter: UserFilter) -> UserPage {
+        Self::assert_operator(&env, &invoker);
+
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let count = Self::get_user_count(env.clone());
+        let scan_end = count.min(offset.saturating_add(MAX_PAGE_SCAN));
+        let mut users = Vec::new(&env);
+        let mut index = offset;
+        while index < scan_end && users.len() < limit {
+            let user_address: Address = env.storage().persistent().get(&DataKey::UserIndex(index)).unwrap();
+            let profile = Self::load_profile(&env, &user_address);
+            index += 1;
+
+            if filter.active_only && !profile.is_active {
+                continue;
+            }
+            if filter.tier.is_some_and(|tier| tier != profile.subscription_tier) {
+                continue;
+            }
+            if filter.verified_only && !Self::is_user_verified(env.clone(), user_address) {
+                continue;
+            }
+            users.push_back(profile);
+        }
+
+        UserPage {
+            users,
+            next_offset: if index < count { Some(index) } else { None },
+        }
+    }
+
     // Get user count
     pub fn get_user_count(env: Env) -> u32 {
-        let user_profiles: Map<Address, UserProfile> = env.storage().persistent().get(&DataKey::UserProfiles).unwrap();
-        user_profiles.len()
+        env.storage().persistent().get(&DataKey::UserCount).unwrap_or(0)
     }
 }
 
@@ -1,7 +1,9 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{contract, contractimpl};
+use ed25519_dalek::SigningKey;
+use soroban_sdk::{testutils::{storage::Persistent as _, Address as _, Events, Ledger}, vec, Address, Bytes, BytesN, Env, IntoVal};
 
 fn create_test_env() -> (Env, Address, Address, Address) {
     let env = Env::default();
@@ -14,6 +16,16 @@ fn create_test_env() -> (Env, Address, Address, Address) {
     (env, admin, usdc_token, user)
 }
 
+fn email_hash(env: &Env, email: &str) -> BytesN<32> {
+    env.crypto().sha256(&Bytes::from_slice(env, email.as_bytes())).into()
+}
+
+fn balance_caller(env: &Env, admin: &Address, client: &UserProfileContractClient) -> Address {
+    let caller = Address::generate(env);
+    client.set_balance_caller(admin, &caller, &true);
+    caller
+}
+
 fn init_contract<'a>(env: &'a Env, admin: &Address, usdc_token: &Address) -> UserProfileContractClient<'a> {
     let contract_id = env.register(UserProfileContract, ());
     let client = UserProfileContractClient::new(env, &contract_id);
@@ -41,7 +53,7 @@ fn test_user_profile_creation() {
     let referral_code = client.create_user_profile(
         &user,
         &String::from_str(&env, "testuser"),
-        &String::from_str(&env, "test@example.com"),
+        &email_hash(&env, "test@example.com"),
         &None
     );
     
@@ -49,9 +61,9 @@ fn test_user_profile_creation() {
     
     let profile = client.get_user_profile(&user).unwrap();
     assert_eq!(profile.username, String::from_str(&env, "testuser"));
-    assert_eq!(profile.email, String::from_str(&env, "test@example.com"));
+    assert_eq!(profile.email_hash, email_hash(&env, "test@example.com"));
     assert_eq!(profile.subscription_tier, 0);
-    assert!(!profile.is_verified);
+    assert_eq!(profile.verification_level, LEVEL_NONE);
     assert!(profile.is_active);
     
     let stats = client.get_platform_stats();
@@ -67,12 +79,12 @@ fn test_wallet_operations() {
     client.create_user_profile(
         &user,
         &String::from_str(&env, "testuser"),
-        &String::from_str(&env, "test@example.com"),
+        &email_hash(&env, "test@example.com"),
         &None
     );
     
     // Test deposit
-    let deposit_amount = 1000_000_000i128; // 1000 USDC
+    let deposit_amount = 1_000_000_000i128; // 1000 USDC
     client.deposit_funds(&user, &usdc_token, &deposit_amount);
     
     let balance = client.get_user_balance(&user, &usdc_token);
@@ -86,6 +98,250 @@ fn test_wallet_operations() {
     assert_eq!(new_balance, deposit_amount - withdraw_amount);
 }
 
+#[test]
+fn test_wallet_actions_require_user_auth() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &500);
+    assert_eq!(env.auths()[0].0, user);
+
+    client.verify_user(&admin, &user);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_withdraw_on_behalf_of_another_user_fails() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &500);
+
+    env.set_auths(&[]);
+    client.withdraw_funds(&user, &usdc_token, &500);
+}
+
+#[test]
+fn test_balance_history_keeps_latest_operations() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let orders = balance_caller(&env, &admin, &client);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &1_000);
+    client.deduct_balance(&orders, &user, &usdc_token, &300);
+
+    let history = client.get_balance_history(&user, &0, &10);
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history.get(0).unwrap(),
+        BalanceOperation {
+            kind: symbol_short!("deduct"),
+            token_address: usdc_token.clone(),
+            amount: -300,
+            timestamp: 0,
+            counterparty: Some(orders.clone()),
+        }
+    );
+    assert_eq!(history.get(1).unwrap().kind, symbol_short!("deposit"));
+
+    // Older operations are overwritten once the buffer is full
+    for i in 0..BALANCE_HISTORY_LEN {
+        client.deposit_funds(&user, &usdc_token, &(i as i128 + 1));
+    }
+    let history = client.get_balance_history(&user, &0, &100);
+    assert_eq!(history.len(), BALANCE_HISTORY_LEN);
+    assert_eq!(history.get(0).unwrap().amount, BALANCE_HISTORY_LEN as i128);
+    assert_eq!(history.last().unwrap().amount, 1);
+    assert_eq!(client.get_balance_history(&user, &(BALANCE_HISTORY_LEN - 1), &5).len(), 1);
+}
+
+#[test]
+fn test_loyalty_points_follow_token_decimals() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let orders = balance_caller(&env, &admin, &client);
+    let xlm = Address::generate(&env);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.whitelist_token(&admin, &xlm, &7);
+    assert_eq!(client.get_token_decimals(&xlm), 7);
+    assert_eq!(client.get_token_decimals(&usdc_token), DEFAULT_TOKEN_DECIMALS);
+
+    // 10 whole tokens earn 10 points whatever the token's decimals
+    client.deposit_funds(&user, &usdc_token, &10_000_000);
+    client.deposit_funds(&user, &xlm, &100_000_000);
+    client.deduct_balance(&orders, &user, &usdc_token, &10_000_000);
+    assert_eq!(client.get_user_profile(&user).unwrap().loyalty_points, 10);
+    client.deduct_balance(&orders, &user, &xlm, &100_000_000);
+    assert_eq!(client.get_user_profile(&user).unwrap().loyalty_points, 20);
+}
+
+#[test]
+fn test_min_deposit_and_withdrawal() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.set_min_amounts(&admin, &usdc_token, &1_000, &500);
+
+    assert_eq!(
+        client.try_deposit_funds(&user, &usdc_token, &999),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::BelowMinDeposit as u32)))
+    );
+    client.deposit_funds(&user, &usdc_token, &1_200);
+    client.withdraw_funds(&user, &usdc_token, &1_000);
+    assert_eq!(
+        client.try_withdraw_funds(&user, &usdc_token, &100),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::BelowMinWithdrawal as u32)))
+    );
+
+    // The remaining dust can still be withdrawn in full
+    client.withdraw_funds(&user, &usdc_token, &200);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 0);
+}
+
+#[test]
+fn test_state_changes_emit_events() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    let (contract, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(contract, client.address);
+    assert_eq!(topics, (symbol_short!("profile"), symbol_short!("created"), user.clone()).into_val(&env));
+
+    client.deposit_funds(&user, &usdc_token, &500);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (symbol_short!("wallet"), symbol_short!("deposit"), user.clone()).into_val(&env));
+    let data: (Address, i128, i128) = data.into_val(&env);
+    assert_eq!(data, (usdc_token.clone(), 500, 500));
+}
+
+#[test]
+fn test_every_balance_change_emits_balance_event() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let orders = balance_caller(&env, &admin, &client);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    // Each call's events are only visible until the next call
+    let balance_topic = symbol_short!("balance").into_val(&env);
+    let last_change = || -> (Address, i128, Symbol, i128) {
+        let (_, _, data) = env.events().all().iter()
+            .find(|(_, topics, _)| topics.get(1).unwrap().shallow_eq(&balance_topic))
+            .unwrap();
+        data.into_val(&env)
+    };
+
+    client.deposit_funds(&user, &usdc_token, &30_000_000);
+    assert_eq!(last_change(), (usdc_token.clone(), 30_000_000, symbol_short!("deposit"), 30_000_000));
+    client.deduct_balance(&orders, &user, &usdc_token, &5_000_000);
+    assert_eq!(last_change(), (usdc_token.clone(), -5_000_000, symbol_short!("deduct"), 25_000_000));
+    client.refund_balance(&orders, &user, &usdc_token, &2_000_000);
+    assert_eq!(last_change(), (usdc_token.clone(), 2_000_000, symbol_short!("refund"), 27_000_000));
+    client.upgrade_subscription(&user, &1, &usdc_token);
+    assert_eq!(last_change(), (usdc_token.clone(), -10_000_000, symbol_short!("sub_fee"), 17_000_000));
+    client.withdraw_funds(&user, &usdc_token, &1_000_000);
+    assert_eq!(last_change(), (usdc_token.clone(), -1_000_000, symbol_short!("withdraw"), 16_000_000));
+}
+
+/// SEP-40 oracle quoting one settable price with 14 decimals
+#[contract]
+struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, price: i128) {
+        env.storage().instance().set(&symbol_short!("price"), &price);
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        14
+    }
+
+    pub fn lastprice(env: Env, _asset: interfaces::Asset) -> Option<interfaces::PriceData> {
+        Some(interfaces::PriceData {
+            price: env.storage().instance().get(&symbol_short!("price")).unwrap(),
+            timestamp: env.ledger().timestamp(),
+        })
+    }
+}
+
+#[test]
+fn test_subscription_priced_in_other_tokens_via_oracle() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let xlm = Address::generate(&env);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.whitelist_token(&admin, &xlm, &7);
+
+    // Without an oracle only the USD token can pay
+    assert_eq!(
+        client.try_get_subscription_price(&1, &xlm),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::PriceUnavailable as u32)))
+    );
+    assert_eq!(
+        client.try_upgrade_subscription(&user, &1, &Address::generate(&env)),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::NotWhitelisted as u32)))
+    );
+
+    // XLM at $0.10: 10 USDC of Premium costs 100 XLM
+    let oracle = MockOracleClient::new(&env, &env.register(MockOracle, ()));
+    oracle.set_price(&10_000_000_000_000);
+    client.set_price_oracle(&admin, &oracle.address, &60);
+    assert_eq!(client.get_subscription_price(&1, &xlm), 1_000_000_000);
+    assert_eq!(client.get_subscription_price(&1, &usdc_token), 10_000_000);
+
+    client.deposit_funds(&user, &xlm, &1_500_000_000);
+    client.upgrade_subscription(&user, &1, &xlm);
+    assert_eq!(client.get_user_balance(&user, &xlm), 500_000_000);
+}
+
 #[test]
 fn test_subscription_upgrade() {
     let (env, admin, usdc_token, user) = create_test_env();
@@ -95,7 +351,7 @@ fn test_subscription_upgrade() {
     client.create_user_profile(
         &user,
         &String::from_str(&env, "testuser"),
-        &String::from_str(&env, "test@example.com"),
+        &email_hash(&env, "test@example.com"),
         &None
     );
     
@@ -108,11 +364,176 @@ fn test_subscription_upgrade() {
     assert_eq!(profile.subscription_tier, 1);
     assert_eq!(profile.total_spent, 10_000_000i128); // 10 USDC
     assert!(profile.loyalty_points > 0);
+    assert_eq!(client.get_subscription_tier(&user), 1);
+    assert_eq!(client.get_loyalty_points(&user), profile.loyalty_points);
     
     let balance = client.get_user_balance(&user, &usdc_token);
     assert_eq!(balance, 90_000_000i128); // 90 USDC remaining
 }
 
+#[test]
+fn test_subscription_renews_and_lapses_to_basic() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let period = client.get_billing_period();
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &100_000_000i128);
+    client.upgrade_subscription(&user, &1, &usdc_token);
+    assert_eq!(client.get_user_profile(&user).unwrap().subscription_expires_at, period);
+
+    // Renewing before expiry stacks another period on top
+    env.ledger().with_mut(|li| li.timestamp = period / 2);
+    assert_eq!(client.renew_subscription(&user, &usdc_token), 2 * period);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 80_000_000i128);
+
+    env.ledger().with_mut(|li| li.timestamp = 2 * period);
+    let profile = client.get_user_profile(&user).unwrap();
+    assert_eq!(profile.subscription_tier, 0);
+    assert_eq!(client.get_platform_stats().active_subscriptions, 0);
+}
+
+#[test]
+fn test_downgrade_refunds_unused_period() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let period = client.get_billing_period();
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &100_000_000i128);
+    client.upgrade_subscription(&user, &2, &usdc_token);
+
+    // Halfway through: half of the 40 USDC Enterprise/Premium difference comes back
+    env.ledger().with_mut(|li| li.timestamp = period / 2);
+    assert_eq!(client.downgrade_subscription(&user, &1), 20_000_000i128);
+    let profile = client.get_user_profile(&user).unwrap();
+    assert_eq!(profile.subscription_tier, 1);
+    assert_eq!(profile.subscription_expires_at, period);
+    assert_eq!(client.get_platform_stats().active_subscriptions, 1);
+
+    // Dropping to Basic refunds the rest of the Premium period and ends the subscription
+    env.ledger().with_mut(|li| li.timestamp = period * 3 / 4);
+    assert_eq!(client.downgrade_subscription(&user, &0), 2_500_000i128);
+    assert_eq!(client.get_platform_stats().active_subscriptions, 0);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 72_500_000i128);
+}
+
+#[test]
+fn test_downgrade_refunds_in_the_token_paid() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let period = client.get_billing_period();
+    let xlm = Address::generate(&env);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.whitelist_token(&admin, &xlm, &7);
+    let oracle = MockOracleClient::new(&env, &env.register(MockOracle, ()));
+    oracle.set_price(&10_000_000_000_000);
+    client.set_price_oracle(&admin, &oracle.address, &60);
+
+    // Premium paid with 100 XLM at $0.10
+    client.deposit_funds(&user, &xlm, &1_000_000_000);
+    client.deposit_funds(&user, &usdc_token, &100_000_000);
+    client.upgrade_subscription(&user, &1, &xlm);
+    let points = client.get_loyalty_points(&user);
+    let payment = client.get_subscription_payment(&user).unwrap();
+    assert_eq!((payment.token.clone(), payment.amount, payment.loyalty_points), (xlm.clone(), 1_000_000_000, points));
+
+    // Renewals stay in the token the subscription was paid in
+    assert_eq!(
+        client.try_renew_subscription(&user, &usdc_token),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::SubscriptionTokenMismatch as u32)))
+    );
+
+    // XLM doubles, but half of what was paid comes back, in XLM, with its points
+    oracle.set_price(&20_000_000_000_000);
+    env.ledger().with_mut(|li| li.timestamp = period / 2);
+    assert_eq!(client.downgrade_subscription(&user, &0), 500_000_000);
+    assert_eq!(client.get_user_balance(&user, &xlm), 500_000_000);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 100_000_000);
+    assert_eq!(client.get_loyalty_points(&user), points - points / 2);
+    assert_eq!(client.get_subscription_payment(&user), None);
+}
+
+#[test]
+fn test_upgrade_credits_unused_time_and_rejects_other_tiers() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let period = client.get_billing_period();
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &100_000_000);
+    client.upgrade_subscription(&user, &1, &usdc_token);
+    let premium_points = client.get_loyalty_points(&user);
+
+    // Renewing or moving down is not an upgrade
+    for tier in [0, 1] {
+        assert_eq!(
+            client.try_upgrade_subscription(&user, &tier, &usdc_token),
+            Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidTier as u32)))
+        );
+    }
+
+    // Half of the Premium period is credited against Enterprise, with half its points
+    let start = env.ledger().timestamp();
+    env.ledger().with_mut(|li| li.timestamp = start + period / 2);
+    client.upgrade_subscription(&user, &2, &usdc_token);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 100_000_000 - 10_000_000 + 5_000_000 - 50_000_000);
+    let payment = client.get_subscription_payment(&user).unwrap();
+    assert_eq!((payment.amount, payment.paid_from), (50_000_000, start + period / 2));
+    assert_eq!(client.get_loyalty_points(&user), premium_points - premium_points / 2 + payment.loyalty_points);
+
+    let profile = client.get_user_profile(&user).unwrap();
+    assert_eq!(profile.subscription_tier, 2);
+    assert_eq!(profile.subscription_expires_at, start + period / 2 + period);
+    assert_eq!(profile.total_spent, 55_000_000);
+}
+
+#[test]
+fn test_loyalty_multiplier_follows_subscription_tier() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let orders = balance_caller(&env, &admin, &client);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &100_000_000i128);
+
+    client.deduct_balance(&orders, &user, &usdc_token, &10_000_000i128);
+    assert_eq!(client.get_user_profile(&user).unwrap().loyalty_points, 10);
+
+    // Premium earns 1.5x by default, then 3x once the admin raises the rate
+    client.upgrade_subscription(&user, &1, &usdc_token);
+    assert_eq!(client.get_user_profile(&user).unwrap().loyalty_points, 25);
+    client.set_loyalty_multiplier(&admin, &1, &30_000);
+    client.deduct_balance(&orders, &user, &usdc_token, &10_000_000i128);
+    assert_eq!(client.get_user_profile(&user).unwrap().loyalty_points, 55);
+}
+
 #[test]
 fn test_referral_system() {
     let (env, admin, usdc_token, _) = create_test_env();
@@ -125,7 +546,7 @@ fn test_referral_system() {
     let referral_code = client.create_user_profile(
         &referrer,
         &String::from_str(&env, "referrer"),
-        &String::from_str(&env, "referrer@example.com"),
+        &email_hash(&env, "referrer@example.com"),
         &None
     );
     
@@ -133,7 +554,7 @@ fn test_referral_system() {
     client.create_user_profile(
         &referee,
         &String::from_str(&env, "referee"),
-        &String::from_str(&env, "referee@example.com"),
+        &email_hash(&env, "referee@example.com"),
         &Some(referral_code)
     );
     
@@ -142,7 +563,51 @@ fn test_referral_system() {
 }
 
 #[test]
-#[should_panic(expected = "User profile already exists")]
+fn test_referrer_rewarded_at_spend_milestones() {
+    let (env, admin, usdc_token, _) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let orders = balance_caller(&env, &admin, &client);
+    let referrer = Address::generate(&env);
+    let referee = Address::generate(&env);
+
+    let referral_code = client.create_user_profile(
+        &referrer,
+        &String::from_str(&env, "referrer"),
+        &email_hash(&env, "referrer@example.com"),
+        &None
+    );
+    client.create_user_profile(
+        &referee,
+        &String::from_str(&env, "referee"),
+        &email_hash(&env, "referee@example.com"),
+        &Some(referral_code)
+    );
+    client.set_referral_program(&admin, &usdc_token, &vec![
+        &env,
+        ReferralMilestone { spend_threshold: 10_000_000, loyalty_points: 50, token_bonus: 0 },
+        ReferralMilestone { spend_threshold: 50_000_000, loyalty_points: 100, token_bonus: 5_000_000 },
+        ReferralMilestone { spend_threshold: 500_000_000, loyalty_points: 1_000, token_bonus: 0 },
+    ]);
+
+    // An Enterprise upgrade costs 50 USDC and crosses the first two milestones at once
+    client.deposit_funds(&referee, &usdc_token, &100_000_000);
+    client.upgrade_subscription(&referee, &2, &usdc_token);
+
+    let stats = client.get_referral_stats(&referrer);
+    assert_eq!(stats.referrals, 1);
+    assert_eq!(stats.milestones_reached, 2);
+    assert_eq!(stats.points_earned, 150);
+    assert_eq!(stats.bonus_earned, 5_000_000);
+    assert_eq!(client.get_user_profile(&referrer).unwrap().loyalty_points, 150);
+    assert_eq!(client.get_user_balance(&referrer, &usdc_token), 5_000_000);
+
+    // Further spend below the next milestone pays nothing more
+    client.deduct_balance(&orders, &referee, &usdc_token, &1_000_000);
+    assert_eq!(client.get_referral_stats(&referrer).milestones_reached, 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
 fn test_duplicate_user_creation() {
     let (env, admin, usdc_token, user) = create_test_env();
     let client = init_contract(&env, &admin, &usdc_token);
@@ -150,7 +615,7 @@ fn test_duplicate_user_creation() {
     client.create_user_profile(
         &user,
         &String::from_str(&env, "testuser"),
-        &String::from_str(&env, "test@example.com"),
+        &email_hash(&env, "test@example.com"),
         &None
     );
     
@@ -158,13 +623,13 @@ fn test_duplicate_user_creation() {
     client.create_user_profile(
         &user,
         &String::from_str(&env, "testuser2"),
-        &String::from_str(&env, "test2@example.com"),
+        &email_hash(&env, "test2@example.com"),
         &None
     );
 }
 
 #[test]
-#[should_panic(expected = "Insufficient balance")]
+#[should_panic(expected = "Error(Contract, #11)")]
 fn test_insufficient_balance_withdrawal() {
     let (env, admin, usdc_token, user) = create_test_env();
     let client = init_contract(&env, &admin, &usdc_token);
@@ -172,7 +637,7 @@ fn test_insufficient_balance_withdrawal() {
     client.create_user_profile(
         &user,
         &String::from_str(&env, "testuser"),
-        &String::from_str(&env, "test@example.com"),
+        &email_hash(&env, "test@example.com"),
         &None
     );
     
@@ -189,7 +654,7 @@ fn test_admin_functions() {
     client.create_user_profile(
         &user,
         &String::from_str(&env, "testuser"),
-        &String::from_str(&env, "test@example.com"),
+        &email_hash(&env, "test@example.com"),
         &None
     );
     
@@ -197,11 +662,11 @@ fn test_admin_functions() {
     client.verify_user(&admin, &user);
     
     let profile = client.get_user_profile(&user).unwrap();
-    assert!(profile.is_verified);
+    assert_eq!(profile.verification_level, LEVEL_KYC_FULL);
     
     // Test token whitelisting
     let new_token = Address::generate(&env);
-    client.whitelist_token(&admin, &new_token);
+    client.whitelist_token(&admin, &new_token, &7);
     
     // Test depositing with newly whitelisted token
     client.deposit_funds(&user, &new_token, &50_000_000i128);
@@ -209,16 +674,91 @@ fn test_admin_functions() {
     assert_eq!(balance, 50_000_000i128);
 }
 
+#[test]
+fn test_users_enumerated_in_registration_order() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let second = Address::generate(&env);
+
+    client.create_user_profile(&user, &String::from_str(&env, "first"), &email_hash(&env, "a@example.com"), &None);
+    client.create_user_profile(&second, &String::from_str(&env, "second"), &email_hash(&env, "b@example.com"), &None);
+
+    assert_eq!(client.get_user_count(), 2);
+    let users = client.get_all_users(&admin);
+    assert_eq!(users.get(0).unwrap().user_address, user);
+    assert_eq!(users.get(1).unwrap().username, String::from_str(&env, "second"));
+}
+
+#[test]
+fn test_operator_verifies_users() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let operator = Address::generate(&env);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.add_operator(&admin, &operator);
+    assert!(client.is_operator(&operator));
+
+    client.verify_user(&operator, &user);
+    assert_eq!(client.get_verification_level(&user), LEVEL_KYC_FULL);
+
+    // Operators cannot manage other operators, and lose access once removed
+    assert!(client.try_add_operator(&operator, &Address::generate(&env)).is_err());
+    client.remove_operator(&admin, &operator);
+    assert_eq!(
+        client.try_whitelist_token(&operator, &Address::generate(&env), &7),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::NotOperator as u32)))
+    );
+}
+
+#[test]
+fn test_users_paged_with_filters() {
+    let (env, admin, usdc_token, _) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let names = ["u0", "u1", "u2", "u3", "u4"];
+    let no_filter = UserFilter { verified_only: false, active_only: false, tier: None };
+
+    let mut users = Vec::new(&env);
+    for name in names {
+        let user = Address::generate(&env);
+        client.create_user_profile(&user, &String::from_str(&env, name), &email_hash(&env, "u@example.com"), &None);
+        users.push_back(user);
+    }
+    client.verify_user(&admin, &users.get(1).unwrap());
+    client.verify_user(&admin, &users.get(3).unwrap());
+
+    let page = client.get_users_page(&admin, &0, &2, &no_filter);
+    assert_eq!(page.users.len(), 2);
+    assert_eq!(page.next_offset, Some(2));
+    let page = client.get_users_page(&admin, &4, &2, &no_filter);
+    assert_eq!(page.users.len(), 1);
+    assert_eq!(page.next_offset, None);
+
+    let verified = UserFilter { verified_only: true, active_only: true, tier: None };
+    let page = client.get_users_page(&admin, &0, &10, &verified);
+    assert_eq!(page.users.len(), 2);
+    assert_eq!(page.users.get(1).unwrap().user_address, users.get(3).unwrap());
+
+    let premium = UserFilter { verified_only: false, active_only: false, tier: Some(1) };
+    assert_eq!(client.get_users_page(&admin, &0, &10, &premium).users.len(), 0);
+}
+
 #[test]
 fn test_balance_utility_functions() {
     let (env, admin, usdc_token, user) = create_test_env();
     let client = init_contract(&env, &admin, &usdc_token);
+    let orders = balance_caller(&env, &admin, &client);
     
     // Create user and deposit funds
     client.create_user_profile(
         &user,
         &String::from_str(&env, "testuser"),
-        &String::from_str(&env, "test@example.com"),
+        &email_hash(&env, "test@example.com"),
         &None
     );
     
@@ -232,14 +772,14 @@ fn test_balance_utility_functions() {
     assert!(!client.has_sufficient_balance(&user, &usdc_token, &150_000_000i128));
     
     // Test deduct balance
-    let success = client.deduct_balance(&user, &usdc_token, &30_000_000i128);
+    let success = client.deduct_balance(&orders, &user, &usdc_token, &30_000_000i128);
     assert!(success);
     
     let balance = client.get_user_balance(&user, &usdc_token);
     assert_eq!(balance, 70_000_000i128);
     
     // Test refund balance
-    client.refund_balance(&user, &usdc_token, &10_000_000i128);
+    client.refund_balance(&orders, &user, &usdc_token, &10_000_000i128);
     let new_balance = client.get_user_balance(&user, &usdc_token);
     assert_eq!(new_balance, 80_000_000i128);
     
@@ -258,7 +798,7 @@ fn test_user_profile_updates() {
     client.create_user_profile(
         &user,
         &String::from_str(&env, "testuser"),
-        &String::from_str(&env, "test@example.com"),
+        &email_hash(&env, "test@example.com"),
         &None
     );
     
@@ -271,16 +811,752 @@ fn test_user_profile_updates() {
     
     let profile = client.get_user_profile(&user).unwrap();
     assert_eq!(profile.username, String::from_str(&env, "newusername"));
-    assert_eq!(profile.email, String::from_str(&env, "test@example.com")); // unchanged
+    assert_eq!(profile.email_hash, email_hash(&env, "test@example.com")); // unchanged
     
     // Update email only
     client.update_user_profile(
         &user,
         &None,
-        &Some(String::from_str(&env, "newemail@example.com"))
+        &Some(email_hash(&env, "newemail@example.com"))
     );
     
     let updated_profile = client.get_user_profile(&user).unwrap();
     assert_eq!(updated_profile.username, String::from_str(&env, "newusername")); // unchanged
-    assert_eq!(updated_profile.email, String::from_str(&env, "newemail@example.com"));
+    assert_eq!(updated_profile.email_hash, email_hash(&env, "newemail@example.com"));
+}
+
+#[test]
+fn test_usernames_are_unique() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let other = Address::generate(&env);
+    let taken = String::from_str(&env, "testuser");
+
+    client.create_user_profile(&user, &taken, &email_hash(&env, "test@example.com"), &None);
+    assert_eq!(client.get_address_by_username(&taken), Some(user.clone()));
+    assert_eq!(
+        client.try_create_user_profile(&other, &taken, &email_hash(&env, "other@example.com"), &None),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::UsernameTaken as u32)))
+    );
+
+    // Renaming frees the old username
+    client.update_user_profile(&user, &Some(String::from_str(&env, "renamed")), &None);
+    assert_eq!(client.get_address_by_username(&taken), None);
+    client.create_user_profile(&other, &taken, &email_hash(&env, "other@example.com"), &None);
+    assert!(client.try_update_user_profile(&other, &Some(String::from_str(&env, "renamed")), &None).is_err());
+}
+
+#[test]
+fn test_legacy_plaintext_emails_migrate_to_salted_hashes() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+
+    let legacy = LegacyUserProfile {
+        user_address: user.clone(),
+        username: String::from_str(&env, "testuser"),
+        email: String::from_str(&env, "test@example.com"),
+        created_at: 0,
+        is_active: true,
+        is_verified: false,
+        referral_code: String::from_str(&env, "NF123456"),
+        referred_by: None,
+        total_spent: 0,
+        loyalty_points: 0,
+        subscription_tier: 0,
+        subscription_expires_at: 0,
+    };
+    env.as_contract(&client.address, || {
+        env.storage().persistent().set(&DataKey::Profile(user.clone()), &legacy);
+    });
+
+    client.set_email_salt(&admin, &salt);
+    assert_eq!(client.migrate_email_hashes(&admin, &vec![&env, user.clone()]), 1);
+    // Already-migrated profiles are skipped
+    assert_eq!(client.migrate_email_hashes(&admin, &vec![&env, user.clone()]), 0);
+
+    let mut preimage = Bytes::from_array(&env, &[7u8; 32]);
+    preimage.extend_from_slice(b"test@example.com");
+    let expected: BytesN<32> = env.crypto().sha256(&preimage).into();
+    assert!(client.verify_email_hash(&user, &expected));
+    assert!(!client.verify_email_hash(&user, &email_hash(&env, "test@example.com")));
+}
+
+#[test]
+fn test_legacy_profiles_upgrade_lazily_on_read() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let other = Address::generate(&env);
+
+    for (address, username) in [(&user, "testuser"), (&other, "otheruser")] {
+        let legacy = LegacyUserProfile {
+            user_address: address.clone(),
+            username: String::from_str(&env, username),
+            email: String::from_str(&env, "test@example.com"),
+            created_at: 0,
+            is_active: true,
+            is_verified: true,
+            referral_code: String::from_str(&env, "NF123456"),
+            referred_by: None,
+            total_spent: 0,
+            loyalty_points: 0,
+            subscription_tier: 0,
+            subscription_expires_at: 0,
+        };
+        env.as_contract(&client.address, || {
+            env.storage().persistent().set(&DataKey::Profile(address.clone()), &legacy);
+        });
+    }
+    client.set_email_salt(&admin, &BytesN::from_array(&env, &[7u8; 32]));
+
+    // Reading upgrades the entry in place
+    let profile = client.get_user_profile(&user).unwrap();
+    assert_eq!(profile.verification_level, LEVEL_KYC_FULL);
+    client.deposit_funds(&user, &usdc_token, &100);
+
+    // Only the profile nobody has read yet is left to migrate
+    assert_eq!(client.migrate_profiles(&admin, &vec![&env, user.clone(), other.clone()]), 1);
+    assert_eq!(client.migrate_profiles(&admin, &vec![&env, user.clone(), other.clone()]), 0);
+    assert_eq!(client.get_user_profile(&other).unwrap().username, String::from_str(&env, "otheruser"));
+}
+
+#[test]
+fn test_profile_metadata_is_capped() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    let locale = Symbol::new(&env, "locale");
+    client.set_profile_metadata(&user, &locale, &Some(String::from_str(&env, "en-US")));
+    client.set_profile_metadata(&user, &symbol_short!("discord"), &Some(String::from_str(&env, "nf#1234")));
+    assert_eq!(client.get_profile_metadata(&user).get(locale.clone()), Some(String::from_str(&env, "en-US")));
+
+    client.set_profile_metadata(&user, &locale, &None);
+    assert_eq!(client.get_profile_metadata(&user).len(), 1);
+
+    let too_large = soroban_sdk::Error::from_contract_error(Error::MetadataTooLarge as u32);
+    let long = String::from_bytes(&env, &[b'a'; MAX_METADATA_VALUE_LEN as usize + 1]);
+    assert_eq!(client.try_set_profile_metadata(&user, &locale, &Some(long)), Err(Ok(too_large)));
+
+    // Fill the remaining slots next to `discord`
+    for name in ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o"] {
+        client.set_profile_metadata(&user, &Symbol::new(&env, name), &Some(String::from_str(&env, "x")));
+    }
+    assert_eq!(client.get_profile_metadata(&user).len(), MAX_METADATA_ENTRIES);
+    assert_eq!(client.try_set_profile_metadata(&user, &locale, &Some(String::from_str(&env, "x"))), Err(Ok(too_large)));
+}
+
+#[test]
+fn test_custom_referral_code_keeps_old_code_as_alias() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let other = Address::generate(&env);
+
+    let old_code = client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    let code = String::from_str(&env, "NODEFAN42");
+    client.set_custom_referral_code(&user, &code);
+    assert_eq!(client.get_user_profile(&user).unwrap().referral_code, code);
+    assert_eq!(client.get_referral_aliases(&user), vec![&env, old_code.clone()]);
+
+    // Both codes credit the same referrer
+    client.create_user_profile(&other, &String::from_str(&env, "other"), &email_hash(&env, "o@example.com"), &Some(old_code));
+    assert_eq!(client.get_user_profile(&other).unwrap().referred_by, Some(user.clone()));
+
+    let invalid = soroban_sdk::Error::from_contract_error(Error::InvalidReferralCode as u32);
+    assert_eq!(client.try_set_custom_referral_code(&other, &String::from_str(&env, "abc1")), Err(Ok(invalid)));
+    assert_eq!(client.try_set_custom_referral_code(&other, &String::from_str(&env, "NF1")), Err(Ok(invalid)));
+    assert_eq!(
+        client.try_set_custom_referral_code(&other, &code),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::ReferralCodeTaken as u32)))
+    );
+}
+
+#[test]
+fn test_referral_tree_tracks_second_level() {
+    let (env, admin, usdc_token, root) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let (direct, second, other) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+
+    let signup = |user: &Address, name: &str, code: Option<&str>| {
+        client.create_user_profile(
+            user,
+            &String::from_str(&env, name),
+            &email_hash(&env, name),
+            &code.map(|code| String::from_str(&env, code)),
+        );
+        client.set_custom_referral_code(user, &String::from_str(&env, &name.to_uppercase()));
+    };
+    signup(&root, "root", None);
+    signup(&direct, "direct", Some("ROOT"));
+    signup(&other, "other", Some("ROOT"));
+    signup(&second, "second", Some("DIRECT"));
+
+    assert_eq!(client.get_referees(&root), vec![&env, direct.clone(), other.clone()]);
+    assert_eq!(client.get_referral_counts(&root), vec![&env, 2u32, 1]);
+    assert_eq!(client.get_referral_counts(&direct), vec![&env, 1u32]);
+
+    let tree = client.get_referral_tree(&root, &10);
+    assert_eq!(tree.len(), 3);
+    assert_eq!(
+        tree.get(2).unwrap(),
+        ReferralNode { user_address: second.clone(), referrer: direct.clone(), depth: 2 }
+    );
+    assert_eq!(client.get_referral_tree(&root, &1).len(), 2);
+}
+
+#[test]
+fn test_get_user_profiles_keeps_request_order() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let stranger = Address::generate(&env);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    let profiles = client.get_user_profiles(&vec![&env, stranger.clone(), user.clone()]);
+    assert_eq!(profiles.get(0).unwrap(), None);
+    assert_eq!(profiles.get(1).unwrap(), client.get_user_profile(&user));
+
+    let mut too_many = Vec::new(&env);
+    for _ in 0..=MAX_PAGE_SIZE {
+        too_many.push_back(stranger.clone());
+    }
+    assert_eq!(
+        client.try_get_user_profiles(&too_many),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::BatchTooLarge as u32)))
+    );
+}
+
+#[test]
+fn test_deactivate_and_purge_account() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let orders = balance_caller(&env, &admin, &client);
+    let username = String::from_str(&env, "testuser");
+
+    client.create_user_profile(&user, &username, &email_hash(&env, "test@example.com"), &None);
+    client.deposit_funds(&user, &usdc_token, &20_000_000i128);
+    client.deduct_balance(&orders, &user, &usdc_token, &10_000_000i128);
+
+    // Balances must be emptied first
+    assert_eq!(
+        client.try_deactivate_account(&user),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::NonZeroBalance as u32)))
+    );
+    client.withdraw_funds(&user, &usdc_token, &10_000_000i128);
+    client.deactivate_account(&user);
+    assert!(client.try_deposit_funds(&user, &usdc_token, &1).is_err());
+
+    client.reactivate_account(&user);
+    client.deactivate_account(&user);
+    client.purge_profile(&admin, &user);
+
+    let profile = client.get_user_profile(&user).unwrap();
+    assert!(profile.username.is_empty());
+    assert!(!client.verify_email_hash(&user, &email_hash(&env, "test@example.com")));
+    assert_eq!(profile.total_spent, 10_000_000i128);
+    assert_eq!(client.get_address_by_username(&username), None);
+    assert!(client.try_reactivate_account(&user).is_err());
+}
+
+#[test]
+fn test_frozen_user_cannot_move_funds() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let orders = balance_caller(&env, &admin, &client);
+    let frozen = soroban_sdk::Error::from_contract_error(Error::UserFrozen as u32);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &20_000_000i128);
+    client.freeze_user(&admin, &user);
+
+    assert_eq!(client.try_deposit_funds(&user, &usdc_token, &1), Err(Ok(frozen)));
+    assert_eq!(client.try_withdraw_funds(&user, &usdc_token, &1), Err(Ok(frozen)));
+    assert_eq!(client.try_deduct_balance(&orders, &user, &usdc_token, &1), Err(Ok(frozen)));
+    assert_eq!(client.try_upgrade_subscription(&user, &1, &usdc_token), Err(Ok(frozen)));
+
+    client.unfreeze_user(&admin, &user);
+    client.withdraw_funds(&user, &usdc_token, &20_000_000i128);
+    assert!(!client.is_frozen(&user));
+}
+
+#[test]
+fn test_only_allowlisted_callers_move_balances() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let orders = balance_caller(&env, &admin, &client);
+    let stranger = Address::generate(&env);
+    let not_caller = soroban_sdk::Error::from_contract_error(Error::NotBalanceCaller as u32);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &1_000);
+
+    assert_eq!(client.try_deduct_balance(&stranger, &user, &usdc_token, &1_000), Err(Ok(not_caller)));
+    assert_eq!(client.try_refund_balance(&stranger, &user, &usdc_token, &1_000), Err(Ok(not_caller)));
+    assert!(client.deduct_balance(&orders, &user, &usdc_token, &1_000));
+
+    client.set_balance_caller(&admin, &orders, &false);
+    assert!(!client.is_balance_caller(&orders));
+    assert_eq!(client.try_refund_balance(&orders, &user, &usdc_token, &1_000), Err(Ok(not_caller)));
+}
+
+#[test]
+fn test_batch_settlement_is_atomic() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let other = Address::generate(&env);
+
+    client.create_user_profile(&user, &String::from_str(&env, "first"), &email_hash(&env, "a@example.com"), &None);
+    client.create_user_profile(&other, &String::from_str(&env, "second"), &email_hash(&env, "b@example.com"), &None);
+
+    client.batch_deposit(&admin, &vec![
+        &env,
+        (user.clone(), usdc_token.clone(), 300),
+        (other.clone(), usdc_token.clone(), 500),
+    ]);
+    // A balance and a deposit event per entry
+    assert_eq!(env.events().all().len(), 4);
+    assert_eq!(client.get_platform_stats().total_deposits, 800);
+
+    // The second entry overdraws, so the first is rolled back too
+    let overdrawn = vec![
+        &env,
+        (user.clone(), usdc_token.clone(), 300),
+        (other.clone(), usdc_token.clone(), 600),
+    ];
+    assert!(client.try_batch_withdraw(&admin, &overdrawn).is_err());
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 300);
+
+    client.batch_withdraw(&admin, &vec![&env, (user.clone(), usdc_token.clone(), 300)]);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 0);
+}
+
+#[test]
+fn test_credit_balance_from_approved_source() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let source = Address::generate(&env);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.set_credit_source(&admin, &source, &true);
+
+    client.credit_balance(&source, &user, &usdc_token, &300);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 300);
+    assert_eq!(client.get_platform_stats().total_deposits, 300);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn test_credit_balance_unapproved_source() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.credit_balance(&Address::generate(&env), &user, &usdc_token, &300);
+}
+
+#[test]
+fn test_verification_follows_compliance_attestations() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let attestor = Address::generate(&env);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &1_000);
+    client.verify_user(&admin, &user);
+    assert!(client.is_user_verified(&user));
+
+    let compliance_id = env.register(compliance::ComplianceContract, ());
+    let compliance_client = compliance::ComplianceContractClient::new(&env, &compliance_id);
+    compliance_client.initialize(&admin);
+    compliance_client.set_attestor(&admin, &attestor, &true);
+    client.set_compliance_contract(&admin, &compliance_id, &1, &500);
+
+    // The admin flag no longer counts once the registry is set
+    assert!(!client.is_user_verified(&user));
+    client.withdraw_funds(&user, &usdc_token, &100);
+    assert_eq!(client.try_withdraw_funds(&user, &usdc_token, &500), Err(Ok(soroban_sdk::Error::from_contract_error(Error::ComplianceRequired as u32))));
+
+    compliance_client.attest(&attestor, &user, &String::from_str(&env, "DE"), &1, &(env.ledger().timestamp() + 3600));
+    assert!(client.is_user_verified(&user));
+    client.withdraw_funds(&user, &usdc_token, &500);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 400);
+}
+
+#[test]
+fn test_withdrawal_limits_follow_verification_level() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &10_000);
+    client.set_withdrawal_limit(&admin, &LEVEL_NONE, &100);
+    client.set_withdrawal_limit(&admin, &LEVEL_EMAIL, &1_000);
+
+    assert_eq!(
+        client.try_withdraw_funds(&user, &usdc_token, &500),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::WithdrawalLimitExceeded as u32)))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 42);
+    client.set_verification_level(&admin, &user, &LEVEL_EMAIL);
+    let profile = client.get_user_profile(&user).unwrap();
+    assert_eq!(profile.verified_at, 42);
+    assert!(!client.is_user_verified(&user));
+    client.withdraw_funds(&user, &usdc_token, &500);
+
+    // KYC-full has no limit configured
+    client.verify_user(&admin, &user);
+    client.withdraw_funds(&user, &usdc_token, &5_000);
+
+    client.revoke_verification(&admin, &user);
+    assert!(client.try_withdraw_funds(&user, &usdc_token, &500).is_err());
+}
+
+#[test]
+fn test_daily_withdrawal_limit_rolls_over_24h() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &10_000);
+    client.set_daily_withdrawal_limit(&admin, &LEVEL_NONE, &1_000);
+
+    client.withdraw_funds(&user, &usdc_token, &600);
+    env.ledger().with_mut(|li| li.timestamp = 12 * 3600);
+    client.withdraw_funds(&user, &usdc_token, &400);
+    assert_eq!(client.get_remaining_daily_limit(&user, &usdc_token), Some(0));
+    assert_eq!(
+        client.try_withdraw_funds(&user, &usdc_token, &1),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::DailyLimitExceeded as u32)))
+    );
+
+    // The first withdrawal leaves the window after 24h; the second is still counted
+    env.ledger().with_mut(|li| li.timestamp = 24 * 3600);
+    assert_eq!(client.get_remaining_daily_limit(&user, &usdc_token), Some(600));
+
+    // Higher levels without a configured cap are unlimited
+    client.verify_user(&admin, &user);
+    assert_eq!(client.get_remaining_daily_limit(&user, &usdc_token), None);
+    client.withdraw_funds(&user, &usdc_token, &5_000);
+}
+
+#[test]
+fn test_withdrawal_request_waits_out_delay() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &1_000);
+    client.set_withdrawal_delay(&admin, &3600);
+
+    let id = client.request_withdrawal(&user, &usdc_token, &700);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 300);
+    assert_eq!(client.get_withdrawal_request(&id).unwrap().unlocks_at, 3600);
+    assert_eq!(
+        client.try_execute_withdrawal(&user, &id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::WithdrawalLocked as u32)))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 3600);
+    client.execute_withdrawal(&user, &id);
+    assert_eq!(client.get_withdrawal_request(&id), None);
+    assert_eq!(client.get_platform_stats().total_withdrawals, 700);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 300);
+}
+
+#[test]
+fn test_cancelled_withdrawal_request_returns_funds() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &1_000);
+    client.set_daily_withdrawal_limit(&admin, &LEVEL_NONE, &1_000);
+
+    let id = client.request_withdrawal(&user, &usdc_token, &700);
+    assert_eq!(client.get_remaining_daily_limit(&user, &usdc_token), Some(300));
+    client.cancel_withdrawal_request(&admin, &id);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 1_000);
+    assert_eq!(client.get_platform_stats().total_withdrawals, 0);
+
+    // The cancelled amount no longer counts against the daily limit
+    assert_eq!(client.get_remaining_daily_limit(&user, &usdc_token), Some(1_000));
+    let retry = client.request_withdrawal(&user, &usdc_token, &1_000);
+    client.cancel_withdrawal_request(&admin, &retry);
+
+    env.ledger().with_mut(|li| li.timestamp = 2 * 24 * 3600);
+    assert_eq!(
+        client.try_execute_withdrawal(&user, &id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::WithdrawalRequestNotFound as u32)))
+    );
+}
+
+#[test]
+fn test_profile_ttl_extension() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    let ttl = |key: DataKey| env.as_contract(&client.address, || env.storage().persistent().get_ttl(&key));
+    // Saving the profile bumped it past the default TTL
+    assert_eq!(ttl(DataKey::Profile(user.clone())), TTL_EXTEND_TO);
+
+    env.ledger().with_mut(|li| li.sequence_number += 1_000);
+    assert_eq!(ttl(DataKey::Profile(user.clone())), TTL_EXTEND_TO - 1_000);
+    client.extend_profile_ttl(&user);
+    assert_eq!(ttl(DataKey::Profile(user.clone())), TTL_EXTEND_TO);
+
+    let stranger = Address::generate(&env);
+    assert_eq!(client.bulk_extend_ttl(&admin, &vec![&env, user.clone(), stranger.clone()]), 1);
+    assert_eq!(ttl(DataKey::Admin), TTL_EXTEND_TO);
+    assert_eq!(
+        client.try_extend_profile_ttl(&stranger),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::UserNotFound as u32)))
+    );
+}
+
+#[test]
+fn test_upgrade_and_migrate_are_admin_only() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+
+    assert_eq!(client.get_version(), CONTRACT_VERSION);
+    assert_eq!(client.migrate(&admin), CONTRACT_VERSION);
+
+    let not_admin = soroban_sdk::Error::from_contract_error(Error::NotAdmin as u32);
+    assert_eq!(client.try_upgrade(&user, &BytesN::from_array(&env, &[7u8; 32])), Err(Ok(not_admin)));
+    assert_eq!(client.try_migrate(&user), Err(Ok(not_admin)));
+}
+
+#[test]
+fn test_interest_accrues_on_idle_balances() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let year = 365 * 24 * 3600;
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &1_000_000);
+    client.set_interest_rate(&admin, &usdc_token, &1_000);
+
+    // Half a year at 10% on 1_000_000, then half a year at 10% on 500_000
+    env.ledger().with_mut(|li| li.timestamp = year / 2);
+    assert_eq!(client.get_accrued_interest(&user, &usdc_token), 50_000);
+    client.withdraw_funds(&user, &usdc_token, &500_000);
+    env.ledger().with_mut(|li| li.timestamp = year);
+    assert_eq!(client.get_accrued_interest(&user, &usdc_token), 75_000);
+
+    assert_eq!(client.claim_interest(&user, &usdc_token), 75_000);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 575_000);
+    assert_eq!(client.get_accrued_interest(&user, &usdc_token), 0);
+    assert_eq!(client.get_interest_pool(&usdc_token).unwrap().total_claimed, 75_000);
+
+    // A rate change only applies from then on
+    client.set_interest_rate(&admin, &usdc_token, &0);
+    env.ledger().with_mut(|li| li.timestamp = 2 * year);
+    assert_eq!(client.claim_interest(&user, &usdc_token), 0);
+}
+
+#[test]
+fn test_spend_from_consumes_allowance() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let spender = Address::generate(&env);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &10_000_000);
+
+    let no_allowance = soroban_sdk::Error::from_contract_error(Error::InsufficientAllowance as u32);
+    assert_eq!(client.try_spend_from(&spender, &user, &usdc_token, &1), Err(Ok(no_allowance)));
+
+    client.approve(&user, &spender, &usdc_token, &3_000_000);
+    client.spend_from(&spender, &user, &usdc_token, &2_000_000);
+    assert_eq!(client.get_allowance(&user, &spender, &usdc_token), 1_000_000);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 8_000_000);
+    assert_eq!(client.get_user_profile(&user).unwrap().total_spent, 2_000_000);
+
+    assert_eq!(client.try_spend_from(&spender, &user, &usdc_token, &1_000_001), Err(Ok(no_allowance)));
+    client.approve(&user, &spender, &usdc_token, &0);
+    assert_eq!(client.try_spend_from(&spender, &user, &usdc_token, &1), Err(Ok(no_allowance)));
+}
+
+fn sign_action(env: &Env, client: &UserProfileContractClient, key: &SigningKey, payload: &SignedAction) -> BytesN<64> {
+    use ed25519_dalek::Signer;
+    use soroban_sdk::xdr::ToXdr;
+
+    let xdr = (client.address.clone(), payload.clone()).to_xdr(env);
+    let mut buffer = [0u8; 1024];
+    let message = &mut buffer[..xdr.len() as usize];
+    xdr.copy_into_slice(message);
+    BytesN::from_array(env, &key.sign(message).to_bytes())
+}
+
+#[test]
+fn test_relayed_actions_need_fresh_signed_nonces() {
+    let (env, admin, usdc_token, _) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let key = SigningKey::from_bytes(&[3u8; 32]);
+    let public_key = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+
+    let create = SignedAction {
+        action: MetaAction::CreateProfile(
+            String::from_str(&env, "relayed"),
+            email_hash(&env, "relayed@example.com"),
+            None,
+        ),
+        nonce: 0,
+        expiry: 100,
+    };
+    client.relay(&public_key, &create, &sign_action(&env, &client, &key, &create));
+
+    // The profile belongs to the signer's account, not the relayer
+    let user = client.get_address_by_username(&String::from_str(&env, "relayed")).unwrap();
+    assert_eq!(user.to_string().len(), 56);
+    assert_eq!(client.get_relay_nonce(&user), 1);
+
+    let deposit = SignedAction { action: MetaAction::Deposit(usdc_token.clone(), 500), nonce: 1, expiry: 100 };
+    let signature = sign_action(&env, &client, &key, &deposit);
+    client.relay(&public_key, &deposit, &signature);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 500);
+
+    // Replays and expired payloads are rejected
+    assert_eq!(
+        client.try_relay(&public_key, &deposit, &signature),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::InvalidNonce as u32)))
+    );
+    let late = SignedAction { nonce: 2, ..deposit };
+    env.ledger().with_mut(|li| li.timestamp = 101);
+    assert_eq!(
+        client.try_relay(&public_key, &late, &sign_action(&env, &client, &key, &late)),
+        Err(Ok(soroban_sdk::Error::from_contract_error(Error::SignatureExpired as u32)))
+    );
+}
+
+#[test]
+fn test_pause_switch_stops_balance_changes() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let orders = balance_caller(&env, &admin, &client);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+    client.deposit_funds(&user, &usdc_token, &20_000_000);
+    client.pause(&admin);
+    assert!(client.is_paused());
+
+    let paused = soroban_sdk::Error::from_contract_error(Error::Paused as u32);
+    assert_eq!(client.try_deposit_funds(&user, &usdc_token, &100), Err(Ok(paused)));
+    assert_eq!(client.try_withdraw_funds(&user, &usdc_token, &100), Err(Ok(paused)));
+    assert_eq!(client.try_deduct_balance(&orders, &user, &usdc_token, &100), Err(Ok(paused)));
+    assert_eq!(client.try_upgrade_subscription(&user, &1, &usdc_token), Err(Ok(paused)));
+
+    // Reads and profile edits keep working
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 20_000_000);
+    client.update_user_profile(&user, &Some(String::from_str(&env, "renamed")), &None);
+
+    client.unpause(&admin);
+    client.withdraw_funds(&user, &usdc_token, &100);
+}
+
+#[test]
+fn test_paused_entry_points_leave_refunds_open() {
+    let (env, admin, usdc_token, user) = create_test_env();
+    let client = init_contract(&env, &admin, &usdc_token);
+    let orders = balance_caller(&env, &admin, &client);
+    let watcher = Address::generate(&env);
+
+    client.create_user_profile(
+        &user,
+        &String::from_str(&env, "testuser"),
+        &email_hash(&env, "test@example.com"),
+        &None
+    );
+
+    let guardian_id = env.register(guardian::GuardianContract, ());
+    let guardian_client = guardian::GuardianContractClient::new(&env, &guardian_id);
+    guardian_client.initialize(&admin, &Address::generate(&env));
+    guardian_client.set_guardian(&admin, &watcher, &true);
+    client.set_pause_guardian(&admin, &guardian_id);
+    guardian_client.pause_function(&watcher, &client.address, &Symbol::new(&env, "deposit_funds"));
+
+    assert_eq!(client.try_deposit_funds(&user, &usdc_token, &100), Err(Ok(soroban_sdk::Error::from_contract_error(Error::Paused as u32))));
+    client.refund_balance(&orders, &user, &usdc_token, &100);
+    assert_eq!(client.get_user_balance(&user, &usdc_token), 100);
 }
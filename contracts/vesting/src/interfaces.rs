@@ -0,0 +1,11 @@
+//! Typed interfaces of the contracts vesting funds move through.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Subset of the treasury used to fund schedules and take back unvested tokens.
+#[allow(dead_code)]
+#[contractclient(name = "TreasuryClient")]
+pub trait TreasuryInterface {
+    fn deposit(env: Env, token: Address, from: Address, amount: i128);
+    fn withdraw(env: Env, token: Address, to: Address, amount: i128, is_admin: bool);
+}
@@ -0,0 +1,260 @@
+#![no_std]
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contracttype, contracterror,
+    Address, Env, IntoVal, Symbol, Vec,
+    panic_with_error, symbol_short, token, vec
+};
+
+mod interfaces;
+use interfaces::TreasuryClient;
+
+#[contract]
+pub struct VestingContract;
+
+/// Cliff + linear vesting schedule of one beneficiary
+///
+/// Nothing vests before `cliff`; from then on the vested amount grows linearly
+/// from `start` and reaches `total` at `end`. Revoking freezes `total` at the
+/// amount vested so far and moves `end` to the revocation time.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Schedule {
+    pub id: u64,
+    pub beneficiary: Address,
+    pub total: i128,
+    pub claimed: i128,
+    pub start: u64,
+    pub cliff: u64,
+    pub end: u64,
+    pub revocable: bool,
+    pub revoked: bool,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Token,                          // Token every schedule vests in
+    Treasury,                       // Treasury funding schedules and receiving revoked tokens
+    ScheduleCounter,                // u64 last issued schedule ID
+    Schedule(u64),                  // schedule ID -> Schedule
+    BeneficiarySchedules(Address),  // beneficiary -> Vec<u64>
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotAdmin = 3,
+    InvalidSchedule = 4,
+    ScheduleNotFound = 5,
+    NotRevocable = 6,
+    AlreadyRevoked = 7,
+    NothingToClaim = 8,
+}
+
+#[contractimpl]
+impl VestingContract {
+    /// Initialize with the vested token and the treasury funding schedules
+    ///
+    /// The admin must be the treasury's admin contract: creating a schedule
+    /// withdraws its tokens from the treasury under the admin's authorization.
+    pub fn initialize(env: Env, admin: Address, token: Address, treasury: Address) -> bool {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        env.storage().persistent().set(&DataKey::Token, &token);
+        env.storage().persistent().set(&DataKey::Treasury, &treasury);
+        true
+    }
+
+    /// Create a schedule funded from the treasury (admin only)
+    ///
+    /// `cliff_seconds` and `duration` count from `start`, which may be in the past
+    /// to honor an earlier grant date.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_schedule(
+        env: Env,
+        admin: Address,
+        beneficiary: Address,
+        amount: i128,
+        start: u64,
+        cliff_seconds: u64,
+        duration: u64,
+        revocable: bool,
+    ) -> u64 {
+        Self::assert_admin(&env, &admin);
+        if amount <= 0 || duration == 0 || cliff_seconds > duration {
+            panic_with_error!(&env, Error::InvalidSchedule);
+        }
+
+        TreasuryClient::new(&env, &Self::treasury(&env))
+            .withdraw(&Self::token(&env), &env.current_contract_address(), &amount, &true);
+
+        let id = env.storage().persistent().get(&DataKey::ScheduleCounter).unwrap_or(0u64) + 1;
+        env.storage().persistent().set(&DataKey::ScheduleCounter, &id);
+
+        let schedule = Schedule {
+            id,
+            beneficiary: beneficiary.clone(),
+            total: amount,
+            claimed: 0,
+            start,
+            cliff: start + cliff_seconds,
+            end: start + duration,
+            revocable,
+            revoked: false,
+        };
+        env.storage().persistent().set(&DataKey::Schedule(id), &schedule);
+
+        let mut ids = Self::get_schedules(env.clone(), beneficiary.clone());
+        ids.push_back(id);
+        env.storage().persistent().set(&DataKey::BeneficiarySchedules(beneficiary.clone()), &ids);
+
+        env.events().publish((symbol_short!("vest_new"), beneficiary), (id, amount, schedule.cliff, schedule.end));
+        id
+    }
+
+    /// Claim everything vested and not yet claimed on a schedule
+    pub fn claim(env: Env, beneficiary: Address, schedule_id: u64) -> i128 {
+        beneficiary.require_auth();
+        let mut schedule = Self::load(&env, schedule_id);
+        if schedule.beneficiary != beneficiary {
+            panic_with_error!(&env, Error::ScheduleNotFound);
+        }
+
+        let amount = Self::vested(&schedule, env.ledger().timestamp()) - schedule.claimed;
+        if amount <= 0 {
+            panic_with_error!(&env, Error::NothingToClaim);
+        }
+
+        schedule.claimed += amount;
+        env.storage().persistent().set(&DataKey::Schedule(schedule_id), &schedule);
+        token::Client::new(&env, &Self::token(&env))
+            .transfer(&env.current_contract_address(), &beneficiary, &amount);
+
+        env.events().publish((symbol_short!("claim"), beneficiary), (schedule_id, amount));
+        amount
+    }
+
+    /// Revoke a schedule, returning the unvested tokens to the treasury (admin only)
+    ///
+    /// Tokens vested up to now stay claimable by the beneficiary.
+    pub fn revoke(env: Env, admin: Address, schedule_id: u64) -> i128 {
+        Self::assert_admin(&env, &admin);
+        let mut schedule = Self::load(&env, schedule_id);
+        if !schedule.revocable {
+            panic_with_error!(&env, Error::NotRevocable);
+        }
+        if schedule.revoked {
+            panic_with_error!(&env, Error::AlreadyRevoked);
+        }
+
+        let now = env.ledger().timestamp();
+        let vested = Self::vested(&schedule, now);
+        let unvested = schedule.total - vested;
+
+        schedule.total = vested;
+        schedule.end = schedule.end.min(now);
+        schedule.revoked = true;
+        env.storage().persistent().set(&DataKey::Schedule(schedule_id), &schedule);
+
+        if unvested > 0 {
+            Self::return_to_treasury(&env, unvested);
+        }
+
+        env.events().publish((symbol_short!("revoke"), schedule.beneficiary), (schedule_id, unvested));
+        unvested
+    }
+
+    /// Get a schedule
+    pub fn get_schedule(env: Env, schedule_id: u64) -> Option<Schedule> {
+        env.storage().persistent().get(&DataKey::Schedule(schedule_id))
+    }
+
+    /// Get the IDs of a beneficiary's schedules
+    pub fn get_schedules(env: Env, beneficiary: Address) -> Vec<u64> {
+        env.storage().persistent()
+            .get(&DataKey::BeneficiarySchedules(beneficiary))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the amount vested on a schedule so far, claimed or not
+    pub fn get_vested(env: Env, schedule_id: u64) -> i128 {
+        Self::vested(&Self::load(&env, schedule_id), env.ledger().timestamp())
+    }
+
+    /// Get the amount a beneficiary can claim on a schedule right now
+    pub fn get_claimable(env: Env, schedule_id: u64) -> i128 {
+        let schedule = Self::load(&env, schedule_id);
+        Self::vested(&schedule, env.ledger().timestamp()) - schedule.claimed
+    }
+
+    // Helper functions
+    fn assert_admin(env: &Env, admin: &Address) {
+        let stored_admin: Address = env.storage().persistent()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        if *admin != stored_admin {
+            panic_with_error!(env, Error::NotAdmin);
+        }
+        admin.require_auth();
+    }
+
+    fn token(env: &Env) -> Address {
+        env.storage().persistent()
+            .get(&DataKey::Token)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized))
+    }
+
+    fn treasury(env: &Env) -> Address {
+        env.storage().persistent()
+            .get(&DataKey::Treasury)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized))
+    }
+
+    fn load(env: &Env, schedule_id: u64) -> Schedule {
+        env.storage().persistent()
+            .get(&DataKey::Schedule(schedule_id))
+            .unwrap_or_else(|| panic_with_error!(env, Error::ScheduleNotFound))
+    }
+
+    fn vested(schedule: &Schedule, now: u64) -> i128 {
+        if now >= schedule.end {
+            schedule.total
+        } else if now < schedule.cliff {
+            0
+        } else {
+            schedule.total * (now - schedule.start) as i128 / (schedule.end - schedule.start) as i128
+        }
+    }
+
+    /// Deposit tokens back into the treasury so its accounting sees them
+    ///
+    /// The treasury pulls the tokens with a `transfer` from this contract, which
+    /// is a nested call and needs this contract's explicit authorization.
+    fn return_to_treasury(env: &Env, amount: i128) {
+        let token = Self::token(env);
+        let treasury = Self::treasury(env);
+        let this = env.current_contract_address();
+
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token.clone(),
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (this.clone(), treasury.clone(), amount).into_val(env),
+                },
+                sub_invocations: vec![env],
+            }),
+        ]);
+        TreasuryClient::new(env, &treasury).deposit(&token, &this, &amount);
+    }
+}
+
+mod test;
@@ -0,0 +1,102 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env
+};
+use treasury::{TreasuryContract, TreasuryContractClient};
+
+const MONTH: u64 = 30 * 24 * 3600;
+
+struct Setup<'a> {
+    vesting: VestingContractClient<'a>,
+    treasury: TreasuryContractClient<'a>,
+    token: TokenClient<'a>,
+    admin: Address,
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let admin = Address::generate(env);
+    let funder = Address::generate(env);
+    let token_id = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    StellarAssetClient::new(env, &token_id).mint(&funder, &10_000);
+
+    let treasury = TreasuryContractClient::new(env, &env.register(TreasuryContract, ()));
+    treasury.initialize(&admin);
+    treasury.deposit(&token_id, &funder, &10_000);
+
+    let vesting = VestingContractClient::new(env, &env.register(VestingContract, ()));
+    vesting.initialize(&admin, &token_id, &treasury.address);
+
+    Setup {
+        vesting,
+        treasury,
+        token: TokenClient::new(env, &token_id),
+        admin,
+    }
+}
+
+#[test]
+fn test_cliff_then_linear_claims() {
+    let env = Env::default();
+    let s = setup(&env);
+    let member = Address::generate(&env);
+
+    let id = s.vesting.create_schedule(&s.admin, &member, &1_200, &0, &(3 * MONTH), &(12 * MONTH), &true);
+    assert_eq!(s.treasury.get_balance(&s.token.address), 8_800);
+    assert_eq!(s.vesting.get_schedules(&member).len(), 1);
+
+    env.ledger().with_mut(|li| li.timestamp = 3 * MONTH - 1);
+    assert_eq!(s.vesting.get_claimable(&id), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 6 * MONTH);
+    assert_eq!(s.vesting.claim(&member, &id), 600);
+
+    env.ledger().with_mut(|li| li.timestamp = 13 * MONTH);
+    assert_eq!(s.vesting.claim(&member, &id), 600);
+    assert_eq!(s.token.balance(&member), 1_200);
+}
+
+#[test]
+fn test_revoke_returns_unvested_to_treasury() {
+    let env = Env::default();
+    let s = setup(&env);
+    let member = Address::generate(&env);
+
+    let id = s.vesting.create_schedule(&s.admin, &member, &1_200, &0, &0, &(12 * MONTH), &true);
+    env.ledger().with_mut(|li| li.timestamp = 4 * MONTH);
+    assert_eq!(s.vesting.revoke(&s.admin, &id), 800);
+    assert_eq!(s.treasury.get_balance(&s.token.address), 9_600);
+
+    // What vested before the revocation stays claimable, and nothing more accrues
+    env.ledger().with_mut(|li| li.timestamp = 12 * MONTH);
+    assert_eq!(s.vesting.claim(&member, &id), 400);
+    assert_eq!(s.vesting.get_claimable(&id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_irrevocable_schedule_cannot_be_revoked() {
+    let env = Env::default();
+    let s = setup(&env);
+    let member = Address::generate(&env);
+
+    let id = s.vesting.create_schedule(&s.admin, &member, &1_200, &0, &0, &(12 * MONTH), &false);
+    s.vesting.revoke(&s.admin, &id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_claim_before_cliff_fails() {
+    let env = Env::default();
+    let s = setup(&env);
+    let member = Address::generate(&env);
+
+    let id = s.vesting.create_schedule(&s.admin, &member, &1_200, &0, &(3 * MONTH), &(12 * MONTH), &true);
+    env.ledger().with_mut(|li| li.timestamp = MONTH);
+    s.vesting.claim(&member, &id);
+}